@@ -3,7 +3,11 @@ use std::{
     io::{Read, Write},
 };
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use emf_core::converter::{
+    DirectoryImageStore, EMFConverter, EMFConverterBuilder, OutputSize,
+    RecoveryPolicy, ResourceLimits,
+};
 use tracing_subscriber::{
     EnvFilter,
     fmt::{format::FmtSpan, time::UtcTime},
@@ -16,9 +20,12 @@ use tracing_subscriber::{
 /// ```sh
 /// emf-cli --input sample.emf --output out.svg --verbose
 /// emf-cli -i sample.emf -o out.svg --quiet
+/// emf-cli -i sample.emf -o out.svg --font-dir /usr/share/fonts
+/// emf-cli -i sample.emf -o out.svg --dpi 96 --background white
 /// ```
 #[derive(Clone, Debug, Parser)]
 #[clap(author, version, about, long_about = None)]
+#[allow(clippy::struct_excessive_bools)]
 struct Cli {
     /// The EMF file path to convert to SVG
     #[arg(short, long)]
@@ -32,6 +39,78 @@ struct Cli {
     /// Print debug logs
     #[arg(long)]
     verbose: bool,
+    /// Directory of TTF/OTF fonts used to render text recorded as glyph
+    /// indices
+    #[arg(long)]
+    font_dir: Option<String>,
+    /// Size of the SVG in pixels, e.g. 640x480
+    #[arg(long, value_parser = parse_size, conflicts_with = "dpi")]
+    size: Option<(u32, u32)>,
+    /// Size the SVG from the picture frame at this many dots per inch
+    #[arg(long)]
+    dpi: Option<f32>,
+    /// Number of decimal places coordinates are rounded to
+    #[arg(long)]
+    precision: Option<usize>,
+    /// CSS color the picture is filled with before drawing
+    #[arg(long)]
+    background: Option<String>,
+    /// Write bitmaps next to the output file instead of embedding them
+    #[arg(long)]
+    external_images: bool,
+    /// Draw text as glyph outlines when its font is available
+    #[arg(long)]
+    text_as_paths: bool,
+    /// How records that cannot be parsed or played are handled
+    #[arg(long, value_enum, default_value_t = Recovery::Strict)]
+    recovery: Recovery,
+    /// Maximum number of records to read
+    #[arg(long)]
+    max_records: Option<usize>,
+    /// Maximum number of pixels of all the bitmaps drawn
+    #[arg(long)]
+    max_bitmap_pixels: Option<u64>,
+    /// Maximum size of the SVG in bytes
+    #[arg(long)]
+    max_output_bytes: Option<usize>,
+    /// Maximum number of entries of the object table
+    #[arg(long)]
+    max_objects: Option<usize>,
+    /// Play flood fills through a raster copy of the drawing
+    #[arg(long)]
+    flood_fill: bool,
+    /// Reduce bitmaps drawn smaller than their size before embedding them
+    #[arg(long)]
+    resample_bitmaps: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Recovery {
+    /// Fail on the first broken record
+    Strict,
+    /// Skip broken records
+    Skip,
+    /// Stop at the first broken record and keep what was drawn
+    Stop,
+}
+
+impl From<Recovery> for RecoveryPolicy {
+    fn from(v: Recovery) -> Self {
+        match v {
+            Recovery::Strict => Self::Strict,
+            Recovery::Skip => Self::SkipRecord,
+            Recovery::Stop => Self::StopAtFirstError,
+        }
+    }
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let parse = |v: &str| v.parse::<u32>().map_err(|err| err.to_string());
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {value}"))?;
+
+    Ok((parse(width)?, parse(height)?))
 }
 
 /// Main entry point for emf-cli.
@@ -115,13 +194,8 @@ fn main() {
     //     .join("\n");
     // println!("{bytes}");
 
-    let wmf_player = wmf_core::converter::SVGPlayer::new();
-    let emf_player = emf_core::converter::SVGPlayer::new();
-    let converter = emf_core::converter::EMFConverter::new(
-        buffer.as_slice(),
-        emf_player,
-        wmf_player,
-    );
+    let converter = converter_builder(&cli)
+        .build(buffer.as_slice(), wmf_core::converter::SVGPlayer::new());
 
     match converter.run() {
         Ok(bytes) => {
@@ -148,3 +222,64 @@ fn main() {
 
     tracing::info!("Converted successfully.");
 }
+
+/// Configures the conversion from the command-line options, exiting if the
+/// font directory cannot be read.
+fn converter_builder(cli: &Cli) -> EMFConverterBuilder {
+    let mut builder = EMFConverter::builder()
+        .text_as_paths(cli.text_as_paths)
+        .recovery_policy(cli.recovery.into())
+        .limits(ResourceLimits {
+            max_records: cli.max_records,
+            max_bitmap_pixels: cli.max_bitmap_pixels,
+            max_output_bytes: cli.max_output_bytes,
+            max_objects: cli.max_objects,
+        });
+
+    if let Some((width, height)) = cli.size {
+        builder = builder.size(OutputSize::Pixels { width, height });
+    } else if let Some(dpi) = cli.dpi {
+        builder = builder.size(OutputSize::Dpi(dpi));
+    }
+
+    if let Some(precision) = cli.precision {
+        builder = builder.precision(precision);
+    }
+
+    if let Some(ref background) = cli.background {
+        builder = builder.background(background);
+    }
+
+    if cli.external_images {
+        let output = std::path::Path::new(&cli.output);
+        let directory = output.parent().unwrap_or(std::path::Path::new(""));
+        let prefix = output
+            .file_stem()
+            .map_or_else(|| "image".into(), |v| v.to_string_lossy());
+
+        builder = builder
+            .external_images(DirectoryImageStore::new(directory, prefix));
+    }
+
+    if cli.flood_fill {
+        builder = builder.flood_fill();
+    }
+
+    if cli.resample_bitmaps {
+        builder = builder.bitmap_resampling();
+    }
+
+    if let Some(ref font_dir) = cli.font_dir {
+        match emf_core::converter::DirectoryFontProvider::new(font_dir) {
+            Ok(provider) => {
+                builder = builder.font_provider(provider);
+            }
+            Err(err) => {
+                tracing::error!(%err, %font_dir);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    builder
+}
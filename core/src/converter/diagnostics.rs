@@ -0,0 +1,33 @@
+use crate::{converter::RecordWarning, imports::*, parser::RecordType};
+
+/// What a conversion could not reproduce exactly, to tell conversions that
+/// may need a review from faithful ones.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics {
+    /// Records the player does not implement and skipped.
+    pub unimplemented: Vec<Diagnostic>,
+    /// Features the player drew approximately or left out, such as raster
+    /// operations, pen styles and hatch styles.
+    pub approximated: Vec<Diagnostic>,
+    /// Records that could not be parsed or played and were skipped, or
+    /// stopped the conversion, under a lenient
+    /// [`RecoveryPolicy`](crate::converter::RecoveryPolicy).
+    pub recovered: Vec<RecordWarning>,
+}
+
+impl Diagnostics {
+    /// Returns `true` if the conversion reproduced every record.
+    pub fn is_empty(&self) -> bool {
+        self.unimplemented.is_empty()
+            && self.approximated.is_empty()
+            && self.recovered.is_empty()
+    }
+}
+
+/// A record, or a feature used by it, that a player could not play exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub record_number: usize,
+    pub record_type: RecordType,
+    pub description: Cow<'static, str>,
+}
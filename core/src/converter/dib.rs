@@ -0,0 +1,264 @@
+//! Reduction of device-independent bitmaps drawn smaller than their size.
+//!
+//! GDI reduces a bitmap stretched into a smaller rectangle by combining the
+//! pixels it eliminates as the stretch mode says. SVG viewers scale the
+//! embedded image on their own, so the reduction is done here where the
+//! stretch mode asks for a result a viewer would not produce, and on
+//! request to keep large bitmaps from bloating the SVG.
+
+use crate::{imports::*, parser::StretchMode};
+
+/// BI_RGB in the Compression field of a BITMAPINFOHEADER.
+const BI_RGB: u32 = 0x0000;
+
+/// An uncompressed DIB: the bitmap header and color table followed by the
+/// pixel rows, each padded to a multiple of four bytes.
+#[derive(Clone, Debug)]
+pub struct PackedDib<'a> {
+    header: &'a [u8],
+    bits: &'a [u8],
+    width: usize,
+    height: usize,
+    top_down: bool,
+    bit_count: usize,
+    stride: usize,
+}
+
+impl<'a> PackedDib<'a> {
+    /// Returns the DIB of a bitmap header with a BITMAPINFOHEADER, or
+    /// `None` if the header is truncated, the bitmap is compressed, has an
+    /// unsupported bit count or holds fewer bits than its size needs.
+    pub fn new(header: &'a [u8], bits: &'a [u8]) -> Option<Self> {
+        let field = |offset: usize| {
+            Some(u32::from_le_bytes(
+                header.get(offset..offset + 4)?.try_into().ok()?,
+            ))
+        };
+
+        if header.len() < 40 || field(0)? < 40 || field(16)? != BI_RGB {
+            return None;
+        }
+
+        let width = usize::try_from(field(4)? as i32).ok()?;
+        let height = field(8)? as i32;
+        let top_down = height < 0;
+        let height = height.unsigned_abs() as usize;
+        let bit_count = usize::from(u16::from_le_bytes(
+            header.get(14..16)?.try_into().ok()?,
+        ));
+
+        if !matches!(bit_count, 1 | 4 | 8 | 16 | 24 | 32) {
+            return None;
+        }
+
+        let stride = row_stride(width, bit_count)?;
+
+        (width > 0 && height > 0 && stride.checked_mul(height)? <= bits.len())
+            .then_some(Self {
+                header,
+                bits,
+                width,
+                height,
+                top_down,
+                bit_count,
+                stride,
+            })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_monochrome(&self) -> bool {
+        self.bit_count == 1
+    }
+
+    /// Reduces the bitmap to `width` by `height` pixels, returning the new
+    /// header and bits. Each pixel combines the block of pixels it replaces:
+    ///
+    /// - STRETCH_ANDSCANS with a bitwise AND of their values, which keeps black
+    ///   pixels of a monochrome bitmap.
+    /// - STRETCH_ORSCANS with a bitwise OR, which keeps white ones.
+    /// - STRETCH_DELETESCANS by keeping the first of them.
+    /// - STRETCH_HALFTONE by averaging the color channels of 24 and 32-bit
+    ///   bitmaps. Palette indices cannot be averaged, so other bitmaps keep the
+    ///   first pixel.
+    ///
+    /// The rows stay in the order of the original bitmap.
+    pub fn reduce(
+        &self,
+        width: usize,
+        height: usize,
+        mode: StretchMode,
+    ) -> (Vec<u8>, Vec<u8>) {
+        let (width, height) =
+            (width.clamp(1, self.width), height.clamp(1, self.height));
+        // The width is at most that of the bitmap, whose stride fits.
+        let stride = row_stride(width, self.bit_count).unwrap_or(self.stride);
+        let mut bits = vec![0_u8; stride * height];
+        let mut block = vec![];
+
+        for row in 0..height {
+            let rows =
+                self.height * row / height..self.height * (row + 1) / height;
+
+            for column in 0..width {
+                let columns = self.width * column / width
+                    ..self.width * (column + 1) / width;
+
+                block.clear();
+                for r in rows.clone() {
+                    for c in columns.clone() {
+                        block.push(self.pixel(c, r));
+                    }
+                }
+
+                let value = match mode {
+                    StretchMode::STRETCH_ANDSCANS => {
+                        block.iter().fold(u32::MAX, |a, b| a & b)
+                    }
+                    StretchMode::STRETCH_ORSCANS => {
+                        block.iter().fold(0, |a, b| a | b)
+                    }
+                    StretchMode::STRETCH_HALFTONE if self.bit_count >= 24 => {
+                        average(&block)
+                    }
+                    _ => block[0],
+                };
+
+                write_pixel(
+                    &mut bits[row * stride..],
+                    column,
+                    self.bit_count,
+                    value,
+                );
+            }
+        }
+
+        // A negative height stands for rows stored top-down.
+        let height =
+            if self.top_down { -(height as i32) } else { height as i32 };
+        let mut header = self.header.to_vec();
+
+        header[4..8].copy_from_slice(&(width as i32).to_le_bytes());
+        header[8..12].copy_from_slice(&height.to_le_bytes());
+        header[20..24].copy_from_slice(&(bits.len() as u32).to_le_bytes());
+
+        (header, bits)
+    }
+
+    fn pixel(&self, column: usize, row: usize) -> u32 {
+        let row = &self.bits[row * self.stride..];
+
+        if self.bit_count < 8 {
+            let bit = column * self.bit_count;
+            let shift = 8 - self.bit_count - bit % 8;
+
+            u32::from(row[bit / 8] >> shift) & ((1 << self.bit_count) - 1)
+        } else {
+            let bytes = self.bit_count / 8;
+
+            row[column * bytes..(column + 1) * bytes]
+                .iter()
+                .rev()
+                .fold(0, |a, b| (a << 8) | u32::from(*b))
+        }
+    }
+}
+
+fn row_stride(width: usize, bit_count: usize) -> Option<usize> {
+    Some(width.checked_mul(bit_count)?.div_ceil(32) * 4)
+}
+
+fn write_pixel(row: &mut [u8], column: usize, bit_count: usize, value: u32) {
+    if bit_count < 8 {
+        let bit = column * bit_count;
+        let shift = 8 - bit_count - bit % 8;
+        let mask = (((1_u32 << bit_count) - 1) << shift) as u8;
+
+        row[bit / 8] = (row[bit / 8] & !mask) | ((value << shift) as u8 & mask);
+    } else {
+        let bytes = bit_count / 8;
+
+        row[column * bytes..(column + 1) * bytes]
+            .copy_from_slice(&value.to_le_bytes()[..bytes]);
+    }
+}
+
+/// Averages each byte of the pixel values, which are the color channels of
+/// 24 and 32-bit pixels.
+fn average(pixels: &[u32]) -> u32 {
+    (0..4).fold(0, |value, channel| {
+        let sum = pixels
+            .iter()
+            .map(|v| u64::from((v >> (channel * 8)) & 0xFF))
+            .sum::<u64>();
+        let mean = (sum + pixels.len() as u64 / 2) / pixels.len() as u64;
+
+        value | ((mean as u32) << (channel * 8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(width: i32, height: i32, bit_count: u16) -> Vec<u8> {
+        [
+            40_u32.to_le_bytes().as_slice(),
+            &width.to_le_bytes(),
+            &height.to_le_bytes(),
+            &1_u16.to_le_bytes(),
+            &bit_count.to_le_bytes(),
+            &[0; 24],
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn reduce_monochrome_with_and_or_scans() {
+        // 4x2 pixels: rows 0b1010 and 0b1111.
+        let header = header(4, 2, 1);
+        let bits = [0b1010_0000, 0, 0, 0, 0b1111_0000, 0, 0, 0];
+        let dib = PackedDib::new(&header, &bits).expect("dib");
+
+        let (reduced, and) = dib.reduce(2, 1, StretchMode::STRETCH_ANDSCANS);
+        assert_eq!(and, [0b0000_0000, 0, 0, 0]);
+        assert_eq!(reduced[4..12], [2, 0, 0, 0, 1, 0, 0, 0]);
+
+        let (_, or) = dib.reduce(2, 1, StretchMode::STRETCH_ORSCANS);
+        assert_eq!(or, [0b1100_0000, 0, 0, 0]);
+    }
+
+    #[test]
+    fn reduce_averages_halftoned_true_color() {
+        // A top-down row of a black and a white pixel.
+        let header = header(2, -1, 24);
+        let bits = [0, 0, 0, 0xFF, 0xFF, 0xFF, 0, 0];
+        let dib = PackedDib::new(&header, &bits).expect("dib");
+
+        let (reduced, halftone) =
+            dib.reduce(1, 1, StretchMode::STRETCH_HALFTONE);
+        assert_eq!(halftone, [0x80, 0x80, 0x80, 0]);
+        assert_eq!(reduced[8..12], (-1_i32).to_le_bytes());
+
+        let (_, deleted) = dib.reduce(1, 1, StretchMode::STRETCH_DELETESCANS);
+        assert_eq!(deleted, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_truncated_header_and_oversized_width() {
+        let header = header(4, 2, 1);
+        let bits = [0; 8];
+
+        // The header states 40 bytes but only 20 are present.
+        assert!(PackedDib::new(&header[..20], &bits).is_none());
+        assert!(
+            PackedDib::new(&self::header(i32::MAX, 1, 32), &bits).is_none()
+        );
+    }
+}
@@ -0,0 +1,894 @@
+//! Font access for text records drawn with ETO_GLYPH_INDEX.
+//!
+//! Such records carry glyph indices of the selected font instead of
+//! characters, so they can only be rendered with the font itself. A
+//! [`FontProvider`] supplies the font data for a LogFont facename and
+//! [`Font`] reads the tables needed to map the indices back to characters
+//! through the `cmap` table, or to draw them from their `glyf` outlines.
+
+use crate::imports::*;
+
+/// Resolves the facename of a LogFont to the data of a TrueType or
+/// OpenType font.
+pub trait FontProvider {
+    /// Returns the contents of the font file (TTF, OTF or TTC) providing
+    /// `facename`, or `None` when no such font is available.
+    fn load(&self, facename: &str) -> Option<Vec<u8>>;
+}
+
+impl<F> FontProvider for F
+where
+    F: Fn(&str) -> Option<Vec<u8>>,
+{
+    fn load(&self, facename: &str) -> Option<Vec<u8>> {
+        self(facename)
+    }
+}
+
+/// A drawing command of a glyph outline in font units, with the y axis
+/// pointing up.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutlineCommand {
+    MoveTo { x: f32, y: f32 },
+    LineTo { x: f32, y: f32 },
+    QuadTo { cx: f32, cy: f32, x: f32, y: f32 },
+    Close,
+}
+
+/// The tables of a TrueType or OpenType face used to render glyph indices.
+#[derive(Clone, Debug)]
+pub struct Font {
+    data: Vec<u8>,
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    index_to_loc_format: i16,
+    number_of_h_metrics: u16,
+    hmtx: Option<(usize, usize)>,
+    loca: Option<(usize, usize)>,
+    glyf: Option<(usize, usize)>,
+    char_glyphs: BTreeMap<char, u16>,
+    glyph_chars: BTreeMap<u16, char>,
+}
+
+/// Name IDs of the `name` table.
+const FAMILY_NAME: u16 = 1;
+const FULL_NAME: u16 = 4;
+const TYPOGRAPHIC_FAMILY_NAME: u16 = 16;
+
+/// Nesting limit of composite glyphs, which reference other glyphs.
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+impl Font {
+    /// Parses the face of `data` named `facename`. Font collections fall
+    /// back to their first face when no face carries the name.
+    pub fn parse(data: Vec<u8>, facename: &str) -> Option<Self> {
+        let faces = face_offsets(&data);
+        let face = faces
+            .iter()
+            .copied()
+            .find(|&face| {
+                names_of(&data, face, &[
+                    FAMILY_NAME,
+                    FULL_NAME,
+                    TYPOGRAPHIC_FAMILY_NAME,
+                ])
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(facename))
+            })
+            .or_else(|| faces.first().copied())?;
+
+        let head = find_table(&data, face, b"head")?;
+        let hhea = find_table(&data, face, b"hhea")?;
+        let units_per_em = read_u16(&data, head.0 + 18)?;
+
+        if units_per_em == 0 {
+            return None;
+        }
+
+        let char_glyphs = find_table(&data, face, b"cmap")
+            .map(|cmap| parse_cmap(&data, cmap))
+            .unwrap_or_default();
+        let mut glyph_chars = BTreeMap::new();
+
+        for (&c, &glyph) in &char_glyphs {
+            glyph_chars.entry(glyph).or_insert(c);
+        }
+
+        Some(Self {
+            units_per_em,
+            ascender: read_i16(&data, hhea.0 + 4)?,
+            descender: read_i16(&data, hhea.0 + 6)?,
+            index_to_loc_format: read_i16(&data, head.0 + 50)?,
+            number_of_h_metrics: read_u16(&data, hhea.0 + 34)?,
+            hmtx: find_table(&data, face, b"hmtx"),
+            loca: find_table(&data, face, b"loca"),
+            glyf: find_table(&data, face, b"glyf"),
+            char_glyphs,
+            glyph_chars,
+            data,
+        })
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    /// Distance from the baseline to the top of the em box, in font units.
+    pub fn ascender(&self) -> i16 {
+        self.ascender
+    }
+
+    /// Distance from the baseline to the bottom of the em box, in font
+    /// units. Usually negative.
+    pub fn descender(&self) -> i16 {
+        self.descender
+    }
+
+    /// Returns the character the `cmap` table maps to `glyph`. When several
+    /// characters share the glyph, the lowest code point wins.
+    pub fn char_for_glyph(&self, glyph: u16) -> Option<char> {
+        self.glyph_chars.get(&glyph).copied()
+    }
+
+    /// Returns the glyph the `cmap` table maps `c` to.
+    pub fn glyph_for_char(&self, c: char) -> Option<u16> {
+        self.char_glyphs.get(&c).copied()
+    }
+
+    /// Returns the advance width of `glyph` in font units.
+    pub fn advance_width(&self, glyph: u16) -> u16 {
+        let Some((start, _)) = self.hmtx else {
+            return 0;
+        };
+
+        // Glyphs past the last long metric share its advance width.
+        let index =
+            core::cmp::min(glyph, self.number_of_h_metrics.saturating_sub(1));
+
+        read_u16(&self.data, start + usize::from(index) * 4).unwrap_or(0)
+    }
+
+    /// Returns the outline of `glyph`, or `None` when the face has no
+    /// TrueType outlines (such as CFF based OpenType fonts) or the glyph
+    /// data is malformed. Glyphs without contours yield an empty outline.
+    pub fn outline(&self, glyph: u16) -> Option<Vec<OutlineCommand>> {
+        let mut commands = vec![];
+
+        self.append_outline(
+            glyph,
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            0,
+            &mut commands,
+        )?;
+
+        Some(commands)
+    }
+
+    fn glyph_range(&self, glyph: u16) -> Option<(usize, usize)> {
+        let (loca, loca_end) = self.loca?;
+        let (glyf, glyf_end) = self.glyf?;
+        let index = usize::from(glyph);
+        let entry_size = if self.index_to_loc_format == 0 { 2 } else { 4 };
+
+        if loca + (index + 2) * entry_size > loca_end {
+            return None;
+        }
+
+        let (start, end) = if self.index_to_loc_format == 0 {
+            (
+                usize::from(read_u16(&self.data, loca + index * 2)?) * 2,
+                usize::from(read_u16(&self.data, loca + index * 2 + 2)?) * 2,
+            )
+        } else {
+            (
+                read_u32(&self.data, loca + index * 4)? as usize,
+                read_u32(&self.data, loca + index * 4 + 4)? as usize,
+            )
+        };
+
+        if start > end || glyf + end > glyf_end {
+            return None;
+        }
+
+        Some((glyf + start, glyf + end))
+    }
+
+    /// Appends the outline of `glyph` mapped through the affine transform
+    /// `[m11, m12, m21, m22, dx, dy]`, laid out like XForm.
+    fn append_outline(
+        &self,
+        glyph: u16,
+        transform: [f32; 6],
+        depth: usize,
+        commands: &mut Vec<OutlineCommand>,
+    ) -> Option<()> {
+        let (start, end) = self.glyph_range(glyph)?;
+
+        if start == end {
+            return Some(());
+        }
+
+        let data = &self.data[..end];
+        let number_of_contours = read_i16(data, start)?;
+
+        if number_of_contours < 0 {
+            if depth >= MAX_COMPONENT_DEPTH {
+                return None;
+            }
+
+            return self.append_composite(
+                start + 10,
+                transform,
+                depth,
+                commands,
+            );
+        }
+
+        let contours = usize::from(number_of_contours.unsigned_abs());
+        let mut end_points = Vec::with_capacity(contours);
+
+        for i in 0..contours {
+            end_points.push(usize::from(read_u16(data, start + 10 + i * 2)?));
+        }
+
+        let Some(&last) = end_points.last() else {
+            return Some(());
+        };
+        let point_count = last + 1;
+        let instruction_length =
+            usize::from(read_u16(data, start + 10 + contours * 2)?);
+        let mut offset = start + 12 + contours * 2 + instruction_length;
+
+        let mut flags = Vec::with_capacity(point_count);
+
+        while flags.len() < point_count {
+            let flag = *data.get(offset)?;
+            offset += 1;
+            flags.push(flag);
+
+            if flag & 0x08 != 0 {
+                let repeat = *data.get(offset)?;
+                offset += 1;
+
+                for _ in 0..repeat {
+                    flags.push(flag);
+                }
+            }
+        }
+
+        flags.truncate(point_count);
+
+        let xs = read_coordinates(data, &mut offset, &flags, 0x02, 0x10)?;
+        let ys = read_coordinates(data, &mut offset, &flags, 0x04, 0x20)?;
+        let [m11, m12, m21, m22, dx, dy] = transform;
+        let points = flags
+            .iter()
+            .zip(xs.iter().zip(ys.iter()))
+            .map(|(flag, (&x, &y))| {
+                let (x, y) = (f32::from(x), f32::from(y));
+
+                (
+                    m11 * x + m21 * y + dx,
+                    m12 * x + m22 * y + dy,
+                    flag & 0x01 != 0,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut first = 0;
+
+        for end_point in end_points {
+            if end_point < first || end_point >= points.len() {
+                return None;
+            }
+
+            contour_commands(&points[first..=end_point], commands);
+            first = end_point + 1;
+        }
+
+        Some(())
+    }
+
+    fn append_composite(
+        &self,
+        mut offset: usize,
+        transform: [f32; 6],
+        depth: usize,
+        commands: &mut Vec<OutlineCommand>,
+    ) -> Option<()> {
+        const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+        const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+        const WE_HAVE_A_SCALE: u16 = 0x0008;
+        const MORE_COMPONENTS: u16 = 0x0020;
+        const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+        const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+        loop {
+            let flags = read_u16(&self.data, offset)?;
+            let glyph = read_u16(&self.data, offset + 2)?;
+            offset += 4;
+
+            let (arg1, arg2) = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                offset += 4;
+                (
+                    f32::from(read_i16(&self.data, offset - 4)?),
+                    f32::from(read_i16(&self.data, offset - 2)?),
+                )
+            } else {
+                offset += 2;
+                (
+                    f32::from(*self.data.get(offset - 2)? as i8),
+                    f32::from(*self.data.get(offset - 1)? as i8),
+                )
+            };
+
+            let mut component = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+            if flags & WE_HAVE_A_SCALE != 0 {
+                let scale = read_f2dot14(&self.data, offset)?;
+                offset += 2;
+                component[0] = scale;
+                component[3] = scale;
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                component[0] = read_f2dot14(&self.data, offset)?;
+                component[3] = read_f2dot14(&self.data, offset + 2)?;
+                offset += 4;
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                for (i, v) in component.iter_mut().take(4).enumerate() {
+                    *v = read_f2dot14(&self.data, offset + i * 2)?;
+                }
+                offset += 8;
+            }
+
+            // Components positioned by matching points are placed at the
+            // origin; point matching needs the hinted outline.
+            if flags & ARGS_ARE_XY_VALUES != 0 {
+                component[4] = arg1;
+                component[5] = arg2;
+            }
+
+            self.append_outline(
+                glyph,
+                concat_transform(component, transform),
+                depth + 1,
+                commands,
+            )?;
+
+            if flags & MORE_COMPONENTS == 0 {
+                return Some(());
+            }
+        }
+    }
+}
+
+/// Returns the family names of every face in `data`, as recorded in their
+/// `name` tables. Every style of a family shares these names.
+pub fn font_family_names(data: &[u8]) -> Vec<String> {
+    face_offsets(data)
+        .into_iter()
+        .flat_map(|face| {
+            names_of(data, face, &[FAMILY_NAME, TYPOGRAPHIC_FAMILY_NAME])
+        })
+        .collect()
+}
+
+/// Returns the full names of every face in `data`, as recorded in their
+/// `name` tables. The full name of a regular face is its family name.
+pub fn font_full_names(data: &[u8]) -> Vec<String> {
+    face_offsets(data)
+        .into_iter()
+        .flat_map(|face| names_of(data, face, &[FULL_NAME]))
+        .collect()
+}
+
+fn face_offsets(data: &[u8]) -> Vec<usize> {
+    if data.get(0..4) != Some(b"ttcf".as_slice()) {
+        return vec![0];
+    }
+
+    let count = read_u32(data, 8).unwrap_or(0) as usize;
+
+    (0..count)
+        .map_while(|i| read_u32(data, 12 + i * 4).map(|v| v as usize))
+        .collect()
+}
+
+/// Returns the `(start, end)` byte range of the table `tag` of the face at
+/// `face`.
+fn find_table(data: &[u8], face: usize, tag: &[u8]) -> Option<(usize, usize)> {
+    let count = usize::from(read_u16(data, face + 4)?);
+
+    for i in 0..count {
+        let record = face + 12 + i * 16;
+
+        if data.get(record..record + 4)? != tag {
+            continue;
+        }
+
+        let start = read_u32(data, record + 8)? as usize;
+        let end = start.checked_add(read_u32(data, record + 12)? as usize)?;
+
+        return (end <= data.len()).then_some((start, end));
+    }
+
+    None
+}
+
+fn names_of(data: &[u8], face: usize, name_ids: &[u16]) -> Vec<String> {
+    let Some((start, end)) = find_table(data, face, b"name") else {
+        return vec![];
+    };
+    let data = &data[..end];
+    let count = usize::from(read_u16(data, start + 2).unwrap_or(0));
+    let storage = start + usize::from(read_u16(data, start + 4).unwrap_or(0));
+    let mut names: Vec<String> = vec![];
+
+    for i in 0..count {
+        let record = start + 6 + i * 12;
+        let (
+            Some(platform),
+            Some(encoding),
+            Some(name_id),
+            Some(length),
+            Some(offset),
+        ) = (
+            read_u16(data, record),
+            read_u16(data, record + 2),
+            read_u16(data, record + 6),
+            read_u16(data, record + 8),
+            read_u16(data, record + 10),
+        )
+        else {
+            break;
+        };
+
+        if !name_ids.contains(&name_id) {
+            continue;
+        }
+
+        let offset = storage + usize::from(offset);
+        let Some(bytes) = data.get(offset..offset + usize::from(length)) else {
+            continue;
+        };
+
+        let name = match (platform, encoding) {
+            (0 | 3, _) => char::decode_utf16(
+                bytes.chunks_exact(2).map(|v| u16::from_be_bytes([v[0], v[1]])),
+            )
+            .collect::<Result<String, _>>()
+            .ok(),
+            // Mac Roman names of the fonts in use are plain ASCII.
+            (1, 0) => Some(bytes.iter().map(|&b| char::from(b)).collect()),
+            _ => None,
+        };
+
+        if let Some(name) = name
+            && !name.is_empty()
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+/// Builds the character to glyph map from the preferred Unicode subtable of
+/// the `cmap` table.
+fn parse_cmap(
+    data: &[u8],
+    (start, end): (usize, usize),
+) -> BTreeMap<char, u16> {
+    // (platformID, encodingID) in order of preference. The Windows Symbol
+    // encoding places the glyphs at U+F020..U+F0FF.
+    const PREFERENCE: [(u16, u16); 7] =
+        [(3, 10), (0, 6), (0, 4), (3, 1), (0, 3), (0, 0), (3, 0)];
+
+    let data = &data[..end];
+    let count = usize::from(read_u16(data, start + 2).unwrap_or(0));
+    let mut subtables = vec![];
+
+    for i in 0..count {
+        let record = start + 4 + i * 8;
+        let (Some(platform), Some(encoding), Some(offset)) = (
+            read_u16(data, record),
+            read_u16(data, record + 2),
+            read_u32(data, record + 4),
+        ) else {
+            break;
+        };
+
+        if let Some(rank) =
+            PREFERENCE.iter().position(|v| *v == (platform, encoding))
+        {
+            subtables.push((rank, encoding, start + offset as usize));
+        }
+    }
+
+    subtables.sort_unstable();
+
+    for (_, encoding, offset) in subtables {
+        let mut char_glyphs = BTreeMap::new();
+        let mut insert = |code: u32, glyph: u32| {
+            let code = if encoding == 0 && (0xF020..=0xF0FF).contains(&code) {
+                code - 0xF000
+            } else {
+                code
+            };
+
+            if let (Ok(glyph @ 1..), Some(c)) =
+                (u16::try_from(glyph), char::from_u32(code))
+            {
+                char_glyphs.entry(c).or_insert(glyph);
+            }
+        };
+
+        let parsed = match read_u16(data, offset) {
+            Some(4) => parse_cmap_format4(data, offset, &mut insert),
+            Some(12) => parse_cmap_format12(data, offset, &mut insert),
+            _ => None,
+        };
+
+        if parsed.is_some() && !char_glyphs.is_empty() {
+            return char_glyphs;
+        }
+    }
+
+    BTreeMap::new()
+}
+
+fn parse_cmap_format4(
+    data: &[u8],
+    offset: usize,
+    insert: &mut impl FnMut(u32, u32),
+) -> Option<()> {
+    let seg_count_x2 = usize::from(read_u16(data, offset + 6)?);
+    let end_codes = offset + 14;
+    let start_codes = end_codes + seg_count_x2 + 2;
+    let id_deltas = start_codes + seg_count_x2;
+    let id_range_offsets = id_deltas + seg_count_x2;
+
+    for segment in (0..seg_count_x2).step_by(2) {
+        let end_code = read_u16(data, end_codes + segment)?;
+        let start_code = read_u16(data, start_codes + segment)?;
+        let id_delta = read_u16(data, id_deltas + segment)?;
+        let id_range_offset = read_u16(data, id_range_offsets + segment)?;
+
+        for code in start_code..=end_code {
+            if code == 0xFFFF {
+                break;
+            }
+
+            let glyph = if id_range_offset == 0 {
+                code.wrapping_add(id_delta)
+            } else {
+                let address = id_range_offsets
+                    + segment
+                    + usize::from(id_range_offset)
+                    + usize::from(code - start_code) * 2;
+
+                match read_u16(data, address)? {
+                    0 => continue,
+                    glyph => glyph.wrapping_add(id_delta),
+                }
+            };
+
+            insert(u32::from(code), u32::from(glyph));
+        }
+    }
+
+    Some(())
+}
+
+fn parse_cmap_format12(
+    data: &[u8],
+    offset: usize,
+    insert: &mut impl FnMut(u32, u32),
+) -> Option<()> {
+    let groups = read_u32(data, offset + 12)? as usize;
+
+    for i in 0..groups {
+        let group = offset + 16 + i * 12;
+        let start_code = read_u32(data, group)?;
+        let end_code = read_u32(data, group + 4)?;
+        let start_glyph = read_u32(data, group + 8)?;
+
+        // Glyph indices are 16-bit, which bounds the useful part of a
+        // group regardless of the code range it claims.
+        let end_code = core::cmp::min(
+            end_code,
+            start_code.saturating_add(0xFFFF_u32.saturating_sub(start_glyph)),
+        );
+
+        for code in start_code..=end_code {
+            insert(code, start_glyph + (code - start_code));
+        }
+    }
+
+    Some(())
+}
+
+fn read_coordinates(
+    data: &[u8],
+    offset: &mut usize,
+    flags: &[u8],
+    short: u8,
+    same_or_positive: u8,
+) -> Option<Vec<i16>> {
+    let mut value = 0_i16;
+    let mut values = Vec::with_capacity(flags.len());
+
+    for flag in flags {
+        if flag & short != 0 {
+            let delta = i16::from(*data.get(*offset)?);
+            *offset += 1;
+            value = if flag & same_or_positive != 0 {
+                value.wrapping_add(delta)
+            } else {
+                value.wrapping_sub(delta)
+            };
+        } else if flag & same_or_positive == 0 {
+            value = value.wrapping_add(read_i16(data, *offset)?);
+            *offset += 2;
+        }
+
+        values.push(value);
+    }
+
+    Some(values)
+}
+
+/// Converts a TrueType contour of `(x, y, on_curve)` points to drawing
+/// commands. Two consecutive off-curve points imply an on-curve point
+/// halfway between them.
+fn contour_commands(
+    points: &[(f32, f32, bool)],
+    commands: &mut Vec<OutlineCommand>,
+) {
+    let (Some(&first), Some(&last)) = (points.first(), points.last()) else {
+        return;
+    };
+    let midpoint = |a: (f32, f32), b: (f32, f32)| {
+        (f32::midpoint(a.0, b.0), f32::midpoint(a.1, b.1))
+    };
+
+    let (start, rest) = if first.2 {
+        ((first.0, first.1), &points[1..])
+    } else if last.2 {
+        ((last.0, last.1), &points[..points.len() - 1])
+    } else {
+        (midpoint((first.0, first.1), (last.0, last.1)), points)
+    };
+
+    commands.push(OutlineCommand::MoveTo { x: start.0, y: start.1 });
+
+    let mut control: Option<(f32, f32)> = None;
+
+    for &(x, y, on_curve) in rest {
+        match (on_curve, control) {
+            (true, Some((cx, cy))) => {
+                commands.push(OutlineCommand::QuadTo { cx, cy, x, y });
+                control = None;
+            }
+            (true, None) => commands.push(OutlineCommand::LineTo { x, y }),
+            (false, Some((cx, cy))) => {
+                let (mx, my) = midpoint((cx, cy), (x, y));
+
+                commands.push(OutlineCommand::QuadTo { cx, cy, x: mx, y: my });
+                control = Some((x, y));
+            }
+            (false, None) => control = Some((x, y)),
+        }
+    }
+
+    if let Some((cx, cy)) = control {
+        commands.push(OutlineCommand::QuadTo {
+            cx,
+            cy,
+            x: start.0,
+            y: start.1,
+        });
+    }
+
+    commands.push(OutlineCommand::Close);
+}
+
+/// Returns the transform applying `inner` and then `outer`.
+fn concat_transform(inner: [f32; 6], outer: [f32; 6]) -> [f32; 6] {
+    let [a1, b1, c1, d1, e1, f1] = inner;
+    let [a2, b2, c2, d2, e2, f2] = outer;
+
+    [
+        a1 * a2 + b1 * c2,
+        a1 * b2 + b1 * d2,
+        c1 * a2 + d1 * c2,
+        c1 * b2 + d1 * d2,
+        e1 * a2 + f1 * c2 + e2,
+        e1 * b2 + f1 * d2 + f2,
+    ]
+}
+
+#[inline]
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let v = data.get(offset..offset.checked_add(2)?)?;
+
+    Some(u16::from_be_bytes([v[0], v[1]]))
+}
+
+#[inline]
+fn read_i16(data: &[u8], offset: usize) -> Option<i16> {
+    read_u16(data, offset).map(|v| v as i16)
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let v = data.get(offset..offset.checked_add(4)?)?;
+
+    Some(u32::from_be_bytes([v[0], v[1], v[2], v[3]]))
+}
+
+#[inline]
+fn read_f2dot14(data: &[u8], offset: usize) -> Option<f32> {
+    read_i16(data, offset).map(|v| f32::from(v) / 16384.0)
+}
+
+/// Loads fonts from the TTF, OTF and TTC files of a local directory and its
+/// subdirectories.
+///
+/// The files are indexed by the family and full names recorded in their
+/// `name` tables, and by their file stem, when the provider is created.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct DirectoryFontProvider {
+    // Lowercase name to font file. Names matching the full name of a face
+    // take precedence over names shared by every style of a family, so a
+    // family name resolves to the regular face.
+    faces: BTreeMap<String, (bool, std::path::PathBuf)>,
+}
+
+#[cfg(feature = "std")]
+impl DirectoryFontProvider {
+    pub fn new(
+        directory: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<Self> {
+        let mut provider = Self::default();
+
+        provider.index(directory.as_ref())?;
+
+        Ok(provider)
+    }
+
+    fn index(&mut self, directory: &std::path::Path) -> std::io::Result<()> {
+        let mut entries = std::fs::read_dir(directory)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Sorted so that the face a name resolves to does not depend on the
+        // order the file system lists the directory in.
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                self.index(&path)?;
+                continue;
+            }
+
+            let is_font =
+                path.extension().and_then(|v| v.to_str()).is_some_and(|v| {
+                    ["ttf", "otf", "ttc"]
+                        .iter()
+                        .any(|ext| v.eq_ignore_ascii_case(ext))
+                });
+
+            if !is_font {
+                continue;
+            }
+
+            let Ok(data) = std::fs::read(&path) else {
+                warn!(path = %path.display(), "failed to read font file");
+                continue;
+            };
+
+            let stem = path.file_stem().and_then(|v| v.to_str());
+
+            for name in
+                font_full_names(&data).iter().map(String::as_str).chain(stem)
+            {
+                self.register(name, &path, true);
+            }
+
+            for name in font_family_names(&data) {
+                self.register(&name, &path, false);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn register(&mut self, name: &str, path: &std::path::Path, full: bool) {
+        let face = self
+            .faces
+            .entry(name.to_lowercase())
+            .or_insert_with(|| (full, path.to_path_buf()));
+
+        if full && !face.0 {
+            *face = (full, path.to_path_buf());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FontProvider for DirectoryFontProvider {
+    fn load(&self, facename: &str) -> Option<Vec<u8>> {
+        let (_, path) = self.faces.get(&facename.to_lowercase())?;
+
+        std::fs::read(path)
+            .inspect_err(|err| {
+                warn!(path = %path.display(), %err, "failed to read font file");
+            })
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contour_with_implied_on_curve_points() {
+        let mut commands = vec![];
+
+        // An off-curve start with no on-curve point at all: every on-curve
+        // point is implied.
+        contour_commands(
+            &[
+                (0.0, 0.0, false),
+                (10.0, 0.0, false),
+                (10.0, 10.0, false),
+                (0.0, 10.0, false),
+            ],
+            &mut commands,
+        );
+
+        assert_eq!(commands, vec![
+            OutlineCommand::MoveTo { x: 0.0, y: 5.0 },
+            OutlineCommand::QuadTo { cx: 0.0, cy: 0.0, x: 5.0, y: 0.0 },
+            OutlineCommand::QuadTo { cx: 10.0, cy: 0.0, x: 10.0, y: 5.0 },
+            OutlineCommand::QuadTo { cx: 10.0, cy: 10.0, x: 5.0, y: 10.0 },
+            OutlineCommand::QuadTo { cx: 0.0, cy: 10.0, x: 0.0, y: 5.0 },
+            OutlineCommand::Close,
+        ]);
+    }
+
+    #[test]
+    fn contour_starting_off_curve_begins_at_last_point() {
+        let mut commands = vec![];
+
+        contour_commands(
+            &[(5.0, 10.0, false), (10.0, 0.0, true), (0.0, 0.0, true)],
+            &mut commands,
+        );
+
+        assert_eq!(commands, vec![
+            OutlineCommand::MoveTo { x: 0.0, y: 0.0 },
+            OutlineCommand::QuadTo { cx: 5.0, cy: 10.0, x: 10.0, y: 0.0 },
+            OutlineCommand::Close,
+        ]);
+    }
+
+    #[test]
+    fn composite_transform_applies_inner_first() {
+        // Scale by two, then translate by (3, 4).
+        let v = concat_transform([2.0, 0.0, 0.0, 2.0, 0.0, 0.0], [
+            1.0, 0.0, 0.0, 1.0, 3.0, 4.0,
+        ]);
+
+        assert!(
+            v.iter()
+                .zip([2.0, 0.0, 0.0, 2.0, 3.0, 4.0])
+                .all(|(a, b)| (a - b).abs() < f32::EPSILON),
+            "{v:?}",
+        );
+    }
+}
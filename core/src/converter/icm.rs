@@ -0,0 +1,441 @@
+//! Image Color Management (ICM) of the playback device context.
+//!
+//! While ICM is on, colors are specified in the selected logical color space
+//! or the color profile set by EMR_SETICMPROFILEA/W. A [`ColorTransform`]
+//! converts them to sRGB, the color space of SVG, from a calibrated RGB
+//! logical color space or an ICC v2/v4 matrix/TRC profile.
+
+use crate::imports::*;
+
+/// Converts XYZ relative to the D50 white point of the ICC profile
+/// connection space to linear sRGB, including the Bradford adaptation to
+/// D65.
+const XYZ_D50_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [3.1338561, -1.6168667, -0.4906146],
+    [-0.9787684, 1.9161415, 0.0334540],
+    [0.0719453, -0.2289914, 1.4052427],
+];
+
+/// A conversion of device RGB colors to sRGB: tone curves to linear RGB,
+/// then a matrix to linear sRGB.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorTransform {
+    curves: [ToneCurve; 3],
+    matrix: [[f64; 3]; 3],
+}
+
+/// A tone reproduction curve from a device value to linear light, both in
+/// `0.0..=1.0`.
+#[derive(Clone, Debug, PartialEq)]
+enum ToneCurve {
+    /// `Y = X ^ g`
+    Gamma(f64),
+    /// Samples spread evenly over the input range, interpolated linearly.
+    Table(Vec<f64>),
+    /// The ICC parametric function `Y = (aX + b) ^ g + e` for `X >= d` and
+    /// `Y = cX + f` otherwise, to which every parametric type is reduced.
+    Parametric { g: f64, a: f64, b: f64, c: f64, d: f64, e: f64, f: f64 },
+}
+
+impl ToneCurve {
+    fn apply(&self, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+
+        match self {
+            Self::Gamma(g) => x.powf(*g),
+            Self::Table(samples) => {
+                let Some(last) = samples.len().checked_sub(1) else {
+                    return x;
+                };
+                let position = x * last as f64;
+                let index = (position.floor() as usize).min(last);
+                let next = (index + 1).min(last);
+                let t = position - index as f64;
+
+                samples[index] + (samples[next] - samples[index]) * t
+            }
+            Self::Parametric { g, a, b, c, d, e, f } => {
+                if x >= *d {
+                    (a * x + b).max(0.0).powf(*g) + e
+                } else {
+                    c * x + f
+                }
+            }
+        }
+    }
+}
+
+impl ColorTransform {
+    /// Returns the transform of a calibrated RGB color space from the CIE
+    /// XYZ values of its red, green and blue endpoints and the gamma of each
+    /// channel. Endpoints that are all zero stand for the sRGB primaries, so
+    /// only the gamma is applied.
+    pub fn calibrated_rgb(endpoints: [[f64; 3]; 3], gamma: [f64; 3]) -> Self {
+        let curves =
+            gamma.map(|g| ToneCurve::Gamma(if g > 0.0 { g } else { 1.0 }));
+
+        if endpoints.iter().flatten().all(|v| *v == 0.0) {
+            return Self { curves, matrix: IDENTITY };
+        }
+
+        Self { curves, matrix: to_linear_srgb(endpoints) }
+    }
+
+    /// Returns the transform of a logical color space, or `None` for sRGB
+    /// and the Windows default color space, which need no conversion.
+    pub fn from_log_color_space(
+        lcs: &wmf_core::parser::LogColorSpace,
+    ) -> Option<Self> {
+        logical_color_space(lcs.color_space_type, &lcs.endpoints, [
+            lcs.gamma_red,
+            lcs.gamma_green,
+            lcs.gamma_blue,
+        ])
+    }
+
+    /// Returns the transform of a logical color space created with
+    /// EMR_CREATECOLORSPACEW, preferring the embedded color profile if any.
+    pub fn from_log_color_space_w(
+        lcs: &wmf_core::parser::LogColorSpaceW,
+        profile: &[u8],
+    ) -> Option<Self> {
+        if !profile.is_empty() {
+            if let Some(v) = Self::from_icc_profile(profile) {
+                return Some(v);
+            }
+
+            info!("embedded color profile is not supported");
+        }
+
+        logical_color_space(lcs.color_space_type, &lcs.endpoints, [
+            lcs.gamma_red,
+            lcs.gamma_green,
+            lcs.gamma_blue,
+        ])
+    }
+
+    /// Parses an RGB matrix/TRC ICC profile: the rXYZ, gXYZ and bXYZ
+    /// colorant tags and the rTRC, gTRC and bTRC tone curves. Returns `None`
+    /// for other profiles, such as ones built on lookup tables only.
+    pub fn from_icc_profile(data: &[u8]) -> Option<Self> {
+        if data.len() < 132 || data.get(36..40)? != b"acsp" {
+            return None;
+        }
+
+        if data.get(16..20)? != b"RGB " {
+            info!("only RGB color profiles are supported");
+            return None;
+        }
+
+        // The tag table cannot hold more entries than the profile has room
+        // for, whatever count it states.
+        let tag_count =
+            (read_u32(data, 128)? as usize).min((data.len() - 132) / 12);
+        let tag = |signature: &[u8; 4]| {
+            (0..tag_count).find_map(|i| {
+                let entry = i.checked_mul(12)?.checked_add(132)?;
+                if data.get(entry..entry + 4)? != signature {
+                    return None;
+                }
+
+                let offset = read_u32(data, entry + 4)? as usize;
+                let size = read_u32(data, entry + 8)? as usize;
+
+                data.get(offset..offset.checked_add(size)?)
+            })
+        };
+
+        let colorant = |signature| parse_xyz(tag(signature)?);
+        let endpoints =
+            [colorant(b"rXYZ")?, colorant(b"gXYZ")?, colorant(b"bXYZ")?];
+        let curve = |signature| parse_curve(tag(signature)?);
+        let curves = [curve(b"rTRC")?, curve(b"gTRC")?, curve(b"bTRC")?];
+
+        Some(Self { curves, matrix: to_linear_srgb(endpoints) })
+    }
+
+    pub fn to_srgb(
+        &self,
+        color: &wmf_core::parser::ColorRef,
+    ) -> wmf_core::parser::ColorRef {
+        let linear = [color.red, color.green, color.blue]
+            .iter()
+            .zip(&self.curves)
+            .map(|(v, curve)| curve.apply(f64::from(*v) / 255.0))
+            .collect::<Vec<_>>();
+        let [red, green, blue] = self.matrix.map(|row| {
+            let v =
+                row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+
+            (encode_srgb(v.clamp(0.0, 1.0)) * 255.0).round() as u8
+        });
+
+        wmf_core::parser::ColorRef { red, green, blue, reserved: 0 }
+    }
+}
+
+const IDENTITY: [[f64; 3]; 3] =
+    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+fn logical_color_space(
+    color_space_type: wmf_core::parser::LogicalColorSpace,
+    endpoints: &wmf_core::parser::CIEXYZTriple,
+    gamma: [u32; 3],
+) -> Option<ColorTransform> {
+    if color_space_type
+        != wmf_core::parser::LogicalColorSpace::LCS_CALIBRATED_RGB
+    {
+        return None;
+    }
+
+    // Endpoints are FXPT2DOT30 and gammas FXPT16DOT16 fixed point values.
+    let xyz = |v: &wmf_core::parser::CIEXYZ| {
+        [v.ciexyz_x, v.ciexyz_y, v.ciexyz_z]
+            .map(|v| f64::from(v) / f64::from(1_u32 << 30))
+    };
+
+    Some(ColorTransform::calibrated_rgb(
+        [
+            xyz(&endpoints.ciexyz_red),
+            xyz(&endpoints.ciexyz_green),
+            xyz(&endpoints.ciexyz_blue),
+        ],
+        gamma.map(|v| f64::from(v) / 65536.0),
+    ))
+}
+
+/// Returns the matrix from linear device RGB to linear sRGB for primaries
+/// with the given XYZ values relative to D50.
+fn to_linear_srgb(endpoints: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    // The endpoints are the columns of the device-to-XYZ matrix.
+    let to_xyz = [0, 1, 2].map(|row| endpoints.map(|xyz| xyz[row]));
+
+    XYZ_D50_TO_LINEAR_SRGB.map(|row| {
+        [0, 1, 2].map(|column| {
+            (0..3).map(|k| row[k] * to_xyz[k][column]).sum::<f64>()
+        })
+    })
+}
+
+fn encode_srgb(v: f64) -> f64 {
+    if v <= 0.003_130_8 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Reads an s15Fixed16Number.
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Option<f64> {
+    Some(f64::from(read_u32(data, offset)? as i32) / 65536.0)
+}
+
+/// Parses an XYZType tag holding a single XYZ value.
+fn parse_xyz(data: &[u8]) -> Option<[f64; 3]> {
+    if data.get(0..4)? != b"XYZ " {
+        return None;
+    }
+
+    Some([
+        read_s15_fixed16(data, 8)?,
+        read_s15_fixed16(data, 12)?,
+        read_s15_fixed16(data, 16)?,
+    ])
+}
+
+/// Parses a curveType or parametricCurveType tag.
+fn parse_curve(data: &[u8]) -> Option<ToneCurve> {
+    match data.get(0..4)? {
+        b"curv" => {
+            let count = read_u32(data, 8)? as usize;
+
+            match count {
+                0 => Some(ToneCurve::Gamma(1.0)),
+                // A single entry is a u8Fixed8Number gamma.
+                1 => Some(ToneCurve::Gamma(
+                    f64::from(read_u16(data, 12)?) / 256.0,
+                )),
+                _ => (0..count)
+                    .map(|i| {
+                        read_u16(data, 12 + i * 2)
+                            .map(|v| f64::from(v) / 65535.0)
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(ToneCurve::Table),
+            }
+        }
+        b"para" => {
+            let function = read_u16(data, 8)?;
+            let count = match function {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => return None,
+            };
+            let p = (0..count)
+                .map(|i| read_s15_fixed16(data, 12 + i * 4))
+                .collect::<Option<Vec<_>>>()?;
+            let g = p[0];
+
+            Some(match function {
+                0 => ToneCurve::Gamma(g),
+                1 => ToneCurve::Parametric {
+                    g,
+                    a: p[1],
+                    b: p[2],
+                    c: 0.0,
+                    d: -p[2] / p[1],
+                    e: 0.0,
+                    f: 0.0,
+                },
+                2 => ToneCurve::Parametric {
+                    g,
+                    a: p[1],
+                    b: p[2],
+                    c: 0.0,
+                    d: -p[2] / p[1],
+                    e: p[3],
+                    f: p[3],
+                },
+                3 => ToneCurve::Parametric {
+                    g,
+                    a: p[1],
+                    b: p[2],
+                    c: p[3],
+                    d: p[4],
+                    e: 0.0,
+                    f: 0.0,
+                },
+                _ => ToneCurve::Parametric {
+                    g,
+                    a: p[1],
+                    b: p[2],
+                    c: p[3],
+                    d: p[4],
+                    e: p[5],
+                    f: p[6],
+                },
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The sRGB primaries relative to D50, as in the sRGB ICC profile.
+    const SRGB_D50: [[f64; 3]; 3] = [
+        [0.436_065_7, 0.222_493_2, 0.013_923_2],
+        [0.385_151_5, 0.716_887_0, 0.097_081_1],
+        [0.143_078_4, 0.060_609_5, 0.714_185_9],
+    ];
+
+    fn gray(v: u8) -> wmf_core::parser::ColorRef {
+        wmf_core::parser::ColorRef { red: v, green: v, blue: v, reserved: 0 }
+    }
+
+    fn icc_profile(curve: &[u8]) -> Vec<u8> {
+        let tags: [(&[u8; 4], Vec<u8>); 6] = [
+            (b"rXYZ", xyz_tag(SRGB_D50[0])),
+            (b"gXYZ", xyz_tag(SRGB_D50[1])),
+            (b"bXYZ", xyz_tag(SRGB_D50[2])),
+            (b"rTRC", curve.to_vec()),
+            (b"gTRC", curve.to_vec()),
+            (b"bTRC", curve.to_vec()),
+        ];
+        let mut header = vec![0_u8; 128];
+        header[16..20].copy_from_slice(b"RGB ");
+        header[20..24].copy_from_slice(b"XYZ ");
+        header[36..40].copy_from_slice(b"acsp");
+
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut body = vec![];
+        let mut offset = 128 + 4 + tags.len() * 12;
+
+        for (signature, data) in &tags {
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&(offset as u32).to_be_bytes());
+            table.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            body.extend_from_slice(data);
+            offset += data.len();
+        }
+
+        [header, table, body].concat()
+    }
+
+    fn xyz_tag(xyz: [f64; 3]) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for v in xyz {
+            tag.extend_from_slice(
+                &((v * 65536.0).round() as i32).to_be_bytes(),
+            );
+        }
+        tag
+    }
+
+    #[test]
+    fn calibrated_rgb_applies_gamma_and_primaries() {
+        let srgb = ColorTransform::calibrated_rgb(SRGB_D50, [2.2, 2.2, 2.2]);
+        // Gamma 2.2 is close to the sRGB curve.
+        let v = srgb.to_srgb(&gray(0x80)).red;
+        assert!((0x7E..=0x82).contains(&v), "{v:#X}");
+
+        // Linear light is brighter once encoded.
+        let linear =
+            ColorTransform::calibrated_rgb([[0.0; 3]; 3], [1.0, 1.0, 1.0]);
+        assert_eq!(linear.to_srgb(&gray(0x80)), gray(0xBC));
+        assert_eq!(linear.to_srgb(&gray(0xFF)), gray(0xFF));
+    }
+
+    #[test]
+    fn icc_profile_reads_colorants_and_tone_curves() {
+        // curveType with a single u8Fixed8Number gamma of 1.0.
+        let curve =
+            [b"curv".as_slice(), &[0; 4], &1_u32.to_be_bytes(), &[1, 0]]
+                .concat();
+        let transform = ColorTransform::from_icc_profile(&icc_profile(&curve))
+            .expect("profile");
+        let v = transform.to_srgb(&gray(0x80));
+        assert!((0xBB..=0xBD).contains(&v.green), "{v:?}");
+
+        // parametricCurveType function 0 with a gamma of 2.4.
+        let curve = [
+            b"para".as_slice(),
+            &[0; 4],
+            &[0, 0, 0, 0],
+            &((2.4 * 65536.0) as i32).to_be_bytes(),
+        ]
+        .concat();
+        let transform = ColorTransform::from_icc_profile(&icc_profile(&curve))
+            .expect("profile");
+        assert_eq!(transform.to_srgb(&gray(0)), gray(0));
+        assert_eq!(transform.to_srgb(&gray(0xFF)), gray(0xFF));
+
+        assert!(ColorTransform::from_icc_profile(&[0; 64]).is_none());
+    }
+
+    #[test]
+    fn icc_profile_bounds_tag_count_by_length() {
+        let curve =
+            [b"curv".as_slice(), &[0; 4], &1_u32.to_be_bytes(), &[1, 0]]
+                .concat();
+        let mut profile = icc_profile(&curve);
+
+        // The stated count is clamped to the entries the profile can hold,
+        // so the tags are still found.
+        profile[128..132].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(ColorTransform::from_icc_profile(&profile).is_some());
+
+        // Without the tags, the lookups stop at the end of the profile.
+        profile.truncate(132);
+        assert!(ColorTransform::from_icc_profile(&profile).is_none());
+    }
+}
@@ -0,0 +1,64 @@
+//! Storing the bitmaps of a conversion outside the output document.
+
+use crate::imports::*;
+
+/// Stores a bitmap drawn by a record and returns the URL the output refers
+/// to it by.
+pub trait ImageStore {
+    /// Stores `data`, an image of `media_type` such as `image/bmp`, and
+    /// returns its URL, or `None` to embed the image instead.
+    fn store(&self, media_type: &str, data: &[u8]) -> Option<String>;
+}
+
+impl<F> ImageStore for F
+where
+    F: Fn(&str, &[u8]) -> Option<String>,
+{
+    fn store(&self, media_type: &str, data: &[u8]) -> Option<String> {
+        self(media_type, data)
+    }
+}
+
+/// Writes the images into a directory as numbered files named after a
+/// prefix, e.g. `picture-1.bmp`, and refers to them by their file names.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct DirectoryImageStore {
+    directory: std::path::PathBuf,
+    prefix: String,
+    count: core::cell::Cell<usize>,
+}
+
+#[cfg(feature = "std")]
+impl DirectoryImageStore {
+    pub fn new(
+        directory: impl Into<std::path::PathBuf>,
+        prefix: impl Into<String>,
+    ) -> Self {
+        Self {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            count: core::cell::Cell::new(0),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ImageStore for DirectoryImageStore {
+    fn store(&self, media_type: &str, data: &[u8]) -> Option<String> {
+        let extension = media_type.strip_prefix("image/").unwrap_or("bin");
+        let count = self.count.get() + 1;
+        let name = format!("{}-{count}.{extension}", self.prefix);
+        let path = self.directory.join(&name);
+
+        self.count.set(count);
+
+        std::fs::write(&path, data)
+            .inspect_err(|err| {
+                warn!(path = %path.display(), %err, "failed to write image");
+            })
+            .ok()?;
+
+        Some(name)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::converter::ConvertError;
+
+/// A metafile [`EMFConverter`](crate::converter::EMFConverter) reads.
+///
+/// A borrowed `&[u8]` is played in place, with bitmaps borrowed rather
+/// than copied. A [`Stream`] is played as it is read, whatever the
+/// [`RecoveryPolicy`](crate::converter::RecoveryPolicy), and is never read
+/// into memory as a whole.
+pub trait Input: crate::Read {
+    /// Returns the first four bytes of the metafile without consuming
+    /// them, or fewer if the metafile is shorter.
+    fn peek(&mut self) -> Result<&[u8], ConvertError>;
+
+    /// Returns the unread part of the metafile if it is already in memory,
+    /// so its records can borrow their bitmaps from it.
+    fn as_slice(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+impl Input for &[u8] {
+    fn peek(&mut self) -> Result<&[u8], ConvertError> {
+        Ok(&self[..self.len().min(4)])
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+/// An [`Input`] reading the metafile from any [`Read`](crate::Read), such
+/// as a file or a socket. Short reads are retried until the reader reports
+/// the end of the input.
+#[derive(Debug)]
+pub struct Stream<R> {
+    inner: R,
+    // The bytes read ahead by `peek`, served before the rest of `inner`.
+    head: [u8; 4],
+    head_len: usize,
+    head_pos: usize,
+}
+
+impl<R: crate::Read> Stream<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, head: [0; 4], head_len: 0, head_pos: 0 }
+    }
+}
+
+impl<R: crate::Read> embedded_io::ErrorType for Stream<R> {
+    type Error = R::Error;
+}
+
+impl<R: crate::Read> embedded_io::Read for Stream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if self.head_pos < self.head_len {
+            let head = &self.head[self.head_pos..self.head_len];
+            let read = head.len().min(buf.len());
+
+            buf[..read].copy_from_slice(&head[..read]);
+            self.head_pos += read;
+
+            return Ok(read);
+        }
+
+        self.inner.read(buf)
+    }
+}
+
+impl<R: crate::Read> Input for Stream<R> {
+    fn peek(&mut self) -> Result<&[u8], ConvertError> {
+        while self.head_pos == 0 && self.head_len < self.head.len() {
+            let read = self
+                .inner
+                .read(&mut self.head[self.head_len..])
+                .map_err(io_error)?;
+
+            if read == 0 {
+                break;
+            }
+
+            self.head_len += read;
+        }
+
+        Ok(&self.head[self.head_pos..self.head_len])
+    }
+}
+
+fn io_error(err: impl core::fmt::Debug) -> ConvertError {
+    ConvertError::IoError { cause: format!("{err:?}") }
+}
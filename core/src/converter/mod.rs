@@ -1,7 +1,20 @@
+mod diagnostics;
+mod dib;
+mod font;
+mod icm;
+mod image_store;
+mod input;
+mod options;
+mod path;
 mod playback_device_context;
 mod player;
+mod raster;
+mod recovery;
 
-pub use self::player::*;
+pub use self::{
+    diagnostics::*, dib::*, font::*, icm::*, image_store::*, input::*,
+    options::*, path::*, player::*, raster::*, recovery::*,
+};
 use crate::{imports::*, parser::*};
 
 #[cfg(feature = "svg")]
@@ -20,6 +33,8 @@ pub enum ConvertError {
     WMFConvertError { source: wmf_core::converter::ConvertError },
     #[snafu(display("I/O error: {cause}"))]
     IoError { cause: String },
+    #[snafu(display("limit exceeded: {cause}"))]
+    LimitExceeded { cause: String },
 }
 
 impl From<ParseError> for ConvertError {
@@ -28,9 +43,20 @@ impl From<ParseError> for ConvertError {
     }
 }
 
+impl From<RecordError> for ConvertError {
+    fn from(err: RecordError) -> Self {
+        Self::ParseError { source: err.source }
+    }
+}
+
 impl From<crate::converter::PlayError> for ConvertError {
     fn from(source: crate::converter::PlayError) -> Self {
-        Self::PlayError { source }
+        match source {
+            crate::converter::PlayError::LimitExceeded { cause } => {
+                Self::LimitExceeded { cause }
+            }
+            source => Self::PlayError { source },
+        }
     }
 }
 
@@ -38,53 +64,71 @@ pub struct EMFConverter<B, P, WP> {
     buffer: B,
     player: P,
     wmf_player: WP,
+    recovery_policy: RecoveryPolicy,
+    limits: ResourceLimits,
 }
 
 impl<B, P, WP> EMFConverter<B, P, WP> {
+    /// Creates a converter of the metafile in `buffer`, an [`Input`]: a
+    /// borrowed `&[u8]`, or a reader wrapped in a [`Stream`].
     pub fn new(buffer: B, player: P, wmf_player: WP) -> Self {
-        Self { buffer, player, wmf_player }
+        Self {
+            buffer,
+            player,
+            wmf_player,
+            recovery_policy: RecoveryPolicy::default(),
+            limits: ResourceLimits::default(),
+        }
+    }
+
+    /// Sets the budgets of the conversion. The converter enforces the
+    /// number of records and the size of the generated output; the player
+    /// enforces the others, and the output size as it emits elements.
+    #[must_use]
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets how records that cannot be parsed or played are handled. The
+    /// default is [`RecoveryPolicy::Strict`].
+    #[must_use]
+    pub fn with_recovery_policy(mut self, policy: RecoveryPolicy) -> Self {
+        self.recovery_policy = policy;
+        self
     }
 }
 
 impl<B, P, WP> EMFConverter<B, P, WP>
 where
-    B: crate::Read,
+    B: Input,
     P: crate::converter::Player,
     WP: wmf_core::converter::Player,
 {
+    pub fn run(self) -> Result<Vec<u8>, ConvertError> {
+        self.run_with_diagnostics().map(|(output, _)| output)
+    }
+
+    /// Converts the metafile, returning the output together with what the
+    /// player could not play exactly and the records skipped, or stopped
+    /// at, under the recovery policy.
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = tracing::Level::TRACE,
         skip_all,
         err(level = tracing::Level::ERROR, Display),
     ))]
-    pub fn run(self) -> Result<Vec<u8>, ConvertError> {
-        use crate::parser::records::{read_field, read_with};
-
-        let Self { mut buffer, mut player, wmf_player } = self;
-
-        let buffer = {
-            let mut buf = vec![];
-
-            // read to end
-            loop {
-                let mut b = vec![0; 8192];
-                let read = buffer.read(&mut b).map_err(|err| {
-                    ConvertError::IoError { cause: format!("{err:?}") }
-                })?;
-
-                buf.extend(&b[..read]);
-
-                if read < 8192 {
-                    break;
-                }
-            }
-
-            buf
-        };
-
-        let mut b = &buffer[0..4];
-
-        match RecordType::parse(&mut b) {
+    pub fn run_with_diagnostics(
+        self,
+    ) -> Result<(Vec<u8>, Diagnostics), ConvertError> {
+        let Self {
+            mut buffer,
+            mut player,
+            wmf_player,
+            recovery_policy,
+            limits,
+        } = self;
+
+        match RecordType::parse(&mut buffer.peek()?) {
             Ok((RecordType::EMR_HEADER, _)) => {}
             Ok((record_type, _)) => {
                 return Err(ConvertError::ParseError {
@@ -102,879 +146,847 @@ where
             Err(_) => {
                 info!("This file may be WMF. Try to convert as WMF.");
 
-                let wmf_converter = wmf_core::converter::WMFConverter::new(
-                    buffer.as_slice(),
-                    wmf_player,
-                );
+                let wmf_converter =
+                    wmf_core::converter::WMFConverter::new(buffer, wmf_player);
 
-                return wmf_converter.run().map_err(|source| {
-                    ConvertError::WMFConvertError { source }
-                });
+                return wmf_converter
+                    .run()
+                    .map(|output| (output, Diagnostics::default()))
+                    .map_err(|source| ConvertError::WMFConvertError {
+                        source,
+                    });
             }
         }
 
-        let buf = &mut buffer.as_slice();
-        let header = EMR_HEADER::parse(buf)?;
-        let mut record_number = 0;
-
-        debug!(%record_number, ?header);
-        player = player.header(record_number, header)?;
-
-        loop {
-            record_number += 1;
+        let warnings = if let Some(bytes) = buffer.as_slice() {
+            let mut reader = EmrReader::new(bytes);
+
+            play_records(
+                || reader.read_borrowed_record(),
+                &mut player,
+                recovery_policy,
+                limits,
+            )?
+        } else {
+            let mut reader = EmrReader::new(buffer);
+
+            play_records(
+                || reader.read_record(),
+                &mut player,
+                recovery_policy,
+                limits,
+            )?
+        };
 
-            // Track header bytes via a stand-alone counter; the record
-            // payload uses its own `Size` tracker created below.
-            let mut header_bytes: usize = 0;
-            let record_type =
-                read_with(buf, &mut header_bytes, RecordType::parse)?;
-            let size_raw = read_field(buf, &mut header_bytes)?;
+        finish(player, warnings, limits)
+    }
+}
 
-            // Reject malformed/oversized record sizes up front. Without
-            // this guard `byte_count()` and `remaining_bytes()` consumers
-            // could be steered toward huge allocations.
-            let mut size = Size::parse(size_raw)?;
-            size.consume(header_bytes);
+/// Generates the output of a finished playback.
+fn finish<P: crate::converter::Player>(
+    mut player: P,
+    warnings: Vec<RecordWarning>,
+    limits: ResourceLimits,
+) -> Result<(Vec<u8>, Diagnostics), ConvertError> {
+    let diagnostics =
+        Diagnostics { recovered: warnings, ..player.take_diagnostics() };
+    let output = player.generate()?;
+
+    if let Some(max) = limits.max_output_bytes
+        && output.len() > max
+    {
+        return Err(ConvertError::LimitExceeded {
+            cause: format!(
+                "output of {} bytes exceeds {max} bytes",
+                output.len()
+            ),
+        });
+    }
 
-            if size.byte_count() == 0 {
-                debug!(%size, "skip parsing zero-sized record");
+    Ok((output, diagnostics))
+}
 
-                continue;
+/// Plays the records returned by `read_record` into `player` under
+/// `policy`, returning the records skipped, or stopped at.
+fn play_records<'r, F, P>(
+    mut read_record: F,
+    player: &mut P,
+    policy: RecoveryPolicy,
+    limits: ResourceLimits,
+) -> Result<Vec<RecordWarning>, ConvertError>
+where
+    F: FnMut() -> Result<Option<RecordEntry<'r>>, RecordError>,
+    P: crate::converter::Player,
+{
+    let mut warnings = vec![];
+
+    loop {
+        let RecordEntry { record_number, record, .. } = match read_record() {
+            Ok(Some(entry)) => entry,
+            Ok(None) => break,
+            Err(err) if policy == RecoveryPolicy::Strict => {
+                return Err(err.into());
             }
+            Err(err) => {
+                let recoverable = err.is_recoverable();
 
-            match record_type {
-                // bitmap record
-                RecordType::EMR_ALPHABLEND => {
-                    let record = EMR_ALPHABLEND::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.alpha_blend(record_number, record)?;
-                }
-                RecordType::EMR_BITBLT => {
-                    let record = EMR_BITBLT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.bit_blt(record_number, record)?;
-                }
-                RecordType::EMR_MASKBLT => {
-                    let record = EMR_MASKBLT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.mask_blt(record_number, record)?;
-                }
-                RecordType::EMR_PLGBLT => {
-                    let record = EMR_PLGBLT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.plg_blt(record_number, record)?;
-                }
-                RecordType::EMR_SETDIBITSTODEVICE => {
-                    let record =
-                        EMR_SETDIBITSTODEVICE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_dibits_to_device(record_number, record)?;
-                }
-                RecordType::EMR_STRETCHBLT => {
-                    let record = EMR_STRETCHBLT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.stretch_blt(record_number, record)?;
-                }
-                RecordType::EMR_STRETCHDIBITS => {
-                    let record =
-                        EMR_STRETCHDIBITS::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.stretch_dibits(record_number, record)?;
-                }
-                RecordType::EMR_TRANSPARENTBLT => {
-                    let record =
-                        EMR_TRANSPARENTBLT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.transparent_blt(record_number, record)?;
-                }
-                // clipping record
-                RecordType::EMR_EXCLUDECLIPRECT => {
-                    let record =
-                        EMR_EXCLUDECLIPRECT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.exclude_clip_rect(record_number, record)?;
-                }
-                RecordType::EMR_EXTSELECTCLIPRGN => {
-                    let record =
-                        EMR_EXTSELECTCLIPRGN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.ext_select_clip_rgn(record_number, record)?;
-                }
-                RecordType::EMR_INTERSECTCLIPRECT => {
-                    let record =
-                        EMR_INTERSECTCLIPRECT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.intersect_clip_rect(record_number, record)?;
-                }
-                RecordType::EMR_OFFSETCLIPRGN => {
-                    let record =
-                        EMR_OFFSETCLIPRGN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.offset_clip_rgn(record_number, record)?;
-                }
-                RecordType::EMR_SELECTCLIPPATH => {
-                    let record =
-                        EMR_SELECTCLIPPATH::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.select_clip_path(record_number, record)?;
-                }
-                RecordType::EMR_SETMETARGN => {
-                    let record = EMR_SETMETARGN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.set_meta_rgn(record_number, record)?;
-                }
-                // comment record
-                RecordType::EMR_COMMENT => {
-                    let record = EMR_COMMENT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.comment(record_number, record)?;
-                }
-                // control record
-                RecordType::EMR_EOF => {
-                    let record = EMR_EOF::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.eof(record_number, record)?;
-                    break;
-                }
-                RecordType::EMR_HEADER => {
-                    // NOOP
-                }
-                // drawing record
-                RecordType::EMR_ANGLEARC => {
-                    let record = EMR_ANGLEARC::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.angle_arc(record_number, record)?;
-                }
-                RecordType::EMR_ARC => {
-                    let record = EMR_ARC::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.arc(record_number, record)?;
-                }
-                RecordType::EMR_ARCTO => {
-                    let record = EMR_ARCTO::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.arc_to(record_number, record)?;
-                }
-                RecordType::EMR_CHORD => {
-                    let record = EMR_CHORD::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.chord(record_number, record)?;
-                }
-                RecordType::EMR_ELLIPSE => {
-                    let record = EMR_ELLIPSE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.ellipse(record_number, record)?;
-                }
-                RecordType::EMR_EXTFLOODFILL => {
-                    let record =
-                        EMR_EXTFLOODFILL::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.ext_flood_fill(record_number, record)?;
-                }
-                RecordType::EMR_EXTTEXTOUTA => {
-                    let record =
-                        EMR_EXTTEXTOUTA::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.ext_text_out_a(record_number, record)?;
-                }
-                RecordType::EMR_EXTTEXTOUTW => {
-                    let record =
-                        EMR_EXTTEXTOUTW::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.ext_text_out_w(record_number, record)?;
-                }
-                RecordType::EMR_FILLPATH => {
-                    let record = EMR_FILLPATH::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.fill_path(record_number, record)?;
-                }
-                RecordType::EMR_FILLRGN => {
-                    let record = EMR_FILLRGN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.fill_rgn(record_number, record)?;
-                }
-                RecordType::EMR_FRAMERGN => {
-                    let record = EMR_FRAMERGN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.frame_rgn(record_number, record)?;
-                }
-                RecordType::EMR_GRADIENTFILL => {
-                    let record =
-                        EMR_GRADIENTFILL::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.gradient_fill(record_number, record)?;
-                }
-                RecordType::EMR_LINETO => {
-                    let record = EMR_LINETO::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.line_to(record_number, record)?;
-                }
-                RecordType::EMR_PAINTRGN => {
-                    let record = EMR_PAINTRGN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.paint_rgn(record_number, record)?;
-                }
-                RecordType::EMR_PIE => {
-                    let record = EMR_PIE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.pie(record_number, record)?;
-                }
-                RecordType::EMR_POLYBEZIER => {
-                    let record = EMR_POLYBEZIER::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_bezier(record_number, record)?;
-                }
-                RecordType::EMR_POLYBEZIER16 => {
-                    let record =
-                        EMR_POLYBEZIER16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_bezier_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYBEZIERTO => {
-                    let record =
-                        EMR_POLYBEZIERTO::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_bezier_to(record_number, record)?;
-                }
-                RecordType::EMR_POLYBEZIERTO16 => {
-                    let record =
-                        EMR_POLYBEZIERTO16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_bezier_to_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYDRAW => {
-                    let record = EMR_POLYDRAW::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_draw(record_number, record)?;
-                }
-                RecordType::EMR_POLYDRAW16 => {
-                    let record = EMR_POLYDRAW16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_draw_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYPOLYGON => {
-                    let record =
-                        EMR_POLYPOLYGON::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_polygon(record_number, record)?;
-                }
-                RecordType::EMR_POLYPOLYGON16 => {
-                    let record =
-                        EMR_POLYPOLYGON16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_polygon_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYPOLYLINE => {
-                    let record =
-                        EMR_POLYPOLYLINE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_polyline(record_number, record)?;
-                }
-                RecordType::EMR_POLYPOLYLINE16 => {
-                    let record =
-                        EMR_POLYPOLYLINE16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_polyline_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYTEXTOUTA => {
-                    let record =
-                        EMR_POLYTEXTOUTA::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_text_out_a(record_number, record)?;
-                }
-                RecordType::EMR_POLYTEXTOUTW => {
-                    let record =
-                        EMR_POLYTEXTOUTW::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.poly_text_out_w(record_number, record)?;
-                }
-                RecordType::EMR_POLYGON => {
-                    let record = EMR_POLYGON::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.polygon(record_number, record)?;
-                }
-                RecordType::EMR_POLYGON16 => {
-                    let record = EMR_POLYGON16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.polygon_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYLINE => {
-                    let record = EMR_POLYLINE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.polyline(record_number, record)?;
-                }
-                RecordType::EMR_POLYLINE16 => {
-                    let record = EMR_POLYLINE16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.polyline_16(record_number, record)?;
-                }
-                RecordType::EMR_POLYLINETO => {
-                    let record = EMR_POLYLINETO::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.polyline_to(record_number, record)?;
-                }
-                RecordType::EMR_POLYLINETO16 => {
-                    let record =
-                        EMR_POLYLINETO16::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.polyline_to_16(record_number, record)?;
-                }
-
-                RecordType::EMR_RECTANGLE => {
-                    let record = EMR_RECTANGLE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.rectangle(record_number, record)?;
-                }
-                RecordType::EMR_ROUNDRECT => {
-                    let record = EMR_ROUNDRECT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.round_rect(record_number, record)?;
-                }
-                RecordType::EMR_SETPIXELV => {
-                    let record = EMR_SETPIXELV::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.set_pixel_v(record_number, record)?;
-                }
-                RecordType::EMR_SMALLTEXTOUT => {
-                    let record =
-                        EMR_SMALLTEXTOUT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.small_text_out(record_number, record)?;
-                }
-                RecordType::EMR_STROKEANDFILLPATH => {
-                    let record =
-                        EMR_STROKEANDFILLPATH::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.stroke_and_fill_path(record_number, record)?;
-                }
-                RecordType::EMR_STROKEPATH => {
-                    let record = EMR_STROKEPATH::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.stroke_path(record_number, record)?;
-                }
-                // escape record
-                RecordType::EMR_DRAWESCAPE => {
-                    let record = EMR_DRAWESCAPE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.draw_escape(record_number, record)?;
-                }
-                RecordType::EMR_EXTESCAPE => {
-                    let record = EMR_EXTESCAPE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.ext_escape(record_number, record)?;
-                }
-                RecordType::EMR_NAMEDESCAPE => {
-                    let record =
-                        EMR_NAMEDESCAPE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.named_escape(record_number, record)?;
-                }
-                // object creation record
-                RecordType::EMR_CREATEBRUSHINDIRECT => {
-                    let record =
-                        EMR_CREATEBRUSHINDIRECT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.create_brush_indirect(record_number, record)?;
-                }
-                RecordType::EMR_CREATECOLORSPACE => {
-                    let record =
-                        EMR_CREATECOLORSPACE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.create_color_space(record_number, record)?;
-                }
-                RecordType::EMR_CREATECOLORSPACEW => {
-                    let record =
-                        EMR_CREATECOLORSPACEW::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.create_color_space_w(record_number, record)?;
-                }
-                RecordType::EMR_CREATEDIBPATTERNBRUSHPT => {
-                    let record = EMR_CREATEDIBPATTERNBRUSHPT::parse(
-                        buf,
-                        record_type,
-                        size,
-                    )?;
-
-                    debug!(%record_number, ?record);
-                    player = player
-                        .create_dib_pattern_brush_pt(record_number, record)?;
-                }
-                RecordType::EMR_CREATEMONOBRUSH => {
-                    let record =
-                        EMR_CREATEMONOBRUSH::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.create_mono_brush(record_number, record)?;
-                }
-                RecordType::EMR_CREATEPALETTE => {
-                    let record =
-                        EMR_CREATEPALETTE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.create_palette(record_number, record)?;
-                }
-                RecordType::EMR_CREATEPEN => {
-                    let record = EMR_CREATEPEN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.create_pen(record_number, record)?;
-                }
-                RecordType::EMR_EXTCREATEFONTINDIRECTW => {
-                    let record = EMR_EXTCREATEFONTINDIRECTW::parse(
-                        buf,
-                        record_type,
-                        size,
-                    )?;
-
-                    debug!(%record_number, ?record);
-                    player = player
-                        .ext_create_font_indirect_w(record_number, record)?;
-                }
-                RecordType::EMR_EXTCREATEPEN => {
-                    let record =
-                        EMR_EXTCREATEPEN::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.ext_create_pen(record_number, record)?;
-                }
-                // object manipulation record
-                RecordType::EMR_COLORCORRECTPALETTE => {
-                    let record =
-                        EMR_COLORCORRECTPALETTE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.color_correct_palette(record_number, record)?;
-                }
-                RecordType::EMR_DELETECOLORSPACE => {
-                    let record =
-                        EMR_DELETECOLORSPACE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.delete_color_space(record_number, record)?;
-                }
-                RecordType::EMR_DELETEOBJECT => {
-                    let record =
-                        EMR_DELETEOBJECT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.delete_object(record_number, record)?;
-                }
-                RecordType::EMR_RESIZEPALETTE => {
-                    let record =
-                        EMR_RESIZEPALETTE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.resize_palette(record_number, record)?;
-                }
-                RecordType::EMR_SELECTOBJECT => {
-                    let record =
-                        EMR_SELECTOBJECT::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.select_object(record_number, record)?;
-                }
-                RecordType::EMR_SELECTPALETTE => {
-                    let record =
-                        EMR_SELECTPALETTE::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player.select_palette(record_number, record)?;
-                }
-                RecordType::EMR_SETCOLORSPACE => {
-                    let record =
-                        EMR_SETCOLORSPACE::parse(buf, record_type, size)?;
+                warn!(%err, "skip record that cannot be parsed");
+                warnings.push(err.into());
 
-                    debug!(%record_number, ?record);
-                    player = player.set_color_space(record_number, record)?;
+                if recoverable && policy == RecoveryPolicy::SkipRecord {
+                    continue;
                 }
-                RecordType::EMR_SETPALETTEENTRIES => {
-                    let record =
-                        EMR_SETPALETTEENTRIES::parse(buf, record_type, size)?;
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_palette_entries(record_number, record)?;
-                }
-                // opengl record
-                RecordType::EMR_GLSBOUNDEDRECORD => {
-                    let record =
-                        EMR_GLSBOUNDEDRECORD::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.gls_bounded_record(record_number, record)?;
-                }
-                RecordType::EMR_GLSRECORD => {
-                    let record = EMR_GLSRECORD::parse(buf, record_type, size)?;
+                break;
+            }
+        };
 
-                    debug!(%record_number, ?record);
-                    player = player.gls_record(record_number, record)?;
-                }
-                // path bracket record
-                RecordType::EMR_ABORTPATH => {
-                    let record = EMR_ABORTPATH::parse(buf, record_type, size)?;
+        if let Some(max) = limits.max_records
+            && record_number >= max
+        {
+            return Err(ConvertError::LimitExceeded {
+                cause: format!("metafile has more than {max} records"),
+            });
+        }
 
-                    debug!(%record_number, ?record);
-                    player = player.abort_path(record_number, record)?;
-                }
-                RecordType::EMR_BEGINPATH => {
-                    let record = EMR_BEGINPATH::parse(buf, record_type, size)?;
+        let record_type = record.record_type();
 
-                    debug!(%record_number, ?record);
-                    player = player.begin_path(record_number, record)?;
-                }
-                RecordType::EMR_CLOSEFIGURE => {
-                    let record =
-                        EMR_CLOSEFIGURE::parse(buf, record_type, size)?;
+        player.record(record_number, &record);
 
-                    debug!(%record_number, ?record);
-                    player = player.close_figure(record_number, record)?;
-                }
-                RecordType::EMR_ENDPATH => {
-                    let record = EMR_ENDPATH::parse(buf, record_type, size)?;
+        match play(player, record_number, record) {
+            Ok(()) => {}
+            Err(source @ crate::converter::PlayError::LimitExceeded { .. }) => {
+                return Err(source.into());
+            }
+            Err(source) if policy == RecoveryPolicy::Strict => {
+                return Err(source.into());
+            }
+            Err(source) => {
+                warn!(%record_number, %source, "record cannot be played");
+                warnings.push(RecordWarning::play_error(
+                    record_number,
+                    record_type,
+                    source,
+                ));
 
-                    debug!(%record_number, ?record);
-                    player = player.end_path(record_number, record)?;
+                if policy == RecoveryPolicy::SkipRecord {
+                    continue;
                 }
-                RecordType::EMR_FLATTENPATH => {
-                    let record =
-                        EMR_FLATTENPATH::parse(buf, record_type, size)?;
 
-                    debug!(%record_number, ?record);
-                    player = player.flatten_path(record_number, record)?;
-                }
-                RecordType::EMR_WIDENPATH => {
-                    let record = EMR_WIDENPATH::parse(buf, record_type, size)?;
+                break;
+            }
+        }
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.widen_path(record_number, record)?;
-                }
-                // state record
-                RecordType::EMR_COLORMATCHTOTARGETW => {
-                    let record =
-                        EMR_COLORMATCHTOTARGETW::parse(buf, record_type, size)?;
-
-                    debug!(%record_number, ?record);
-                    player = player
-                        .color_match_to_target_w(record_number, record)?;
-                }
-                RecordType::EMR_FORCEUFIMAPPING => {
-                    let record =
-                        EMR_FORCEUFIMAPPING::parse(buf, record_type, size)?;
+    Ok(warnings)
+}
 
-                    debug!(%record_number, ?record);
-                    player = player.force_ufi_mapping(record_number, record)?;
-                }
-                RecordType::EMR_INVERTRGN => {
-                    let record = EMR_INVERTRGN::parse(buf, record_type, size)?;
+/// Plays `record` through the player method of its record type.
+fn play<P: crate::converter::Player>(
+    player: &mut P,
+    record_number: usize,
+    record: Record<'_>,
+) -> Result<(), crate::converter::PlayError> {
+    match record {
+        // bitmap record
+        Record::EMR_ALPHABLEND(record) => {
+            player.alpha_blend(record_number, record)
+        }
+        Record::EMR_BITBLT(record) => player.bit_blt(record_number, record),
+        Record::EMR_MASKBLT(record) => player.mask_blt(record_number, record),
+        Record::EMR_PLGBLT(record) => player.plg_blt(record_number, record),
+        Record::EMR_SETDIBITSTODEVICE(record) => {
+            player.set_dibits_to_device(record_number, record)
+        }
+        Record::EMR_STRETCHBLT(record) => {
+            player.stretch_blt(record_number, record)
+        }
+        Record::EMR_STRETCHDIBITS(record) => {
+            player.stretch_dibits(record_number, record)
+        }
+        Record::EMR_TRANSPARENTBLT(record) => {
+            player.transparent_blt(record_number, record)
+        }
+        // clipping record
+        Record::EMR_EXCLUDECLIPRECT(record) => {
+            player.exclude_clip_rect(record_number, record)
+        }
+        Record::EMR_EXTSELECTCLIPRGN(record) => {
+            player.ext_select_clip_rgn(record_number, record)
+        }
+        Record::EMR_INTERSECTCLIPRECT(record) => {
+            player.intersect_clip_rect(record_number, record)
+        }
+        Record::EMR_OFFSETCLIPRGN(record) => {
+            player.offset_clip_rgn(record_number, record)
+        }
+        Record::EMR_SELECTCLIPPATH(record) => {
+            player.select_clip_path(record_number, record)
+        }
+        Record::EMR_SETMETARGN(record) => {
+            player.set_meta_rgn(record_number, record)
+        }
+        // comment record
+        Record::EMR_COMMENT(record) => player.comment(record_number, record),
+        // control record
+        Record::EMR_EOF(record) => player.eof(record_number, record),
+        Record::EMR_HEADER(record) => player.header(record_number, record),
+        // drawing record
+        Record::EMR_ANGLEARC(record) => player.angle_arc(record_number, record),
+        Record::EMR_ARC(record) => player.arc(record_number, record),
+        Record::EMR_ARCTO(record) => player.arc_to(record_number, record),
+        Record::EMR_CHORD(record) => player.chord(record_number, record),
+        Record::EMR_ELLIPSE(record) => player.ellipse(record_number, record),
+        Record::EMR_EXTFLOODFILL(record) => {
+            player.ext_flood_fill(record_number, record)
+        }
+        Record::EMR_EXTTEXTOUTA(record) => {
+            player.ext_text_out_a(record_number, record)
+        }
+        Record::EMR_EXTTEXTOUTW(record) => {
+            player.ext_text_out_w(record_number, record)
+        }
+        Record::EMR_FILLPATH(record) => player.fill_path(record_number, record),
+        Record::EMR_FILLRGN(record) => player.fill_rgn(record_number, record),
+        Record::EMR_FRAMERGN(record) => player.frame_rgn(record_number, record),
+        Record::EMR_GRADIENTFILL(record) => {
+            player.gradient_fill(record_number, record)
+        }
+        Record::EMR_LINETO(record) => player.line_to(record_number, record),
+        Record::EMR_PAINTRGN(record) => player.paint_rgn(record_number, record),
+        Record::EMR_PIE(record) => player.pie(record_number, record),
+        Record::EMR_POLYBEZIER(record) => {
+            player.poly_bezier(record_number, record)
+        }
+        Record::EMR_POLYBEZIER16(record) => {
+            player.poly_bezier_16(record_number, record)
+        }
+        Record::EMR_POLYBEZIERTO(record) => {
+            player.poly_bezier_to(record_number, record)
+        }
+        Record::EMR_POLYBEZIERTO16(record) => {
+            player.poly_bezier_to_16(record_number, record)
+        }
+        Record::EMR_POLYDRAW(record) => player.poly_draw(record_number, record),
+        Record::EMR_POLYDRAW16(record) => {
+            player.poly_draw_16(record_number, record)
+        }
+        Record::EMR_POLYPOLYGON(record) => {
+            player.poly_polygon(record_number, record)
+        }
+        Record::EMR_POLYPOLYGON16(record) => {
+            player.poly_polygon_16(record_number, record)
+        }
+        Record::EMR_POLYPOLYLINE(record) => {
+            player.poly_polyline(record_number, record)
+        }
+        Record::EMR_POLYPOLYLINE16(record) => {
+            player.poly_polyline_16(record_number, record)
+        }
+        Record::EMR_POLYTEXTOUTA(record) => {
+            player.poly_text_out_a(record_number, record)
+        }
+        Record::EMR_POLYTEXTOUTW(record) => {
+            player.poly_text_out_w(record_number, record)
+        }
+        Record::EMR_POLYGON(record) => player.polygon(record_number, record),
+        Record::EMR_POLYGON16(record) => {
+            player.polygon_16(record_number, record)
+        }
+        Record::EMR_POLYLINE(record) => player.polyline(record_number, record),
+        Record::EMR_POLYLINE16(record) => {
+            player.polyline_16(record_number, record)
+        }
+        Record::EMR_POLYLINETO(record) => {
+            player.polyline_to(record_number, record)
+        }
+        Record::EMR_POLYLINETO16(record) => {
+            player.polyline_to_16(record_number, record)
+        }
+        Record::EMR_RECTANGLE(record) => {
+            player.rectangle(record_number, record)
+        }
+        Record::EMR_ROUNDRECT(record) => {
+            player.round_rect(record_number, record)
+        }
+        Record::EMR_SETPIXELV(record) => {
+            player.set_pixel_v(record_number, record)
+        }
+        Record::EMR_SMALLTEXTOUT(record) => {
+            player.small_text_out(record_number, record)
+        }
+        Record::EMR_STROKEANDFILLPATH(record) => {
+            player.stroke_and_fill_path(record_number, record)
+        }
+        Record::EMR_STROKEPATH(record) => {
+            player.stroke_path(record_number, record)
+        }
+        // escape record
+        Record::EMR_DRAWESCAPE(record) => {
+            player.draw_escape(record_number, record)
+        }
+        Record::EMR_EXTESCAPE(record) => {
+            player.ext_escape(record_number, record)
+        }
+        Record::EMR_NAMEDESCAPE(record) => {
+            player.named_escape(record_number, record)
+        }
+        // object creation record
+        Record::EMR_CREATEBRUSHINDIRECT(record) => {
+            player.create_brush_indirect(record_number, record)
+        }
+        Record::EMR_CREATECOLORSPACE(record) => {
+            player.create_color_space(record_number, record)
+        }
+        Record::EMR_CREATECOLORSPACEW(record) => {
+            player.create_color_space_w(record_number, record)
+        }
+        Record::EMR_CREATEDIBPATTERNBRUSHPT(record) => {
+            player.create_dib_pattern_brush_pt(record_number, record)
+        }
+        Record::EMR_CREATEMONOBRUSH(record) => {
+            player.create_mono_brush(record_number, record)
+        }
+        Record::EMR_CREATEPALETTE(record) => {
+            player.create_palette(record_number, record)
+        }
+        Record::EMR_CREATEPEN(record) => {
+            player.create_pen(record_number, record)
+        }
+        Record::EMR_EXTCREATEFONTINDIRECTW(record) => {
+            player.ext_create_font_indirect_w(record_number, record)
+        }
+        Record::EMR_EXTCREATEPEN(record) => {
+            player.ext_create_pen(record_number, record)
+        }
+        // object manipulation record
+        Record::EMR_COLORCORRECTPALETTE(record) => {
+            player.color_correct_palette(record_number, record)
+        }
+        Record::EMR_DELETECOLORSPACE(record) => {
+            player.delete_color_space(record_number, record)
+        }
+        Record::EMR_DELETEOBJECT(record) => {
+            player.delete_object(record_number, record)
+        }
+        Record::EMR_RESIZEPALETTE(record) => {
+            player.resize_palette(record_number, record)
+        }
+        Record::EMR_SELECTOBJECT(record) => {
+            player.select_object(record_number, record)
+        }
+        Record::EMR_SELECTPALETTE(record) => {
+            player.select_palette(record_number, record)
+        }
+        Record::EMR_SETCOLORSPACE(record) => {
+            player.set_color_space(record_number, record)
+        }
+        Record::EMR_SETPALETTEENTRIES(record) => {
+            player.set_palette_entries(record_number, record)
+        }
+        // opengl record
+        Record::EMR_GLSBOUNDEDRECORD(record) => {
+            player.gls_bounded_record(record_number, record)
+        }
+        Record::EMR_GLSRECORD(record) => {
+            player.gls_record(record_number, record)
+        }
+        // path bracket record
+        Record::EMR_ABORTPATH(record) => {
+            player.abort_path(record_number, record)
+        }
+        Record::EMR_BEGINPATH(record) => {
+            player.begin_path(record_number, record)
+        }
+        Record::EMR_CLOSEFIGURE(record) => {
+            player.close_figure(record_number, record)
+        }
+        Record::EMR_ENDPATH(record) => player.end_path(record_number, record),
+        Record::EMR_FLATTENPATH(record) => {
+            player.flatten_path(record_number, record)
+        }
+        Record::EMR_WIDENPATH(record) => {
+            player.widen_path(record_number, record)
+        }
+        // state record
+        Record::EMR_COLORMATCHTOTARGETW(record) => {
+            player.color_match_to_target_w(record_number, record)
+        }
+        Record::EMR_FORCEUFIMAPPING(record) => {
+            player.force_ufi_mapping(record_number, record)
+        }
+        Record::EMR_INVERTRGN(record) => {
+            player.invert_rgn(record_number, record)
+        }
+        Record::EMR_MOVETOEX(record) => {
+            player.move_to_ex(record_number, record)
+        }
+        Record::EMR_PIXELFORMAT(record) => {
+            player.pixel_format(record_number, record)
+        }
+        Record::EMR_REALIZEPALETTE(record) => {
+            player.realize_palette(record_number, record)
+        }
+        Record::EMR_RESTOREDC(record) => {
+            player.restore_dc(record_number, record)
+        }
+        Record::EMR_SAVEDC(record) => player.save_dc(record_number, record),
+        Record::EMR_SCALEVIEWPORTEXTEX(record) => {
+            player.scale_viewport_ext_ex(record_number, record)
+        }
+        Record::EMR_SCALEWINDOWEXTEX(record) => {
+            player.scale_window_ext_ex(record_number, record)
+        }
+        Record::EMR_SETARCDIRECTION(record) => {
+            player.set_arc_direction(record_number, record)
+        }
+        Record::EMR_SETBKCOLOR(record) => {
+            player.set_bk_color(record_number, record)
+        }
+        Record::EMR_SETBKMODE(record) => {
+            player.set_bk_mode(record_number, record)
+        }
+        Record::EMR_SETBRUSHORGEX(record) => {
+            player.set_brush_org_ex(record_number, record)
+        }
+        Record::EMR_SETCOLORADJUSTMENT(record) => {
+            player.set_color_adjustment(record_number, record)
+        }
+        Record::EMR_SETICMMODE(record) => {
+            player.set_icm_mode(record_number, record)
+        }
+        Record::EMR_SETICMPROFILEA(record) => {
+            player.set_icm_profile_a(record_number, record)
+        }
+        Record::EMR_SETICMPROFILEW(record) => {
+            player.set_icm_profile_w(record_number, record)
+        }
+        Record::EMR_SETLAYOUT(record) => {
+            player.set_layout(record_number, record)
+        }
+        Record::EMR_SETLINKEDUFIS(record) => {
+            player.set_linked_ufis(record_number, record)
+        }
+        Record::EMR_SETMAPMODE(record) => {
+            player.set_map_mode(record_number, record)
+        }
+        Record::EMR_SETMAPPERFLAGS(record) => {
+            player.set_mapper_flags(record_number, record)
+        }
+        Record::EMR_SETMITERLIMIT(record) => {
+            player.set_miter_limit(record_number, record)
+        }
+        Record::EMR_SETPOLYFILLMODE(record) => {
+            player.set_polyfill_mode(record_number, record)
+        }
+        Record::EMR_SETROP2(record) => player.set_rop2(record_number, record),
+        Record::EMR_SETSTRETCHBLTMODE(record) => {
+            player.set_stretch_blt_mode(record_number, record)
+        }
+        Record::EMR_SETTEXTALIGN(record) => {
+            player.set_text_align(record_number, record)
+        }
+        Record::EMR_SETTEXTCOLOR(record) => {
+            player.set_text_color(record_number, record)
+        }
+        Record::EMR_SETTEXTJUSTIFICATION(record) => {
+            player.set_text_justification(record_number, record)
+        }
+        Record::EMR_SETVIEWPORTEXTEX(record) => {
+            player.set_viewport_ext_ex(record_number, record)
+        }
+        Record::EMR_SETVIEWPORTORGEX(record) => {
+            player.set_viewport_org_ex(record_number, record)
+        }
+        Record::EMR_SETWINDOWEXTEX(record) => {
+            player.set_window_ext_ex(record_number, record)
+        }
+        Record::EMR_SETWINDOWORGEX(record) => {
+            player.set_window_org_ex(record_number, record)
+        }
+        // transform record
+        Record::EMR_MODIFYWORLDTRANSFORM(record) => {
+            player.modify_world_transform(record_number, record)
+        }
+        Record::EMR_SETWORLDTRANSFORM(record) => {
+            player.set_world_transform(record_number, record)
+        }
+    }
+}
 
-                    debug!(%record_number, ?record);
-                    player = player.invert_rgn(record_number, record)?;
-                }
-                RecordType::EMR_MOVETOEX => {
-                    let record = EMR_MOVETOEX::parse(buf, record_type, size)?;
+#[cfg(all(test, feature = "svg"))]
+mod tests {
+    use super::*;
+
+    /// Overrides only the hook and EMR_SETBKMODE, relying on the default
+    /// methods for every other record.
+    #[derive(Default)]
+    struct RecordingPlayer {
+        records: Vec<(usize, RecordType)>,
+        background_mode: Option<BackgroundMode>,
+        // Records passed to the hook, kept after the player is moved.
+        plays: alloc::rc::Rc<core::cell::Cell<usize>>,
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.move_to_ex(record_number, record)?;
-                }
-                RecordType::EMR_PIXELFORMAT => {
-                    let record =
-                        EMR_PIXELFORMAT::parse(buf, record_type, size)?;
+    impl Player for RecordingPlayer {
+        fn generate(self) -> Result<Vec<u8>, PlayError> {
+            Ok(format!("{:?} {:?}", self.records, self.background_mode)
+                .into_bytes())
+        }
 
-                    debug!(%record_number, ?record);
-                    player = player.pixel_format(record_number, record)?;
-                }
-                RecordType::EMR_REALIZEPALETTE => {
-                    let record =
-                        EMR_REALIZEPALETTE::parse(buf, record_type, size)?;
+        fn record(&mut self, record_number: usize, record: &Record) {
+            self.records.push((record_number, record.record_type()));
+            self.plays.set(self.plays.get() + 1);
+        }
 
-                    debug!(%record_number, ?record);
-                    player = player.realize_palette(record_number, record)?;
-                }
-                RecordType::EMR_RESTOREDC => {
-                    let record = EMR_RESTOREDC::parse(buf, record_type, size)?;
+        fn set_bk_mode(
+            &mut self,
+            _: usize,
+            record: EMR_SETBKMODE,
+        ) -> Result<(), PlayError> {
+            if record.background_mode == BackgroundMode::TRANSPARENT {
+                return Err(PlayError::InvalidRecord {
+                    cause: "transparent background".to_owned(),
+                });
+            }
 
-                    debug!(%record_number, ?record);
-                    player = player.restore_dc(record_number, record)?;
-                }
-                RecordType::EMR_SAVEDC => {
-                    let record = EMR_SAVEDC::parse(buf, record_type, size)?;
+            self.background_mode = Some(record.background_mode);
+            Ok(())
+        }
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.save_dc(record_number, record)?;
-                }
-                RecordType::EMR_SCALEVIEWPORTEXTEX => {
-                    let record =
-                        EMR_SCALEVIEWPORTEXTEX::parse(buf, record_type, size)?;
+    fn record(record_type: u32, fields: &[u32]) -> Vec<u8> {
+        let size = 8 + fields.len() as u32 * 4;
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.scale_viewport_ext_ex(record_number, record)?;
-                }
-                RecordType::EMR_SCALEWINDOWEXTEX => {
-                    let record =
-                        EMR_SCALEWINDOWEXTEX::parse(buf, record_type, size)?;
+        [record_type, size]
+            .iter()
+            .chain(fields)
+            .flat_map(|v| v.to_le_bytes())
+            .collect()
+    }
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.scale_window_ext_ex(record_number, record)?;
-                }
-                RecordType::EMR_SETARCDIRECTION => {
-                    let record =
-                        EMR_SETARCDIRECTION::parse(buf, record_type, size)?;
+    /// Returns a metafile of a header followed by `records`.
+    fn metafile(records: &[Vec<u8>]) -> Vec<u8> {
+        let header = record(RecordType::EMR_HEADER as u32, &[
+            0,
+            0,
+            100,
+            100,
+            0,
+            0,
+            2646,
+            2646,
+            0x464D_4520,
+            0x0001_0000,
+            0,
+            records.len() as u32 + 1,
+            1,
+            0,
+            0,
+            0,
+            1024,
+            768,
+            320,
+            240,
+        ]);
+
+        [&[header], records].concat().concat()
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_arc_direction(record_number, record)?;
-                }
-                RecordType::EMR_SETBKCOLOR => {
-                    let record = EMR_SETBKCOLOR::parse(buf, record_type, size)?;
+    fn convert(
+        metafile: &[u8],
+        policy: RecoveryPolicy,
+    ) -> Result<(String, Vec<RecordWarning>), ConvertError> {
+        let (output, diagnostics) = EMFConverter::new(
+            metafile,
+            RecordingPlayer::default(),
+            wmf_core::converter::SVGPlayer::new(),
+        )
+        .with_recovery_policy(policy)
+        .run_with_diagnostics()?;
+
+        Ok((
+            String::from_utf8(output).expect("output is UTF-8"),
+            diagnostics.recovered,
+        ))
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_bk_color(record_number, record)?;
-                }
-                RecordType::EMR_SETBKMODE => {
-                    let record = EMR_SETBKMODE::parse(buf, record_type, size)?;
+    fn eof() -> Vec<u8> {
+        record(RecordType::EMR_EOF as u32, &[0, 0x10, 20])
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_bk_mode(record_number, record)?;
-                }
-                RecordType::EMR_SETBRUSHORGEX => {
-                    let record =
-                        EMR_SETBRUSHORGEX::parse(buf, record_type, size)?;
+    #[test]
+    fn default_methods_pass_records_through() {
+        let metafile = metafile(&[
+            record(RecordType::EMR_SAVEDC as u32, &[]),
+            record(RecordType::EMR_SETBKMODE as u32, &[2]),
+            eof(),
+        ]);
+
+        let output = EMFConverter::new(
+            metafile.as_slice(),
+            RecordingPlayer::default(),
+            wmf_core::converter::SVGPlayer::new(),
+        )
+        .run()
+        .expect("conversion should succeed");
+
+        assert_eq!(
+            String::from_utf8(output).expect("output is UTF-8"),
+            "[(0, EMR_HEADER), (1, EMR_SAVEDC), (2, EMR_SETBKMODE), (3, \
+             EMR_EOF)] Some(OPAQUE)"
+        );
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_brush_org_ex(record_number, record)?;
-                }
-                RecordType::EMR_SETCOLORADJUSTMENT => {
-                    let record =
-                        EMR_SETCOLORADJUSTMENT::parse(buf, record_type, size)?;
+    #[test]
+    fn recovery_policy_skips_or_stops_at_failed_records() {
+        let metafile = metafile(&[
+            // An unknown record type.
+            record(0xFFFF, &[0]),
+            // A background mode that is not a BackgroundMode.
+            record(RecordType::EMR_SETBKMODE as u32, &[7]),
+            // A background mode the player fails to play.
+            record(RecordType::EMR_SETBKMODE as u32, &[1]),
+            record(RecordType::EMR_SETBKMODE as u32, &[2]),
+            eof(),
+        ]);
+
+        assert!(matches!(
+            convert(&metafile, RecoveryPolicy::Strict),
+            Err(ConvertError::ParseError { .. })
+        ));
+
+        let (output, warnings) = convert(&metafile, RecoveryPolicy::SkipRecord)
+            .expect("conversion should succeed");
+        let skipped = warnings
+            .iter()
+            .map(|v| (v.record_number, v.record_type))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            output,
+            "[(0, EMR_HEADER), (3, EMR_SETBKMODE), (4, EMR_SETBKMODE), (5, \
+             EMR_EOF)] Some(OPAQUE)"
+        );
+        assert_eq!(skipped, [
+            (1, None),
+            (2, Some(RecordType::EMR_SETBKMODE)),
+            (3, Some(RecordType::EMR_SETBKMODE)),
+        ]);
+        assert!(matches!(warnings[2].error, ConvertError::PlayError { .. }));
+
+        let (output, warnings) =
+            convert(&metafile, RecoveryPolicy::StopAtFirstError)
+                .expect("conversion should succeed");
+
+        assert_eq!(output, "[(0, EMR_HEADER)] None");
+        assert_eq!(warnings.len(), 1);
+    }
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_color_adjustment(record_number, record)?;
-                }
-                RecordType::EMR_SETICMMODE => {
-                    let record = EMR_SETICMMODE::parse(buf, record_type, size)?;
+    #[test]
+    fn each_record_is_played_once_under_skip_record() {
+        let failing = 100;
+        let records = (0..failing)
+            .flat_map(|_| {
+                [
+                    record(RecordType::EMR_SAVEDC as u32, &[]),
+                    record(RecordType::EMR_SETBKMODE as u32, &[1]),
+                ]
+            })
+            .chain([eof()])
+            .collect::<Vec<_>>();
+        let metafile = metafile(&records);
+        let player = RecordingPlayer::default();
+        let plays = player.plays.clone();
+        let (_, diagnostics) = EMFConverter::new(
+            metafile.as_slice(),
+            player,
+            wmf_core::converter::SVGPlayer::new(),
+        )
+        .with_recovery_policy(RecoveryPolicy::SkipRecord)
+        .run_with_diagnostics()
+        .expect("conversion should succeed");
+
+        assert_eq!(diagnostics.recovered.len(), failing);
+        // The header and every record, including the failed ones.
+        assert_eq!(plays.get(), records.len() + 1);
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_icm_mode(record_number, record)?;
-                }
-                RecordType::EMR_SETICMPROFILEA => {
-                    let record =
-                        EMR_SETICMPROFILEA::parse(buf, record_type, size)?;
+    #[test]
+    fn limits_fail_the_conversion_under_any_policy() {
+        let metafile = metafile(&[
+            record(RecordType::EMR_SAVEDC as u32, &[]),
+            record(RecordType::EMR_SAVEDC as u32, &[]),
+            eof(),
+        ]);
+        let convert = |limits| {
+            EMFConverter::new(
+                metafile.as_slice(),
+                RecordingPlayer::default(),
+                wmf_core::converter::SVGPlayer::new(),
+            )
+            .with_recovery_policy(RecoveryPolicy::SkipRecord)
+            .with_limits(limits)
+            .run()
+        };
 
-                    debug!(%record_number, ?record);
-                    player = player.set_icm_profile_a(record_number, record)?;
-                }
-                RecordType::EMR_SETICMPROFILEW => {
-                    let record =
-                        EMR_SETICMPROFILEW::parse(buf, record_type, size)?;
+        assert!(
+            convert(ResourceLimits {
+                max_records: Some(4),
+                max_output_bytes: Some(1024),
+                ..Default::default()
+            })
+            .is_ok()
+        );
+        assert!(matches!(
+            convert(ResourceLimits {
+                max_records: Some(3),
+                ..Default::default()
+            }),
+            Err(ConvertError::LimitExceeded { .. })
+        ));
+        assert!(matches!(
+            convert(ResourceLimits {
+                max_output_bytes: Some(16),
+                ..Default::default()
+            }),
+            Err(ConvertError::LimitExceeded { .. })
+        ));
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_icm_profile_w(record_number, record)?;
-                }
-                RecordType::EMR_SETLAYOUT => {
-                    let record = EMR_SETLAYOUT::parse(buf, record_type, size)?;
+    /// Returns at most one byte per read, like a slow network stream.
+    struct Trickle<'a>(&'a [u8]);
 
-                    debug!(%record_number, ?record);
-                    player = player.set_layout(record_number, record)?;
-                }
-                RecordType::EMR_SETLINKEDUFIS => {
-                    let record =
-                        EMR_SETLINKEDUFIS::parse(buf, record_type, size)?;
+    impl embedded_io::ErrorType for Trickle<'_> {
+        type Error = core::convert::Infallible;
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_linked_ufis(record_number, record)?;
-                }
-                RecordType::EMR_SETMAPMODE => {
-                    let record = EMR_SETMAPMODE::parse(buf, record_type, size)?;
+    impl embedded_io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let read = self.0.len().min(buf.len()).min(1);
 
-                    debug!(%record_number, ?record);
-                    player = player.set_map_mode(record_number, record)?;
-                }
-                RecordType::EMR_SETMAPPERFLAGS => {
-                    let record =
-                        EMR_SETMAPPERFLAGS::parse(buf, record_type, size)?;
+            buf[..read].copy_from_slice(&self.0[..read]);
+            self.0 = &self.0[read..];
 
-                    debug!(%record_number, ?record);
-                    player = player.set_mapper_flags(record_number, record)?;
-                }
-                RecordType::EMR_SETMITERLIMIT => {
-                    let record =
-                        EMR_SETMITERLIMIT::parse(buf, record_type, size)?;
+            Ok(read)
+        }
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_miter_limit(record_number, record)?;
-                }
-                RecordType::EMR_SETPOLYFILLMODE => {
-                    let record =
-                        EMR_SETPOLYFILLMODE::parse(buf, record_type, size)?;
+    #[test]
+    fn stream_with_short_reads_converts_like_a_slice() {
+        let metafile = metafile(&[
+            record(RecordType::EMR_SETBKMODE as u32, &[1]),
+            record(RecordType::EMR_SETBKMODE as u32, &[2]),
+            eof(),
+        ]);
+
+        for policy in [RecoveryPolicy::SkipRecord, RecoveryPolicy::Strict] {
+            let from_slice = EMFConverter::new(
+                metafile.as_slice(),
+                RecordingPlayer::default(),
+                wmf_core::converter::SVGPlayer::new(),
+            )
+            .with_recovery_policy(policy)
+            .run();
+            let from_stream = EMFConverter::new(
+                Stream::new(Trickle(&metafile)),
+                RecordingPlayer::default(),
+                wmf_core::converter::SVGPlayer::new(),
+            )
+            .with_recovery_policy(policy)
+            .run();
+
+            assert_eq!(
+                format!("{from_slice:?}"),
+                format!("{from_stream:?}"),
+                "{policy:?}"
+            );
+        }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_polyfill_mode(record_number, record)?;
-                }
-                RecordType::EMR_SETROP2 => {
-                    let record = EMR_SETROP2::parse(buf, record_type, size)?;
+        let output = EMFConverter::new(
+            Stream::new(Trickle(&metafile)),
+            RecordingPlayer::default(),
+            wmf_core::converter::SVGPlayer::new(),
+        )
+        .with_recovery_policy(RecoveryPolicy::SkipRecord)
+        .run()
+        .expect("conversion should succeed");
+
+        assert_eq!(
+            String::from_utf8(output).expect("output is UTF-8"),
+            "[(0, EMR_HEADER), (1, EMR_SETBKMODE), (2, EMR_SETBKMODE), (3, \
+             EMR_EOF)] Some(OPAQUE)"
+        );
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_rop2(record_number, record)?;
-                }
-                RecordType::EMR_SETSTRETCHBLTMODE => {
-                    let record =
-                        EMR_SETSTRETCHBLTMODE::parse(buf, record_type, size)?;
+    /// Returns the metafile followed by zeros that never end.
+    struct Endless<'a>(&'a [u8]);
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_stretch_blt_mode(record_number, record)?;
-                }
-                RecordType::EMR_SETTEXTALIGN => {
-                    let record =
-                        EMR_SETTEXTALIGN::parse(buf, record_type, size)?;
+    impl embedded_io::ErrorType for Endless<'_> {
+        type Error = core::convert::Infallible;
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_text_align(record_number, record)?;
-                }
-                RecordType::EMR_SETTEXTCOLOR => {
-                    let record =
-                        EMR_SETTEXTCOLOR::parse(buf, record_type, size)?;
+    impl embedded_io::Read for Endless<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let read = self.0.len().min(buf.len());
 
-                    debug!(%record_number, ?record);
-                    player = player.set_text_color(record_number, record)?;
-                }
-                RecordType::EMR_SETTEXTJUSTIFICATION => {
-                    let record = EMR_SETTEXTJUSTIFICATION::parse(
-                        buf,
-                        record_type,
-                        size,
-                    )?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_text_justification(record_number, record)?;
-                }
-                RecordType::EMR_SETVIEWPORTEXTEX => {
-                    let record =
-                        EMR_SETVIEWPORTEXTEX::parse(buf, record_type, size)?;
+            buf[..read].copy_from_slice(&self.0[..read]);
+            buf[read..].fill(0);
+            self.0 = &self.0[read..];
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_viewport_ext_ex(record_number, record)?;
-                }
-                RecordType::EMR_SETVIEWPORTORGEX => {
-                    let record =
-                        EMR_SETVIEWPORTORGEX::parse(buf, record_type, size)?;
+            Ok(buf.len())
+        }
+    }
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_viewport_org_ex(record_number, record)?;
-                }
-                RecordType::EMR_SETWINDOWEXTEX => {
-                    let record =
-                        EMR_SETWINDOWEXTEX::parse(buf, record_type, size)?;
+    #[test]
+    fn lenient_stream_is_played_without_reading_it_to_the_end() {
+        let metafile = metafile(&[
+            record(RecordType::EMR_SETBKMODE as u32, &[1]),
+            record(RecordType::EMR_SETBKMODE as u32, &[2]),
+            eof(),
+        ]);
+
+        for policy in
+            [RecoveryPolicy::SkipRecord, RecoveryPolicy::StopAtFirstError]
+        {
+            let result = EMFConverter::new(
+                Stream::new(Endless(&metafile)),
+                RecordingPlayer::default(),
+                wmf_core::converter::SVGPlayer::new(),
+            )
+            .with_recovery_policy(policy)
+            .run_with_diagnostics();
+
+            assert!(result.is_ok(), "{policy:?}: {result:?}");
+        }
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_window_ext_ex(record_number, record)?;
-                }
-                RecordType::EMR_SETWINDOWORGEX => {
-                    let record =
-                        EMR_SETWINDOWORGEX::parse(buf, record_type, size)?;
+    #[test]
+    fn input_shorter_than_a_record_type_does_not_panic() {
+        // Such input is handed to the WMF converter, which rejects it.
+        for input in [&[][..], &[1, 0]] {
+            let _ = EMFConverter::new(
+                input,
+                RecordingPlayer::default(),
+                wmf_core::converter::SVGPlayer::new(),
+            )
+            .run();
+        }
+    }
 
-                    debug!(%record_number, ?record);
-                    player = player.set_window_org_ex(record_number, record)?;
-                }
-                // transform record
-                RecordType::EMR_MODIFYWORLDTRANSFORM => {
-                    let record = EMR_MODIFYWORLDTRANSFORM::parse(
-                        buf,
-                        record_type,
-                        size,
-                    )?;
-
-                    debug!(%record_number, ?record);
-                    player =
-                        player.modify_world_transform(record_number, record)?;
-                }
-                RecordType::EMR_SETWORLDTRANSFORM => {
-                    let record =
-                        EMR_SETWORLDTRANSFORM::parse(buf, record_type, size)?;
+    #[test]
+    fn builder_applies_options_to_the_svg_player() {
+        let metafile = metafile(&[eof()]);
 
-                    debug!(%record_number, ?record);
-                    player =
-                        player.set_world_transform(record_number, record)?;
-                }
-            }
-        }
+        let output = EMFConverter::builder()
+            .background("white")
+            .size(OutputSize::Pixels { width: 64, height: 48 })
+            .build(metafile.as_slice(), wmf_core::converter::SVGPlayer::new())
+            .run()
+            .expect("conversion should succeed");
+        let svg = String::from_utf8(output).expect("output is UTF-8");
 
-        Ok(player.generate()?)
+        assert!(svg.contains(r#"width="64""#), "{svg}");
+        assert!(svg.contains(r#"<rect fill="white""#), "{svg}");
     }
 }
@@ -0,0 +1,74 @@
+use crate::{
+    converter::{ImageStore, RecoveryPolicy},
+    imports::*,
+};
+
+/// Options of a conversion, shared by
+/// [`EMFConverter`](crate::converter::EMFConverter) and the players.
+/// Players ignore the options that do not apply to their output format.
+#[derive(Clone, Debug, Default)]
+pub struct ConvertOptions {
+    pub size: OutputSize,
+    /// Number of decimal places coordinates are rounded to, or `None` to
+    /// write them as computed.
+    pub precision: Option<usize>,
+    /// A CSS color the picture is filled with before the records are
+    /// drawn, or `None` to leave it transparent.
+    pub background: Option<String>,
+    pub images: ImageMode,
+    /// Whether text is drawn as the outlines of its glyphs instead of as
+    /// text. Text whose font is not available is drawn as text.
+    pub text_as_paths: bool,
+    pub recovery_policy: RecoveryPolicy,
+    pub limits: ResourceLimits,
+}
+
+/// The size of the output picture.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputSize {
+    /// Leaves the size to the viewer, which scales the picture to fit.
+    #[default]
+    Auto,
+    /// A size in pixels. The picture is scaled to fill it.
+    Pixels { width: u32, height: u32 },
+    /// The physical size of the picture frame in the header, in pixels of
+    /// the given dots per inch.
+    Dpi(f32),
+}
+
+/// How the bitmaps drawn by the records are stored.
+#[derive(Clone, Default)]
+pub enum ImageMode {
+    /// Embeds the bitmaps into the output as data URLs.
+    #[default]
+    Embed,
+    /// Hands the bitmaps to a store and refers to them by the URLs it
+    /// returns.
+    External(Rc<dyn ImageStore>),
+}
+
+impl core::fmt::Debug for ImageMode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Embed => write!(f, "Embed"),
+            Self::External(_) => write!(f, "External(..)"),
+        }
+    }
+}
+
+/// Budgets a conversion fails with
+/// [`ConvertError::LimitExceeded`](crate::converter::ConvertError::LimitExceeded)
+/// when it exceeds them. `None` leaves a resource unbounded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The number of records read, including the header.
+    pub max_records: Option<usize>,
+    /// The number of pixels of all the bitmaps drawn, as their headers
+    /// declare them.
+    pub max_bitmap_pixels: Option<u64>,
+    /// The size of the output in bytes.
+    pub max_output_bytes: Option<usize>,
+    /// The number of entries of the object table, which is one more than
+    /// the highest object index a record may create an object at.
+    pub max_objects: Option<usize>,
+}
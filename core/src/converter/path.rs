@@ -0,0 +1,767 @@
+//! Geometric model of the path bracket.
+//!
+//! Records drawn between EMR_BEGINPATH and EMR_ENDPATH build a [`Path`] in
+//! device coordinates instead of being drawn. The path is then filled,
+//! stroked or selected as the clipping region, or first transformed by
+//! EMR_FLATTENPATH and EMR_WIDENPATH.
+
+use crate::{
+    imports::*,
+    parser::{LogPenEx, PenEndCap, PenLineJoin},
+};
+
+/// Default flatness of [`Path::flatten`] in device units: the largest
+/// distance allowed between a curve and the lines replacing it.
+pub const FLATTEN_TOLERANCE: f64 = 0.25;
+
+/// Largest number of lines a single Bézier curve is flattened into.
+const MAX_FLATTEN_SEGMENTS: usize = 1024;
+
+/// Lines used per quarter turn when round caps and joins are widened.
+const ROUND_SEGMENTS_PER_QUARTER: usize = 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PointF {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl PointF {
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+
+    fn scale(self, v: f64) -> Self {
+        Self::new(self.x * v, self.y * v)
+    }
+
+    fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+}
+
+impl From<&wmf_core::parser::PointL> for PointF {
+    fn from(v: &wmf_core::parser::PointL) -> Self {
+        Self::new(f64::from(v.x), f64::from(v.y))
+    }
+}
+
+impl From<&wmf_core::parser::PointS> for PointF {
+    fn from(v: &wmf_core::parser::PointS) -> Self {
+        Self::new(f64::from(v.x), f64::from(v.y))
+    }
+}
+
+/// A segment of a subpath, ending at `to`. It starts where the previous
+/// segment ends, or at the start of the subpath.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Line { to: PointF },
+    Bezier { c1: PointF, c2: PointF, to: PointF },
+}
+
+impl Segment {
+    pub fn end(&self) -> PointF {
+        match self {
+            Self::Line { to } | Self::Bezier { to, .. } => *to,
+        }
+    }
+}
+
+/// A figure of a path: a sequence of connected segments that is closed
+/// back to its start when `closed` is set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Subpath {
+    pub start: PointF,
+    pub segments: Vec<Segment>,
+    pub closed: bool,
+}
+
+impl Subpath {
+    pub fn end(&self) -> PointF {
+        self.segments.last().map_or(self.start, Segment::end)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    pub subpaths: Vec<Subpath>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subpaths.is_empty()
+    }
+
+    /// Returns the point the next segment starts from.
+    pub fn current_point(&self) -> Option<PointF> {
+        self.subpaths.last().map(|v| if v.closed { v.start } else { v.end() })
+    }
+
+    /// Starts a new subpath at `to`. A subpath that has no segment yet is
+    /// moved instead, so consecutive moves do not leave empty figures.
+    pub fn move_to(&mut self, to: PointF) {
+        match self.subpaths.last_mut() {
+            Some(last) if last.segments.is_empty() && !last.closed => {
+                last.start = to;
+            }
+            _ => {
+                self.subpaths.push(Subpath { start: to, ..Default::default() });
+            }
+        }
+    }
+
+    pub fn line_to(&mut self, to: PointF) {
+        self.push_segment(to, Segment::Line { to });
+    }
+
+    pub fn bezier_to(&mut self, c1: PointF, c2: PointF, to: PointF) {
+        self.push_segment(to, Segment::Bezier { c1, c2, to });
+    }
+
+    /// Closes the current subpath. The next segment starts a new subpath
+    /// at the start of the closed one.
+    pub fn close(&mut self) {
+        if let Some(last) = self.subpaths.last_mut() {
+            last.closed = true;
+        }
+    }
+
+    /// Adds a closed figure through `points`.
+    pub fn add_polygon(&mut self, points: Vec<PointF>) {
+        let mut points = points.into_iter();
+        let Some(start) = points.next() else {
+            return;
+        };
+
+        self.subpaths.push(Subpath {
+            start,
+            segments: points.map(|to| Segment::Line { to }).collect(),
+            closed: true,
+        });
+    }
+
+    /// Adds a closed figure approximating the ellipse around `center` with
+    /// four Bézier curves, starting at its rightmost point.
+    pub fn add_ellipse(&mut self, center: PointF, rx: f64, ry: f64) {
+        // Distance of the control points from the ends of a quarter arc.
+        const KAPPA: f64 = 0.552_284_749_830_793_4;
+
+        let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+        let at = |x: f64, y: f64| center.add(PointF::new(x, y));
+
+        self.subpaths.push(Subpath {
+            start: at(rx, 0.0),
+            segments: vec![
+                Segment::Bezier {
+                    c1: at(rx, ky),
+                    c2: at(kx, ry),
+                    to: at(0.0, ry),
+                },
+                Segment::Bezier {
+                    c1: at(-kx, ry),
+                    c2: at(-rx, ky),
+                    to: at(-rx, 0.0),
+                },
+                Segment::Bezier {
+                    c1: at(-rx, -ky),
+                    c2: at(-kx, -ry),
+                    to: at(0.0, -ry),
+                },
+                Segment::Bezier {
+                    c1: at(kx, -ry),
+                    c2: at(rx, -ky),
+                    to: at(rx, 0.0),
+                },
+            ],
+            closed: true,
+        });
+    }
+
+    /// Appends the subpaths of `other`.
+    pub fn extend(&mut self, other: Self) {
+        self.subpaths.extend(other.subpaths);
+    }
+
+    fn push_segment(&mut self, to: PointF, segment: Segment) {
+        // A segment without a current point starts the first subpath at
+        // its own end; after a closed subpath it starts where that one
+        // began.
+        match self.subpaths.last() {
+            None => self.move_to(to),
+            Some(last) if last.closed => {
+                let start = last.start;
+                self.subpaths.push(Subpath { start, ..Default::default() });
+            }
+            Some(_) => {}
+        }
+
+        if let Some(last) = self.subpaths.last_mut() {
+            last.segments.push(segment);
+        }
+    }
+
+    /// Returns the path with every Bézier curve replaced by lines that
+    /// deviate from it by at most `tolerance`.
+    #[must_use]
+    pub fn flatten(&self, tolerance: f64) -> Self {
+        let subpaths = self
+            .subpaths
+            .iter()
+            .map(|subpath| {
+                let mut from = subpath.start;
+                let mut segments = vec![];
+
+                for segment in &subpath.segments {
+                    match *segment {
+                        Segment::Line { to } => {
+                            segments.push(Segment::Line { to });
+                        }
+                        Segment::Bezier { c1, c2, to } => {
+                            segments.extend(
+                                flatten_bezier(from, c1, c2, to, tolerance)
+                                    .into_iter()
+                                    .map(|to| Segment::Line { to }),
+                            );
+                        }
+                    }
+
+                    from = segment.end();
+                }
+
+                Subpath {
+                    start: subpath.start,
+                    segments,
+                    closed: subpath.closed,
+                }
+            })
+            .collect();
+
+        Self { subpaths }
+    }
+
+    /// Returns the outline of the area painted when the path is stroked
+    /// with `stroke`. Curves are flattened first.
+    ///
+    /// Each figure becomes closed polygons wound in one direction, so the
+    /// outline fills correctly with the nonzero rule: an open figure turns
+    /// into a single polygon running along one side and back the other,
+    /// and a closed figure into an outer and an inner ring.
+    #[must_use]
+    pub fn widen(&self, stroke: &StrokeGeometry) -> Self {
+        let half = stroke.width / 2.0;
+        let mut widened = Self::new();
+
+        if half <= 0.0 {
+            return widened;
+        }
+
+        for subpath in self.flatten(FLATTEN_TOLERANCE).subpaths {
+            let mut points = vec![subpath.start];
+
+            for segment in &subpath.segments {
+                let to = segment.end();
+
+                // Zero-length segments have no direction to offset along.
+                if points.last().is_some_and(|p| p.sub(to).length() > 1e-9) {
+                    points.push(to);
+                }
+            }
+
+            if subpath.closed
+                && points.len() > 2
+                && points[0].sub(points[points.len() - 1]).length() <= 1e-9
+            {
+                points.pop();
+            }
+
+            if points.len() == 1 {
+                // A figure without extent only shows its caps.
+                widened.add_polygon(dot_outline(points[0], half, stroke));
+                continue;
+            }
+
+            if subpath.closed && points.len() > 2 {
+                let outer = offset_side(&points, half, true, stroke);
+                let mut reversed = points.clone();
+                reversed.reverse();
+                widened.add_polygon(outer);
+                widened.add_polygon(offset_side(&reversed, half, true, stroke));
+                continue;
+            }
+
+            let mut outline = offset_side(&points, half, false, stroke);
+            let last = points.len() - 1;
+
+            outline.extend(cap(points[last - 1], points[last], half, stroke));
+
+            let mut reversed = points.clone();
+            reversed.reverse();
+
+            outline.extend(offset_side(&reversed, half, false, stroke));
+            outline.extend(cap(points[1], points[0], half, stroke));
+            widened.add_polygon(outline);
+        }
+
+        widened
+    }
+}
+
+/// The pen attributes that shape a widened path, in device units.
+#[derive(Clone, Debug)]
+pub struct StrokeGeometry {
+    pub width: f64,
+    pub end_cap: PenEndCap,
+    pub line_join: PenLineJoin,
+    /// Largest ratio of the miter length to the pen width before a miter
+    /// join is beveled.
+    pub miter_limit: f64,
+}
+
+impl StrokeGeometry {
+    /// Returns the geometry of `pen` scaled to device units, or `None` for
+    /// a cosmetic pen, which GDI does not widen.
+    pub fn from_pen(
+        pen: &LogPenEx,
+        scale: f64,
+        miter_limit: f64,
+    ) -> Option<Self> {
+        // A pen created by EMR_CREATEPEN wider than one unit is promoted to
+        // a geometric pen, as in `Stroke`.
+        if !pen.pen_style.is_geometric() && pen.width <= 1 {
+            return None;
+        }
+
+        Some(Self {
+            width: f64::from(pen.width) * scale,
+            end_cap: pen.pen_style.end_cap(),
+            line_join: pen.pen_style.line_join(),
+            miter_limit,
+        })
+    }
+}
+
+/// Returns the end points of the lines approximating the cubic Bézier
+/// curve from `p0` to `p3`, excluding `p0`.
+fn flatten_bezier(
+    p0: PointF,
+    p1: PointF,
+    p2: PointF,
+    p3: PointF,
+    tolerance: f64,
+) -> Vec<PointF> {
+    // The distance between a cubic curve and the chords of n equal
+    // parameter steps is bounded by 3/4 * max|second difference| / n^2.
+    let dd = core::cmp::max_by(
+        p0.sub(p1.scale(2.0)).add(p2).length(),
+        p1.sub(p2.scale(2.0)).add(p3).length(),
+        f64::total_cmp,
+    );
+    let steps = (0.75 * dd / tolerance.max(f64::EPSILON)).sqrt();
+    let steps = if steps.is_finite() {
+        (steps.ceil() as usize).clamp(1, MAX_FLATTEN_SEGMENTS)
+    } else {
+        1
+    };
+
+    (1..=steps)
+        .map(|i| {
+            if i == steps {
+                return p3;
+            }
+
+            let t = i as f64 / steps as f64;
+            let u = 1.0 - t;
+
+            p0.scale(u * u * u)
+                .add(p1.scale(3.0 * u * u * t))
+                .add(p2.scale(3.0 * u * t * t))
+                .add(p3.scale(t * t * t))
+        })
+        .collect()
+}
+
+/// Returns the unit normal to the left of the direction from `from` to
+/// `to`.
+fn normal(from: PointF, to: PointF) -> PointF {
+    let d = to.sub(from);
+    let length = d.length();
+
+    PointF::new(-d.y / length, d.x / length)
+}
+
+/// Offsets the polyline `points` by `half` to its left, joining the
+/// offset segments at each vertex. When `closed`, the offset is a loop and
+/// the join at the first vertex is included.
+fn offset_side(
+    points: &[PointF],
+    half: f64,
+    closed: bool,
+    stroke: &StrokeGeometry,
+) -> Vec<PointF> {
+    let count = points.len();
+    let mut outline = vec![];
+
+    if !closed {
+        outline.push(points[0].add(normal(points[0], points[1]).scale(half)));
+    }
+
+    let vertices = if closed { 0..count } else { 1..count - 1 };
+
+    for i in vertices {
+        let prev = points[(i + count - 1) % count];
+        let vertex = points[i];
+        let next = points[(i + 1) % count];
+
+        outline.extend(join(prev, vertex, next, half, stroke));
+    }
+
+    if !closed {
+        let last = count - 1;
+
+        outline.push(
+            points[last]
+                .add(normal(points[last - 1], points[last]).scale(half)),
+        );
+    }
+
+    outline
+}
+
+/// Returns the points of the left offset around `vertex`, where the
+/// segment from `prev` turns towards `next`.
+fn join(
+    prev: PointF,
+    vertex: PointF,
+    next: PointF,
+    half: f64,
+    stroke: &StrokeGeometry,
+) -> Vec<PointF> {
+    let n0 = normal(prev, vertex);
+    let n1 = normal(vertex, next);
+    let a = vertex.add(n0.scale(half));
+    let b = vertex.add(n1.scale(half));
+    let d0 = vertex.sub(prev);
+    let d1 = next.sub(vertex);
+    let cross = d0.x * d1.y - d0.y * d1.x;
+
+    // A turn towards the left side puts the offset on the inside of the
+    // corner, where the offset segments cross at the miter point. When
+    // that point lies beyond either segment, they are joined through the
+    // vertex instead.
+    if cross >= 0.0 || a.sub(b).length() <= 1e-9 {
+        let bisector = n0.add(n1);
+        let cos_half = bisector.length() / 2.0;
+
+        if cos_half > f64::EPSILON {
+            let miter =
+                vertex.add(bisector.scale(half / (2.0 * cos_half * cos_half)));
+            let reach = miter.sub(a).length();
+
+            if reach <= d0.length() && reach <= d1.length() {
+                return vec![miter];
+            }
+        }
+
+        return vec![a, vertex, b];
+    }
+
+    match stroke.line_join {
+        PenLineJoin::PS_JOIN_BEVEL => vec![a, b],
+        PenLineJoin::PS_JOIN_ROUND => {
+            let mut points = vec![a];
+            points.extend(arc(vertex, n0, n1, half, false));
+            points.push(b);
+            points
+        }
+        PenLineJoin::PS_JOIN_MITER => {
+            // The miter point lies along the bisector of the normals, at
+            // half / cos(theta / 2) from the vertex.
+            let bisector = n0.add(n1);
+            let cos_half = bisector.length() / 2.0;
+
+            if cos_half <= f64::EPSILON || 1.0 / cos_half > stroke.miter_limit {
+                return vec![a, b];
+            }
+
+            vec![vertex.add(bisector.scale(half / (2.0 * cos_half * cos_half)))]
+        }
+    }
+}
+
+/// Returns the cap points at `end` of the segment from `from`, going from
+/// the left offset to the right one.
+fn cap(
+    from: PointF,
+    end: PointF,
+    half: f64,
+    stroke: &StrokeGeometry,
+) -> Vec<PointF> {
+    let n = normal(from, end);
+    let d = PointF::new(n.y, -n.x);
+
+    match stroke.end_cap {
+        PenEndCap::PS_ENDCAP_FLAT => vec![],
+        PenEndCap::PS_ENDCAP_SQUARE => vec![
+            end.add(n.scale(half)).add(d.scale(half)),
+            end.sub(n.scale(half)).add(d.scale(half)),
+        ],
+        PenEndCap::PS_ENDCAP_ROUND => arc(end, n, n.scale(-1.0), half, true),
+    }
+}
+
+/// Returns the outline of a figure made of a single point: a circle for
+/// round caps, a square for square caps and nothing for flat caps.
+fn dot_outline(
+    center: PointF,
+    half: f64,
+    stroke: &StrokeGeometry,
+) -> Vec<PointF> {
+    match stroke.end_cap {
+        PenEndCap::PS_ENDCAP_FLAT => vec![],
+        PenEndCap::PS_ENDCAP_SQUARE => vec![
+            center.add(PointF::new(-half, -half)),
+            center.add(PointF::new(half, -half)),
+            center.add(PointF::new(half, half)),
+            center.add(PointF::new(-half, half)),
+        ],
+        PenEndCap::PS_ENDCAP_ROUND => {
+            let (up, down) = (PointF::new(0.0, -1.0), PointF::new(0.0, 1.0));
+            let mut points = vec![center.add(up.scale(half))];
+
+            points.extend(arc(center, up, down, half, true));
+            points.push(center.add(down.scale(half)));
+            points.extend(arc(center, down, up, half, true));
+            points
+        }
+    }
+}
+
+/// Returns the points strictly between the directions `from` and `to` of
+/// an arc of radius `half` around `center`, turning towards decreasing
+/// angles as the normals do around an outer join. With `half_turn` the arc
+/// sweeps exactly half a turn, whose direction would otherwise be
+/// ambiguous.
+fn arc(
+    center: PointF,
+    from: PointF,
+    to: PointF,
+    half: f64,
+    half_turn: bool,
+) -> Vec<PointF> {
+    let start = from.y.atan2(from.x);
+    let mut sweep = to.y.atan2(to.x) - start;
+
+    if half_turn {
+        sweep = -core::f64::consts::PI;
+    } else {
+        while sweep > 0.0 {
+            sweep -= 2.0 * core::f64::consts::PI;
+        }
+    }
+
+    let steps = (sweep.abs() / core::f64::consts::FRAC_PI_2
+        * ROUND_SEGMENTS_PER_QUARTER as f64)
+        .ceil() as usize;
+
+    (1..steps)
+        .map(|i| {
+            let angle = start + sweep * i as f64 / steps as f64;
+
+            center.add(PointF::new(angle.cos(), angle.sin()).scale(half))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(subpath: &Subpath) -> Vec<(f64, f64)> {
+        core::iter::once(subpath.start)
+            .chain(subpath.segments.iter().map(Segment::end))
+            .map(|p| {
+                ((p.x * 100.0).round() / 100.0, (p.y * 100.0).round() / 100.0)
+            })
+            .collect()
+    }
+
+    fn stroke(end_cap: PenEndCap, line_join: PenLineJoin) -> StrokeGeometry {
+        StrokeGeometry { width: 4.0, end_cap, line_join, miter_limit: 10.0 }
+    }
+
+    #[test]
+    fn flatten_stays_within_tolerance() {
+        let (p0, p1, p2, p3) = (
+            PointF::new(0.0, 0.0),
+            PointF::new(0.0, 100.0),
+            PointF::new(100.0, 100.0),
+            PointF::new(100.0, 0.0),
+        );
+        let mut path = Path::new();
+
+        path.move_to(p0);
+        path.bezier_to(p1, p2, p3);
+
+        let flattened = path.flatten(0.25);
+        let subpath = &flattened.subpaths[0];
+
+        assert!(subpath.segments.len() > 8);
+        assert!(
+            subpath.segments.iter().all(|v| matches!(v, Segment::Line { .. }))
+        );
+        assert_eq!(subpath.end(), p3);
+
+        // Every point of the curve lies within the tolerance of the lines.
+        let vertices = points(subpath);
+
+        for i in 0..=100 {
+            let t = f64::from(i) / 100.0;
+            let u = 1.0 - t;
+            let on_curve = p0
+                .scale(u * u * u)
+                .add(p1.scale(3.0 * u * u * t))
+                .add(p2.scale(3.0 * u * t * t))
+                .add(p3.scale(t * t * t));
+            let distance = vertices
+                .windows(2)
+                .map(|v| {
+                    let from = PointF::new(v[0].0, v[0].1);
+                    let chord = PointF::new(v[1].0, v[1].1).sub(from);
+                    let offset = on_curve.sub(from);
+                    let along = ((offset.x * chord.x + offset.y * chord.y)
+                        / (chord.x * chord.x + chord.y * chord.y))
+                        .clamp(0.0, 1.0);
+
+                    offset.sub(chord.scale(along)).length()
+                })
+                .fold(f64::MAX, f64::min);
+
+            assert!(distance <= 0.25 + 0.01, "{distance} away at t = {t}");
+        }
+    }
+
+    #[test]
+    fn widen_open_line_with_caps() {
+        let mut path = Path::new();
+
+        path.move_to(PointF::new(0.0, 0.0));
+        path.line_to(PointF::new(10.0, 0.0));
+
+        let flat = path.widen(&stroke(
+            PenEndCap::PS_ENDCAP_FLAT,
+            PenLineJoin::PS_JOIN_MITER,
+        ));
+
+        assert_eq!(points(&flat.subpaths[0]), vec![
+            (0.0, 2.0),
+            (10.0, 2.0),
+            (10.0, -2.0),
+            (0.0, -2.0)
+        ]);
+        assert!(flat.subpaths[0].closed);
+
+        let square = path.widen(&stroke(
+            PenEndCap::PS_ENDCAP_SQUARE,
+            PenLineJoin::PS_JOIN_MITER,
+        ));
+
+        assert_eq!(points(&square.subpaths[0]), vec![
+            (0.0, 2.0),
+            (10.0, 2.0),
+            (12.0, 2.0),
+            (12.0, -2.0),
+            (10.0, -2.0),
+            (0.0, -2.0),
+            (-2.0, -2.0),
+            (-2.0, 2.0)
+        ]);
+
+        let round = path.widen(&stroke(
+            PenEndCap::PS_ENDCAP_ROUND,
+            PenLineJoin::PS_JOIN_MITER,
+        ));
+
+        assert!(points(&round.subpaths[0]).contains(&(12.0, 0.0)));
+        assert!(points(&round.subpaths[0]).contains(&(-2.0, 0.0)));
+    }
+
+    #[test]
+    fn widen_joins_outer_corner() {
+        // A right turn in device space, whose outer side is the offset
+        // towards negative y before the corner.
+        let mut path = Path::new();
+
+        path.move_to(PointF::new(0.0, 0.0));
+        path.line_to(PointF::new(10.0, 0.0));
+        path.line_to(PointF::new(10.0, 10.0));
+
+        let outline = |line_join| {
+            points(
+                &path
+                    .widen(&stroke(PenEndCap::PS_ENDCAP_FLAT, line_join))
+                    .subpaths[0],
+            )
+        };
+
+        assert!(outline(PenLineJoin::PS_JOIN_MITER).contains(&(12.0, -2.0)));
+
+        let bevel = outline(PenLineJoin::PS_JOIN_BEVEL);
+
+        assert!(bevel.contains(&(10.0, -2.0)) && bevel.contains(&(12.0, 0.0)));
+        assert!(!bevel.contains(&(12.0, -2.0)));
+
+        let mut limited =
+            stroke(PenEndCap::PS_ENDCAP_FLAT, PenLineJoin::PS_JOIN_MITER);
+        limited.miter_limit = 1.2;
+
+        assert!(
+            !points(&path.widen(&limited).subpaths[0]).contains(&(12.0, -2.0))
+        );
+    }
+
+    #[test]
+    fn widen_closed_figure_into_two_rings() {
+        let mut path = Path::new();
+
+        path.move_to(PointF::new(0.0, 0.0));
+        path.line_to(PointF::new(10.0, 0.0));
+        path.line_to(PointF::new(10.0, 10.0));
+        path.line_to(PointF::new(0.0, 10.0));
+        path.close();
+
+        let widened = path.widen(&stroke(
+            PenEndCap::PS_ENDCAP_FLAT,
+            PenLineJoin::PS_JOIN_MITER,
+        ));
+
+        assert_eq!(widened.subpaths.len(), 2);
+
+        let mut corners =
+            widened.subpaths.iter().flat_map(points).collect::<Vec<_>>();
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        assert_eq!(corners, vec![
+            (-2.0, -2.0),
+            (-2.0, 12.0),
+            (2.0, 2.0),
+            (2.0, 8.0),
+            (8.0, 2.0),
+            (8.0, 8.0),
+            (12.0, -2.0),
+            (12.0, 12.0)
+        ]);
+    }
+}
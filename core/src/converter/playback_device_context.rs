@@ -73,6 +73,67 @@ impl PlaybackDeviceContext {
                 + self.xform.dy) as i16,
         }
     }
+
+    /// Resolves a ColorRef against the realized palette. PALETTEINDEX
+    /// colors (high byte 0x01) name a palette entry by the index in their
+    /// low-order word, and PALETTERGB colors (high byte 0x02) are matched
+    /// to the nearest entry. Other colors are explicit RGB values. While ICM
+    /// is on, the result is converted to sRGB.
+    pub fn resolve_color(
+        &self,
+        color: &wmf_core::parser::ColorRef,
+    ) -> wmf_core::parser::ColorRef {
+        let palette = &self.graphics_environment.color.palette;
+        let entry = match color.reserved {
+            0x01 => {
+                let index = u16::from_le_bytes([color.red, color.green]);
+
+                // GDI falls back to the first entry for an index past the
+                // end of the palette.
+                palette.get(usize::from(index)).or_else(|| palette.first())
+            }
+            0x02 => palette.iter().min_by_key(|v| {
+                let distance = |a: u8, b: u8| {
+                    let d = i32::from(a) - i32::from(b);
+                    d * d
+                };
+
+                distance(v.red, color.red)
+                    + distance(v.green, color.green)
+                    + distance(v.blue, color.blue)
+            }),
+            _ => return self.color_managed(color.clone()),
+        };
+
+        self.color_managed(match entry {
+            Some(v) => wmf_core::parser::ColorRef {
+                red: v.red,
+                green: v.green,
+                blue: v.blue,
+                reserved: 0,
+            },
+            None => wmf_core::parser::ColorRef { reserved: 0, ..color.clone() },
+        })
+    }
+
+    /// Converts `color` to sRGB from the selected color space or color
+    /// profile while ICM is on.
+    fn color_managed(
+        &self,
+        color: wmf_core::parser::ColorRef,
+    ) -> wmf_core::parser::ColorRef {
+        let colors = &self.graphics_environment.color;
+
+        if colors.icm_mode != crate::parser::ICMMode::ICM_ON {
+            return color;
+        }
+
+        match colors.color_transform.as_ref().or(colors.color_profile.as_ref())
+        {
+            Some(transform) => transform.to_srgb(&color),
+            None => color,
+        }
+    }
 }
 
 pub fn point_s_to_point_l(
@@ -103,27 +164,71 @@ pub fn point_s_to_point_l(
 ///
 /// Before a graphics object is instantiated and activated, a default stock
 /// object for that type is used in graphics operations.
+///
+/// The table grows as objects are created instead of being sized from the
+/// handle count in the header, which cannot be trusted.
 #[derive(Clone, Debug)]
-pub struct EmfObjectTable(Vec<GraphicsObject>, GraphicsObject);
+pub struct EmfObjectTable {
+    objects: Vec<GraphicsObject>,
+    null: GraphicsObject,
+    // The number of entries the table may grow to, index 0 included.
+    capacity: usize,
+}
 
 impl EmfObjectTable {
-    pub fn new(v: usize) -> Self {
-        let mut objects = vec![GraphicsObject::Null; v + 1];
-        objects[0] = GraphicsObject::ReferenceSelf;
+    /// The number of entries of a metafile with every handle the header
+    /// can declare in use.
+    const MAX_OBJECTS: usize = u16::MAX as usize + 1;
 
-        Self(objects, GraphicsObject::Null)
+    pub fn new(max_objects: Option<usize>) -> Self {
+        Self {
+            objects: vec![GraphicsObject::ReferenceSelf],
+            null: GraphicsObject::Null,
+            capacity: max_objects
+                .map_or(Self::MAX_OBJECTS, |v| v.min(Self::MAX_OBJECTS)),
+        }
     }
 
+    /// Deletes the object at `i`. Indices that hold no object are ignored.
     pub fn delete(&mut self, i: usize) {
-        self.0[i] = GraphicsObject::Null;
+        if i != 0
+            && let Some(object) = self.objects.get_mut(i)
+        {
+            *object = GraphicsObject::Null;
+        }
     }
 
     pub fn get(&self, i: usize) -> &GraphicsObject {
-        self.0.get(i).unwrap_or(&self.1)
+        self.objects.get(i).unwrap_or(&self.null)
     }
 
-    pub fn set(&mut self, idx: usize, g: GraphicsObject) {
-        self.0[idx] = g;
+    pub fn set(
+        &mut self,
+        idx: usize,
+        g: GraphicsObject,
+    ) -> Result<(), crate::converter::PlayError> {
+        if idx == 0 || idx >= Self::MAX_OBJECTS {
+            return Err(crate::converter::PlayError::InvalidRecord {
+                cause: format!("object index {idx} is out of range"),
+            });
+        }
+
+        if idx >= self.capacity {
+            return Err(crate::converter::PlayError::LimitExceeded {
+                cause: format!(
+                    "object index {idx} exceeds the object table of {} entries",
+                    self.capacity,
+                ),
+            });
+        }
+
+        if idx >= self.objects.len() {
+            self.objects.resize(idx + 1, GraphicsObject::Null);
+        }
+
+        self.objects[idx] = g;
+
+        Ok(())
     }
 }
 
@@ -131,11 +236,15 @@ impl EmfObjectTable {
 pub struct SelectedObject {
     pub dib: Option<wmf_core::parser::DeviceIndependentBitmap>,
     pub brush: crate::parser::LogBrushEx,
-    // pub color_space: Option<wmf_core::parser::LogColorSpace>,
-    // pub color_space_w: Option<wmf_core::parser::LogColorSpaceW>,
+    pub color_space: Option<wmf_core::parser::LogColorSpace>,
+    pub color_space_w: Option<wmf_core::parser::LogColorSpaceW>,
     pub font: Option<crate::parser::LogFont>,
     pub font_ex_dv: Option<crate::parser::LogFontExDv>,
     pub palette: Option<crate::parser::LogPalette>,
+    /// Index of the selected palette in the EMF object table, which
+    /// EMR_REALIZEPALETTE reads the current entries from. `None` for the
+    /// stock palette.
+    pub palette_index: Option<usize>,
     pub pen: crate::parser::LogPenEx,
 }
 
@@ -144,11 +253,12 @@ impl Default for SelectedObject {
         Self {
             dib: None,
             brush: crate::parser::LogBrushEx::black_brush(),
-            // color_space: None,
-            // color_space_w: None,
+            color_space: None,
+            color_space_w: None,
             font: None,
             font_ex_dv: None,
             palette: None,
+            palette_index: None,
             pen: crate::parser::LogPenEx::black_pen(),
         }
     }
@@ -158,8 +268,10 @@ impl Default for SelectedObject {
 pub enum GraphicsObject {
     DeviceIndependentBitmap(wmf_core::parser::DeviceIndependentBitmap),
     LogBrushEx(crate::parser::LogBrushEx),
-    // LogColorSpace(wmf_core::parser::LogColorSpace),
-    // LogColorSpaceW(wmf_core::parser::LogColorSpaceW),
+    LogColorSpace(wmf_core::parser::LogColorSpace),
+    /// A logical color space with the color profile embedded in
+    /// EMR_CREATECOLORSPACEW, which is empty if the record holds none.
+    LogColorSpaceW(wmf_core::parser::LogColorSpaceW, Vec<u8>),
     LogFont(crate::parser::LogFont),
     LogFontExDv(crate::parser::LogFontExDv),
     LogPalette(crate::parser::LogPalette),
@@ -315,11 +427,7 @@ impl GraphicsObject {
                 })
             }
             StockObject::DEFAULT_PALETTE => {
-                Self::LogPalette(crate::parser::LogPalette {
-                    version: 0x0300,
-                    number_of_entries: 0,
-                    palette_entries: vec![],
-                })
+                Self::LogPalette(crate::parser::LogPalette::default_palette())
             }
             StockObject::SYSTEM_FIXED_FONT => {
                 Self::LogFont(crate::parser::LogFont {
@@ -431,13 +539,20 @@ pub struct Window {
 #[derive(Clone, Debug)]
 pub struct PlaybackStateColors {
     pub color_adjustment: crate::parser::ColorAdjustment,
-    /// Data field in `EMR_COLORMATCHTOTARGETW`, `EMR_SETICMPROFILEA`,
-    /// `EMR_SETICMPROFILEW`.
-    // pub color_profile: Vec<u8>,
-    // pub color_profile_embedded: bool,
-    // pub color_proofing: u32,
-    // pub color_transform: Vec<u8>,
+    /// Color profile set by `EMR_SETICMPROFILEA` and `EMR_SETICMPROFILEW`,
+    /// which colors are converted from while ICM is on and no logical color
+    /// space that needs a conversion is selected.
+    pub color_profile: Option<crate::converter::ColorTransform>,
+    /// Whether `EMR_COLORMATCHTOTARGETW` enabled proofing against a target
+    /// device.
+    pub color_proofing: bool,
+    /// Conversion of the logical color space selected by
+    /// `EMR_SETCOLORSPACE`, which is `None` for sRGB.
+    pub color_transform: Option<crate::converter::ColorTransform>,
     pub icm_mode: crate::parser::ICMMode,
+    /// Entries of the realized logical palette, which PALETTEINDEX and
+    /// PALETTERGB colors are resolved against.
+    pub palette: Vec<crate::parser::LogPaletteEntry>,
     pub pixel_format: Option<crate::parser::PixelFormatDescriptor>,
 }
 
@@ -445,11 +560,12 @@ impl Default for PlaybackStateColors {
     fn default() -> Self {
         Self {
             color_adjustment: crate::parser::ColorAdjustment::default(),
-            // color_profile: vec![],
-            // color_profile_embedded: false,
-            // color_proofing: 0,
-            // color_transform: vec![],
+            color_profile: None,
+            color_proofing: false,
+            color_transform: None,
             icm_mode: crate::parser::ICMMode::ICM_DONE_OUTSIDEDC,
+            palette: crate::parser::LogPalette::default_palette()
+                .palette_entries,
             pixel_format: None,
         }
     }
@@ -520,7 +636,7 @@ impl Default for PlaybackStateDrawing {
             miter_limit: None,
             path_bracket: false,
             polyfill_mode: crate::parser::PolygonFillMode::ALTERNATE,
-            rop2: wmf_core::parser::BinaryRasterOperation::R2_BLACK,
+            rop2: wmf_core::parser::BinaryRasterOperation::R2_COPYPEN,
             stretch_blt_mode: crate::parser::StretchMode::STRETCH_ANDSCANS,
             text_color: wmf_core::parser::ColorRef::black(),
         }
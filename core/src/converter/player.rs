@@ -1,4 +1,4 @@
-use crate::{imports::*, parser::*};
+use crate::{converter::Diagnostics, imports::*, parser::*};
 
 #[derive(Clone, Debug, snafu::prelude::Snafu)]
 pub enum PlayError {
@@ -8,61 +8,96 @@ pub enum PlayError {
     InvalidBrush { cause: String },
     #[snafu(display("invalid record: {cause}"))]
     InvalidRecord { cause: String },
+    #[snafu(display("limit exceeded: {cause}"))]
+    LimitExceeded { cause: String },
     #[snafu(display("unexpected graphics object: {cause}"))]
     UnexpectedGraphicsObject { cause: String },
     #[snafu(display("unknown: {cause}"))]
     Unknown { cause: String },
 }
 
+/// Plays the records of a metafile into an output format.
+///
+/// Every record method defaults to doing nothing, so a player only overrides
+/// the records it handles. [`Player::record`] is called with each record
+/// before its method. A record method that fails leaves the player in use, so
+/// a lenient recovery policy drops only that record.
+#[allow(unused_variables)]
 pub trait Player: Sized {
     /// Call after converting to write output.
     fn generate(self) -> Result<Vec<u8>, PlayError>;
 
+    /// Called with every record before the method of its record type, e.g. to
+    /// count, log or collect records without handling each type.
+    fn record(&mut self, record_number: usize, record: &Record) {}
+
+    /// Returns the records and features the player could not play exactly.
+    /// Called once after the last record, before [`Player::generate`].
+    fn take_diagnostics(&mut self) -> Diagnostics {
+        Diagnostics::default()
+    }
+
     // .
     // .
     // Functions to handle Bitmap Record
     // .
     // .
     fn alpha_blend(
-        self,
+        &mut self,
         record_number: usize,
-        record: EMR_ALPHABLEND,
-    ) -> Result<Self, PlayError>;
+        record: EMR_ALPHABLEND<'_>,
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn bit_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_BITBLT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn mask_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_MASKBLT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn plg_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PLGBLT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_dibits_to_device(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETDIBITSTODEVICE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn stretch_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_STRETCHBLT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn stretch_dibits(
-        self,
+        &mut self,
         record_number: usize,
-        record: EMR_STRETCHDIBITS,
-    ) -> Result<Self, PlayError>;
+        record: EMR_STRETCHDIBITS<'_>,
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn transparent_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_TRANSPARENTBLT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -70,35 +105,47 @@ pub trait Player: Sized {
     // .
     // .
     fn exclude_clip_rect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXCLUDECLIPRECT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_select_clip_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTSELECTCLIPRGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn intersect_clip_rect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_INTERSECTCLIPRECT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn offset_clip_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_OFFSETCLIPRGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn select_clip_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SELECTCLIPPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_meta_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMETARGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -106,10 +153,12 @@ pub trait Player: Sized {
     // .
     // .
     fn comment(
-        self,
+        &mut self,
         record_number: usize,
-        record: EMR_COMMENT,
-    ) -> Result<Self, PlayError>;
+        record: EMR_COMMENT<'_>,
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -117,15 +166,19 @@ pub trait Player: Sized {
     // .
     // .
     fn eof(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EOF,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn header(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_HEADER,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -133,200 +186,278 @@ pub trait Player: Sized {
     // .
     // .
     fn angle_arc(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ANGLEARC,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn arc(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ARC,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn arc_to(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ARCTO,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn chord(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CHORD,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ellipse(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ELLIPSE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_flood_fill(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTFLOODFILL,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_text_out_a(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTTEXTOUTA,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_text_out_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTTEXTOUTW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn fill_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FILLPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn fill_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FILLRGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn frame_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FRAMERGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn gradient_fill(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_GRADIENTFILL,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn line_to(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_LINETO,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn paint_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PAINTRGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn pie(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PIE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_bezier(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIER,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_bezier_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIER16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_bezier_to(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIERTO,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_bezier_to_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIERTO16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_draw(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYDRAW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_draw_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYDRAW16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_polygon(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYPOLYGON,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_polygon_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYPOLYGON16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_polyline(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYPOLYLINE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_polyline_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYPOLYLINE16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_text_out_a(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYTEXTOUTA,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn poly_text_out_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYTEXTOUTW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn polygon(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYGON,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn polygon_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYGON16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn polyline(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn polyline_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINE16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn polyline_to(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINETO,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn polyline_to_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINETO16,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn rectangle(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_RECTANGLE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn round_rect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ROUNDRECT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_pixel_v(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETPIXELV,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn small_text_out(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SMALLTEXTOUT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn stroke_and_fill_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_STROKEANDFILLPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn stroke_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_STROKEPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -334,20 +465,26 @@ pub trait Player: Sized {
     // .
     // .
     fn draw_escape(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_DRAWESCAPE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_escape(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTESCAPE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn named_escape(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_NAMEDESCAPE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -355,50 +492,68 @@ pub trait Player: Sized {
     // .
     // .
     fn create_brush_indirect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEBRUSHINDIRECT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn create_color_space(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATECOLORSPACE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn create_color_space_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATECOLORSPACEW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn create_dib_pattern_brush_pt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEDIBPATTERNBRUSHPT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn create_mono_brush(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEMONOBRUSH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn create_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEPALETTE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn create_pen(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEPEN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_create_font_indirect_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTCREATEFONTINDIRECTW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn ext_create_pen(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTCREATEPEN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -406,45 +561,61 @@ pub trait Player: Sized {
     // .
     // .
     fn color_correct_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_COLORCORRECTPALETTE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn delete_color_space(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_DELETECOLORSPACE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn delete_object(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_DELETEOBJECT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn resize_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_RESIZEPALETTE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn select_object(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SELECTOBJECT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn select_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SELECTPALETTE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_color_space(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETCOLORSPACE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_palette_entries(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETPALETTEENTRIES,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -452,15 +623,19 @@ pub trait Player: Sized {
     // .
     // .
     fn gls_bounded_record(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_GLSBOUNDEDRECORD,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn gls_record(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_GLSRECORD,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -468,35 +643,47 @@ pub trait Player: Sized {
     // .
     // .
     fn abort_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ABORTPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn begin_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_BEGINPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn close_figure(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CLOSEFIGURE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn end_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ENDPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn flatten_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FLATTENPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn widen_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_WIDENPATH,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -504,170 +691,236 @@ pub trait Player: Sized {
     // .
     // .
     fn color_match_to_target_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_COLORMATCHTOTARGETW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn force_ufi_mapping(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FORCEUFIMAPPING,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn invert_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_INVERTRGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn move_to_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_MOVETOEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn pixel_format(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PIXELFORMAT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn realize_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_REALIZEPALETTE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn restore_dc(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_RESTOREDC,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn save_dc(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SAVEDC,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn scale_viewport_ext_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SCALEVIEWPORTEXTEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn scale_window_ext_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SCALEWINDOWEXTEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_arc_direction(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETARCDIRECTION,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_bk_color(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETBKCOLOR,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_bk_mode(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETBKMODE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_brush_org_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETBRUSHORGEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_color_adjustment(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETCOLORADJUSTMENT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_icm_mode(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETICMMODE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_icm_profile_a(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETICMPROFILEA,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_icm_profile_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETICMPROFILEW,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_layout(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETLAYOUT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_linked_ufis(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETLINKEDUFIS,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_map_mode(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMAPMODE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_mapper_flags(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMAPPERFLAGS,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_miter_limit(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMITERLIMIT,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_polyfill_mode(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETPOLYFILLMODE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_rop2(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETROP2,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_stretch_blt_mode(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETSTRETCHBLTMODE,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_text_align(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETTEXTALIGN,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_text_color(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETTEXTCOLOR,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_text_justification(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETTEXTJUSTIFICATION,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_viewport_ext_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETVIEWPORTEXTEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_viewport_org_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETVIEWPORTORGEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_window_ext_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETWINDOWEXTEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_window_org_ex(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETWINDOWORGEX,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 
     // .
     // .
@@ -675,13 +928,17 @@ pub trait Player: Sized {
     // .
     // .
     fn modify_world_transform(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_MODIFYWORLDTRANSFORM,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
     fn set_world_transform(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETWORLDTRANSFORM,
-    ) -> Result<Self, PlayError>;
+    ) -> Result<(), PlayError> {
+        Ok(())
+    }
 }
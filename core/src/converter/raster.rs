@@ -0,0 +1,358 @@
+//! Raster copy of the drawing for EMR_EXTFLOODFILL.
+//!
+//! A flood fill depends on the colors already drawn around its seed point,
+//! which the emitted SVG cannot be asked for. When enabled, the player also
+//! paints every filled and stroked shape into a [`Raster`] at device
+//! resolution and runs the flood fill there.
+
+use crate::{
+    converter::{FLATTEN_TOLERANCE, Path, PointF},
+    imports::*,
+    parser::FloodFill,
+};
+
+/// Largest number of pixels a [`Raster`] is allocated with.
+pub const MAX_RASTER_PIXELS: usize = 1 << 24;
+
+/// A device-pixel surface covering the picture frame of the metafile,
+/// starting out white.
+#[derive(Clone, Debug)]
+pub struct Raster {
+    left: i32,
+    top: i32,
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+}
+
+impl Raster {
+    /// Returns a white raster whose top-left pixel is at `(left, top)`, or
+    /// `None` if it would be empty or hold more than [`MAX_RASTER_PIXELS`].
+    pub fn new(left: i32, top: i32, width: u32, height: u32) -> Option<Self> {
+        let (width, height) = (width as usize, height as usize);
+        let count = width.checked_mul(height)?;
+
+        if count == 0 || count > MAX_RASTER_PIXELS {
+            return None;
+        }
+
+        Some(Self {
+            left,
+            top,
+            width,
+            height,
+            pixels: vec![rgb(&wmf_core::parser::ColorRef::white()); count],
+        })
+    }
+
+    pub fn set_pixel(
+        &mut self,
+        x: i32,
+        y: i32,
+        color: &wmf_core::parser::ColorRef,
+    ) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = rgb(color);
+        }
+    }
+
+    /// Paints the pixels inside `path`. As in GDI, a pixel is addressed by
+    /// the integer coordinates of its center, so a rectangle excludes its
+    /// right and bottom edges. Every subpath is treated as closed, as when
+    /// a path is filled.
+    pub fn fill_path(
+        &mut self,
+        path: &Path,
+        even_odd: bool,
+        color: &wmf_core::parser::ColorRef,
+    ) {
+        let color = rgb(color);
+        let edges = self.edges(path);
+        let Some((min_y, max_y)) =
+            edges.iter().fold(None, |acc, e| {
+                let (y0, y1) = (e.0.y.min(e.1.y), e.0.y.max(e.1.y));
+                Some(acc.map_or((y0, y1), |(a, b): (f64, f64)| {
+                    (a.min(y0), b.max(y1))
+                }))
+            })
+        else {
+            return;
+        };
+
+        let first_row = min_y.ceil().max(0.0) as usize;
+        let last_row = (max_y.ceil().max(0.0) as usize).min(self.height);
+        let mut crossings = vec![];
+
+        for row in first_row..last_row {
+            let yc = row as f64;
+
+            crossings.clear();
+            for (a, b) in &edges {
+                // Half-open in y so a vertex shared by two edges counts
+                // once.
+                if (a.y <= yc) != (b.y <= yc) {
+                    let x = a.x + (yc - a.y) / (b.y - a.y) * (b.x - a.x);
+                    crossings.push((x, if b.y > a.y { 1 } else { -1 }));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+
+                let inside =
+                    if even_odd { winding % 2 != 0 } else { winding != 0 };
+                if !inside {
+                    continue;
+                }
+
+                let start = pair[0].0.ceil().max(0.0) as usize;
+                let end = (pair[1].0.ceil().max(0.0) as usize).min(self.width);
+                let offset = row * self.width;
+
+                if start < end {
+                    self.pixels[offset + start..offset + end].fill(color);
+                }
+            }
+        }
+    }
+
+    /// Returns the area a flood fill from `(x, y)` paints as a path of
+    /// rectangles in device coordinates, or `None` if the seed point is
+    /// outside the raster or on a pixel the fill stops at.
+    ///
+    /// FLOODFILLBORDER fills the area bounded by `color`, FLOODFILLSURFACE
+    /// the area painted in `color`. Pixels are connected to their four
+    /// neighbours.
+    pub fn flood_fill(
+        &self,
+        x: i32,
+        y: i32,
+        color: &wmf_core::parser::ColorRef,
+        mode: FloodFill,
+    ) -> Option<Path> {
+        let color = rgb(color);
+        let fills = |index: usize| match mode {
+            FloodFill::FLOODFILLBORDER => self.pixels[index] != color,
+            FloodFill::FLOODFILLSURFACE => self.pixels[index] == color,
+        };
+
+        let seed = self.index(x, y)?;
+        if !fills(seed) {
+            return None;
+        }
+
+        let mut filled = vec![false; self.pixels.len()];
+        let mut stack = vec![seed];
+
+        while let Some(index) = stack.pop() {
+            if filled[index] {
+                continue;
+            }
+
+            // Fill the whole run on the row, then queue the rows above and
+            // below it.
+            let row_start = index - index % self.width;
+            let row_end = row_start + self.width;
+            let mut start = index;
+            let mut end = index + 1;
+
+            while start > row_start && !filled[start - 1] && fills(start - 1) {
+                start -= 1;
+            }
+            while end < row_end && !filled[end] && fills(end) {
+                end += 1;
+            }
+
+            filled[start..end].fill(true);
+
+            for i in start..end {
+                if i >= self.width
+                    && !filled[i - self.width]
+                    && fills(i - self.width)
+                {
+                    stack.push(i - self.width);
+                }
+                if i + self.width < self.pixels.len()
+                    && !filled[i + self.width]
+                    && fills(i + self.width)
+                {
+                    stack.push(i + self.width);
+                }
+            }
+        }
+
+        Some(self.trace(&filled))
+    }
+
+    /// Converts the `filled` pixels into rectangles, merging runs that
+    /// span the same columns on consecutive rows.
+    fn trace(&self, filled: &[bool]) -> Path {
+        let mut path = Path::new();
+        // Rectangles still growing downwards: (start, end) -> first row.
+        let mut open = BTreeMap::<(usize, usize), usize>::new();
+
+        for row in 0..=self.height {
+            let mut runs = BTreeSet::new();
+
+            if row < self.height {
+                let pixels = &filled[row * self.width..(row + 1) * self.width];
+                let mut column = 0;
+
+                while column < self.width {
+                    if !pixels[column] {
+                        column += 1;
+                        continue;
+                    }
+
+                    let start = column;
+                    while column < self.width && pixels[column] {
+                        column += 1;
+                    }
+                    runs.insert((start, column));
+                }
+            }
+
+            open.retain(|run, first_row| {
+                if runs.contains(run) {
+                    return true;
+                }
+
+                path.add_polygon(self.rectangle(run.0, *first_row, run.1, row));
+                false
+            });
+
+            for run in runs {
+                open.entry(run).or_insert(row);
+            }
+        }
+
+        path
+    }
+
+    fn rectangle(
+        &self,
+        left: usize,
+        top: usize,
+        right: usize,
+        bottom: usize,
+    ) -> Vec<PointF> {
+        let x = |v: usize| f64::from(self.left) + v as f64;
+        let y = |v: usize| f64::from(self.top) + v as f64;
+
+        vec![
+            PointF::new(x(left), y(top)),
+            PointF::new(x(right), y(top)),
+            PointF::new(x(right), y(bottom)),
+            PointF::new(x(left), y(bottom)),
+        ]
+    }
+
+    /// Returns the lines of the flattened `path` in raster coordinates.
+    fn edges(&self, path: &Path) -> Vec<(PointF, PointF)> {
+        let local = |p: PointF| {
+            PointF::new(p.x - f64::from(self.left), p.y - f64::from(self.top))
+        };
+        let mut edges = vec![];
+
+        for subpath in path.flatten(FLATTEN_TOLERANCE).subpaths {
+            let mut from = local(subpath.start);
+
+            for segment in &subpath.segments {
+                let to = local(segment.end());
+
+                edges.push((from, to));
+                from = to;
+            }
+
+            edges.push((from, local(subpath.start)));
+        }
+
+        edges
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let x = usize::try_from(x.checked_sub(self.left)?).ok()?;
+        let y = usize::try_from(y.checked_sub(self.top)?).ok()?;
+
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+fn rgb(color: &wmf_core::parser::ColorRef) -> u32 {
+    u32::from(color.red)
+        | (u32::from(color.green) << 8)
+        | (u32::from(color.blue) << 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn red() -> wmf_core::parser::ColorRef {
+        wmf_core::parser::ColorRef { red: 255, green: 0, blue: 0, reserved: 0 }
+    }
+
+    fn square(raster: &mut Raster, left: f64, top: f64, size: f64) {
+        let mut path = Path::new();
+        path.add_polygon(vec![
+            PointF::new(left, top),
+            PointF::new(left + size, top),
+            PointF::new(left + size, top + size),
+            PointF::new(left, top + size),
+        ]);
+        raster.fill_path(&path, false, &red());
+    }
+
+    #[test]
+    fn fill_path_excludes_right_and_bottom_edges() {
+        let mut raster = Raster::new(10, 10, 8, 8).expect("raster");
+        square(&mut raster, 12.0, 12.0, 3.0);
+
+        let painted: Vec<_> = (0..64)
+            .filter(|i| raster.pixels[*i] == rgb(&red()))
+            .map(|i| (i % 8 + 10, i / 8 + 10))
+            .collect();
+
+        assert_eq!(painted.len(), 9);
+        assert_eq!(painted.first(), Some(&(12, 12)));
+        assert_eq!(painted.last(), Some(&(14, 14)));
+    }
+
+    #[test]
+    fn flood_fill_stops_at_border_color() {
+        let mut raster = Raster::new(0, 0, 10, 10).expect("raster");
+        square(&mut raster, 2.0, 2.0, 6.0);
+        // Clear the inside to leave a frame.
+        let mut inner = Path::new();
+        inner.add_polygon(vec![
+            PointF::new(3.0, 3.0),
+            PointF::new(7.0, 3.0),
+            PointF::new(7.0, 7.0),
+            PointF::new(3.0, 7.0),
+        ]);
+        raster.fill_path(&inner, false, &wmf_core::parser::ColorRef::white());
+
+        let area = raster
+            .flood_fill(5, 5, &red(), FloodFill::FLOODFILLBORDER)
+            .expect("area");
+        assert_eq!(area.subpaths.len(), 1);
+        assert_eq!(area.subpaths[0].start, PointF::new(3.0, 3.0));
+        assert_eq!(area.subpaths[0].segments[1].end(), PointF::new(7.0, 7.0));
+
+        // The seed point on the border fills nothing.
+        assert!(
+            raster
+                .flood_fill(2, 2, &red(), FloodFill::FLOODFILLBORDER)
+                .is_none()
+        );
+
+        let area = raster
+            .flood_fill(2, 2, &red(), FloodFill::FLOODFILLSURFACE)
+            .expect("area");
+        // The frame is traced as the rows above, beside and below the
+        // hole.
+        assert_eq!(area.subpaths.len(), 4);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    converter::{ConvertError, PlayError},
+    parser::{RecordError, RecordType},
+};
+
+/// How [`EMFConverter`](crate::converter::EMFConverter) handles a record
+/// that cannot be parsed or played.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+    /// Fails the conversion with the error of the record.
+    #[default]
+    Strict,
+    /// Skips the record and continues with the next one. Parsing stops
+    /// when the next record cannot be found, e.g. because the record header
+    /// is malformed.
+    SkipRecord,
+    /// Stops at the record and returns the output of the records before it.
+    StopAtFirstError,
+}
+
+/// A record that was skipped, or stopped the conversion, under a lenient
+/// [`RecoveryPolicy`].
+#[derive(Clone, Debug)]
+pub struct RecordWarning {
+    pub record_number: usize,
+    /// The record type, or `None` if the record header could not be read or
+    /// holds an unknown record type.
+    pub record_type: Option<RecordType>,
+    pub error: ConvertError,
+}
+
+impl From<RecordError> for RecordWarning {
+    fn from(err: RecordError) -> Self {
+        Self {
+            record_number: err.record_number,
+            record_type: err.record_type,
+            error: ConvertError::ParseError { source: err.source },
+        }
+    }
+}
+
+impl RecordWarning {
+    pub(crate) fn play_error(
+        record_number: usize,
+        record_type: RecordType,
+        source: PlayError,
+    ) -> Self {
+        Self {
+            record_number,
+            record_type: Some(record_type),
+            error: ConvertError::PlayError { source },
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    converter::{
+        ConvertOptions, EMFConverter, FontProvider, ImageMode, ImageStore,
+        OutputSize, RecoveryPolicy, ResourceLimits, SVGPlayer,
+    },
+    imports::*,
+};
+
+impl EMFConverter<(), SVGPlayer, ()> {
+    /// Starts configuring a conversion to SVG with [`SVGPlayer`].
+    pub fn builder() -> EMFConverterBuilder {
+        EMFConverterBuilder::default()
+    }
+}
+
+/// Configures an [`EMFConverter`] that converts to SVG, created by
+/// [`EMFConverter::builder`].
+#[derive(Clone, Default)]
+pub struct EMFConverterBuilder {
+    options: ConvertOptions,
+    player: SVGPlayer,
+}
+
+impl EMFConverterBuilder {
+    /// Replaces all the options set so far.
+    #[must_use]
+    pub fn options(mut self, options: ConvertOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    #[must_use]
+    pub fn size(mut self, size: OutputSize) -> Self {
+        self.options.size = size;
+        self
+    }
+
+    /// Rounds coordinates to `precision` decimal places.
+    #[must_use]
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.options.precision = Some(precision);
+        self
+    }
+
+    /// Fills the picture with a CSS color before drawing the records.
+    #[must_use]
+    pub fn background(mut self, color: impl Into<String>) -> Self {
+        self.options.background = Some(color.into());
+        self
+    }
+
+    /// Hands the bitmaps to `store` instead of embedding them.
+    #[must_use]
+    pub fn external_images(mut self, store: impl ImageStore + 'static) -> Self {
+        self.options.images = ImageMode::External(Rc::new(store));
+        self
+    }
+
+    #[must_use]
+    pub fn text_as_paths(mut self, text_as_paths: bool) -> Self {
+        self.options.text_as_paths = text_as_paths;
+        self
+    }
+
+    #[must_use]
+    pub fn recovery_policy(mut self, policy: RecoveryPolicy) -> Self {
+        self.options.recovery_policy = policy;
+        self
+    }
+
+    #[must_use]
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.options.limits = limits;
+        self
+    }
+
+    /// See [`SVGPlayer::with_font_provider`].
+    #[must_use]
+    pub fn font_provider(
+        mut self,
+        provider: impl FontProvider + 'static,
+    ) -> Self {
+        self.player = self.player.with_font_provider(provider);
+        self
+    }
+
+    /// See [`SVGPlayer::with_flood_fill`].
+    #[must_use]
+    pub fn flood_fill(mut self) -> Self {
+        self.player = self.player.with_flood_fill();
+        self
+    }
+
+    /// See [`SVGPlayer::with_bitmap_resampling`].
+    #[must_use]
+    pub fn bitmap_resampling(mut self) -> Self {
+        self.player = self.player.with_bitmap_resampling();
+        self
+    }
+
+    /// Creates the converter of `buffer`. `wmf_player` converts the input
+    /// when it turns out to be WMF; the options do not apply to it.
+    pub fn build<B, WP>(
+        self,
+        buffer: B,
+        wmf_player: WP,
+    ) -> EMFConverter<B, SVGPlayer, WP> {
+        let Self { options, player } = self;
+        let (policy, limits) = (options.recovery_policy, options.limits);
+
+        EMFConverter::new(buffer, player.with_options(options), wmf_player)
+            .with_recovery_policy(policy)
+            .with_limits(limits)
+    }
+}
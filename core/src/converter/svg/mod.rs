@@ -1,11 +1,15 @@
+mod builder;
 mod node;
 mod util;
 
-use wmf_core::parser::{PointL, SizeL};
+use wmf_core::parser::{ColorRef, PointL, SizeL};
 
+pub use self::builder::*;
 use crate::{
     converter::{
-        PlayError,
+        ColorTransform, ConvertOptions, Diagnostic, Diagnostics,
+        FLATTEN_TOLERANCE, Font, FontProvider, ImageMode, OutputSize,
+        PackedDib, Path, PlayError, PointF, Raster, StrokeGeometry,
         playback_device_context::{
             EmfObjectTable, GraphicsEnvironment, GraphicsObject,
             PlaybackDeviceContext, PlaybackStateColors, PlaybackStateDrawing,
@@ -16,8 +20,10 @@ use crate::{
             node::{Data, Node},
             util::{
                 Fill, Stroke, as_point_string_from_point_l,
-                as_point_string_from_point_s, color_from_color_ref,
-                polygon_fill_rule, text_align, text_baseline, url_string,
+                as_point_string_from_point_s, color_adjustment_filter,
+                color_from_color_ref, decode_base64, justification_extras,
+                polygon_fill_rule, rect_node, text_align, text_baseline,
+                url_string,
             },
         },
     },
@@ -25,14 +31,18 @@ use crate::{
     parser::*,
 };
 
+#[derive(Clone)]
 pub struct SVGPlayer {
-    context_stack: Vec<PlaybackDeviceContext>,
+    context_stack: Vec<SavedDeviceContext>,
     context: PlaybackDeviceContext,
     definitions: Vec<Node>,
     elements: Vec<Node>,
     emf_object_table: EmfObjectTable,
     selected_emf_object: SelectedObject,
-    path: Data,
+    path: Path,
+    // The clipping region selected from a path bracket, applied to every
+    // element emitted while it is selected.
+    clip: Option<ClipRegion>,
     window: Window,
     // Tracks how many SVG elements have already been emitted under each
     // EMF record number. Records like POLYPOLYGON / POLYPOLYLINE expand
@@ -40,6 +50,29 @@ pub struct SVGPlayer {
     // collide on the same `elem{record_number}` id and produce SVG that
     // violates the id-uniqueness constraint.
     record_element_counts: BTreeMap<usize, usize>,
+    font_provider: Option<Rc<dyn FontProvider>>,
+    // Fonts loaded through `font_provider` for ETO_GLYPH_INDEX text, keyed
+    // by facename. `None` remembers a facename the provider could not
+    // resolve so it is only asked once.
+    fonts: BTreeMap<String, Option<Font>>,
+    // Whether EMR_EXTFLOODFILL is played through `raster`, a copy of the
+    // drawing created by the header record.
+    flood_fill: bool,
+    raster: Option<Raster>,
+    // Whether bitmaps drawn smaller than their size are reduced before they
+    // are embedded.
+    resample_bitmaps: bool,
+    diagnostics: Diagnostics,
+    options: ConvertOptions,
+    // The picture frame of the header in .01 millimeter units, which
+    // `OutputSize::Dpi` sizes the picture from.
+    frame: SizeL,
+    // Pixels of the bitmaps drawn so far, counted against
+    // `ResourceLimits::max_bitmap_pixels`.
+    bitmap_pixels: u64,
+    // Bytes of the elements emitted so far, counted against
+    // `ResourceLimits::max_output_bytes` before numbers are rounded.
+    output_bytes: usize,
 }
 
 impl Default for SVGPlayer {
@@ -49,45 +82,930 @@ impl Default for SVGPlayer {
             context: PlaybackDeviceContext::default(),
             definitions: vec![],
             elements: vec![],
-            emf_object_table: EmfObjectTable::new(0),
+            emf_object_table: EmfObjectTable::new(None),
             selected_emf_object: SelectedObject::default(),
-            path: Data::new(),
+            path: Path::new(),
+            clip: None,
             window: Window {
                 extent: wmf_core::parser::SizeL { cx: 0, cy: 0 },
                 origin: wmf_core::parser::PointL { x: 0, y: 0 },
             },
             record_element_counts: BTreeMap::new(),
+            font_provider: None,
+            fonts: BTreeMap::new(),
+            flood_fill: false,
+            raster: None,
+            resample_bitmaps: false,
+            diagnostics: Diagnostics::default(),
+            options: ConvertOptions::default(),
+            frame: SizeL { cx: 0, cy: 0 },
+            bitmap_pixels: 0,
+            output_bytes: 0,
         }
     }
 }
 
+/// A clipping region made of the shapes of a `clipPath` definition. The
+/// shapes are kept so a later union with another path can extend them.
+#[derive(Clone, Debug)]
+struct ClipRegion {
+    id: String,
+    shapes: Vec<Node>,
+}
+
+/// The playback device context state saved by EMR_SAVEDC: the graphics
+/// environment and transform, the selected objects, the path bracket and
+/// the clipping region.
+#[derive(Clone)]
+struct SavedDeviceContext {
+    context: PlaybackDeviceContext,
+    selected_emf_object: SelectedObject,
+    path: Path,
+    clip: Option<ClipRegion>,
+}
+
 impl SVGPlayer {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Sets the provider of the fonts used to render text records whose
+    /// string holds glyph indices (ETO_GLYPH_INDEX). Without a provider
+    /// such text is skipped.
+    #[must_use]
+    pub fn with_font_provider(
+        mut self,
+        provider: impl FontProvider + 'static,
+    ) -> Self {
+        self.font_provider = Some(Rc::new(provider));
+        self
+    }
+
+    /// Plays EMR_EXTFLOODFILL by painting the filled and stroked shapes
+    /// into a raster copy of the drawing as well, and emitting the area
+    /// flooded there as a path. Text and bitmaps are not painted into the
+    /// copy. Without it flood fills are skipped.
+    #[must_use]
+    pub fn with_flood_fill(mut self) -> Self {
+        self.flood_fill = true;
+        self
+    }
+
+    /// Reduces uncompressed bitmaps drawn by EMR_STRETCHDIBITS into a
+    /// smaller area to the size they are drawn at, combining the pixels as
+    /// the stretch mode says, so large bitmaps do not bloat the SVG.
+    /// Without it only monochrome bitmaps are reduced, in the
+    /// STRETCH_ANDSCANS and STRETCH_ORSCANS modes.
+    #[must_use]
+    pub fn with_bitmap_resampling(mut self) -> Self {
+        self.resample_bitmaps = true;
+        self
+    }
+
+    /// Applies the options of a conversion: the size, background and
+    /// precision of the SVG, how bitmaps are stored, whether text is drawn
+    /// as paths and the bitmap, object and output budgets. The recovery
+    /// policy and the record budget are enforced by the converter.
+    #[must_use]
+    pub fn with_options(mut self, options: ConvertOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // Counts the pixels of a bitmap against the bitmap budget from the
+    // dimensions in its header, before the bitmap is decoded.
+    fn count_bitmap_pixels(
+        &mut self,
+        dib_header_info: &wmf_core::parser::BitmapInfoHeader,
+    ) -> Result<(), PlayError> {
+        let (width, height) =
+            (dib_header_info.width(), dib_header_info.height());
+        let pixels = (width as u64)
+            .checked_mul(height as u64)
+            .and_then(|v| self.bitmap_pixels.checked_add(v));
+
+        if let Some(max) = self.options.limits.max_bitmap_pixels
+            && pixels.is_none_or(|v| v > max)
+        {
+            return Err(PlayError::LimitExceeded {
+                cause: format!(
+                    "bitmap of {width} x {height} pixels after {} pixels \
+                     exceeds {max} pixels",
+                    self.bitmap_pixels,
+                ),
+            });
+        }
+
+        self.bitmap_pixels = pixels.unwrap_or(u64::MAX);
+
+        Ok(())
+    }
+
+    // Returns the URL an `image` element refers to `bitmap` by.
+    fn image_href(&self, bitmap: &wmf_core::converter::Bitmap) -> String {
+        let data_url = bitmap.as_data_url();
+        let ImageMode::External(ref store) = self.options.images else {
+            return data_url;
+        };
+
+        // Falls back to the data URL when the store declines the image.
+        let url = data_url
+            .strip_prefix("data:")
+            .and_then(|v| v.split_once(";base64,"))
+            .and_then(|(media_type, data)| {
+                store.store(media_type, &decode_base64(data)?)
+            });
+
+        url.unwrap_or(data_url)
+    }
+
+    fn not_implemented(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+    ) {
+        info!("{record_type:?}: not implemented");
+
+        self.diagnostics.unimplemented.push(Diagnostic {
+            record_number,
+            record_type,
+            description: "not implemented".into(),
+        });
+    }
+
+    fn approximated(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        description: impl Into<Cow<'static, str>>,
+    ) {
+        let description = description.into();
+
+        info!(%record_number, ?record_type, "{description}");
+
+        self.diagnostics.approximated.push(Diagnostic {
+            record_number,
+            record_type,
+            description,
+        });
+    }
+
+    fn load_font(&mut self, facename: &str) {
+        if self.fonts.contains_key(facename) {
+            return;
+        }
+
+        let font = self
+            .font_provider
+            .as_ref()
+            .and_then(|provider| provider.load(facename))
+            .and_then(|data| Font::parse(data, facename));
+
+        if font.is_none() {
+            warn!(%facename, "font is not available");
+        }
+
+        self.fonts.insert(facename.to_owned(), font);
+    }
+
     #[inline]
     fn generate_definition_id(&self) -> String {
         format!("defs{}", self.definitions.len())
     }
 
-    // The first emission for a record keeps `id="elem{N}"`; subsequent
-    // emissions get `id="elem{N}-1"`, `id="elem{N}-2"`, ... The suffix
-    // form lets call sites address individual sub-shapes (e.g. one
-    // sub-polygon of a POLYPOLYGON) while keeping the prefix stable as
-    // the EMF record identifier.
-    #[inline]
-    fn push_element(&mut self, record_number: usize, element: Node) {
-        let count =
-            self.record_element_counts.entry(record_number).or_insert(0);
-        let id = if *count == 0 {
-            format!("elem{record_number}")
-        } else {
-            format!("elem{record_number}-{count}")
+    // The first emission for a record keeps `id="elem{N}"`; subsequent
+    // emissions get `id="elem{N}-1"`, `id="elem{N}-2"`, ... The suffix
+    // form lets call sites address individual sub-shapes (e.g. one
+    // sub-polygon of a POLYPOLYGON) while keeping the prefix stable as
+    // the EMF record identifier.
+    //
+    // Fails once the elements emitted exceed the output budget.
+    fn push_element(
+        &mut self,
+        record_number: usize,
+        element: Node,
+    ) -> Result<(), PlayError> {
+        let count =
+            self.record_element_counts.entry(record_number).or_insert(0);
+        let id = if *count == 0 {
+            format!("elem{record_number}")
+        } else {
+            format!("elem{record_number}-{count}")
+        };
+
+        *count += 1;
+
+        let element = match self.clip {
+            // An element clipped on its own, such as ETO_CLIPPED text, is
+            // grouped so both clipping paths apply.
+            Some(ref clip) if element.has("clip-path") => Node::new("g")
+                .set("clip-path", url_string(format!("#{}", clip.id).as_str()))
+                .add(element),
+            Some(ref clip) => element
+                .set("clip-path", url_string(format!("#{}", clip.id).as_str())),
+            None => element,
+        };
+
+        let element = element.set("id", id);
+
+        if let Some(max) = self.options.limits.max_output_bytes {
+            self.output_bytes =
+                self.output_bytes.saturating_add(element.to_string().len());
+
+            if self.output_bytes > max {
+                return Err(PlayError::LimitExceeded {
+                    cause: format!(
+                        "output of {} bytes exceeds {max} bytes",
+                        self.output_bytes,
+                    ),
+                });
+            }
+        }
+
+        self.elements.push(element);
+
+        Ok(())
+    }
+
+    // Pushes a filled or stroked shape drawn with the binary raster
+    // operation (ROP2) of the playback device context. SVG cannot combine
+    // the colors bitwise, so the operations are approximated with a blend
+    // mode over the drawing so far and the pen and brush colors, forced or
+    // inverted as needed.
+    fn push_shape(
+        &mut self,
+        record_number: usize,
+        element: Node,
+    ) -> Result<(), PlayError> {
+        let Some((paint, blend_mode)) =
+            rop2_paint(self.context.graphics_environment.drawing.rop2)
+        else {
+            return Ok(());
+        };
+
+        let mut element = element;
+        for name in ["fill", "stroke"] {
+            if let Some(value) = element.get(name)
+                && value != "none"
+                && let Some(value) = paint.paint(value)
+            {
+                element = element.set(name, value);
+            }
+        }
+
+        if let Some(blend_mode) = blend_mode {
+            element =
+                element.set("style", format!("mix-blend-mode:{blend_mode}"));
+        }
+
+        self.push_element(record_number, element)
+    }
+
+    // Line and curve records build on the path inside a path bracket and
+    // on a figure of their own otherwise, which `finish_path` then
+    // strokes.
+    fn take_path(&mut self) -> Path {
+        if self.context.graphics_environment.drawing.path_bracket {
+            core::mem::take(&mut self.path)
+        } else {
+            Path::new()
+        }
+    }
+
+    fn finish_path(
+        &mut self,
+        record_number: usize,
+        path: Path,
+    ) -> Result<(), PlayError> {
+        if self.context.graphics_environment.drawing.path_bracket {
+            self.path = path;
+            return Ok(());
+        }
+
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        let stroke = Stroke::from(self.selected_emf_object.pen.clone());
+        let element = Node::new("path")
+            .set("fill", "none")
+            .set("d", Data::from(&path).to_string());
+        let element = stroke.set_props(&self.context, element);
+
+        self.rasterize(&path, false, true);
+        self.push_shape(record_number, element)
+    }
+
+    // Records continuing from the current position, such as LineTo right
+    // after BeginPath, start their figure there.
+    fn start_at_current_position(&self, path: &mut Path) {
+        if path.is_empty() {
+            let start = self.context.transform_point_l(
+                &self.context.graphics_environment.drawing.current_position,
+            );
+
+            path.move_to(PointF::from(&start));
+        }
+    }
+
+    // A DIB_PAL_COLORS bitmap holds indices into the realized palette in
+    // place of a color table.
+    fn resolve_dib_colors(
+        &self,
+        colors: wmf_core::parser::Colors,
+    ) -> wmf_core::parser::Colors {
+        let wmf_core::parser::Colors::PaletteIndices(indices) = colors else {
+            return colors;
+        };
+
+        wmf_core::parser::Colors::RGBQuad(
+            indices
+                .into_iter()
+                .map(|index| {
+                    let [red, green] = index.to_le_bytes();
+                    let color = self.context.resolve_color(&ColorRef {
+                        red,
+                        green,
+                        blue: 0,
+                        reserved: 0x01,
+                    });
+
+                    wmf_core::parser::RGBQuad {
+                        blue: color.blue,
+                        green: color.green,
+                        red: color.red,
+                        reserved: 0,
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn palette_object(&self, index: u32) -> Result<LogPalette, PlayError> {
+        match self.emf_object_table.get(index as usize) {
+            GraphicsObject::LogPalette(v) => Ok(v.clone()),
+            v => Err(PlayError::UnexpectedGraphicsObject {
+                cause: format!("palette is not found: index={index}, {v:?}"),
+            }),
+        }
+    }
+
+    // Returns the transform of the color profile in EMR_SETICMPROFILEA/W,
+    // whose data is the profile name followed by the profile itself if the
+    // first bit of flags is set.
+    fn icm_profile(
+        flags: u32,
+        name_size: u32,
+        data: &[u8],
+    ) -> Option<ColorTransform> {
+        let profile = data.get(name_size as usize..).unwrap_or_default();
+
+        if flags & 0x0000_0001 == 0 || profile.is_empty() {
+            info!("color profile is not embedded, colors are left as sRGB");
+            return None;
+        }
+
+        let transform = ColorTransform::from_icc_profile(profile);
+        if transform.is_none() {
+            info!("embedded color profile is not supported");
+        }
+
+        transform
+    }
+
+    fn miter_limit(&self) -> f64 {
+        self.context
+            .graphics_environment
+            .drawing
+            .miter_limit
+            .map_or(10.0, |v| f64::from(core::cmp::max(v, 1)))
+    }
+
+    // Paints a shape drawn in device coordinates into the raster copy kept
+    // for flood fills: the interior with a solid brush when `fill` is set,
+    // then the outline with the pen when `stroke` is. Dashes are painted as
+    // solid lines.
+    fn rasterize(&mut self, path: &Path, fill: bool, stroke: bool) {
+        let miter_limit = self.miter_limit();
+        let Some(raster) = self.raster.as_mut() else {
+            return;
+        };
+        // The copy is painted with the color `push_shape` draws the shape
+        // with, before it is blended with the drawing.
+        let Some((paint, _)) =
+            rop2_paint(self.context.graphics_environment.drawing.rop2)
+        else {
+            return;
+        };
+
+        if fill
+            && let LogBrushEx::Solid { color } = &self.selected_emf_object.brush
+        {
+            let even_odd =
+                self.context.graphics_environment.drawing.polyfill_mode
+                    == PolygonFillMode::ALTERNATE;
+
+            raster.fill_path(
+                path,
+                even_odd,
+                &paint.color(self.context.resolve_color(color)),
+            );
+        }
+
+        let pen = &self.selected_emf_object.pen;
+        if !stroke {
+            return;
+        }
+
+        let (LogPenExBrush::Solid { color_ref }
+        | LogPenExBrush::Hatched { color_ref, .. }) = &pen.brush
+        else {
+            return;
+        };
+
+        if pen.pen_style.line_style() == Some(PenStyle::PS_NULL) {
+            return;
+        }
+
+        // A cosmetic pen is one pixel wide.
+        let stroke = StrokeGeometry::from_pen(
+            pen,
+            f64::from(self.context.xform.calc_scale()),
+            miter_limit,
+        )
+        .unwrap_or(StrokeGeometry {
+            width: 1.0,
+            end_cap: PenEndCap::PS_ENDCAP_FLAT,
+            line_join: PenLineJoin::PS_JOIN_MITER,
+            miter_limit,
+        });
+
+        raster.fill_path(
+            &path.widen(&stroke),
+            false,
+            &paint.color(self.context.resolve_color(color_ref)),
+        );
+    }
+
+    // Shared by EMR_POLYPOLYGON and EMR_POLYPOLYGON16. Each polygon takes
+    // the next `polygon_point_count` points, and is added to the path
+    // inside a path bracket or drawn right away otherwise.
+    fn poly_polygon_points(
+        &mut self,
+        record_number: usize,
+        number_of_polygons: u32,
+        polygon_point_count: &[u32],
+        a_points: Vec<wmf_core::parser::PointL>,
+    ) -> Result<(), PlayError> {
+        // Inside a path bracket every polygon is a closed figure of the
+        // path.
+        if self.context.graphics_environment.drawing.path_bracket {
+            let mut points = a_points.iter();
+
+            for count in polygon_point_count {
+                let polygon = points
+                    .by_ref()
+                    .take(*count as usize)
+                    .map(|v| PointF::from(&self.context.transform_point_l(v)))
+                    .collect();
+
+                self.path.add_polygon(polygon);
+            }
+
+            return Ok(());
+        }
+
+        let stroke = Stroke::from(self.selected_emf_object.pen.clone());
+        let fill = match Fill::from(
+            &self.context,
+            self.selected_emf_object.brush.clone(),
+        ) {
+            Fill::Pattern { pattern } => {
+                let id = self.generate_definition_id();
+                self.definitions.push(pattern.set("id", id.as_str()));
+                url_string(format!("#{id}").as_str())
+            }
+            Fill::Value { value } => value,
+        };
+        let fill_rule = polygon_fill_rule(
+            self.context.graphics_environment.drawing.polyfill_mode,
+        );
+
+        let mut a_point: VecDeque<_> = a_points.into();
+        let mut current_point_index = 0;
+
+        for i in 0..number_of_polygons {
+            let Some(points_of_polygon) = polygon_point_count.get(i as usize)
+            else {
+                return Err(PlayError::InvalidRecord {
+                    cause: format!("PolygonPointCount[{i}] is not defined"),
+                });
+            };
+
+            let mut points = vec![];
+            let mut shape = vec![];
+
+            for _ in 0..*points_of_polygon {
+                let Some(point) = a_point.pop_front() else {
+                    return Err(PlayError::InvalidRecord {
+                        cause: format!(
+                            "aPoints[{current_point_index}] is not defined"
+                        ),
+                    });
+                };
+
+                self.context.graphics_environment.drawing.current_position =
+                    point.clone();
+
+                let point = self.context.transform_point_l(&point);
+                points.push(as_point_string_from_point_l(&point));
+                shape.push(PointF::from(&point));
+                current_point_index += 1;
+            }
+
+            let polygon = Node::new("polygon")
+                .set("fill", fill.as_str())
+                .set("fill-rule", fill_rule.as_str())
+                .set("points", points.join(" "));
+            let polygon = stroke.set_props(&self.context, polygon);
+
+            if self.raster.is_some() {
+                let mut path = Path::new();
+
+                path.add_polygon(shape);
+                self.rasterize(&path, true, true);
+            }
+
+            self.push_shape(record_number, polygon)?;
+        }
+
+        Ok(())
+    }
+
+    // Shared by EMR_POLYDRAW and EMR_POLYDRAW16. The points continue from
+    // the current position; inside a path bracket they are added to the
+    // path, otherwise they are stroked right away.
+    fn poly_draw_points(
+        &mut self,
+        record_number: usize,
+        points: &[PointL],
+        types: &[PointFlags],
+    ) -> Result<(), PlayError> {
+        let mut path = self.take_path();
+        let mut current =
+            self.context.graphics_environment.drawing.current_position.clone();
+        // Logical start of the figure, where PT_CLOSEFIGURE leaves the
+        // current position.
+        let mut figure_start = current.clone();
+
+        self.start_at_current_position(&mut path);
+
+        let mut i = 0;
+
+        while i < points.len() {
+            let Some(flags) = types.get(i) else {
+                return Err(PlayError::InvalidRecord {
+                    cause: format!("abTypes[{i}] is not defined"),
+                });
+            };
+            let device =
+                |v: &PointL| PointF::from(&self.context.transform_point_l(v));
+
+            let last = match flags.point_type() {
+                Some(Point::PT_MOVETO) => {
+                    path.move_to(device(&points[i]));
+                    figure_start = points[i].clone();
+                    i
+                }
+                Some(Point::PT_LINETO) => {
+                    path.line_to(device(&points[i]));
+                    i
+                }
+                // PT_BEZIERTO points come in sets of three: two control
+                // points and the end point.
+                Some(Point::PT_BEZIERTO)
+                    if types.get(i..i + 3).is_some_and(|v| {
+                        v.iter()
+                            .all(|t| t.point_type() == Some(Point::PT_BEZIERTO))
+                    }) && i + 3 <= points.len() =>
+                {
+                    path.bezier_to(
+                        device(&points[i]),
+                        device(&points[i + 1]),
+                        device(&points[i + 2]),
+                    );
+                    i + 2
+                }
+                _ => {
+                    return Err(PlayError::InvalidRecord {
+                        cause: format!("abTypes[{i}] is invalid: {flags:?}"),
+                    });
+                }
+            };
+
+            current = points[last].clone();
+
+            if types[last].contains(Point::PT_CLOSEFIGURE) {
+                path.close();
+                current = figure_start.clone();
+            }
+
+            i = last + 1;
+        }
+
+        self.context.graphics_environment.drawing.current_position = current;
+
+        self.finish_path(record_number, path)?;
+
+        Ok(())
+    }
+
+    // Shared by the text output records. ETO_OPAQUE paints the rectangle
+    // with the background color before the string, and ETO_CLIPPED
+    // clips the string to the same rectangle. When the record carries an
+    // intercharacter spacing array, every glyph gets an absolute `x` so
+    // the run keeps the exact widths it had when it was recorded.
+    //
+    // Glyph indices are written as text when the cmap of the font maps
+    // every one of them back to a character, and as outlines otherwise.
+    fn push_text(
+        &mut self,
+        record_number: usize,
+        emr_text: &EmrText,
+    ) -> Result<(), PlayError> {
+        let font = if let Some(ref font) = self.selected_emf_object.font_ex_dv {
+            font.log_font_ex.log_font.clone()
+        } else if let Some(ref font) = self.selected_emf_object.font {
+            font.clone()
+        } else {
+            return Err(PlayError::UnexpectedGraphicsObject {
+                cause: "font is not selected".to_owned(),
+            });
+        };
+
+        let state = &self.context.graphics_environment;
+        let path_bracket = state.drawing.path_bracket;
+
+        if path_bracket
+            || self.options.text_as_paths
+            || matches!(emr_text.string_buffer, OutputString::GlyphIndices(_))
+        {
+            self.load_font(&font.facename);
+        }
+
+        let state = &self.context.graphics_environment;
+        let alignment = state.text.text_alignment;
+        // TA_UPDATECP
+        let update_cp = alignment & 0x0000_0001 != 0;
+        // TA_RTLREADING
+        let rtl = alignment & 0x0000_0100 != 0
+            || emr_text.options.contains(ExtTextOutOptions::ETO_RTLREADING)
+            || state.drawing.layout_mode == LayoutMode::LAYOUT_RTL;
+        let face = self.fonts.get(&font.facename).and_then(Option::as_ref);
+        let glyphs = emr_text.glyphs(font.charset, face);
+        let extras = glyphs.as_deref().map_or_else(Vec::new, |glyphs| {
+            justification_extras(glyphs, state.text.text_justification)
+        });
+
+        // The reference point of the record is ignored with TA_UPDATECP;
+        // the string starts at the current position instead.
+        let mut emr_text = emr_text.clone();
+
+        if update_cp {
+            emr_text.reference = state.drawing.current_position.clone();
+        }
+
+        if let Some(ref glyphs) = glyphs {
+            emr_text.justify(glyphs, &extras);
+        }
+
+        let (advance_x, advance_y) = emr_text.advance().unwrap_or_else(|| {
+            (emr_text.estimated_advance(&font, face, &extras), 0)
+        });
+
+        // Inside a path bracket, or when text is drawn as paths, the
+        // outlines of the glyphs are used instead of the string. The
+        // outlines of characters follow the logical order, so right-to-left
+        // strings are only drawn as paths in a path bracket.
+        let outline = match emr_text.string_buffer {
+            OutputString::GlyphIndices(ref indices) => Some(indices.clone()),
+            _ if path_bracket || self.options.text_as_paths && !rtl => {
+                glyphs.as_deref().zip(face).and_then(|(glyphs, face)| {
+                    glyphs
+                        .iter()
+                        .map(|&(c, _)| face.glyph_for_char(c))
+                        .collect()
+                })
+            }
+            _ => None,
+        }
+        .zip(face)
+        .and_then(|(indices, face)| {
+            emr_text.glyph_outlines(
+                &self.context,
+                alignment,
+                &font,
+                face,
+                &indices,
+            )
+        });
+
+        let element = if path_bracket {
+            if let Some(outline) = outline {
+                self.path.extend(outline);
+            } else {
+                warn!(
+                    facename = %font.facename,
+                    "font has no outlines to add text to the path",
+                );
+            }
+
+            None
+        } else if let Some(outline) =
+            outline.filter(|_| self.options.text_as_paths || glyphs.is_none())
+        {
+            // Glyph indices are already in visual order, so they are drawn
+            // left to right whatever the reading order.
+            Some(
+                Node::new("path")
+                    .set("d", Data::from(&outline).to_string())
+                    .set(
+                        "fill",
+                        color_from_color_ref(
+                            &self
+                                .context
+                                .resolve_color(&state.drawing.text_color),
+                        ),
+                    ),
+            )
+        } else if let Some(glyphs) = glyphs {
+            let mut text = self.text_node(&emr_text, &font, glyphs, rtl);
+
+            // Without a spacing array the extra space goes to the spaces
+            // of the string through `word-spacing`.
+            if emr_text.dx_buffer.is_empty()
+                && let Some(&extra) = extras.iter().find(|v| **v != 0)
+            {
+                let scale = self.context.xform.calc_scale();
+
+                text = text.set(
+                    "word-spacing",
+                    ((extra as f32) * scale).round().to_string(),
+                );
+            }
+
+            Some(text)
+        } else if let OutputString::GlyphIndices(_) = emr_text.string_buffer {
+            warn!(
+                facename = %font.facename,
+                "font has neither characters nor outlines for glyph indices",
+            );
+            None
+        } else {
+            None
+        };
+
+        // With TA_UPDATECP the current position moves past the string:
+        // to its right end for TA_LEFT and to its left end for TA_RIGHT.
+        // Centered strings leave it unchanged.
+        if update_cp {
+            let reference = &emr_text.reference;
+            let (dx, dy) = match alignment & 0x0000_0006 {
+                0x0000_0006 => (0, 0),
+                0x0000_0002 => (-advance_x, -advance_y),
+                _ => (advance_x, advance_y),
+            };
+
+            self.context.graphics_environment.drawing.current_position =
+                PointL {
+                    x: reference.x.saturating_add(dx),
+                    y: reference.y.saturating_add(dy),
+                };
+        }
+
+        if path_bracket {
+            return Ok(());
+        }
+
+        if let Some(ref rectangle) = emr_text.rectangle {
+            if emr_text.options.contains(ExtTextOutOptions::ETO_OPAQUE) {
+                let background = rect_node(&self.context, rectangle).set(
+                    "fill",
+                    color_from_color_ref(
+                        &self.context.resolve_color(
+                            &self
+                                .context
+                                .graphics_environment
+                                .drawing
+                                .background_color,
+                        ),
+                    ),
+                );
+
+                self.push_element(record_number, background)?;
+            }
+        }
+
+        let Some(mut element) = element else {
+            return Ok(());
+        };
+
+        if let Some(ref rectangle) = emr_text.rectangle
+            && emr_text.options.contains(ExtTextOutOptions::ETO_CLIPPED)
+        {
+            let id = self.generate_definition_id();
+            self.definitions.push(
+                Node::new("clipPath")
+                    .set("id", id.as_str())
+                    .add(rect_node(&self.context, rectangle)),
+            );
+            element =
+                element.set("clip-path", url_string(format!("#{id}").as_str()));
+        }
+
+        self.push_element(record_number, element)?;
+
+        Ok(())
+    }
+
+    fn text_node(
+        &self,
+        emr_text: &EmrText,
+        font: &LogFont,
+        glyphs: Vec<(char, usize)>,
+        rtl: bool,
+    ) -> Node {
+        let color = color_from_color_ref(&self.context.resolve_color(
+            &self.context.graphics_environment.drawing.text_color,
+        ));
+        let alignment = self.context.graphics_environment.text.text_alignment;
+        let point = self.context.transform_point_l(&emr_text.reference);
+
+        let mut text = Node::new("text")
+            .set("dominant-baseline", text_baseline(alignment))
+            .set("fill", color);
+
+        text = match emr_text.glyph_positions(
+            &self.context,
+            alignment,
+            &glyphs,
+            rtl,
+        ) {
+            Some(positions) => {
+                let x = positions
+                    .iter()
+                    .map(|p| p.x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                text = text
+                    .set("x", x)
+                    .set("y", positions[0].y.to_string())
+                    .set("text-anchor", "start");
+
+                if emr_text.options.contains(ExtTextOutOptions::ETO_PDY) {
+                    let dy = positions
+                        .windows(2)
+                        .map(|p| {
+                            (i64::from(p[1].y) - i64::from(p[0].y)).to_string()
+                        })
+                        .collect::<Vec<_>>();
+
+                    text = text.set(
+                        "dy",
+                        core::iter::once("0".to_owned())
+                            .chain(dy)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    );
+                }
+
+                text
+            }
+            // In right-to-left text the start of the string is its right
+            // end, so the anchor that keeps the aligned edge at the
+            // reference point is the opposite one.
+            None if rtl => text
+                .set("direction", "rtl")
+                .set("x", point.x.to_string())
+                .set("y", point.y.to_string())
+                .set("text-anchor", match alignment & 0x0000_0006 {
+                    0x0000_0006 => "middle",
+                    0x0000_0002 => "start",
+                    _ => "end",
+                }),
+            None => text
+                .set("x", point.x.to_string())
+                .set("y", point.y.to_string())
+                .set("text-anchor", text_align(alignment)),
         };
 
-        *count += 1;
-        self.elements.push(element.set("id", id));
+        let text = text.add(Node::new_text(
+            glyphs.into_iter().map(|(c, _)| c).collect::<String>(),
+        ));
+        let (text, styles) = font.set_props(&self.context, text, &point);
+
+        text.set("style", styles.join(""))
     }
 }
 
@@ -98,7 +1016,7 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn generate(self) -> Result<Vec<u8>, PlayError> {
-        let Self { definitions, elements, window, .. } = self;
+        let Self { definitions, elements, window, options, frame, .. } = self;
 
         let mut document =
             Node::new("svg").set("xmlns", "http://www.w3.org/2000/svg").set(
@@ -112,6 +1030,24 @@ impl crate::converter::Player for SVGPlayer {
                 ),
             );
 
+        match options.size {
+            OutputSize::Auto => {}
+            OutputSize::Pixels { width, height } => {
+                document = document
+                    .set("width", width.to_string())
+                    .set("height", height.to_string())
+                    .set("preserveAspectRatio", "none");
+            }
+            // The frame is in .01 millimeter units, 2540 to the inch.
+            OutputSize::Dpi(dpi) => {
+                let pixels = |v: u32| (v as f32 / 2540.0 * dpi).to_string();
+
+                document = document
+                    .set("width", pixels(frame.cx))
+                    .set("height", pixels(frame.cy));
+            }
+        }
+
         if !definitions.is_empty() {
             let mut defs = Node::new("defs");
             for v in definitions {
@@ -121,13 +1057,32 @@ impl crate::converter::Player for SVGPlayer {
             document = document.add(defs);
         }
 
+        if let Some(background) = options.background {
+            document = document.add(
+                Node::new("rect")
+                    .set("x", window.origin.x.to_string())
+                    .set("y", window.origin.y.to_string())
+                    .set("width", window.extent.cx.to_string())
+                    .set("height", window.extent.cy.to_string())
+                    .set("fill", background),
+            );
+        }
+
         for v in elements {
             document = document.add(v);
         }
 
+        if let Some(precision) = options.precision {
+            document.round_numbers(precision);
+        }
+
         Ok(document.to_string().into_bytes())
     }
 
+    fn take_diagnostics(&mut self) -> Diagnostics {
+        core::mem::take(&mut self.diagnostics)
+    }
+
     // .
     // .
     // Functions to handle Bitmap Record
@@ -139,10 +1094,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn alpha_blend(
-        mut self,
+        &mut self,
         record_number: usize,
-        record: EMR_ALPHABLEND,
-    ) -> Result<Self, PlayError> {
+        record: EMR_ALPHABLEND<'_>,
+    ) -> Result<(), PlayError> {
         let dib_header_info = {
             let mut buf = &record.bmi_src[..];
             let (dib_header_info, _) =
@@ -152,6 +1107,9 @@ impl crate::converter::Player for SVGPlayer {
 
             dib_header_info
         };
+
+        self.count_bitmap_pixels(&dib_header_info)?;
+
         let colors = {
             let mut buf =
                 &record.bmi_src[dib_header_info.header_size() as usize..];
@@ -164,7 +1122,7 @@ impl crate::converter::Player for SVGPlayer {
                 cause: err.to_string(),
             })?;
 
-            colors
+            self.resolve_dib_colors(colors)
         };
 
         let (width, height) =
@@ -174,7 +1132,7 @@ impl crate::converter::Player for SVGPlayer {
                 dib_header_info,
                 colors,
                 bitmap_buffer: wmf_core::parser::BitmapBuffer {
-                    a_data: record.bits_src,
+                    a_data: record.bits_src.into_owned(),
                 },
             }
             .into();
@@ -189,11 +1147,11 @@ impl crate::converter::Player for SVGPlayer {
             .set("y", point.y.to_string())
             .set("width", width.to_string())
             .set("height", height.to_string())
-            .set("href", bitmap.as_data_url());
+            .set("href", self.image_href(&bitmap));
 
-        self.push_element(record_number, image);
+        self.push_element(record_number, image)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -202,10 +1160,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn bit_blt(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_BITBLT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         use wmf_core::parser::TernaryRasterOperation;
 
         if record.cx_dest == 0 || record.cy_dest == 0 {
@@ -214,7 +1172,7 @@ impl crate::converter::Player for SVGPlayer {
                 cy_dest = %record.cy_dest,
                 "EMR_BITBLT is skipped because cx_dest or cy_dest is zero.",
             );
-            return Ok(self);
+            return Ok(());
         }
 
         // Transform both corners and derive width/height from the
@@ -224,14 +1182,14 @@ impl crate::converter::Player for SVGPlayer {
             .context
             .transform_point_l(&PointL { x: record.x_dest, y: record.y_dest });
         let bottom_right = self.context.transform_point_l(&PointL {
-            x: record.x_dest + record.cx_dest,
-            y: record.y_dest + record.cy_dest,
+            x: record.x_dest.saturating_add(record.cx_dest),
+            y: record.y_dest.saturating_add(record.cy_dest),
         });
 
         let x = top_left.x.min(bottom_right.x);
         let y = top_left.y.min(bottom_right.y);
-        let width = (bottom_right.x - top_left.x).abs();
-        let height = (bottom_right.y - top_left.y).abs();
+        let width = bottom_right.x.abs_diff(top_left.x);
+        let height = bottom_right.y.abs_diff(top_left.y);
 
         // Approximate ROP3 operations that do not consume a source
         // bitmap as a filled rectangle. Source-dependent operations
@@ -251,8 +1209,12 @@ impl crate::converter::Player for SVGPlayer {
                 Fill::Value { value } => value,
             },
             op => {
-                info!(?op, "EMR_BITBLT raster operation is not implemented",);
-                return Ok(self);
+                self.approximated(
+                    record_number,
+                    record.record_type,
+                    format!("raster operation {op:?} is not drawn"),
+                );
+                return Ok(());
             }
         };
 
@@ -264,9 +1226,39 @@ impl crate::converter::Player for SVGPlayer {
             .set("width", width.to_string())
             .set("height", height.to_string());
 
-        self.push_element(record_number, rect);
+        if let Some(raster) = self.raster.as_mut() {
+            let color = match record.bit_blt_raster_operation {
+                TernaryRasterOperation::BLACKNESS => Some(ColorRef::black()),
+                TernaryRasterOperation::WHITENESS => Some(ColorRef::white()),
+                _ => match &self.selected_emf_object.brush {
+                    LogBrushEx::Solid { color } => {
+                        Some(self.context.resolve_color(color))
+                    }
+                    _ => None,
+                },
+            };
+
+            if let Some(color) = color {
+                let mut path = Path::new();
+
+                path.add_polygon(
+                    [
+                        (top_left.x, top_left.y),
+                        (bottom_right.x, top_left.y),
+                        (bottom_right.x, bottom_right.y),
+                        (top_left.x, bottom_right.y),
+                    ]
+                    .into_iter()
+                    .map(|(x, y)| PointF::new(f64::from(x), f64::from(y)))
+                    .collect(),
+                );
+                raster.fill_path(&path, false, &color);
+            }
+        }
+
+        self.push_element(record_number, rect)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -275,12 +1267,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn mask_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_MASKBLT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_MASKBLT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -289,12 +1281,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn plg_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PLGBLT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_PLGBLT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -303,12 +1295,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_dibits_to_device(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETDIBITSTODEVICE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETDIBITSTODEVICE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -317,12 +1309,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn stretch_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_STRETCHBLT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_STRETCHBLT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -331,19 +1323,59 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn stretch_dibits(
-        mut self,
+        &mut self,
         record_number: usize,
-        record: EMR_STRETCHDIBITS,
-    ) -> Result<Self, PlayError> {
-        let dib_header_info = {
-            let mut buf = &record.bmi_src[..];
-            let (dib_header_info, _) =
-                wmf_core::parser::BitmapInfoHeader::parse(&mut buf).map_err(
-                    |err| PlayError::InvalidRecord { cause: err.to_string() },
-                )?;
-
-            dib_header_info
+        mut record: EMR_STRETCHDIBITS<'_>,
+    ) -> Result<(), PlayError> {
+        let top_left = self
+            .context
+            .transform_point_l(&PointL { x: record.x_dest, y: record.y_dest });
+        let bottom_right = self.context.transform_point_l(&PointL {
+            x: record.x_dest.saturating_add(record.cx_dest),
+            y: record.y_dest.saturating_add(record.cy_dest),
+        });
+        let (width, height) = (
+            bottom_right.x.abs_diff(top_left.x),
+            bottom_right.y.abs_diff(top_left.y),
+        );
+        let stretch_mode =
+            self.context.graphics_environment.drawing.stretch_blt_mode;
+        let parse_header = |bmi_src: &[u8]| {
+            wmf_core::parser::BitmapInfoHeader::parse(&mut &bmi_src[..])
+                .map(|(dib_header_info, _)| dib_header_info)
+                .map_err(|err| PlayError::InvalidRecord {
+                    cause: err.to_string(),
+                })
         };
+        let mut dib_header_info = parse_header(&record.bmi_src)?;
+
+        self.count_bitmap_pixels(&dib_header_info)?;
+
+        // Reduce the bitmap to the destination when a viewer would not
+        // combine the eliminated pixels the same way, or when asked to keep
+        // the embedded image small. Only whole bitmaps are reduced.
+        if let Some(dib) = PackedDib::new(&record.bmi_src, &record.bits_src)
+            && (record.x_src, record.y_src) == (0, 0)
+            && usize::try_from(record.cx_src) == Ok(dib.width())
+            && usize::try_from(record.cy_src) == Ok(dib.height())
+            && ((width as usize) < dib.width()
+                || (height as usize) < dib.height())
+            && (self.resample_bitmaps
+                || dib.is_monochrome()
+                    && matches!(
+                        stretch_mode,
+                        StretchMode::STRETCH_ANDSCANS
+                            | StretchMode::STRETCH_ORSCANS
+                    ))
+        {
+            let (bmi_src, bits_src) =
+                dib.reduce(width as usize, height as usize, stretch_mode);
+
+            record.bmi_src = bmi_src.into();
+            record.bits_src = bits_src.into();
+            dib_header_info = parse_header(&record.bmi_src)?;
+        }
+
         let colors = {
             let mut buf =
                 &record.bmi_src[dib_header_info.header_size() as usize..];
@@ -356,36 +1388,51 @@ impl crate::converter::Player for SVGPlayer {
                 cause: err.to_string(),
             })?;
 
-            colors
+            self.resolve_dib_colors(colors)
         };
 
-        let (width, height) =
-            (dib_header_info.width(), dib_header_info.height());
         let bitmap: wmf_core::converter::Bitmap =
             wmf_core::parser::DeviceIndependentBitmap {
                 dib_header_info,
                 colors,
                 bitmap_buffer: wmf_core::parser::BitmapBuffer {
-                    a_data: record.bits_src,
+                    a_data: record.bits_src.into_owned(),
                 },
             }
             .into();
-
-        let point = self.context.transform_point_l(&wmf_core::parser::PointL {
-            x: record.x_dest,
-            y: record.y_dest,
-        });
-
-        let image = Node::new("image")
-            .set("x", point.x.to_string())
-            .set("y", point.y.to_string())
+        let href = self.image_href(&bitmap);
+
+        // Viewers smooth scaled images unless told otherwise, which only
+        // matches STRETCH_HALFTONE. The other modes drop or combine whole
+        // pixels.
+        let image_rendering = match stretch_mode {
+            StretchMode::STRETCH_HALFTONE => "optimizeQuality",
+            _ => "pixelated",
+        };
+        let mut image = Node::new("image")
+            .set("x", top_left.x.min(bottom_right.x).to_string())
+            .set("y", top_left.y.min(bottom_right.y).to_string())
             .set("width", width.to_string())
             .set("height", height.to_string())
-            .set("href", bitmap.as_data_url());
+            .set("preserveAspectRatio", "none")
+            .set("image-rendering", image_rendering)
+            .set("href", href);
+
+        // The color adjustment only applies to halftoned bitmaps.
+        if stretch_mode == StretchMode::STRETCH_HALFTONE
+            && let Some(filter) = color_adjustment_filter(
+                &self.context.graphics_environment.color.color_adjustment,
+            )
+        {
+            let id = self.generate_definition_id();
 
-        self.push_element(record_number, image);
+            self.definitions.push(filter.set("id", id.as_str()));
+            image = image.set("filter", url_string(format!("#{id}").as_str()));
+        }
+
+        self.push_element(record_number, image)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -394,12 +1441,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn transparent_blt(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_TRANSPARENTBLT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_TRANSPARENTBLT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     // .
@@ -413,12 +1460,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn exclude_clip_rect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXCLUDECLIPRECT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_EXCLUDECLIPRECT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -427,12 +1474,21 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_select_clip_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTSELECTCLIPRGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_EXTSELECTCLIPRGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // MS-EMF 2.3.2.2: RGN_COPY without a region restores the default
+        // clipping region, the whole surface.
+        if record.region_mode == RegionMode::RGN_COPY
+            && record.rgn_data.is_empty()
+        {
+            self.clip = None;
+            return Ok(());
+        }
+
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -441,12 +1497,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn intersect_clip_rect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_INTERSECTCLIPRECT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_INTERSECTCLIPRECT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -455,12 +1511,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn offset_clip_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_OFFSETCLIPRGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_OFFSETCLIPRGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -469,12 +1525,66 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn select_clip_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SELECTCLIPPATH,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SELECTCLIPPATH: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // MS-EMF 2.3.2.5: the path is combined with the clipping region
+        // and then discarded.
+        let path = core::mem::take(&mut self.path);
+
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        let shape = Node::new("path")
+            .set(
+                "clip-rule",
+                polygon_fill_rule(
+                    self.context.graphics_environment.drawing.polyfill_mode,
+                ),
+            )
+            .set("d", Data::from(&path).to_string());
+        let shapes = match (record.region_mode, self.clip.take()) {
+            (RegionMode::RGN_COPY, _) | (RegionMode::RGN_AND, None) => {
+                vec![shape]
+            }
+            // The intersection clips the path with the current region.
+            (RegionMode::RGN_AND, Some(clip)) => vec![shape.set(
+                "clip-path",
+                url_string(format!("#{}", clip.id).as_str()),
+            )],
+            (RegionMode::RGN_OR, Some(clip)) => {
+                let mut shapes = clip.shapes;
+                shapes.push(shape);
+                shapes
+            }
+            // Without a clipping region the whole surface is visible, and
+            // so is its union with the path.
+            (RegionMode::RGN_OR, None) => return Ok(()),
+            (mode, clip) => {
+                self.approximated(
+                    record_number,
+                    record.record_type,
+                    format!(
+                        "region mode {mode:?} is not supported for clip paths"
+                    ),
+                );
+                self.clip = clip;
+                return Ok(());
+            }
+        };
+
+        let id = self.generate_definition_id();
+        let clip_path = shapes
+            .iter()
+            .cloned()
+            .fold(Node::new("clipPath").set("id", id.as_str()), Node::add);
+
+        self.definitions.push(clip_path);
+        self.clip = Some(ClipRegion { id, shapes });
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -483,12 +1593,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_meta_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMETARGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETMETARGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     // .
@@ -502,12 +1612,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn comment(
-        self,
+        &mut self,
         record_number: usize,
-        record: EMR_COMMENT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_COMMENT: not implemented");
-        Ok(self)
+        record: EMR_COMMENT<'_>,
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     // .
@@ -521,11 +1631,11 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn eof(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EOF,
-    ) -> Result<Self, PlayError> {
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -534,12 +1644,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn header(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_HEADER,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.emf_object_table =
-            EmfObjectTable::new(record.emf_header.handles as usize);
+            EmfObjectTable::new(self.options.limits.max_objects);
 
         // Picture-frame rectangle in device units. Used for the SVG
         // viewBox so the recorded drawing maps directly to that area.
@@ -554,6 +1664,21 @@ impl crate::converter::Player for SVGPlayer {
             y: record.emf_header.bounds.top,
         };
 
+        self.frame = SizeL {
+            cx: record
+                .emf_header
+                .frame
+                .right
+                .saturating_sub(record.emf_header.frame.left)
+                .unsigned_abs(),
+            cy: record
+                .emf_header
+                .frame
+                .bottom
+                .saturating_sub(record.emf_header.frame.top)
+                .unsigned_abs(),
+        };
+
         let color = if let Some(record_buffer) = record.emf_header_record_buffer
         {
             let pixel_format = match record_buffer {
@@ -573,6 +1698,22 @@ impl crate::converter::Player for SVGPlayer {
             PlaybackStateColors::default()
         };
 
+        if self.flood_fill {
+            self.raster = Raster::new(
+                viewbox_origin.x,
+                viewbox_origin.y,
+                viewbox_extent.cx,
+                viewbox_extent.cy,
+            );
+
+            if self.raster.is_none() {
+                warn!(
+                    ?viewbox_extent,
+                    "picture frame is too large to flood fill in",
+                );
+            }
+        }
+
         self.window =
             Window { extent: viewbox_extent.clone(), origin: viewbox_origin };
         // The window-to-viewport mapping starts as identity (Windows
@@ -600,7 +1741,7 @@ impl crate::converter::Player for SVGPlayer {
             drawing: PlaybackStateDrawing::default(),
         };
 
-        Ok(self)
+        Ok(())
     }
 
     // .
@@ -614,12 +1755,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn angle_arc(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ANGLEARC,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_ANGLEARC: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -628,12 +1769,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn arc(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ARC,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_ARC: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -642,12 +1783,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn arc_to(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ARCTO,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_ARCTO: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -656,12 +1797,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn chord(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CHORD,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_CHORD: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -670,10 +1811,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ellipse(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_ELLIPSE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // Transform both corners of the bounding box and derive the
         // center and radii from them. The radius is a direction vector,
         // so transforming it as a point would double-apply the
@@ -690,8 +1831,8 @@ impl crate::converter::Player for SVGPlayer {
                 y: record.bx.bottom,
             });
 
-        let rx = (bottom_right.x - top_left.x).abs() / 2;
-        let ry = (bottom_right.y - top_left.y).abs() / 2;
+        let rx = bottom_right.x.abs_diff(top_left.x) / 2;
+        let ry = bottom_right.y.abs_diff(top_left.y) / 2;
 
         if rx == 0 || ry == 0 {
             info!(
@@ -699,12 +1840,22 @@ impl crate::converter::Player for SVGPlayer {
                 "EMR_ELLIPSE is skipped because rx or ry is zero.",
             );
 
-            return Ok(self);
+            return Ok(());
         }
 
         let cx = i32::midpoint(top_left.x, bottom_right.x);
         let cy = i32::midpoint(top_left.y, bottom_right.y);
 
+        // Inside a path bracket the ellipse is a closed figure of the path.
+        if self.context.graphics_environment.drawing.path_bracket {
+            self.path.add_ellipse(
+                PointF::new(f64::from(cx), f64::from(cy)),
+                f64::from(rx),
+                f64::from(ry),
+            );
+            return Ok(());
+        }
+
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
         let fill = match Fill::from(
             &self.context,
@@ -730,9 +1881,20 @@ impl crate::converter::Player for SVGPlayer {
             .set("ry", ry.to_string());
         let ellipse = stroke.set_props(&self.context, ellipse);
 
-        self.push_element(record_number, ellipse);
+        if self.raster.is_some() {
+            let mut path = Path::new();
+
+            path.add_ellipse(
+                PointF::new(f64::from(cx), f64::from(cy)),
+                f64::from(rx),
+                f64::from(ry),
+            );
+            self.rasterize(&path, true, true);
+        }
+
+        self.push_shape(record_number, ellipse)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -741,12 +1903,60 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_flood_fill(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTFLOODFILL,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_EXTFLOODFILL: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        let Some(ref raster) = self.raster else {
+            self.approximated(
+                record_number,
+                record.record_type,
+                "flood fill skipped without SVGPlayer::with_flood_fill",
+            );
+            return Ok(());
+        };
+
+        let start = self.context.transform_point_l(&record.start);
+        let Some(area) = raster.flood_fill(
+            start.x,
+            start.y,
+            &self.context.resolve_color(&record.color),
+            record.flood_fill_mode,
+        ) else {
+            // The raster copy lacks text and bitmaps, so the start pixel may
+            // be one the drawing would fill.
+            self.approximated(
+                record_number,
+                record.record_type,
+                format!(
+                    "flood fill skipped as ({}, {}) is a pixel it does not \
+                     fill",
+                    start.x, start.y,
+                ),
+            );
+            return Ok(());
+        };
+
+        let fill = match Fill::from(
+            &self.context,
+            self.selected_emf_object.brush.clone(),
+        ) {
+            Fill::Pattern { pattern } => {
+                let id = self.generate_definition_id();
+                self.definitions.push(pattern.set("id", id.as_str()));
+                url_string(format!("#{id}").as_str())
+            }
+            Fill::Value { value } => value,
+        };
+        let path = Node::new("path")
+            .set("fill", fill.as_str())
+            .set("stroke", "none")
+            .set("d", Data::from(&area).to_string());
+
+        self.rasterize(&area, true, false);
+        self.push_shape(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -755,12 +1965,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_text_out_a(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTTEXTOUTA,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_EXTTEXTOUTA: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.push_text(record_number, &record.a_emr_text)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -769,39 +1980,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_text_out_w(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTTEXTOUTW,
-    ) -> Result<Self, PlayError> {
-        let font = if let Some(ref font) = self.selected_emf_object.font_ex_dv {
-            &font.log_font_ex.log_font
-        } else if let Some(ref font) = self.selected_emf_object.font {
-            font
-        } else {
-            return Err(PlayError::UnexpectedGraphicsObject {
-                cause: "font is not selected".to_owned(),
-            });
-        };
-        let color = color_from_color_ref(
-            &self.context.graphics_environment.drawing.text_color,
-        );
-        let alignment = self.context.graphics_environment.text.text_alignment;
-        let point =
-            self.context.transform_point_l(&record.w_emr_text.reference);
-
-        let text = Node::new("text")
-            .set("x", point.x.to_string())
-            .set("y", point.y.to_string())
-            .set("text-anchor", text_align(alignment))
-            .set("dominant-baseline", text_baseline(alignment))
-            .set("fill", color)
-            .add(Node::new_text(record.w_emr_text.string_buffer));
-        let (text, styles) = font.set_props(&self.context, text, &point);
-        let text = text.set("style", styles.join(""));
+    ) -> Result<(), PlayError> {
+        self.push_text(record_number, &record.w_emr_text)?;
 
-        self.push_element(record_number, text);
-
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -810,13 +1995,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn fill_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_FILLPATH,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.path_bracket = false;
         if self.path.is_empty() {
-            return Ok(self);
+            return Ok(());
         }
 
         let fill = match Fill::from(
@@ -837,12 +2022,13 @@ impl crate::converter::Player for SVGPlayer {
         let path = Node::new("path")
             .set("fill", fill.as_str())
             .set("fill-rule", fill_rule.as_str())
-            .set("d", self.path.to_string());
+            .set("d", Data::from(&self.path).to_string());
+        let filled = core::mem::take(&mut self.path);
 
-        self.push_element(record_number, path);
-        self.path = Data::new();
+        self.rasterize(&filled, true, false);
+        self.push_shape(record_number, path)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -851,12 +2037,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn fill_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FILLRGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_FILLRGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -865,12 +2051,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn frame_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_FRAMERGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_FRAMERGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -879,12 +2065,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn gradient_fill(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_GRADIENTFILL,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_GRADIENTFILL: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -893,10 +2079,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn line_to(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_LINETO,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // Transform the start (current position, in logical coords)
         // and the end point.
         let from = self.context.transform_point_l(
@@ -912,7 +2098,11 @@ impl crate::converter::Player for SVGPlayer {
         if self.context.graphics_environment.drawing.path_bracket {
             // Inside a path bracket, accumulate into the shared path
             // instead of emitting an element.
-            self.path = self.path.clone().line_to(format!("{} {}", to.x, to.y));
+            if self.path.is_empty() {
+                self.path.move_to(PointF::from(&from));
+            }
+
+            self.path.line_to(PointF::from(&to));
         } else {
             // Outside a path bracket the line must be rendered
             // immediately.
@@ -925,10 +2115,18 @@ impl crate::converter::Player for SVGPlayer {
                 .set("d", data.to_string());
             let path = stroke.set_props(&self.context, path);
 
-            self.push_element(record_number, path);
+            if self.raster.is_some() {
+                let mut line = Path::new();
+
+                line.move_to(PointF::from(&from));
+                line.line_to(PointF::from(&to));
+                self.rasterize(&line, false, true);
+            }
+
+            self.push_shape(record_number, path)?;
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -937,12 +2135,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn paint_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PAINTRGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_PAINTRGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -951,12 +2149,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn pie(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_PIE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_PIE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -965,15 +2163,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_bezier(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIER,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
+        let mut path = self.take_path();
+
         // MS-EMF 2.3.5.16: aPoints[0] is the starting point of the
         // first Bezier curve. Emit a SVG moveto so the path data
         // begins with M (an SVG path that starts with a curveto is
@@ -984,8 +2184,7 @@ impl crate::converter::Player for SVGPlayer {
             });
         };
         let point = self.context.transform_point_l(point);
-        self.path =
-            self.path.clone().move_to(format!("{} {}", point.x, point.y));
+        path.move_to(PointF::from(&point));
 
         let mut c = vec![];
 
@@ -1000,22 +2199,19 @@ impl crate::converter::Player for SVGPlayer {
                 point.clone();
 
             let point = self.context.transform_point_l(point);
-            c.extend([point.x, point.y]);
-
-            if c.len() % 3 == 0 {
-                self.path = self.path.curve_to(
-                    c.iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .join(" "),
-                );
+            c.push(PointF::from(&point));
+
+            if let [c1, c2, to] = c[..] {
+                path.bezier_to(c1, c2, to);
 
                 // reset for next curve.
                 c = vec![];
             }
         }
 
-        Ok(self)
+        self.finish_path(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1024,15 +2220,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_bezier_16(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIER16,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
+        let mut path = self.take_path();
+
         // MS-EMF 2.3.5.17: aPoints[0] is the starting point of the
         // first Bezier curve. Emit a SVG moveto so the path data
         // begins with M (an SVG path that starts with a curveto is
@@ -1043,8 +2241,7 @@ impl crate::converter::Player for SVGPlayer {
             });
         };
         let point = self.context.transform_point_s(point);
-        self.path =
-            self.path.clone().move_to(format!("{} {}", point.x, point.y));
+        path.move_to(PointF::from(&point));
 
         let mut c = vec![];
 
@@ -1059,20 +2256,17 @@ impl crate::converter::Player for SVGPlayer {
                 point_s_to_point_l(point);
 
             let point = self.context.transform_point_s(point);
-            c.extend([point.x, point.y]);
-
-            if c.len() % 3 == 0 {
-                self.path = self.path.curve_to(
-                    c.iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .join(" "),
-                );
+            c.push(PointF::from(&point));
+
+            if let [c1, c2, to] = c[..] {
+                path.bezier_to(c1, c2, to);
                 c = vec![];
             }
         }
 
-        Ok(self)
+        self.finish_path(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1081,33 +2275,24 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_bezier_to(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIERTO,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
+        let mut path = self.take_path();
+
         // MS-EMF 2.3.5.18: the curve starts from the current position.
         // When the shared path buffer is still empty (e.g. PolyBezierTo
         // is the first command after BeginPath, or it is emitted
-        // outside any path bracket), seed it with M(current position)
-        // so the resulting path data does not start with a curveto,
-        // which is invalid SVG.
-        if self.path.is_empty() {
-            let start = self.context.transform_point_l(
-                &self
-                    .context
-                    .graphics_environment
-                    .drawing
-                    .current_position
-                    .clone(),
-            );
-            self.path =
-                self.path.clone().move_to(format!("{} {}", start.x, start.y));
-        }
+        // outside any path bracket), start the figure at the current
+        // position so the resulting path data does not start with a
+        // curveto, which is invalid SVG.
+        self.start_at_current_position(&mut path);
 
         let mut c = vec![];
 
@@ -1122,20 +2307,17 @@ impl crate::converter::Player for SVGPlayer {
                 point.clone();
 
             let point = self.context.transform_point_l(point);
-            c.extend([point.x, point.y]);
-
-            if c.len() % 3 == 0 {
-                self.path = self.path.curve_to(
-                    c.iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .join(" "),
-                );
+            c.push(PointF::from(&point));
+
+            if let [c1, c2, to] = c[..] {
+                path.bezier_to(c1, c2, to);
                 c = vec![];
             }
         }
 
-        Ok(self)
+        self.finish_path(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1144,33 +2326,24 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_bezier_to_16(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYBEZIERTO16,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
+        let mut path = self.take_path();
+
         // MS-EMF 2.3.5.19: the curve starts from the current position.
         // When the shared path buffer is still empty (e.g. PolyBezierTo16
         // is the first command after BeginPath, or it is emitted
-        // outside any path bracket), seed it with M(current position)
-        // so the resulting path data does not start with a curveto,
-        // which is invalid SVG.
-        if self.path.is_empty() {
-            let start = self.context.transform_point_l(
-                &self
-                    .context
-                    .graphics_environment
-                    .drawing
-                    .current_position
-                    .clone(),
-            );
-            self.path =
-                self.path.clone().move_to(format!("{} {}", start.x, start.y));
-        }
+        // outside any path bracket), start the figure at the current
+        // position so the resulting path data does not start with a
+        // curveto, which is invalid SVG.
+        self.start_at_current_position(&mut path);
 
         let mut c = vec![];
 
@@ -1185,20 +2358,17 @@ impl crate::converter::Player for SVGPlayer {
                 point_s_to_point_l(point);
 
             let point = self.context.transform_point_s(point);
-            c.extend([point.x, point.y]);
-
-            if c.len() % 3 == 0 {
-                self.path = self.path.curve_to(
-                    c.iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .join(" "),
-                );
+            c.push(PointF::from(&point));
+
+            if let [c1, c2, to] = c[..] {
+                path.bezier_to(c1, c2, to);
                 c = vec![];
             }
         }
 
-        Ok(self)
+        self.finish_path(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1207,12 +2377,22 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_draw(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYDRAW,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_POLYDRAW: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        if record.count == 0 {
+            info!(%record.count, "polydraw has no points");
+            return Ok(());
+        }
+
+        self.poly_draw_points(
+            record_number,
+            &record.a_points,
+            &record.ab_types,
+        )?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1221,26 +2401,21 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_draw_16(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYDRAW16,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_POLYDRAW16: not implemented");
-        Ok(self)
-    }
+    ) -> Result<(), PlayError> {
+        if record.count == 0 {
+            info!(%record.count, "polydraw has no points");
+            return Ok(());
+        }
 
-    #[cfg_attr(feature = "tracing", tracing::instrument(
-        level = tracing::Level::TRACE,
-        skip(self),
-        err(level = tracing::Level::ERROR, Display),
-    ))]
-    fn poly_polygon(
-        self,
-        record_number: usize,
-        record: EMR_POLYPOLYGON,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_POLYPOLYGON: not implemented");
-        Ok(self)
+        let points =
+            record.a_points.iter().map(point_s_to_point_l).collect::<Vec<_>>();
+
+        self.poly_draw_points(record_number, &points, &record.ab_types)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1248,73 +2423,52 @@ impl crate::converter::Player for SVGPlayer {
         skip(self),
         err(level = tracing::Level::ERROR, Display),
     ))]
-    fn poly_polygon_16(
-        mut self,
-        record_number: usize,
-        record: EMR_POLYPOLYGON16,
-    ) -> Result<Self, PlayError> {
-        if record.number_of_polygons == 0 || record.count == 0 {
-            info!(%record.number_of_polygons, %record.count, "polygon has no points");
-            return Ok(self);
-        }
-
-        let stroke = Stroke::from(self.selected_emf_object.pen.clone());
-        let fill = match Fill::from(
-            &self.context,
-            self.selected_emf_object.brush.clone(),
-        ) {
-            Fill::Pattern { pattern } => {
-                let id = self.generate_definition_id();
-                self.definitions.push(pattern.set("id", id.as_str()));
-                url_string(format!("#{id}").as_str())
-            }
-            Fill::Value { value } => value,
-        };
-        let fill_rule = polygon_fill_rule(
-            self.context.graphics_environment.drawing.polyfill_mode,
-        );
-
-        let mut a_point: VecDeque<_> = record.a_points.into();
-        let mut current_point_index = 0;
-
-        for i in 0..record.number_of_polygons {
-            let Some(points_of_polygon) =
-                record.polygon_point_count.get(i as usize)
-            else {
-                return Err(PlayError::InvalidRecord {
-                    cause: format!("PolygonPointCount[{i}] is not defined"),
-                });
-            };
-
-            let mut points = vec![];
-
-            for _ in 0..*points_of_polygon {
-                let Some(point) = a_point.pop_front() else {
-                    return Err(PlayError::InvalidRecord {
-                        cause: format!(
-                            "aPoints[{current_point_index}] is not defined"
-                        ),
-                    });
-                };
-
-                self.context.graphics_environment.drawing.current_position =
-                    point_s_to_point_l(&point);
+    fn poly_polygon(
+        &mut self,
+        record_number: usize,
+        record: EMR_POLYPOLYGON,
+    ) -> Result<(), PlayError> {
+        if record.number_of_polygons == 0 || record.count == 0 {
+            info!(%record.number_of_polygons, %record.count, "polygon has no points");
+            return Ok(());
+        }
 
-                let point = self.context.transform_point_s(&point);
-                points.push(as_point_string_from_point_s(&point));
-                current_point_index += 1;
-            }
+        self.poly_polygon_points(
+            record_number,
+            record.number_of_polygons,
+            &record.polygon_point_count,
+            record.a_points,
+        )?;
 
-            let polygon = Node::new("polygon")
-                .set("fill", fill.as_str())
-                .set("fill-rule", fill_rule.as_str())
-                .set("points", points.join(" "));
-            let polygon = stroke.set_props(&self.context, polygon);
+        Ok(())
+    }
 
-            self.push_element(record_number, polygon);
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn poly_polygon_16(
+        &mut self,
+        record_number: usize,
+        record: EMR_POLYPOLYGON16,
+    ) -> Result<(), PlayError> {
+        if record.number_of_polygons == 0 || record.count == 0 {
+            info!(%record.number_of_polygons, %record.count, "polygon has no points");
+            return Ok(());
         }
 
-        Ok(self)
+        let points =
+            record.a_points.iter().map(point_s_to_point_l).collect::<Vec<_>>();
+
+        self.poly_polygon_points(
+            record_number,
+            record.number_of_polygons,
+            &record.polygon_point_count,
+            points,
+        )?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1323,13 +2477,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_polyline(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYPOLYLINE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "poly_polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
@@ -1347,7 +2501,7 @@ impl crate::converter::Player for SVGPlayer {
                 continue;
             }
 
-            let mut data = Data::new();
+            let mut path = Path::new();
 
             let Some(first_point) = record.a_points.get(point_index) else {
                 return Err(PlayError::InvalidRecord {
@@ -1356,7 +2510,7 @@ impl crate::converter::Player for SVGPlayer {
             };
 
             let first_point = self.context.transform_point_l(first_point);
-            data = data.move_to(format!("{} {}", first_point.x, first_point.y));
+            path.move_to(PointF::from(&first_point));
 
             for j in 1..point_count {
                 let idx = point_index + j as usize;
@@ -1367,19 +2521,28 @@ impl crate::converter::Player for SVGPlayer {
                 };
 
                 let point = self.context.transform_point_l(point);
-                data = data.line_to(format!("{} {}", point.x, point.y));
+                path.line_to(PointF::from(&point));
+            }
+
+            point_index += point_count as usize;
+
+            // Inside a path bracket every polyline is a figure of the path.
+            if self.context.graphics_environment.drawing.path_bracket {
+                self.path.extend(path);
+                continue;
             }
 
+            self.rasterize(&path, false, true);
+
             let path = Node::new("path")
                 .set("fill", "none")
-                .set("d", data.to_string());
+                .set("d", Data::from(&path).to_string());
             let path = stroke.set_props(&self.context, path);
 
-            self.push_element(record_number, path);
-            point_index += point_count as usize;
+            self.push_shape(record_number, path)?;
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1388,13 +2551,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_polyline_16(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYPOLYLINE16,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "poly_polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
@@ -1412,7 +2575,7 @@ impl crate::converter::Player for SVGPlayer {
                 continue;
             }
 
-            let mut data = Data::new();
+            let mut path = Path::new();
 
             let Some(first_point) = record.a_points.get(point_index) else {
                 return Err(PlayError::InvalidRecord {
@@ -1421,7 +2584,7 @@ impl crate::converter::Player for SVGPlayer {
             };
 
             let first_point = self.context.transform_point_s(first_point);
-            data = data.move_to(format!("{} {}", first_point.x, first_point.y));
+            path.move_to(PointF::from(&first_point));
 
             for j in 1..point_count {
                 let idx = point_index + j as usize;
@@ -1432,19 +2595,28 @@ impl crate::converter::Player for SVGPlayer {
                 };
 
                 let point = self.context.transform_point_s(point);
-                data = data.line_to(format!("{} {}", point.x, point.y));
+                path.line_to(PointF::from(&point));
+            }
+
+            point_index += point_count as usize;
+
+            // Inside a path bracket every polyline is a figure of the path.
+            if self.context.graphics_environment.drawing.path_bracket {
+                self.path.extend(path);
+                continue;
             }
 
+            self.rasterize(&path, false, true);
+
             let path = Node::new("path")
                 .set("fill", "none")
-                .set("d", data.to_string());
+                .set("d", Data::from(&path).to_string());
             let path = stroke.set_props(&self.context, path);
 
-            self.push_element(record_number, path);
-            point_index += point_count as usize;
+            self.push_shape(record_number, path)?;
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1453,12 +2625,15 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_text_out_a(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYTEXTOUTA,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_POLYTEXTOUTA: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        for emr_text in &record.a_emr_text {
+            self.push_text(record_number, emr_text)?;
+        }
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1467,12 +2642,15 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn poly_text_out_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYTEXTOUTW,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_POLYTEXTOUTW: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        for emr_text in &record.w_emr_text {
+            self.push_text(record_number, emr_text)?;
+        }
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1481,13 +2659,25 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn polygon(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYGON,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polygon has no points");
-            return Ok(self);
+            return Ok(());
+        }
+
+        // Inside a path bracket the polygon is a closed figure of the path.
+        if self.context.graphics_environment.drawing.path_bracket {
+            let points = record
+                .a_points
+                .iter()
+                .map(|v| PointF::from(&self.context.transform_point_l(v)))
+                .collect();
+
+            self.path.add_polygon(points);
+            return Ok(());
         }
 
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
@@ -1507,6 +2697,7 @@ impl crate::converter::Player for SVGPlayer {
         );
 
         let mut points = vec![];
+        let mut shape = vec![];
 
         for i in 0..record.count {
             let Some(point) = record.a_points.get(i as usize) else {
@@ -1520,6 +2711,7 @@ impl crate::converter::Player for SVGPlayer {
 
             let point = self.context.transform_point_l(point);
             points.push(as_point_string_from_point_l(&point));
+            shape.push(PointF::from(&point));
         }
 
         let polygon = Node::new("polygon")
@@ -1528,9 +2720,16 @@ impl crate::converter::Player for SVGPlayer {
             .set("points", points.join(" "));
         let polygon = stroke.set_props(&self.context, polygon);
 
-        self.push_element(record_number, polygon);
+        if self.raster.is_some() {
+            let mut path = Path::new();
+
+            path.add_polygon(shape);
+            self.rasterize(&path, true, true);
+        }
+
+        self.push_shape(record_number, polygon)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1539,13 +2738,25 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn polygon_16(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYGON16,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polygon has no points");
-            return Ok(self);
+            return Ok(());
+        }
+
+        // Inside a path bracket the polygon is a closed figure of the path.
+        if self.context.graphics_environment.drawing.path_bracket {
+            let points = record
+                .a_points
+                .iter()
+                .map(|v| PointF::from(&self.context.transform_point_s(v)))
+                .collect();
+
+            self.path.add_polygon(points);
+            return Ok(());
         }
 
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
@@ -1565,6 +2776,7 @@ impl crate::converter::Player for SVGPlayer {
         );
 
         let mut points = vec![];
+        let mut shape = vec![];
 
         for i in 0..record.count {
             let Some(point) = record.a_points.get(i as usize) else {
@@ -1578,6 +2790,7 @@ impl crate::converter::Player for SVGPlayer {
 
             let point = self.context.transform_point_s(point);
             points.push(as_point_string_from_point_s(&point));
+            shape.push(PointF::from(&point));
         }
 
         let polygon = Node::new("polygon")
@@ -1586,9 +2799,16 @@ impl crate::converter::Player for SVGPlayer {
             .set("points", points.join(" "));
         let polygon = stroke.set_props(&self.context, polygon);
 
-        self.push_element(record_number, polygon);
+        if self.raster.is_some() {
+            let mut path = Path::new();
+
+            path.add_polygon(shape);
+            self.rasterize(&path, true, true);
+        }
+
+        self.push_shape(record_number, polygon)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1597,15 +2817,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn polyline(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
+        let mut path = self.take_path();
+
         let Some(point) = record.a_points.first() else {
             return Err(PlayError::InvalidRecord {
                 cause: "aPoints[0] is not defined".to_owned(),
@@ -1613,7 +2835,7 @@ impl crate::converter::Player for SVGPlayer {
         };
 
         let point = self.context.transform_point_l(point);
-        self.path = self.path.move_to(format!("{} {}", point.x, point.y));
+        path.move_to(PointF::from(&point));
 
         for i in 1..record.count {
             let Some(point) = record.a_points.get(i as usize) else {
@@ -1626,10 +2848,12 @@ impl crate::converter::Player for SVGPlayer {
                 point.clone();
 
             let point = self.context.transform_point_l(point);
-            self.path = self.path.line_to(format!("{} {}", point.x, point.y));
+            path.line_to(PointF::from(&point));
         }
 
-        Ok(self)
+        self.finish_path(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1638,29 +2862,25 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn polyline_16(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINE16,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
-        let mut data = Data::new();
-
-        data = {
-            let Some(point) = record.a_points.first() else {
-                return Err(PlayError::InvalidRecord {
-                    cause: "aPoints[0] is not defined".to_owned(),
-                });
-            };
-
-            let point = self.context.transform_point_s(point);
-
-            data.move_to(format!("{} {}", point.x, point.y))
+        let mut path = self.take_path();
+        let Some(point) = record.a_points.first() else {
+            return Err(PlayError::InvalidRecord {
+                cause: "aPoints[0] is not defined".to_owned(),
+            });
         };
 
+        let point = self.context.transform_point_s(point);
+        path.move_to(PointF::from(&point));
+
         for i in 1..record.count {
             let Some(point) = record.a_points.get(i as usize) else {
                 return Err(PlayError::InvalidRecord {
@@ -1669,17 +2889,12 @@ impl crate::converter::Player for SVGPlayer {
             };
 
             let point = self.context.transform_point_s(point);
-            data = data.line_to(format!("{} {}", point.x, point.y));
+            path.line_to(PointF::from(&point));
         }
 
-        let stroke = Stroke::from(self.selected_emf_object.pen.clone());
-        let path =
-            Node::new("path").set("fill", "none").set("d", data.to_string());
-        let path = stroke.set_props(&self.context, path);
-
-        self.push_element(record_number, path);
+        self.finish_path(record_number, path)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1688,15 +2903,19 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn polyline_to(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINETO,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
+        let mut path = self.take_path();
+
+        self.start_at_current_position(&mut path);
+
         for i in 0..record.count {
             let Some(point) = record.a_points.get(i as usize) else {
                 return Err(PlayError::InvalidRecord {
@@ -1708,10 +2927,12 @@ impl crate::converter::Player for SVGPlayer {
                 point.clone();
 
             let point = self.context.transform_point_l(point);
-            self.path = self.path.line_to(format!("{} {}", point.x, point.y));
+            path.line_to(PointF::from(&point));
         }
 
-        Ok(self)
+        self.finish_path(record_number, path)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1720,16 +2941,18 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn polyline_to_16(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_POLYLINETO16,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if record.count == 0 {
             info!(%record.count, "polyline has no points");
-            return Ok(self);
+            return Ok(());
         }
 
-        let mut data = self.path.clone();
+        let mut path = self.take_path();
+
+        self.start_at_current_position(&mut path);
 
         for i in 0..record.count {
             let Some(point) = record.a_points.get(i as usize) else {
@@ -1742,12 +2965,12 @@ impl crate::converter::Player for SVGPlayer {
                 point_s_to_point_l(point);
 
             let point = self.context.transform_point_s(point);
-            data = data.line_to(format!("{} {}", point.x, point.y));
+            path.line_to(PointF::from(&point));
         }
 
-        self.path = data;
+        self.finish_path(record_number, path)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1756,10 +2979,30 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn rectangle(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_RECTANGLE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
+        // Inside a path bracket the rectangle is a closed figure of the
+        // path.
+        if self.context.graphics_environment.drawing.path_bracket {
+            let bx = &record.bx;
+            let points = [
+                (bx.left, bx.top),
+                (bx.right, bx.top),
+                (bx.right, bx.bottom),
+                (bx.left, bx.bottom),
+            ]
+            .into_iter()
+            .map(|(x, y)| {
+                PointF::from(&self.context.transform_point_l(&PointL { x, y }))
+            })
+            .collect();
+
+            self.path.add_polygon(points);
+            return Ok(());
+        }
+
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
         let fill = match Fill::from(
             &self.context,
@@ -1796,9 +3039,21 @@ impl crate::converter::Player for SVGPlayer {
             .set("height", (bottom_right.y - top_left.y).to_string());
         let rect = stroke.set_props(&self.context, rect);
 
-        self.push_element(record_number, rect);
+        if self.raster.is_some() {
+            let mut path = Path::new();
+
+            path.add_polygon(vec![
+                PointF::from(&top_left),
+                PointF::new(f64::from(bottom_right.x), f64::from(top_left.y)),
+                PointF::from(&bottom_right),
+                PointF::new(f64::from(top_left.x), f64::from(bottom_right.y)),
+            ]);
+            self.rasterize(&path, true, true);
+        }
+
+        self.push_shape(record_number, rect)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1807,12 +3062,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn round_rect(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_ROUNDRECT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_ROUNDRECT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1821,12 +3076,28 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_pixel_v(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETPIXELV,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETPIXELV: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // The pixel is one device unit wide whatever the mapping mode.
+        let pixel = self.context.transform_point_l(&record.pixel);
+        let color = self.context.resolve_color(&record.color);
+        let rect = Node::new("rect")
+            .set("fill", color_from_color_ref(&color))
+            .set("stroke", "none")
+            .set("x", pixel.x.to_string())
+            .set("y", pixel.y.to_string())
+            .set("width", "1")
+            .set("height", "1");
+
+        if let Some(raster) = self.raster.as_mut() {
+            raster.set_pixel(pixel.x, pixel.y, &color);
+        }
+
+        self.push_element(record_number, rect)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1835,12 +3106,27 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn small_text_out(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SMALLTEXTOUT,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SMALLTEXTOUT: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // The parser already widened ETO_SMALL_CHARS strings to Unicode
+        // and kept ETO_GLYPH_INDEX strings as glyph indices, so the record
+        // is replayed as an EmrText without spacing.
+        let emr_text = EmrText {
+            reference: PointL { x: record.x, y: record.y },
+            chars: record.c_chars,
+            off_string: 0,
+            options: record.fu_options,
+            rectangle: record.bounds,
+            off_dx: 0,
+            string_buffer: record.text_string,
+            dx_buffer: vec![],
+        };
+
+        self.push_text(record_number, &emr_text)?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1849,12 +3135,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn stroke_and_fill_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_STROKEANDFILLPATH,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if self.path.is_empty() {
-            return Ok(self);
+            return Ok(());
         }
 
         let brush = &self.selected_emf_object.brush;
@@ -1874,13 +3160,14 @@ impl crate::converter::Player for SVGPlayer {
         let path = Node::new("path")
             .set("fill", fill.as_str())
             .set("fill-rule", fill_rule.as_str())
-            .set("d", self.path.to_string());
+            .set("d", Data::from(&self.path).to_string());
         let path = stroke.set_props(&self.context, path);
+        let drawn = core::mem::take(&mut self.path);
 
-        self.push_element(record_number, path);
-        self.path = Data::new();
+        self.rasterize(&drawn, true, true);
+        self.push_shape(record_number, path)?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1889,24 +3176,25 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn stroke_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_STROKEPATH,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if self.path.is_empty() {
-            return Ok(self);
+            return Ok(());
         }
 
         let stroke = Stroke::from(self.selected_emf_object.pen.clone());
         let path = Node::new("path")
             .set("fill", "none")
-            .set("d", self.path.to_string());
+            .set("d", Data::from(&self.path).to_string());
         let path = stroke.set_props(&self.context, path);
+        let drawn = core::mem::take(&mut self.path);
 
-        self.push_element(record_number, path);
-        self.path = Data::new();
+        self.rasterize(&drawn, false, true);
+        self.push_shape(record_number, path)?;
 
-        Ok(self)
+        Ok(())
     }
 
     // .
@@ -1920,12 +3208,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn draw_escape(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_DRAWESCAPE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_DRAWESCAPE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1934,12 +3222,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_escape(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTESCAPE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_EXTESCAPE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1948,12 +3236,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn named_escape(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_NAMEDESCAPE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_NAMEDESCAPE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     // .
@@ -1967,16 +3255,20 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_brush_indirect(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEBRUSHINDIRECT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
+        if let Some(description) = Fill::approximation(&record.log_brush) {
+            self.approximated(record_number, record.record_type, description);
+        }
+
         self.emf_object_table.set(
             record.ih_brush as usize,
             GraphicsObject::LogBrushEx(record.log_brush),
-        );
+        )?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1985,12 +3277,16 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_color_space(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATECOLORSPACE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_CREATECOLORSPACE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.emf_object_table.set(
+            record.ih_cs as usize,
+            GraphicsObject::LogColorSpace(record.lcs),
+        )?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1999,12 +3295,23 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_color_space_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATECOLORSPACEW,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_CREATECOLORSPACEW: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // Data holds a color profile if the first bit of flags is set.
+        let profile = if record.dw_flags & 0x0000_0001 == 0 {
+            vec![]
+        } else {
+            record.data
+        };
+
+        self.emf_object_table.set(
+            record.ih_cs as usize,
+            GraphicsObject::LogColorSpaceW(record.lcs, profile),
+        )?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2013,16 +3320,22 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_dib_pattern_brush_pt(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEDIBPATTERNBRUSHPT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.emf_object_table.set(
             record.ih_brush as usize,
-            GraphicsObject::DeviceIndependentBitmap(record.into()),
-        );
-
-        Ok(self)
+            GraphicsObject::DeviceIndependentBitmap(
+                record.try_into().map_err(
+                    |err: crate::parser::ParseError| PlayError::InvalidRecord {
+                        cause: err.to_string(),
+                    },
+                )?,
+            ),
+        )?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2031,12 +3344,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_mono_brush(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEMONOBRUSH,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_CREATEMONOBRUSH: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2045,12 +3358,16 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEPALETTE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_CREATEPALETTE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.emf_object_table.set(
+            record.ih_pal as usize,
+            GraphicsObject::LogPalette(record.log_palette),
+        )?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2059,16 +3376,20 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn create_pen(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_CREATEPEN,
-    ) -> Result<Self, PlayError> {
-        self.emf_object_table.set(
-            record.ih_pen as usize,
-            GraphicsObject::LogPenEx(record.log_pen.into()),
-        );
+    ) -> Result<(), PlayError> {
+        let pen = record.log_pen.into();
+
+        if let Some(description) = Stroke::approximation(&pen) {
+            self.approximated(record_number, record.record_type, description);
+        }
+
+        self.emf_object_table
+            .set(record.ih_pen as usize, GraphicsObject::LogPenEx(pen))?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2077,10 +3398,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_create_font_indirect_w(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTCREATEFONTINDIRECTW,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         let font = match record.elw {
             crate::parser::ELW::LogFontExDv(v) => {
                 v.first().expect("should be set").clone()
@@ -2089,9 +3410,9 @@ impl crate::converter::Player for SVGPlayer {
         };
 
         self.emf_object_table
-            .set(record.ih_fonts as usize, GraphicsObject::LogFontExDv(font));
+            .set(record.ih_fonts as usize, GraphicsObject::LogFontExDv(font))?;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2100,14 +3421,20 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_create_pen(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_EXTCREATEPEN,
-    ) -> Result<Self, PlayError> {
-        self.emf_object_table
-            .set(record.ih_pen as usize, GraphicsObject::LogPenEx(record.elp));
+    ) -> Result<(), PlayError> {
+        if let Some(description) = Stroke::approximation(&record.elp) {
+            self.approximated(record_number, record.record_type, description);
+        }
+
+        self.emf_object_table.set(
+            record.ih_pen as usize,
+            GraphicsObject::LogPenEx(record.elp),
+        )?;
 
-        Ok(self)
+        Ok(())
     }
 
     // .
@@ -2121,12 +3448,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn color_correct_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_COLORCORRECTPALETTE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_COLORCORRECTPALETTE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2135,12 +3462,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn delete_color_space(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_DELETECOLORSPACE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_DELETECOLORSPACE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.emf_object_table.delete(record.ih_cs as usize);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2149,12 +3476,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn delete_object(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_DELETEOBJECT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.emf_object_table.delete(record.in_object as usize);
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2163,12 +3490,22 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn resize_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_RESIZEPALETTE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_RESIZEPALETTE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        let mut palette = self.palette_object(record.ih_pal)?;
+
+        // Added entries are black until set.
+        palette.palette_entries.resize(
+            record.number_of_entries as usize,
+            LogPaletteEntry { reserved: 0, blue: 0, green: 0, red: 0 },
+        );
+        palette.number_of_entries = palette.palette_entries.len() as u16;
+        self.emf_object_table
+            .set(record.ih_pal as usize, GraphicsObject::LogPalette(palette))?;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2177,10 +3514,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn select_object(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SELECTOBJECT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         let emf_object =
             match self.emf_object_table.get(record.in_object as usize) {
                 GraphicsObject::Null => {
@@ -2210,12 +3547,6 @@ impl crate::converter::Player for SVGPlayer {
             GraphicsObject::LogBrushEx(v) => {
                 self.selected_emf_object.brush = v;
             }
-            // GraphicsObject::LogColorSpace(v) => {
-            //     self.selected_emf_object.color_space = v.into();
-            // }
-            // GraphicsObject::LogColorSpaceW(v) => {
-            //     self.selected_emf_object.color_space_w = v.into();
-            // }
             GraphicsObject::LogFont(v) => {
                 self.selected_emf_object.font = v.into();
             }
@@ -2238,7 +3569,7 @@ impl crate::converter::Player for SVGPlayer {
             }
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2247,12 +3578,29 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn select_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SELECTPALETTE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SELECTPALETTE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        let (palette, palette_index) = if record.in_pal
+            == StockObject::DEFAULT_PALETTE as u32
+        {
+            (LogPalette::default_palette(), None)
+        } else {
+            (self.palette_object(record.in_pal)?, Some(record.in_pal as usize))
+        };
+
+        // The colors are resolved against the selected palette even if the
+        // metafile does not realize it.
+        self.context
+            .graphics_environment
+            .color
+            .palette
+            .clone_from(&palette.palette_entries);
+        self.selected_emf_object.palette = Some(palette);
+        self.selected_emf_object.palette_index = palette_index;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2261,12 +3609,39 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_color_space(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETCOLORSPACE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETCOLORSPACE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        let transform = match self.emf_object_table.get(record.in_cs as usize) {
+            GraphicsObject::LogColorSpace(v) => {
+                let transform = ColorTransform::from_log_color_space(v);
+
+                self.selected_emf_object.color_space = Some(v.clone());
+                self.selected_emf_object.color_space_w = None;
+                transform
+            }
+            GraphicsObject::LogColorSpaceW(v, profile) => {
+                let transform =
+                    ColorTransform::from_log_color_space_w(v, profile);
+
+                self.selected_emf_object.color_space = None;
+                self.selected_emf_object.color_space_w = Some(v.clone());
+                transform
+            }
+            v => {
+                return Err(PlayError::UnexpectedGraphicsObject {
+                    cause: format!(
+                        "color space is not found: index={}, {v:?}",
+                        record.in_cs,
+                    ),
+                });
+            }
+        };
+
+        self.context.graphics_environment.color.color_transform = transform;
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2275,12 +3650,27 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_palette_entries(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETPALETTEENTRIES,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETPALETTEENTRIES: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        let mut palette = self.palette_object(record.ih_pal)?;
+        let start = record.start as usize;
+
+        // Entries past the end of the palette are ignored.
+        for (entry, v) in palette
+            .palette_entries
+            .iter_mut()
+            .skip(start)
+            .zip(record.a_pal_entries)
+        {
+            *entry = v;
+        }
+
+        self.emf_object_table
+            .set(record.ih_pal as usize, GraphicsObject::LogPalette(palette))?;
+
+        Ok(())
     }
 
     // .
@@ -2294,12 +3684,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn gls_bounded_record(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_GLSBOUNDEDRECORD,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_GLSBOUNDEDRECORD: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2308,12 +3698,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn gls_record(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_GLSRECORD,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_GLSRECORD: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     // .
@@ -2327,14 +3717,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn abort_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_ABORTPATH,
-    ) -> Result<Self, PlayError> {
-        self.path = Data::new();
+    ) -> Result<(), PlayError> {
+        self.path = Path::new();
         self.context.graphics_environment.drawing.path_bracket = false;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2343,10 +3733,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn begin_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_BEGINPATH,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         if self.context.graphics_environment.drawing.path_bracket {
             return Err(PlayError::InvalidRecord {
                 cause: "Path bracket construction MUST be closed by an \
@@ -2356,9 +3746,9 @@ impl crate::converter::Player for SVGPlayer {
         }
 
         self.context.graphics_environment.drawing.path_bracket = true;
-        self.path = Data::new();
+        self.path = Path::new();
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2367,17 +3757,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn close_figure(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_CLOSEFIGURE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // MS-EMF 2.3.10.3: CLOSEFIGURE only closes the current subpath
         // and does not end the path bracket; the bracket continues
         // until EndPath or AbortPath. Subsequent MoveToEx/LineTo
         // records must keep accumulating into the same path.
-        self.path = self.path.close();
+        self.path.close();
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2386,12 +3776,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn end_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_ENDPATH,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.path_bracket = false;
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2400,23 +3790,15 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn flatten_path(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_FLATTENPATH,
-    ) -> Result<Self, PlayError> {
-        if self.path.is_empty() {
-            return Ok(self);
-        }
-
-        let stroke = Stroke::from(self.selected_emf_object.pen.clone());
-        let path = Node::new("path")
-            .set("fill", "none")
-            .set("d", self.path.to_string());
-        let path = stroke.set_props(&self.context, path);
+    ) -> Result<(), PlayError> {
+        // MS-EMF 2.3.10.4: the curves of the path are replaced by lines;
+        // nothing is drawn until the path is filled or stroked.
+        self.path = self.path.flatten(FLATTEN_TOLERANCE);
 
-        self.push_element(record_number, path);
-
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2425,12 +3807,26 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn widen_path(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_WIDENPATH,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_WIDENPATH: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // MS-EMF 2.3.10.5: the path is replaced by the area painted when
+        // it is stroked with the selected pen, so filling it afterwards
+        // paints the stroke.
+        let miter_limit = self.miter_limit();
+        let Some(stroke) = StrokeGeometry::from_pen(
+            &self.selected_emf_object.pen,
+            f64::from(self.context.xform.calc_scale()),
+            miter_limit,
+        ) else {
+            info!("path is not widened with a cosmetic pen");
+            return Ok(());
+        };
+
+        self.path = self.path.widen(&stroke);
+
+        Ok(())
     }
 
     // .
@@ -2444,12 +3840,25 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn color_match_to_target_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_COLORMATCHTOTARGETW,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_COLORMATCHTOTARGETW: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        let colors = &mut self.context.graphics_environment.color;
+
+        match record.dw_action {
+            ColorSpace::CS_ENABLE => {
+                // Proofing shows how colors look on the target device, which
+                // the SVG cannot simulate, so colors are left as they are.
+                info!("EMR_COLORMATCHTOTARGETW: proofing is not simulated");
+                colors.color_proofing = true;
+            }
+            ColorSpace::CS_DISABLE | ColorSpace::CS_DELETE_TRANSFORM => {
+                colors.color_proofing = false;
+            }
+        }
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2458,14 +3867,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn force_ufi_mapping(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_FORCEUFIMAPPING,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.text.force_ufi_mapping =
             record.ufi.into();
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2474,12 +3883,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn invert_rgn(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_INVERTRGN,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_INVERTRGN: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.not_implemented(record_number, record.record_type);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2488,10 +3897,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn move_to_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_MOVETOEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.current_position =
             record.offset.clone();
 
@@ -2502,11 +3911,10 @@ impl crate::converter::Player for SVGPlayer {
         if self.context.graphics_environment.drawing.path_bracket {
             let point = self.context.transform_point_l(&record.offset);
 
-            self.path =
-                self.path.clone().move_to(format!("{} {}", point.x, point.y));
+            self.path.move_to(PointF::from(&point));
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2515,14 +3923,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn pixel_format(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_PIXELFORMAT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.color.pixel_format =
             record.pfd.into();
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2531,12 +3939,27 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn realize_palette(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_REALIZEPALETTE,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_REALIZEPALETTE: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        // Changes to the selected palette take effect when it is realized.
+        if let Some(index) = self.selected_emf_object.palette_index
+            && let GraphicsObject::LogPalette(v) =
+                self.emf_object_table.get(index)
+        {
+            self.selected_emf_object.palette = Some(v.clone());
+        }
+
+        if let Some(ref palette) = self.selected_emf_object.palette {
+            self.context
+                .graphics_environment
+                .color
+                .palette
+                .clone_from(&palette.palette_entries);
+        }
+
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2545,24 +3968,43 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn restore_dc(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_RESTOREDC,
-    ) -> Result<Self, PlayError> {
-        let mut current = record.saved_dc;
+    ) -> Result<(), PlayError> {
+        let depth = self.context_stack.len();
+        // Negative values are relative to the current state, positive ones
+        // are the instance number of the saved state starting at 1.
+        let index = if record.saved_dc < 0 {
+            depth.checked_sub(record.saved_dc.unsigned_abs() as usize)
+        } else {
+            (record.saved_dc as usize).checked_sub(1).filter(|i| *i < depth)
+        };
 
-        while current < 0 {
-            let Some(context) = self.context_stack.pop() else {
-                return Err(PlayError::InvalidRecord {
-                    cause: "device context to restore is not saved.".to_owned(),
-                });
-            };
+        let Some(index) = index else {
+            self.approximated(
+                record_number,
+                record.record_type,
+                format!(
+                    "device context {} to restore is not among the {depth} \
+                     saved, so the current one is kept",
+                    record.saved_dc
+                ),
+            );
+
+            return Ok(());
+        };
+
+        self.context_stack.truncate(index + 1);
 
-            self.context = context;
-            current += 1;
+        if let Some(saved) = self.context_stack.pop() {
+            self.context = saved.context;
+            self.selected_emf_object = saved.selected_emf_object;
+            self.path = saved.path;
+            self.clip = saved.clip;
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2571,13 +4013,18 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn save_dc(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SAVEDC,
-    ) -> Result<Self, PlayError> {
-        self.context_stack.push(self.context.clone());
+    ) -> Result<(), PlayError> {
+        self.context_stack.push(SavedDeviceContext {
+            context: self.context.clone(),
+            selected_emf_object: self.selected_emf_object.clone(),
+            path: self.path.clone(),
+            clip: self.clip.clone(),
+        });
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2586,10 +4033,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn scale_viewport_ext_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SCALEVIEWPORTEXTEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         let wmf_core::parser::SizeL { cx, cy } =
             self.context.graphics_environment.regions.viewport.extent;
 
@@ -2601,7 +4048,7 @@ impl crate::converter::Player for SVGPlayer {
                     .unsigned_abs(),
             };
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2610,10 +4057,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn scale_window_ext_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SCALEWINDOWEXTEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         let wmf_core::parser::SizeL { cx, cy } =
             self.context.graphics_environment.regions.window.extent;
 
@@ -2625,7 +4072,7 @@ impl crate::converter::Player for SVGPlayer {
                     .unsigned_abs(),
             };
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2634,14 +4081,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_arc_direction(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETARCDIRECTION,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.arc_direction =
             record.arc_direction;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2650,14 +4097,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_bk_color(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETBKCOLOR,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.background_color =
             record.color;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2666,14 +4113,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_bk_mode(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETBKMODE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.background_mode =
             record.background_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2682,12 +4129,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_brush_org_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETBRUSHORGEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.brush_origin = record.origin;
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2696,14 +4143,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_color_adjustment(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETCOLORADJUSTMENT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.color.color_adjustment =
             record.color_adjustment;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2712,12 +4159,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_icm_mode(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETICMMODE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.color.icm_mode = record.icm_mode;
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2726,12 +4173,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_icm_profile_a(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETICMPROFILEA,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETICMPROFILEA: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.context.graphics_environment.color.color_profile =
+            Self::icm_profile(record.dw_flags, record.cb_name, &record.data);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2740,12 +4188,13 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_icm_profile_w(
-        self,
+        &mut self,
         record_number: usize,
         record: EMR_SETICMPROFILEW,
-    ) -> Result<Self, PlayError> {
-        info!("EMR_SETICMPROFILEW: not implemented");
-        Ok(self)
+    ) -> Result<(), PlayError> {
+        self.context.graphics_environment.color.color_profile =
+            Self::icm_profile(record.dw_flags, record.cb_name, &record.data);
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2754,14 +4203,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_layout(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETLAYOUT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.layout_mode =
             record.layout_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2770,12 +4219,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_linked_ufis(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETLINKEDUFIS,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.text.linked_ufis = record.ufis;
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2784,14 +4233,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_map_mode(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMAPMODE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.mapping_mode =
             record.map_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2800,12 +4249,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_mapper_flags(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMAPPERFLAGS,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.text.font_mapper_flags = record.flags;
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2814,14 +4263,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_miter_limit(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETMITERLIMIT,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.miter_limit =
             record.miter_limit.into();
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2830,14 +4279,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_polyfill_mode(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETPOLYFILLMODE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.polyfill_mode =
             record.polygon_fill_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2846,13 +4295,45 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_rop2(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETROP2,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
+        use wmf_core::parser::BinaryRasterOperation;
+
+        // Mirrors how `rop2_paint` draws each operation.
+        match record.rop2_mode {
+            BinaryRasterOperation::R2_NOP
+            | BinaryRasterOperation::R2_COPYPEN
+            | BinaryRasterOperation::R2_BLACK
+            | BinaryRasterOperation::R2_WHITE
+            | BinaryRasterOperation::R2_NOTCOPYPEN => {}
+            rop2 @ (BinaryRasterOperation::R2_NOT
+            | BinaryRasterOperation::R2_XORPEN
+            | BinaryRasterOperation::R2_NOTXORPEN
+            | BinaryRasterOperation::R2_MASKPEN
+            | BinaryRasterOperation::R2_MASKNOTPEN
+            | BinaryRasterOperation::R2_MERGEPEN
+            | BinaryRasterOperation::R2_MERGENOTPEN) => self.approximated(
+                record_number,
+                record.record_type,
+                format!(
+                    "binary raster operation {rop2:?} is drawn with a blend \
+                     mode"
+                ),
+            ),
+            rop2 => self.approximated(
+                record_number,
+                record.record_type,
+                format!(
+                    "binary raster operation {rop2:?} is drawn as R2_COPYPEN"
+                ),
+            ),
+        }
+
         self.context.graphics_environment.drawing.rop2 = record.rop2_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2861,14 +4342,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_stretch_blt_mode(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETSTRETCHBLTMODE,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.stretch_blt_mode =
             record.stretch_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2877,14 +4358,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_text_align(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETTEXTALIGN,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.text.text_alignment =
             record.text_alignment_mode;
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2893,12 +4374,12 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_text_color(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETTEXTCOLOR,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.drawing.text_color = record.color;
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2907,14 +4388,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_text_justification(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETTEXTJUSTIFICATION,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.graphics_environment.text.text_justification =
             (record.n_break_extra, record.n_break_count);
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2923,10 +4404,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_viewport_ext_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETVIEWPORTEXTEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // MS-EMF 2.3.11.28: The viewport extent is only updated when
         // the mapping mode is MM_ISOTROPIC or MM_ANISOTROPIC. In every
         // other mapping mode the extent is fixed and this record is
@@ -2941,7 +4422,7 @@ impl crate::converter::Player for SVGPlayer {
             self.context.apply_transformation();
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2950,10 +4431,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_viewport_org_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETVIEWPORTORGEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // The viewport origin always feeds into the transformation,
         // regardless of mapping mode. MS-EMF 2.3.11.29 places no
         // mapping-mode restriction on this record.
@@ -2961,7 +4442,7 @@ impl crate::converter::Player for SVGPlayer {
             record.origin;
         self.context.apply_transformation();
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2970,10 +4451,10 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_window_ext_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETWINDOWEXTEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // MS-EMF 2.3.11.30: The window extent is only updated when the
         // mapping mode is MM_ISOTROPIC or MM_ANISOTROPIC. In every
         // other mapping mode the extent is fixed and this record is
@@ -2988,7 +4469,7 @@ impl crate::converter::Player for SVGPlayer {
             self.context.apply_transformation();
         }
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -2997,17 +4478,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_window_org_ex(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETWINDOWORGEX,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         // The window origin always feeds into the transformation,
         // regardless of mapping mode. MS-EMF 2.3.11.31 places no
         // mapping-mode restriction on this record.
         self.context.graphics_environment.regions.window.origin = record.origin;
         self.context.apply_transformation();
 
-        Ok(self)
+        Ok(())
     }
 
     // .
@@ -3021,14 +4502,14 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn modify_world_transform(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_MODIFYWORLDTRANSFORM,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         let (a, b) = match record.modify_world_transform_mode {
             ModifyWorldTransformMode::MWT_IDENTITY => {
                 // NOOP
-                return Ok(self);
+                return Ok(());
             }
             ModifyWorldTransformMode::MWT_LEFTMULTIPLY => {
                 (record.x_form, self.context.xform.clone())
@@ -3038,7 +4519,7 @@ impl crate::converter::Player for SVGPlayer {
             }
             ModifyWorldTransformMode::MWT_SET => {
                 self.context.xform = record.x_form;
-                return Ok(self);
+                return Ok(());
             }
         };
 
@@ -3051,7 +4532,7 @@ impl crate::converter::Player for SVGPlayer {
             dy: a.dy * b.m12 + a.dy * b.m22 + b.dy,
         };
 
-        Ok(self)
+        Ok(())
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -3060,12 +4541,98 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_world_transform(
-        mut self,
+        &mut self,
         record_number: usize,
         record: EMR_SETWORLDTRANSFORM,
-    ) -> Result<Self, PlayError> {
+    ) -> Result<(), PlayError> {
         self.context.xform = record.x_form;
 
-        Ok(self)
+        Ok(())
+    }
+}
+
+// Inverts a color of the pen or the brush. Patterns are left as they are.
+/// The paint of a shape drawn with a binary raster operation (ROP2): the
+/// pen and brush colors as they are, forced or inverted.
+#[derive(Clone, Copy, Debug)]
+enum Rop2Paint {
+    Unchanged,
+    Black,
+    White,
+    Inverted,
+}
+
+impl Rop2Paint {
+    /// Returns the SVG paint replacing `value`, or `None` to keep it.
+    fn paint(self, value: &str) -> Option<String> {
+        match self {
+            Self::Unchanged => None,
+            Self::Black => Some("#000000".to_owned()),
+            Self::White => Some("#FFFFFF".to_owned()),
+            Self::Inverted => invert_paint(value),
+        }
+    }
+
+    fn color(self, color: ColorRef) -> ColorRef {
+        match self {
+            Self::Unchanged => color,
+            Self::Black => ColorRef::black(),
+            Self::White => ColorRef::white(),
+            Self::Inverted => ColorRef {
+                red: !color.red,
+                green: !color.green,
+                blue: !color.blue,
+                reserved: color.reserved,
+            },
+        }
     }
 }
+
+/// Returns how a shape is painted under `rop2` and the blend mode that
+/// approximates combining it with the drawing, or `None` if R2_NOP leaves
+/// the drawing as it is. Operations `set_rop2` reports as unsupported are
+/// drawn as R2_COPYPEN.
+fn rop2_paint(
+    rop2: wmf_core::parser::BinaryRasterOperation,
+) -> Option<(Rop2Paint, Option<&'static str>)> {
+    use wmf_core::parser::BinaryRasterOperation;
+
+    Some(match rop2 {
+        BinaryRasterOperation::R2_NOP => return None,
+        BinaryRasterOperation::R2_BLACK => (Rop2Paint::Black, None),
+        BinaryRasterOperation::R2_WHITE => (Rop2Paint::White, None),
+        // The difference from white inverts the destination.
+        BinaryRasterOperation::R2_NOT => (Rop2Paint::White, Some("difference")),
+        BinaryRasterOperation::R2_NOTCOPYPEN => (Rop2Paint::Inverted, None),
+        BinaryRasterOperation::R2_XORPEN => {
+            (Rop2Paint::Unchanged, Some("difference"))
+        }
+        BinaryRasterOperation::R2_NOTXORPEN => {
+            (Rop2Paint::Inverted, Some("difference"))
+        }
+        BinaryRasterOperation::R2_MASKPEN => {
+            (Rop2Paint::Unchanged, Some("multiply"))
+        }
+        BinaryRasterOperation::R2_MASKNOTPEN => {
+            (Rop2Paint::Inverted, Some("multiply"))
+        }
+        BinaryRasterOperation::R2_MERGEPEN => {
+            (Rop2Paint::Unchanged, Some("lighten"))
+        }
+        BinaryRasterOperation::R2_MERGENOTPEN => {
+            (Rop2Paint::Inverted, Some("lighten"))
+        }
+        _ => (Rop2Paint::Unchanged, None),
+    })
+}
+
+fn invert_paint(value: &str) -> Option<String> {
+    let Some(rgb) =
+        value.strip_prefix('#').and_then(|v| u32::from_str_radix(v, 16).ok())
+    else {
+        info!(value, "pattern cannot be inverted for the raster operation");
+        return None;
+    };
+
+    Some(format!("#{:06X}", !rgb & 0x00FF_FFFF))
+}
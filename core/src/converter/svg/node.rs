@@ -39,6 +39,14 @@ impl Node {
         self
     }
 
+    pub fn has(&self, name: &str) -> bool {
+        self.attrs.contains_key(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(String::as_str)
+    }
+
     pub fn set(mut self, name: impl ToString, value: impl ToString) -> Self {
         if matches!(self.typ, NodeType::Node(_)) {
             self.attrs.insert(name.to_string(), value.to_string());
@@ -47,6 +55,46 @@ impl Node {
         self
     }
 
+    /// Rounds the decimal numbers in the geometric attributes of the node
+    /// and its descendants to `precision` decimal places, dropping trailing
+    /// zeros.
+    pub fn round_numbers(&mut self, precision: usize) {
+        const ATTRIBUTES: &[&str] = &[
+            "cx",
+            "cy",
+            "d",
+            "dx",
+            "dy",
+            "font-size",
+            "height",
+            "points",
+            "r",
+            "rx",
+            "ry",
+            "stroke-dasharray",
+            "stroke-width",
+            "transform",
+            "viewBox",
+            "width",
+            "x",
+            "x1",
+            "x2",
+            "y",
+            "y1",
+            "y2",
+        ];
+
+        for (name, value) in &mut self.attrs {
+            if ATTRIBUTES.contains(&name.as_str()) {
+                *value = round_numbers(value, precision);
+            }
+        }
+
+        for child in &mut self.inner {
+            child.round_numbers(precision);
+        }
+    }
+
     fn escape_text(value: impl ToString) -> String {
         let s = value.to_string();
         let mut out = String::with_capacity(s.len());
@@ -82,6 +130,50 @@ impl Node {
     }
 }
 
+// Rounds every number with a fractional part in `value`, leaving the text
+// around the numbers as it is.
+fn round_numbers(value: &str, precision: usize) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let end = rest[start..]
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .map_or(rest.len(), |v| start + v);
+        let number = &rest[start..end];
+
+        out.push_str(&rest[..start]);
+
+        match number.parse::<f64>() {
+            Ok(v) if number.contains('.') => {
+                let v = format!("{v:.precision$}");
+                let v = if v.contains('.') {
+                    v.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    &v
+                };
+
+                // A negative number rounded to zero drops its sign.
+                if v.bytes().all(|c| c == b'0') && out.ends_with('-') {
+                    out.pop();
+
+                    if out.ends_with(|c: char| c.is_ascii_digit()) {
+                        out.push(' ');
+                    }
+                }
+
+                out.push_str(v);
+            }
+            _ => out.push_str(number),
+        }
+
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
 impl core::fmt::Display for Node {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match &self.typ {
@@ -115,10 +207,6 @@ impl Data {
         Self::default()
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
-    }
-
     fn push_command(&mut self, cmd: char, param: &Parameters) {
         if !self.0.is_empty() {
             self.0.push(' ');
@@ -65,6 +65,293 @@ impl LogFont {
     }
 }
 
+impl EmrText {
+    /// Decodes the output string into characters paired with the number of
+    /// intercharacter spacing entries each one consumes: one per UTF-16
+    /// code unit for Unicode strings, one per byte for 8-bit strings and
+    /// one per glyph index.
+    ///
+    /// Glyph indices are mapped back through the cmap of `font`; `None` is
+    /// returned when the font is not available or lacks any of the glyphs.
+    pub fn glyphs(
+        &self,
+        charset: wmf_core::parser::CharacterSet,
+        font: Option<&Font>,
+    ) -> Option<Vec<(char, usize)>> {
+        match self.string_buffer {
+            OutputString::Ansi(ref v) => {
+                Some(CodePage::from_charset(charset).decode(v))
+            }
+            OutputString::Unicode(ref v) => {
+                Some(v.chars().map(|c| (c, c.len_utf16())).collect())
+            }
+            OutputString::GlyphIndices(ref v) => {
+                let font = font?;
+
+                v.iter()
+                    .map(|&g| font.char_for_glyph(g).map(|c| (c, 1)))
+                    .collect()
+            }
+        }
+    }
+
+    /// Adds the extra space of SetTextJustification to the intercharacter
+    /// spacing, after the last spacing entry of each glyph. `extras` holds
+    /// one value per glyph as returned by `justification_extras`.
+    pub fn justify(&mut self, glyphs: &[(char, usize)], extras: &[i32]) {
+        let stride = if self.options.contains(ExtTextOutOptions::ETO_PDY) {
+            2
+        } else {
+            1
+        };
+        let mut unit = 0;
+
+        for (&(_, units), &extra) in glyphs.iter().zip(extras) {
+            unit += units;
+
+            if let Some(v) = self.dx_buffer.get_mut((unit - 1) * stride) {
+                *v = (*v as i32).saturating_add(extra) as u32;
+            }
+        }
+    }
+
+    /// Returns the total advance of the intercharacter spacing array in
+    /// logical units, or `None` when the record carries no spacing.
+    pub fn advance(&self) -> Option<(i32, i32)> {
+        if self.dx_buffer.is_empty() {
+            return None;
+        }
+
+        if self.options.contains(ExtTextOutOptions::ETO_PDY) {
+            Some(self.dx_buffer.chunks_exact(2).fold((0, 0), |(x, y), v| {
+                (x.saturating_add(v[0] as i32), y.saturating_sub(v[1] as i32))
+            }))
+        } else {
+            Some((
+                self.dx_buffer
+                    .iter()
+                    .fold(0_i32, |x, v| x.saturating_add(*v as i32)),
+                0,
+            ))
+        }
+    }
+
+    /// Approximates the advance of a run without intercharacter spacing in
+    /// logical units. Glyph indices use the advance widths of `font`;
+    /// other strings are assumed to be half an em per character, plus the
+    /// justification `extras`.
+    pub fn estimated_advance(
+        &self,
+        log_font: &LogFont,
+        font: Option<&Font>,
+        extras: &[i32],
+    ) -> i32 {
+        let em = f64::from(log_font.height.unsigned_abs());
+        let advance = match (&self.string_buffer, font) {
+            (OutputString::GlyphIndices(indices), Some(font)) => {
+                indices
+                    .iter()
+                    .map(|&g| f64::from(font.advance_width(g)))
+                    .sum::<f64>()
+                    * em
+                    / f64::from(font.units_per_em())
+            }
+            _ => f64::from(self.chars) * em / 2.0,
+        };
+
+        (advance.round() as i32)
+            .saturating_add(extras.iter().fold(0, |a, v| a.saturating_add(*v)))
+    }
+
+    /// Draws glyph indices from the outlines of `font`, returning the path
+    /// data in device coordinates, or `None` when the font has no outline
+    /// for one of the glyphs.
+    ///
+    /// Glyphs advance by the intercharacter spacing array when present and
+    /// by the advance widths of the font otherwise. The em box is scaled to
+    /// the height of `log_font`.
+    pub fn glyph_outlines(
+        &self,
+        ctx: &PlaybackDeviceContext,
+        text_alignment: u32,
+        log_font: &LogFont,
+        font: &Font,
+        indices: &[u16],
+    ) -> Option<Path> {
+        let em = f64::from(log_font.height.unsigned_abs());
+        let units_per_em = f64::from(font.units_per_em());
+        let pdy = self.options.contains(ExtTextOutOptions::ETO_PDY);
+        let mut advances = self.dx_buffer.chunks_exact(if pdy { 2 } else { 1 });
+        let (mut x, mut y) = (0_f64, 0_f64);
+        let mut origins = vec![];
+
+        for &glyph in indices {
+            origins.push((x, y));
+
+            if let Some(v) = advances.next() {
+                x += f64::from(v[0] as i32);
+                if pdy {
+                    y -= f64::from(v[1] as i32);
+                }
+            } else {
+                x += f64::from(font.advance_width(glyph)) * em / units_per_em;
+            }
+        }
+
+        let (shift_x, shift_y) = match text_alignment & 0x0000_0006 {
+            0x0000_0006 => (x / 2.0, y / 2.0),
+            0x0000_0002 => (x, y),
+            _ => (0.0, 0.0),
+        };
+
+        // Glyphs are drawn upright in device space whatever the direction
+        // of the logical axes, so only the origins go through the world
+        // transform.
+        let xform = &ctx.xform;
+        let scale = f64::from(
+            (xform.m11 * xform.m22 - xform.m12 * xform.m21).abs().sqrt(),
+        ) * em
+            / units_per_em;
+        let baseline = match text_alignment & 0x0000_0018 {
+            0x0000_0018 => 0.0,
+            0x0000_0008 => f64::from(font.descender()) * scale,
+            _ => f64::from(font.ascender()) * scale,
+        };
+        let mut path = Path::new();
+
+        for (&glyph, (ox, oy)) in indices.iter().zip(origins) {
+            let lx = f64::from(self.reference.x) + ox - shift_x;
+            let ly = f64::from(self.reference.y) + oy - shift_y;
+            let dx = f64::from(xform.m11) * lx
+                + f64::from(xform.m21) * ly
+                + f64::from(xform.dx);
+            let dy = f64::from(xform.m12) * lx
+                + f64::from(xform.m22) * ly
+                + f64::from(xform.dy)
+                + baseline;
+            let point = |x: f32, y: f32| {
+                PointF::new(
+                    dx + f64::from(x) * scale,
+                    dy - f64::from(y) * scale,
+                )
+            };
+
+            for command in font.outline(glyph)? {
+                match command {
+                    OutlineCommand::MoveTo { x, y } => {
+                        path.move_to(point(x, y));
+                    }
+                    OutlineCommand::LineTo { x, y } => {
+                        path.line_to(point(x, y));
+                    }
+                    OutlineCommand::QuadTo { cx, cy, x, y } => {
+                        // A quadratic curve is the cubic whose control
+                        // points lie two thirds of the way to its own.
+                        let from = path.current_point().unwrap_or_default();
+                        let (c, to) = (point(cx, cy), point(x, y));
+
+                        path.bezier_to(
+                            PointF::new(
+                                from.x + (c.x - from.x) * 2.0 / 3.0,
+                                from.y + (c.y - from.y) * 2.0 / 3.0,
+                            ),
+                            PointF::new(
+                                to.x + (c.x - to.x) * 2.0 / 3.0,
+                                to.y + (c.y - to.y) * 2.0 / 3.0,
+                            ),
+                            to,
+                        );
+                    }
+                    OutlineCommand::Close => path.close(),
+                }
+            }
+        }
+
+        Some(path)
+    }
+
+    /// Returns the device position of each character origin laid out from
+    /// the intercharacter spacing array, or `None` when the record carries
+    /// no usable spacing.
+    ///
+    /// Horizontal alignment is resolved here by shifting the run by its
+    /// total advance: every absolute `x` starts a new SVG text chunk, so
+    /// `text-anchor` would otherwise anchor each glyph on its own. With
+    /// right-to-left reading the first character is placed at the right
+    /// end of the run and each following one to the left of it.
+    pub fn glyph_positions(
+        &self,
+        ctx: &PlaybackDeviceContext,
+        text_alignment: u32,
+        glyphs: &[(char, usize)],
+        rtl: bool,
+    ) -> Option<Vec<wmf_core::parser::PointL>> {
+        let pdy = self.options.contains(ExtTextOutOptions::ETO_PDY);
+
+        if self.dx_buffer.is_empty() || glyphs.is_empty() {
+            return None;
+        }
+
+        // With ETO_PDY the buffer holds (dx, dy) pairs. The vertical
+        // displacement is measured in the font's upward direction, which
+        // is the negative logical y direction.
+        let mut advances = self.dx_buffer.chunks_exact(if pdy { 2 } else { 1 });
+        let (mut x, mut y) = (0_i32, 0_i32);
+        let mut offsets = vec![];
+
+        for &(_, units) in glyphs {
+            offsets.push((x, y));
+
+            for _ in 0..units {
+                let v = advances.next()?;
+
+                x = x.saturating_add(v[0] as i32);
+                if pdy {
+                    y = y.saturating_sub(v[1] as i32);
+                }
+            }
+
+            if rtl {
+                // The glyph origin is its left edge, which lies one
+                // advance before the end of the glyph.
+                offsets.last_mut()?.0 = x;
+            }
+        }
+
+        if rtl {
+            for offset in &mut offsets {
+                offset.0 = x.saturating_sub(offset.0);
+            }
+        }
+
+        let (shift_x, shift_y) = match text_alignment & 0x0000_0006 {
+            0x0000_0006 => (x / 2, y / 2),
+            0x0000_0002 => (x, y),
+            _ => (0, 0),
+        };
+
+        Some(
+            offsets
+                .into_iter()
+                .map(|(ox, oy)| {
+                    ctx.transform_point_l(&wmf_core::parser::PointL {
+                        x: self
+                            .reference
+                            .x
+                            .saturating_add(ox)
+                            .saturating_sub(shift_x),
+                        y: self
+                            .reference
+                            .y
+                            .saturating_add(oy)
+                            .saturating_sub(shift_y),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Fill {
     Pattern { pattern: Node },
@@ -72,13 +359,31 @@ pub enum Fill {
 }
 
 impl Fill {
+    /// Describes how [`Fill::from`] draws `brush` approximately, or returns
+    /// `None` if it draws the brush as specified.
+    pub fn approximation(brush: &LogBrushEx) -> Option<String> {
+        match brush {
+            LogBrushEx::Hatched {
+                brush_hatch:
+                    hatch @ (HatchStyle::HS_DITHEREDCLR
+                    | HatchStyle::HS_DITHEREDTEXTCLR
+                    | HatchStyle::HS_DITHEREDBKCLR),
+                ..
+            } => {
+                Some(format!("hatch style {hatch:?} is drawn as a solid color"))
+            }
+            _ => None,
+        }
+    }
+
     pub fn from(ctx: &PlaybackDeviceContext, v: LogBrushEx) -> Self {
         match v {
-            LogBrushEx::Solid { color } => {
-                Fill::Value { value: color_from_color_ref(&color) }
-            }
+            LogBrushEx::Solid { color } => Fill::Value {
+                value: color_from_color_ref(&ctx.resolve_color(&color)),
+            },
             LogBrushEx::Null => Fill::Value { value: "none".to_owned() },
             LogBrushEx::Hatched { color, brush_hatch } => {
+                let color = ctx.resolve_color(&color);
                 let ten = (10_f32 * ctx.xform.calc_scale()) as i32;
                 let path = match brush_hatch {
                     HatchStyle::HS_HORIZONTAL => {
@@ -153,26 +458,28 @@ impl Fill {
                     }
                     HatchStyle::HS_SOLIDTEXTCLR => {
                         return Fill::Value {
-                            value: color_from_color_ref(
+                            value: color_from_color_ref(&ctx.resolve_color(
                                 &ctx.graphics_environment.drawing.text_color,
-                            ),
+                            )),
                         };
                     }
                     HatchStyle::HS_DITHEREDTEXTCLR => {
                         info!(?brush_hatch, "HatchStyle is not implemented.");
 
                         return Fill::Value {
-                            value: color_from_color_ref(
+                            value: color_from_color_ref(&ctx.resolve_color(
                                 &ctx.graphics_environment.drawing.text_color,
-                            ),
+                            )),
                         };
                     }
                     HatchStyle::HS_SOLIDBKCLR => {
                         return Fill::Value {
                             value: color_from_color_ref(
-                                &ctx.graphics_environment
-                                    .drawing
-                                    .background_color,
+                                &ctx.resolve_color(
+                                    &ctx.graphics_environment
+                                        .drawing
+                                        .background_color,
+                                ),
                             ),
                         };
                     }
@@ -181,9 +488,11 @@ impl Fill {
 
                         return Fill::Value {
                             value: color_from_color_ref(
-                                &ctx.graphics_environment
-                                    .drawing
-                                    .background_color,
+                                &ctx.resolve_color(
+                                    &ctx.graphics_environment
+                                        .drawing
+                                        .background_color,
+                                ),
                             ),
                         };
                     }
@@ -252,64 +561,70 @@ impl From<LogPenEx> for Stroke {
 
         stroke.width = v.width as i16;
 
-        for style in v.pen_style.iter() {
-            stroke = match style {
-                PenStyle::PS_SOLID => stroke,
-                PenStyle::PS_DASH => {
-                    stroke.dash_array =
-                        format!("{v} {v}", v = stroke.width * 10);
-                    stroke
-                }
-                PenStyle::PS_DOT | PenStyle::PS_ALTERNATE => {
-                    stroke.dash_array =
-                        format!("{} {}", stroke.width, stroke.width * 10);
-                    stroke
-                }
-                PenStyle::PS_DASHDOT => {
-                    stroke.dash_array = format!(
-                        "{} {} {} {}",
-                        stroke.width * 10,
-                        stroke.width * 2,
-                        stroke.width,
-                        stroke.width * 2,
-                    );
-                    stroke
-                }
-                PenStyle::PS_DASHDOTDOT => {
-                    stroke.dash_array = format!(
-                        "{} {} {} {} {} {}",
-                        stroke.width * 10,
-                        stroke.width * 2,
-                        stroke.width,
-                        stroke.width * 2,
-                        stroke.width,
-                        stroke.width * 2,
-                    );
-                    stroke
-                }
-                PenStyle::PS_NULL => {
-                    stroke.opacity = 0_f32;
-                    stroke
-                }
-                PenStyle::PS_ENDCAP_SQUARE | PenStyle::PS_ENDCAP_FLAT => {
-                    "square".clone_into(&mut stroke.line_cap);
-                    stroke
-                }
-                PenStyle::PS_JOIN_BEVEL => {
-                    "bevel".clone_into(&mut stroke.line_join);
-                    stroke
-                }
-                PenStyle::PS_JOIN_MITER => {
-                    "miter".clone_into(&mut stroke.line_join);
-                    stroke
+        stroke = match v.pen_style.line_style() {
+            Some(PenStyle::PS_SOLID) => stroke,
+            Some(PenStyle::PS_DASH) => {
+                stroke.dash_array = format!("{v} {v}", v = stroke.width * 10);
+                stroke
+            }
+            Some(PenStyle::PS_DOT | PenStyle::PS_ALTERNATE) => {
+                stroke.dash_array =
+                    format!("{} {}", stroke.width, stroke.width * 10);
+                stroke
+            }
+            Some(PenStyle::PS_DASHDOT) => {
+                stroke.dash_array = format!(
+                    "{} {} {} {}",
+                    stroke.width * 10,
+                    stroke.width * 2,
+                    stroke.width,
+                    stroke.width * 2,
+                );
+                stroke
+            }
+            Some(PenStyle::PS_DASHDOTDOT) => {
+                stroke.dash_array = format!(
+                    "{} {} {} {} {} {}",
+                    stroke.width * 10,
+                    stroke.width * 2,
+                    stroke.width,
+                    stroke.width * 2,
+                    stroke.width,
+                    stroke.width * 2,
+                );
+                stroke
+            }
+            Some(PenStyle::PS_NULL) => {
+                stroke.opacity = 0_f32;
+                stroke
+            }
+            // not implemented
+            style => {
+                info!(?style, "pen style is not implemented");
+                stroke
+            }
+        };
+
+        // Line caps and joins only apply to geometric pens; cosmetic
+        // pens keep flat ends and mitered corners. GDI promotes a pen
+        // created by EMR_CREATEPEN with a width above one unit to a
+        // geometric pen, so it also picks up the round cap and join
+        // that a zero cap/join field stands for.
+        if v.pen_style.is_geometric() || v.width > 1 {
+            stroke = Self {
+                line_cap: match v.pen_style.end_cap() {
+                    PenEndCap::PS_ENDCAP_ROUND => "round",
+                    PenEndCap::PS_ENDCAP_SQUARE => "square",
+                    PenEndCap::PS_ENDCAP_FLAT => "butt",
                 }
-                // not implemented
-                PenStyle::PS_INSIDEFRAME
-                | PenStyle::PS_USERSTYLE
-                | PenStyle::PS_GEOMETRIC => {
-                    info!(?style, "pen style is not implemented");
-                    stroke
+                .to_owned(),
+                line_join: match v.pen_style.line_join() {
+                    PenLineJoin::PS_JOIN_ROUND => "round",
+                    PenLineJoin::PS_JOIN_BEVEL => "bevel",
+                    PenLineJoin::PS_JOIN_MITER => "miter",
                 }
+                .to_owned(),
+                ..stroke
             };
         }
 
@@ -318,8 +633,41 @@ impl From<LogPenEx> for Stroke {
 }
 
 impl Stroke {
-    pub fn color(&self) -> String {
-        color_from_color_ref(&self.color)
+    /// Describes how [`Stroke::from`] draws `pen` approximately, or returns
+    /// `None` if it draws the pen as specified.
+    pub fn approximation(pen: &LogPenEx) -> Option<String> {
+        let description = match pen.brush {
+            LogPenExBrush::DIBPattern { .. }
+            | LogPenExBrush::DIBPatternPT { .. } => {
+                "pen with a DIB pattern is not drawn"
+            }
+            LogPenExBrush::Pattern { .. } => {
+                "pen with a pattern is drawn black"
+            }
+            LogPenExBrush::Hatched { .. } => "hatched pen is drawn solid",
+            LogPenExBrush::Solid { .. } | LogPenExBrush::Null => {
+                return match pen.pen_style.line_style() {
+                    Some(
+                        PenStyle::PS_SOLID
+                        | PenStyle::PS_DASH
+                        | PenStyle::PS_DOT
+                        | PenStyle::PS_ALTERNATE
+                        | PenStyle::PS_DASHDOT
+                        | PenStyle::PS_DASHDOTDOT
+                        | PenStyle::PS_NULL,
+                    ) => None,
+                    Some(style) => Some(format!(
+                        "pen style {style:?} is drawn as a solid line"
+                    )),
+                    None => Some(
+                        "undefined pen style is drawn as a solid line"
+                            .to_owned(),
+                    ),
+                };
+            }
+        };
+
+        Some(description.to_owned())
     }
 
     pub fn dash_array(&self) -> String {
@@ -350,7 +698,10 @@ impl Stroke {
         let scale = ctx.xform.calc_scale();
         let width = core::cmp::max((f32::from(self.width()) * scale) as i32, 1);
         let mut elem = elem
-            .set("stroke", self.color())
+            .set(
+                "stroke",
+                color_from_color_ref(&ctx.resolve_color(&self.color)),
+            )
             .set("stroke-dasharray", self.dash_array())
             .set("stroke-linecap", self.line_cap())
             .set("stroke-linejoin", self.line_join())
@@ -370,6 +721,35 @@ impl Stroke {
     }
 }
 
+/// Spreads the `break_extra` logical units of SetTextJustification over
+/// `break_count` break characters, returning the extra space to add after
+/// each glyph. As in GDI, the remainder of the division goes one unit at a
+/// time to the first break characters of the string.
+pub fn justification_extras(
+    glyphs: &[(char, usize)],
+    (break_extra, break_count): (i32, i32),
+) -> Vec<i32> {
+    if break_extra == 0 || break_count <= 0 {
+        return vec![0; glyphs.len()];
+    }
+
+    let extra = break_extra / break_count;
+    let mut remainder = break_extra % break_count;
+
+    glyphs
+        .iter()
+        .map(|&(c, _)| {
+            if c != ' ' {
+                return 0;
+            }
+
+            let carry = remainder.signum();
+            remainder -= carry;
+            extra + carry
+        })
+        .collect()
+}
+
 // `TA_CENTER` (0x0006) shares its low bit with `TA_RIGHT` (0x0002),
 // so the horizontal portion must be masked against 0x0006 and matched
 // as a whole instead of probing for individual flags.
@@ -396,6 +776,46 @@ pub fn text_baseline(value: u32) -> String {
     }
 }
 
+impl From<&Path> for Data {
+    fn from(path: &Path) -> Self {
+        let point = |p: &PointF| {
+            format!("{} {}", round_coordinate(p.x), round_coordinate(p.y))
+        };
+        let mut data = Self::new();
+
+        for subpath in &path.subpaths {
+            data = data.move_to(point(&subpath.start));
+
+            for segment in &subpath.segments {
+                data = match segment {
+                    Segment::Line { to } => data.line_to(point(to)),
+                    Segment::Bezier { c1, c2, to } => data.curve_to(format!(
+                        "{} {} {}",
+                        point(c1),
+                        point(c2),
+                        point(to)
+                    )),
+                };
+            }
+
+            if subpath.closed {
+                data = data.close();
+            }
+        }
+
+        data
+    }
+}
+
+/// Formats a device coordinate with at most two decimal places.
+#[inline]
+fn round_coordinate(v: f64) -> String {
+    let v = format!("{v:.2}");
+    let v = v.trim_end_matches('0').trim_end_matches('.');
+
+    if v == "-0" { "0".to_owned() } else { v.to_owned() }
+}
+
 #[inline]
 pub fn as_point_string_from_point_l(
     point: &wmf_core::parser::PointL,
@@ -415,6 +835,28 @@ pub fn color_from_color_ref(c: &wmf_core::parser::ColorRef) -> String {
     format!("#{:02X}{:02X}{:02X}", c.red, c.green, c.blue)
 }
 
+/// Builds a `<rect>` covering `rect` in device coordinates. The corners are
+/// normalized because mapping modes with an upward y axis flip them.
+pub fn rect_node(
+    ctx: &PlaybackDeviceContext,
+    rect: &wmf_core::parser::RectL,
+) -> Node {
+    let top_left = ctx.transform_point_l(&wmf_core::parser::PointL {
+        x: rect.left,
+        y: rect.top,
+    });
+    let bottom_right = ctx.transform_point_l(&wmf_core::parser::PointL {
+        x: rect.right,
+        y: rect.bottom,
+    });
+
+    Node::new("rect")
+        .set("x", top_left.x.min(bottom_right.x).to_string())
+        .set("y", top_left.y.min(bottom_right.y).to_string())
+        .set("width", bottom_right.x.abs_diff(top_left.x).to_string())
+        .set("height", bottom_right.y.abs_diff(top_left.y).to_string())
+}
+
 #[inline]
 pub fn url_string(link: &str) -> String {
     format!("url({link})")
@@ -428,3 +870,179 @@ pub fn polygon_fill_rule(polyfill_mode: PolygonFillMode) -> String {
     }
     .to_owned()
 }
+
+/// Builds a `<filter>` applying `adjustment` to a bitmap drawn in the
+/// STRETCH_HALFTONE stretch mode, or returns `None` if it leaves colors
+/// unchanged.
+///
+/// The adjustments are applied in the order of the halftoning in GDI:
+/// reference black and white, gamma, contrast and brightness,
+/// colorfulness, red-green tint, then the logarithmic filter and the
+/// negative.
+pub fn color_adjustment_filter(adjustment: &ColorAdjustment) -> Option<Node> {
+    let mut primitives = vec![];
+
+    if adjustment.reference_black != 0 || adjustment.reference_white != 10_000 {
+        let black = f64::from(adjustment.reference_black) / 10_000.0;
+        let white = f64::from(adjustment.reference_white) / 10_000.0;
+        let slope = 1.0 / (white - black);
+
+        primitives.push(component_transfer(|_, function| {
+            linear_function(function, slope, -black * slope)
+        }));
+    }
+
+    let gammas = [
+        &adjustment.red_gamma,
+        &adjustment.green_gamma,
+        &adjustment.blue_gamma,
+    ];
+    if !gammas.iter().all(|v| v.must_not_be_performed()) {
+        // The exponent is the inverse of the gamma, so a larger gamma
+        // lightens the midtones.
+        primitives.push(component_transfer(|channel, function| {
+            let exponent = 10_000.0 / f64::from(gammas[channel].value());
+
+            function
+                .set("type", "gamma")
+                .set("amplitude", "1")
+                .set("exponent", format_number(exponent))
+                .set("offset", "0")
+        }));
+    }
+
+    if !adjustment.contrast.must_not_be_performed()
+        || !adjustment.brightness.must_not_be_performed()
+    {
+        // Contrast scales the distance from the middle gray.
+        let slope = 1.0 + f64::from(adjustment.contrast.value()) / 100.0;
+        let intercept = 0.5 * (1.0 - slope)
+            + f64::from(adjustment.brightness.value()) / 100.0;
+
+        primitives.push(component_transfer(|_, function| {
+            linear_function(function, slope, intercept)
+        }));
+    }
+
+    if !adjustment.colorfulness.must_not_be_performed() {
+        // -100 leaves only the luminance.
+        let saturation =
+            1.0 + f64::from(adjustment.colorfulness.value()) / 100.0;
+
+        primitives.push(
+            Node::new("feColorMatrix")
+                .set("type", "saturate")
+                .set("values", format_number(saturation)),
+        );
+    }
+
+    if !adjustment.red_green_tint.must_not_be_performed() {
+        // Positive values shift towards red and negative ones towards
+        // green.
+        let tint = f64::from(adjustment.red_green_tint.value()) / 200.0;
+
+        primitives.push(component_transfer(
+            |channel, function| match channel {
+                0 => linear_function(function, 1.0 + tint, 0.0),
+                1 => linear_function(function, 1.0 - tint, 0.0),
+                _ => function.set("type", "identity"),
+            },
+        ));
+    }
+
+    if adjustment.values.contains(ColorAdjustmentEnum::CA_LOG_FILTER) {
+        // Expands the dark end of the range.
+        let table = (0..=16)
+            .map(|i| format_number((1.0 + 9.0 * f64::from(i) / 16.0).log10()))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        primitives.push(component_transfer(|_, function| {
+            function.set("type", "table").set("tableValues", &table)
+        }));
+    }
+
+    if adjustment.values.contains(ColorAdjustmentEnum::CA_NEGATIVE) {
+        primitives.push(component_transfer(|_, function| {
+            function.set("type", "table").set("tableValues", "1 0")
+        }));
+    }
+
+    if primitives.is_empty() {
+        return None;
+    }
+
+    Some(primitives.into_iter().fold(
+        Node::new("filter").set("color-interpolation-filters", "sRGB"),
+        Node::add,
+    ))
+}
+
+/// Builds an `<feComponentTransfer>` whose `<feFuncR>`, `<feFuncG>` and
+/// `<feFuncB>` are set up by `function` from the channel index. The alpha
+/// channel is left unchanged.
+fn component_transfer(function: impl Fn(usize, Node) -> Node) -> Node {
+    ["feFuncR", "feFuncG", "feFuncB"].into_iter().enumerate().fold(
+        Node::new("feComponentTransfer"),
+        |transfer, (channel, name)| {
+            transfer.add(function(channel, Node::new(name)))
+        },
+    )
+}
+
+fn linear_function(function: Node, slope: f64, intercept: f64) -> Node {
+    function
+        .set("type", "linear")
+        .set("slope", format_number(slope))
+        .set("intercept", format_number(intercept))
+}
+
+fn format_number(v: f64) -> String {
+    let v = format!("{v:.4}");
+    let v = v.trim_end_matches('0').trim_end_matches('.');
+
+    if v == "-0" { "0".to_owned() } else { v.to_owned() }
+}
+
+/// Decodes standard base64 with padding, as in the data URLs of bitmaps.
+/// Returns `None` if `data` is not valid base64.
+pub fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some(u32::from(c - b'A')),
+            b'a'..=b'z' => Some(u32::from(c - b'a') + 26),
+            b'0'..=b'9' => Some(u32::from(c - b'0') + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let data = data.as_bytes();
+
+    if data.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(data.len() / 4 * 3);
+
+    for (i, chunk) in data.chunks(4).enumerate() {
+        let last = i + 1 == data.len() / 4;
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+
+        if padding > 2 || padding > 0 && !last {
+            return None;
+        }
+
+        let mut bits = 0;
+
+        for &c in &chunk[..4 - padding] {
+            bits = bits << 6 | value(c)?;
+        }
+
+        bits <<= 6 * padding;
+        out.extend_from_slice(&bits.to_be_bytes()[1..4 - padding]);
+    }
+
+    Some(out)
+}
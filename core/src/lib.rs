@@ -20,6 +20,9 @@
 #[macro_use]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(feature = "tracing")]
 #[macro_use]
 extern crate tracing;
@@ -55,10 +58,11 @@ mod imports {
     pub use alloc::{
         borrow::{Cow, ToOwned},
         collections::{BTreeMap, BTreeSet, VecDeque},
+        rc::Rc,
         str,
         string::{String, ToString},
         vec::Vec,
     };
 }
 
-pub use embedded_io::Read;
+pub use embedded_io::{Read, Write};
@@ -0,0 +1,789 @@
+//! Generating EMF metafiles from drawing calls.
+//!
+//! [`EmfBuilder`] mirrors the GDI functions that record into a metafile
+//! device context, so a metafile can be generated without Windows. It
+//! writes through an [`EmfWriter`], which fills in the header.
+
+use crate::{imports::*, parser::*};
+
+/// Pixels per inch of the reference device of built metafiles.
+const DPI: i64 = 96;
+
+/// Height in pixels assumed for the stock fonts when estimating the extent
+/// of text.
+const STOCK_FONT_HEIGHT: i32 = 16;
+
+/// Builds a metafile from calls that mirror the GDI functions recording
+/// into a metafile device context.
+///
+/// Coordinates are pixels of a 96 DPI reference device, in the default
+/// MM_TEXT mapping mode without a world transform. Objects are created at
+/// the lowest free index of the object table, as a metafile device context
+/// does. Every drawing call widens the bounds of the header by its extent,
+/// including half the width of the selected pen. The extent of text is
+/// estimated from its character spacing and the height of the selected
+/// font.
+#[derive(Clone, Debug)]
+pub struct EmfBuilder {
+    writer: EmfWriter,
+    // The objects created at each index of the object table. Index 0 is
+    // reserved.
+    objects: Vec<Option<Object>>,
+    position: wmf_core::parser::PointL,
+    pen_width: i32,
+    font_height: i32,
+}
+
+#[derive(Clone, Debug)]
+enum Object {
+    Pen { width: i32 },
+    Brush,
+    Font { height: i32 },
+    Other,
+}
+
+impl EmfBuilder {
+    /// Starts a metafile picture of `width` by `height` pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        // The frame is in 0.01 millimeter units.
+        let hundredths = |pixels: u32| (i64::from(pixels) * 2540 / DPI) as i32;
+        let millimeters =
+            |pixels: u32| (i64::from(pixels) * 254 / DPI / 10) as u32;
+        let header = EMR_HEADER {
+            record_type: RecordType::EMR_HEADER,
+            size: Size::from(0),
+            emf_header: Header {
+                bounds: empty_rect(),
+                frame: wmf_core::parser::RectL {
+                    left: 0,
+                    top: 0,
+                    right: hundredths(width),
+                    bottom: hundredths(height),
+                },
+                record_signature: FormatSignature::ENHMETA_SIGNATURE,
+                version: 0x0001_0000,
+                bytes: 0,
+                records: 0,
+                handles: 0,
+                reserved: 0,
+                n_description: 0,
+                off_description: 0,
+                n_pal_entries: 0,
+                device: wmf_core::parser::SizeL { cx: width, cy: height },
+                millimeters: wmf_core::parser::SizeL {
+                    cx: millimeters(width),
+                    cy: millimeters(height),
+                },
+            },
+            emf_header_record_buffer: Some(
+                EmfHeaderRecordBuffer::EmfMetafileHeaderExtension2 {
+                    emf_header_extension_1: HeaderExtension1 {
+                        cb_pixel_format: 0,
+                        off_pixel_format: 0,
+                        b_open_gl: 0,
+                    },
+                    emf_header_extension_2: HeaderExtension2 {
+                        micrometers_x: millimeters(width) * 1000,
+                        micrometers_y: millimeters(height) * 1000,
+                    },
+                    emf_description: None,
+                    emf_pixel_format: None,
+                },
+            ),
+        };
+
+        Self {
+            writer: EmfWriter::new(header),
+            objects: vec![None],
+            position: wmf_core::parser::PointL { x: 0, y: 0 },
+            pen_width: 1,
+            font_height: STOCK_FONT_HEIGHT,
+        }
+    }
+
+    /// Sets the description of the metafile to the name of the
+    /// `application` that created it and the `title` of the picture.
+    #[must_use]
+    pub fn with_description(mut self, application: &str, title: &str) -> Self {
+        let description = format!("{application}\0{title}\0\0");
+
+        if let Some(EmfHeaderRecordBuffer::EmfMetafileHeaderExtension2 {
+            emf_description,
+            ..
+        }) = &mut self.writer.header_mut().emf_header_record_buffer
+        {
+            *emf_description = Some(description);
+        }
+
+        self
+    }
+
+    /// Writes a record the builder has no method for.
+    ///
+    /// Its extent only widens the bounds of the header if it has a `Bounds`
+    /// field. An object it creates takes its index in the object table,
+    /// which must leave the number of handles within the 16 bits of the
+    /// header.
+    pub fn record(&mut self, record: &Record<'_>) -> Result<(), WriteError> {
+        if let Some(index) = object_index(record) {
+            if index >= u32::from(u16::MAX) {
+                return Err(WriteError::UnrepresentableField {
+                    field: "handles",
+                    cause: format!(
+                        "object index {index} does not fit in 16 bits"
+                    )
+                    .into(),
+                });
+            }
+
+            let index = index as usize;
+
+            if self.objects.len() <= index {
+                self.objects.resize(index + 1, None);
+            }
+
+            self.objects[index] = Some(Object::Other);
+        }
+
+        self.writer.write_record(record)
+    }
+
+    /// Creates a pen, as `CreatePenIndirect` does, and returns its handle.
+    pub fn create_pen(&mut self, log_pen: LogPen) -> Result<u32, WriteError> {
+        let ih_pen = self.allocate(Object::Pen { width: log_pen.width.x });
+
+        self.writer.write_record(&Record::EMR_CREATEPEN(EMR_CREATEPEN {
+            record_type: RecordType::EMR_CREATEPEN,
+            size: Size::from(0),
+            ih_pen,
+            log_pen,
+        }))?;
+
+        Ok(ih_pen)
+    }
+
+    /// Creates a brush, as `CreateBrushIndirect` does, and returns its
+    /// handle.
+    pub fn create_brush_indirect(
+        &mut self,
+        log_brush: LogBrushEx,
+    ) -> Result<u32, WriteError> {
+        let ih_brush = self.allocate(Object::Brush);
+
+        self.writer.write_record(&Record::EMR_CREATEBRUSHINDIRECT(
+            EMR_CREATEBRUSHINDIRECT {
+                record_type: RecordType::EMR_CREATEBRUSHINDIRECT,
+                size: Size::from(0),
+                ih_brush,
+                log_brush,
+            },
+        ))?;
+
+        Ok(ih_brush)
+    }
+
+    /// Creates a font, as `CreateFontIndirectExW` does, and returns its
+    /// handle.
+    pub fn ext_create_font_indirect_w(
+        &mut self,
+        log_font_ex: LogFontEx,
+    ) -> Result<u32, WriteError> {
+        let ih_fonts = self.allocate(Object::Font {
+            height: log_font_ex.log_font.height.saturating_abs(),
+        });
+        let elw = ELW::LogFontExDv(vec![LogFontExDv {
+            log_font_ex,
+            design_vector: DesignVector {
+                signature: 0x0800_7664,
+                num_axes: 0,
+                values: vec![],
+            },
+        }]);
+
+        self.writer.write_record(&Record::EMR_EXTCREATEFONTINDIRECTW(
+            EMR_EXTCREATEFONTINDIRECTW {
+                record_type: RecordType::EMR_EXTCREATEFONTINDIRECTW,
+                size: Size::from(0),
+                ih_fonts,
+                elw,
+            },
+        ))?;
+
+        Ok(ih_fonts)
+    }
+
+    /// Selects the object at `handle`, created by this builder, into the
+    /// device context.
+    pub fn select_object(&mut self, handle: u32) -> Result<(), WriteError> {
+        match self.objects.get(handle as usize) {
+            Some(Some(Object::Pen { width })) => self.pen_width = *width,
+            Some(Some(Object::Font { height })) => self.font_height = *height,
+            Some(Some(Object::Brush | Object::Other)) => {}
+            _ => return Err(WriteError::UnknownObject { handle }),
+        }
+
+        self.write_select_object(handle)
+    }
+
+    /// Selects a stock object into the device context.
+    pub fn select_stock_object(
+        &mut self,
+        object: StockObject,
+    ) -> Result<(), WriteError> {
+        match object {
+            StockObject::WHITE_PEN
+            | StockObject::BLACK_PEN
+            | StockObject::DC_PEN => self.pen_width = 1,
+            StockObject::NULL_PEN => self.pen_width = 0,
+            StockObject::OEM_FIXED_FONT
+            | StockObject::ANSI_FIXED_FONT
+            | StockObject::ANSI_VAR_FONT
+            | StockObject::SYSTEM_FONT
+            | StockObject::DEVICE_DEFAULT_FONT
+            | StockObject::SYSTEM_FIXED_FONT
+            | StockObject::DEFAULT_GUI_FONT => {
+                self.font_height = STOCK_FONT_HEIGHT;
+            }
+            _ => {}
+        }
+
+        self.write_select_object(object as u32)
+    }
+
+    /// Deletes the object at `handle` and frees its index in the object
+    /// table for the next object.
+    pub fn delete_object(&mut self, handle: u32) -> Result<(), WriteError> {
+        match self.objects.get_mut(handle as usize) {
+            Some(object @ Some(_)) => *object = None,
+            _ => return Err(WriteError::UnknownObject { handle }),
+        }
+
+        self.writer.write_record(&Record::EMR_DELETEOBJECT(EMR_DELETEOBJECT {
+            record_type: RecordType::EMR_DELETEOBJECT,
+            size: Size::from(0),
+            in_object: handle,
+        }))
+    }
+
+    pub fn set_bk_mode(
+        &mut self,
+        background_mode: BackgroundMode,
+    ) -> Result<(), WriteError> {
+        self.writer.write_record(&Record::EMR_SETBKMODE(EMR_SETBKMODE {
+            record_type: RecordType::EMR_SETBKMODE,
+            size: Size::from(0),
+            background_mode,
+        }))
+    }
+
+    pub fn set_bk_color(
+        &mut self,
+        color: wmf_core::parser::ColorRef,
+    ) -> Result<(), WriteError> {
+        self.writer.write_record(&Record::EMR_SETBKCOLOR(EMR_SETBKCOLOR {
+            record_type: RecordType::EMR_SETBKCOLOR,
+            size: Size::from(0),
+            color,
+        }))
+    }
+
+    pub fn set_text_color(
+        &mut self,
+        color: wmf_core::parser::ColorRef,
+    ) -> Result<(), WriteError> {
+        self.writer.write_record(&Record::EMR_SETTEXTCOLOR(EMR_SETTEXTCOLOR {
+            record_type: RecordType::EMR_SETTEXTCOLOR,
+            size: Size::from(0),
+            color,
+        }))
+    }
+
+    /// Moves the current position to (`x`, `y`).
+    pub fn move_to(&mut self, x: i32, y: i32) -> Result<(), WriteError> {
+        self.position = wmf_core::parser::PointL { x, y };
+        self.writer.write_record(&Record::EMR_MOVETOEX(EMR_MOVETOEX {
+            record_type: RecordType::EMR_MOVETOEX,
+            size: Size::from(0),
+            offset: self.position.clone(),
+        }))
+    }
+
+    /// Draws a line from the current position to (`x`, `y`), which becomes
+    /// the current position.
+    pub fn line_to(&mut self, x: i32, y: i32) -> Result<(), WriteError> {
+        let point = wmf_core::parser::PointL { x, y };
+        let bounds = self
+            .stroked(points_bounds(&[self.position.clone(), point.clone()]));
+
+        self.position = point.clone();
+        self.writer.write_record(&Record::EMR_LINETO(EMR_LINETO {
+            record_type: RecordType::EMR_LINETO,
+            size: Size::from(0),
+            point,
+        }))?;
+        self.writer.include_bounds(&bounds);
+
+        Ok(())
+    }
+
+    /// Draws a rectangle outlined with the selected pen and filled with
+    /// the selected brush. The right and bottom edges are excluded.
+    pub fn rectangle(
+        &mut self,
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    ) -> Result<(), WriteError> {
+        let bx = wmf_core::parser::RectL { left, top, right, bottom };
+        let bounds = self.stroked(box_bounds(&bx));
+
+        self.writer.write_record(&Record::EMR_RECTANGLE(EMR_RECTANGLE {
+            record_type: RecordType::EMR_RECTANGLE,
+            size: Size::from(0),
+            bx,
+        }))?;
+        self.writer.include_bounds(&bounds);
+
+        Ok(())
+    }
+
+    /// Draws an ellipse inscribed in a rectangle, outlined with the
+    /// selected pen and filled with the selected brush.
+    pub fn ellipse(
+        &mut self,
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    ) -> Result<(), WriteError> {
+        let bx = wmf_core::parser::RectL { left, top, right, bottom };
+        let bounds = self.stroked(box_bounds(&bx));
+
+        self.writer.write_record(&Record::EMR_ELLIPSE(EMR_ELLIPSE {
+            record_type: RecordType::EMR_ELLIPSE,
+            size: Size::from(0),
+            bx,
+        }))?;
+        self.writer.include_bounds(&bounds);
+
+        Ok(())
+    }
+
+    /// Draws line segments connecting `points`.
+    pub fn polyline(
+        &mut self,
+        points: &[wmf_core::parser::PointL],
+    ) -> Result<(), WriteError> {
+        let bounds = self.stroked(points_bounds(points));
+
+        self.writer.write_record(&Record::EMR_POLYLINE(EMR_POLYLINE {
+            record_type: RecordType::EMR_POLYLINE,
+            size: Size::from(0),
+            bounds,
+            count: WriteError::u32_field("count", points.len())?,
+            a_points: points.to_vec(),
+        }))
+    }
+
+    /// Draws a polygon of `points`, outlined with the selected pen and
+    /// filled with the selected brush.
+    pub fn polygon(
+        &mut self,
+        points: &[wmf_core::parser::PointL],
+    ) -> Result<(), WriteError> {
+        let bounds = self.stroked(points_bounds(points));
+
+        self.writer.write_record(&Record::EMR_POLYGON(EMR_POLYGON {
+            record_type: RecordType::EMR_POLYGON,
+            size: Size::from(0),
+            bounds,
+            count: WriteError::u32_field("count", points.len())?,
+            a_points: points.to_vec(),
+        }))
+    }
+
+    /// Draws `text` with the selected font, as `ExtTextOutW` does.
+    ///
+    /// `dx` holds the spacing from each character cell to the next, one
+    /// value per UTF-16 code unit of `text`, or two with `ETO_PDY`.
+    pub fn ext_text_out_w(
+        &mut self,
+        x: i32,
+        y: i32,
+        options: ExtTextOutOptionsFlags,
+        rectangle: Option<wmf_core::parser::RectL>,
+        text: &str,
+        dx: &[u32],
+    ) -> Result<(), WriteError> {
+        let advance = if options.contains(ExtTextOutOptions::ETO_PDY) {
+            dx.iter().step_by(2).map(|v| i64::from(*v)).sum::<i64>()
+        } else {
+            dx.iter().map(|v| i64::from(*v)).sum::<i64>()
+        };
+        let mut bounds = wmf_core::parser::RectL {
+            left: x,
+            top: y,
+            right: (i64::from(x) + advance - 1) as i32,
+            bottom: y.saturating_add(self.font_height - 1),
+        };
+
+        if let Some(rectangle) = &rectangle {
+            let rectangle = box_bounds(rectangle);
+
+            bounds = wmf_core::parser::RectL {
+                left: bounds.left.min(rectangle.left),
+                top: bounds.top.min(rectangle.top),
+                right: bounds.right.max(rectangle.right),
+                bottom: bounds.bottom.max(rectangle.bottom),
+            };
+        }
+
+        self.writer.write_record(&Record::EMR_EXTTEXTOUTW(EMR_EXTTEXTOUTW {
+            record_type: RecordType::EMR_EXTTEXTOUTW,
+            size: Size::from(0),
+            bounds,
+            i_graphics_mode: GraphicsMode::GM_COMPATIBLE,
+            // Page units are pixels, 2540 / 96 hundredths of a millimeter.
+            ex_scale: 2540.0 / DPI as f32,
+            ey_scale: 2540.0 / DPI as f32,
+            w_emr_text: EmrText {
+                reference: wmf_core::parser::PointL { x, y },
+                chars: 0,
+                off_string: 0,
+                options,
+                rectangle,
+                off_dx: 0,
+                string_buffer: OutputString::Unicode(text.into()),
+                dx_buffer: dx.to_vec(),
+            },
+        }))
+    }
+
+    /// Copies the whole of a DIB to a destination rectangle, stretching it
+    /// to fit, as `StretchDIBits` with `SRCCOPY` does.
+    ///
+    /// `bmi` holds the DIB header and color table, and `bits` the pixels.
+    pub fn stretch_dibits(
+        &mut self,
+        x_dest: i32,
+        y_dest: i32,
+        cx_dest: i32,
+        cy_dest: i32,
+        bmi: &[u8],
+        bits: &[u8],
+    ) -> Result<(), WriteError> {
+        let (cx_src, cy_src) = dib_size(bmi)?;
+
+        self.writer.write_record(&Record::EMR_STRETCHDIBITS(
+            EMR_STRETCHDIBITS {
+                record_type: RecordType::EMR_STRETCHDIBITS,
+                size: Size::from(0),
+                bounds: box_bounds(&wmf_core::parser::RectL {
+                    left: x_dest,
+                    top: y_dest,
+                    right: x_dest.saturating_add(cx_dest),
+                    bottom: y_dest.saturating_add(cy_dest),
+                }),
+                x_dest,
+                y_dest,
+                x_src: 0,
+                y_src: 0,
+                cx_src,
+                cy_src,
+                off_bmi_src: 0,
+                cb_bmi_src: 0,
+                off_bits_src: 0,
+                cb_bits_src: 0,
+                usage_src: DIBColors::DIB_RGB_COLORS,
+                bit_bt_raster_operation:
+                    wmf_core::parser::TernaryRasterOperation::SRCCOPY,
+                cx_dest,
+                cy_dest,
+                bmi_src: Cow::Borrowed(bmi),
+                bits_src: Cow::Borrowed(bits),
+            },
+        ))
+    }
+
+    /// Writes the metafile to `buf` and returns the number of bytes
+    /// written.
+    pub fn finish_to<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        self.writer.finish_to(buf)
+    }
+
+    /// Returns the metafile as bytes.
+    pub fn finish(self) -> Result<Vec<u8>, WriteError> {
+        self.writer.finish()
+    }
+
+    /// Takes the lowest free index of the object table for `object`.
+    fn allocate(&mut self, object: Object) -> u32 {
+        let index = if let Some(free) =
+            self.objects.iter().skip(1).position(Option::is_none)
+        {
+            free + 1
+        } else {
+            self.objects.push(None);
+            self.objects.len() - 1
+        };
+
+        self.objects[index] = Some(object);
+        index as u32
+    }
+
+    fn write_select_object(
+        &mut self,
+        in_object: u32,
+    ) -> Result<(), WriteError> {
+        self.writer.write_record(&Record::EMR_SELECTOBJECT(EMR_SELECTOBJECT {
+            record_type: RecordType::EMR_SELECTOBJECT,
+            size: Size::from(0),
+            in_object,
+        }))
+    }
+
+    /// Widens `bounds` by half the width of the selected pen.
+    fn stroked(
+        &self,
+        bounds: wmf_core::parser::RectL,
+    ) -> wmf_core::parser::RectL {
+        let half = self.pen_width / 2;
+
+        if bounds.left > bounds.right {
+            return bounds;
+        }
+
+        wmf_core::parser::RectL {
+            left: bounds.left.saturating_sub(half),
+            top: bounds.top.saturating_sub(half),
+            right: bounds.right.saturating_add(half),
+            bottom: bounds.bottom.saturating_add(half),
+        }
+    }
+}
+
+/// The rectangle that bounds nothing, as Windows writes it.
+fn empty_rect() -> wmf_core::parser::RectL {
+    wmf_core::parser::RectL { left: 0, top: 0, right: -1, bottom: -1 }
+}
+
+/// Returns the inclusive bounds of a box whose right and bottom edges are
+/// excluded, whichever corners it is given by.
+fn box_bounds(bx: &wmf_core::parser::RectL) -> wmf_core::parser::RectL {
+    wmf_core::parser::RectL {
+        left: bx.left.min(bx.right),
+        top: bx.top.min(bx.bottom),
+        right: bx.left.max(bx.right) - 1,
+        bottom: bx.top.max(bx.bottom) - 1,
+    }
+}
+
+/// Returns the inclusive bounds of `points`.
+fn points_bounds(
+    points: &[wmf_core::parser::PointL],
+) -> wmf_core::parser::RectL {
+    let Some((first, rest)) = points.split_first() else {
+        return empty_rect();
+    };
+    let bounds = wmf_core::parser::RectL {
+        left: first.x,
+        top: first.y,
+        right: first.x,
+        bottom: first.y,
+    };
+
+    rest.iter().fold(bounds, |bounds, point| wmf_core::parser::RectL {
+        left: bounds.left.min(point.x),
+        top: bounds.top.min(point.y),
+        right: bounds.right.max(point.x),
+        bottom: bounds.bottom.max(point.y),
+    })
+}
+
+/// Returns the width and height of the DIB whose header `bmi` holds.
+fn dib_size(bmi: &[u8]) -> Result<(i32, i32), WriteError> {
+    let invalid = |cause: String| WriteError::UnrepresentableField {
+        field: "bmi_src",
+        cause: cause.into(),
+    };
+
+    wmf_core::parser::BitmapInfoHeader::parse(&mut &bmi[..])
+        .map_err(|err| invalid(err.to_string()))?;
+
+    let field = |offset: usize, len: usize| {
+        bmi.get(offset..offset + len)
+            .map(|v| v.iter().rev().fold(0_u32, |n, b| n << 8 | u32::from(*b)))
+            .ok_or_else(|| invalid("DIB header is truncated".into()))
+    };
+
+    // A BITMAPCOREHEADER of 12 bytes holds 16-bit dimensions, the others
+    // 32-bit ones. A negative height marks a top-down DIB.
+    if field(0, 4)? == 12 {
+        Ok((field(4, 2)? as i32, field(6, 2)? as i32))
+    } else {
+        Ok((field(4, 4)? as i32, (field(8, 4)? as i32).saturating_abs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_records(metafile: &[u8]) -> Vec<Record<'_>> {
+        EmrReader::new(metafile)
+            .borrowed_records()
+            .map(|entry| entry.map(|v| v.record))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("records should parse")
+    }
+
+    fn pen(width: i32) -> LogPen {
+        LogPen {
+            pen_style: PenStyleFlags::empty(),
+            width: wmf_core::parser::PointL { x: width, y: 0 },
+            color_ref: wmf_core::parser::ColorRef::black(),
+        }
+    }
+
+    #[test]
+    fn builds_a_metafile_with_recomputed_header() {
+        let mut builder =
+            EmfBuilder::new(200, 100).with_description("report", "chart");
+        let ih_pen = builder.create_pen(pen(4)).expect("pen should be created");
+
+        builder.select_object(ih_pen).expect("pen should be selected");
+        builder.move_to(10, 10).expect("move should be written");
+        builder.line_to(50, 30).expect("line should be written");
+        builder
+            .rectangle(60, 60, 100, 80)
+            .expect("rectangle should be written");
+
+        let metafile = builder.finish().expect("metafile should be written");
+        let records = read_records(&metafile);
+        let Record::EMR_HEADER(header) = &records[0] else {
+            panic!("first record should be EMR_HEADER");
+        };
+        let header_bounds = &header.emf_header.bounds;
+
+        assert_eq!(ih_pen, 1);
+        assert_eq!(records.len(), 7);
+        assert!(matches!(records.last(), Some(Record::EMR_EOF(_))));
+        assert_eq!(
+            (header.emf_header.records, header.emf_header.handles),
+            (7, 2)
+        );
+        assert_eq!(header.emf_header.bytes as usize, metafile.len());
+        // The line and the rectangle, widened by half the pen width.
+        assert_eq!(
+            (
+                header_bounds.left,
+                header_bounds.top,
+                header_bounds.right,
+                header_bounds.bottom
+            ),
+            (8, 8, 101, 81)
+        );
+        assert_eq!(
+            (header.emf_header.frame.right, header.emf_header.frame.bottom),
+            (5291, 2645)
+        );
+        assert!(matches!(
+            &header.emf_header_record_buffer,
+            Some(EmfHeaderRecordBuffer::EmfMetafileHeaderExtension2 {
+                emf_description: Some(description),
+                ..
+            }) if description == "report\0chart\0\0"
+        ));
+    }
+
+    #[test]
+    fn reuses_deleted_object_handles() {
+        let mut builder = EmfBuilder::new(10, 10);
+        let first = builder.create_pen(pen(1)).expect("pen should be created");
+        let second = builder.create_pen(pen(1)).expect("pen should be created");
+
+        builder.delete_object(first).expect("pen should be deleted");
+
+        let third = builder.create_pen(pen(1)).expect("pen should be created");
+
+        assert_eq!((first, second, third), (1, 2, 1));
+        assert!(matches!(
+            builder.delete_object(3),
+            Err(WriteError::UnknownObject { handle: 3 })
+        ));
+
+        builder.delete_object(third).expect("pen should be deleted");
+
+        assert!(matches!(
+            builder.select_object(third),
+            Err(WriteError::UnknownObject { handle: 1 })
+        ));
+        builder
+            .select_stock_object(StockObject::BLACK_PEN)
+            .expect("stock pen should be selected");
+    }
+
+    #[test]
+    fn record_rejects_object_indices_beyond_the_header() {
+        let mut builder = EmfBuilder::new(10, 10);
+        let create_pen = |ih_pen| {
+            Record::EMR_CREATEPEN(EMR_CREATEPEN {
+                record_type: RecordType::EMR_CREATEPEN,
+                size: Size::from(0),
+                ih_pen,
+                log_pen: pen(1),
+            })
+        };
+
+        assert!(matches!(
+            builder.record(&create_pen(u32::MAX)),
+            Err(WriteError::UnrepresentableField { field: "handles", .. })
+        ));
+        builder.record(&create_pen(3)).expect("record should be written");
+        builder.select_object(3).expect("pen should be selected");
+
+        let metafile = builder.finish().expect("metafile should be written");
+        let Record::EMR_HEADER(header) = &read_records(&metafile)[0] else {
+            panic!("first record should be EMR_HEADER");
+        };
+
+        assert_eq!(header.emf_header.handles, 4);
+    }
+
+    #[test]
+    fn stretch_dibits_copies_the_whole_bitmap() {
+        // A BITMAPINFOHEADER of a 2x3 top-down 24-bit bitmap.
+        let bmi = [
+            40_u32.to_le_bytes().as_slice(),
+            &2_i32.to_le_bytes(),
+            &(-3_i32).to_le_bytes(),
+            &1_u16.to_le_bytes(),
+            &24_u16.to_le_bytes(),
+            &[0; 24],
+        ]
+        .concat();
+        let mut builder = EmfBuilder::new(100, 100);
+
+        builder
+            .stretch_dibits(5, 5, 20, 30, &bmi, &[0; 24])
+            .expect("bitmap should be written");
+        assert!(matches!(
+            builder.stretch_dibits(0, 0, 1, 1, &bmi[..8], &[]),
+            Err(WriteError::UnrepresentableField { field: "bmi_src", .. })
+        ));
+
+        let metafile = builder.finish().expect("metafile should be written");
+        let records = read_records(&metafile);
+        let Record::EMR_STRETCHDIBITS(record) = &records[1] else {
+            panic!("second record should be EMR_STRETCHDIBITS");
+        };
+
+        assert_eq!((record.cx_src, record.cy_src), (2, 3));
+        assert_eq!((record.bounds.right, record.bounds.bottom), (24, 34));
+        assert_eq!(&record.bmi_src[..], &bmi[..]);
+    }
+}
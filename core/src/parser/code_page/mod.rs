@@ -0,0 +1,347 @@
+//! Decoding of the 8-bit character strings carried by EMR_EXTTEXTOUTA and
+//! EMR_POLYTEXTOUTA records.
+//!
+//! The code page of such a string is not recorded in the metafile; it is
+//! implied by the charset of the font selected when the record is played
+//! back. The tables under `tables/` are generated by `tables/generate.py`.
+
+use crate::imports::*;
+
+/// A Windows code page used to decode an ANSI character string.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CodePage {
+    /// OEM United States, used by OEM_CHARSET.
+    CP437,
+    /// Thai, used by THAI_CHARSET.
+    CP874,
+    /// Japanese Shift_JIS, used by SHIFTJIS_CHARSET.
+    CP932,
+    /// Simplified Chinese GBK, used by GB2312_CHARSET.
+    CP936,
+    /// Korean Unified Hangul Code, used by HANGUL_CHARSET.
+    CP949,
+    /// Traditional Chinese Big5, used by CHINESEBIG5_CHARSET.
+    CP950,
+    /// Central European, used by EASTEUROPE_CHARSET.
+    CP1250,
+    /// Cyrillic, used by RUSSIAN_CHARSET.
+    CP1251,
+    /// Western European, used by ANSI_CHARSET and DEFAULT_CHARSET.
+    CP1252,
+    /// Greek, used by GREEK_CHARSET.
+    CP1253,
+    /// Turkish, used by TURKISH_CHARSET.
+    CP1254,
+    /// Hebrew, used by HEBREW_CHARSET.
+    CP1255,
+    /// Arabic, used by ARABIC_CHARSET.
+    CP1256,
+    /// Baltic, used by BALTIC_CHARSET.
+    CP1257,
+    /// Vietnamese, used by VIETNAMESE_CHARSET.
+    CP1258,
+    /// Korean Johab, used by JOHAB_CHARSET. Hangul is composed from the
+    /// jamo bits of each code, and the symbols and Hanja share the CP949
+    /// table through their KS X 1001 position.
+    CP1361,
+    /// Every byte maps to the code point of the same value. Used by
+    /// SYMBOL_CHARSET, whose fonts index glyphs by the byte itself.
+    Latin1,
+}
+
+/// Number of trail byte entries stored per lead byte, covering
+/// `0x40..=0xFE`.
+const TRAIL_COUNT: usize = 191;
+
+impl CodePage {
+    /// Returns the code page Windows uses for strings drawn with a font of
+    /// the given charset. Charsets without a dedicated table fall back to
+    /// Windows-1252.
+    pub fn from_charset(charset: wmf_core::parser::CharacterSet) -> Self {
+        use wmf_core::parser::CharacterSet;
+
+        match charset {
+            CharacterSet::SYMBOL_CHARSET => Self::Latin1,
+            CharacterSet::SHIFTJIS_CHARSET => Self::CP932,
+            CharacterSet::HANGUL_CHARSET => Self::CP949,
+            CharacterSet::JOHAB_CHARSET => Self::CP1361,
+            CharacterSet::GB2312_CHARSET => Self::CP936,
+            CharacterSet::CHINESEBIG5_CHARSET => Self::CP950,
+            CharacterSet::GREEK_CHARSET => Self::CP1253,
+            CharacterSet::TURKISH_CHARSET => Self::CP1254,
+            CharacterSet::VIETNAMESE_CHARSET => Self::CP1258,
+            CharacterSet::HEBREW_CHARSET => Self::CP1255,
+            CharacterSet::ARABIC_CHARSET => Self::CP1256,
+            CharacterSet::BALTIC_CHARSET => Self::CP1257,
+            CharacterSet::RUSSIAN_CHARSET => Self::CP1251,
+            CharacterSet::THAI_CHARSET => Self::CP874,
+            CharacterSet::EASTEUROPE_CHARSET => Self::CP1250,
+            CharacterSet::OEM_CHARSET => Self::CP437,
+            _ => Self::CP1252,
+        }
+    }
+
+    /// Decodes `bytes`, pairing each character with the number of bytes
+    /// that encode it so callers can map per-byte values (such as the
+    /// intercharacter spacing of EmrText) onto characters. Undefined byte
+    /// sequences decode to U+FFFD.
+    pub fn decode(self, bytes: &[u8]) -> Vec<(char, usize)> {
+        let mut chars = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let b = bytes[i];
+
+            if b < 0x80 || self == Self::Latin1 {
+                chars.push((char::from(b), 1));
+                i += 1;
+                continue;
+            }
+
+            if self == Self::CP1361 {
+                let (c, len) = decode_johab(b, bytes.get(i + 1).copied());
+
+                chars.push((c, len));
+                i += len;
+                continue;
+            }
+
+            let table = self.table();
+
+            if !self.is_double_byte() {
+                chars.push((lookup(table, usize::from(b - 0x80)), 1));
+                i += 1;
+                continue;
+            }
+
+            // Double-byte tables start with a 256 byte row index followed
+            // by the 128 single-byte entries.
+            let row = usize::from(table[usize::from(b)]);
+
+            if row == 0 {
+                chars.push((lookup(&table[256..], usize::from(b - 0x80)), 1));
+                i += 1;
+                continue;
+            }
+
+            match bytes.get(i + 1) {
+                Some(&t) if (0x40..=0xFE).contains(&t) => {
+                    let rows = &table[256 + 128 * 2..];
+
+                    chars.push((
+                        lookup(
+                            rows,
+                            (row - 1) * TRAIL_COUNT + usize::from(t - 0x40),
+                        ),
+                        2,
+                    ));
+                    i += 2;
+                }
+                // A lead byte without a valid trail byte is dropped on its
+                // own so the following byte is decoded afresh.
+                _ => {
+                    chars.push((char::REPLACEMENT_CHARACTER, 1));
+                    i += 1;
+                }
+            }
+        }
+
+        chars
+    }
+
+    /// Decodes `bytes` into a `String`. See [`CodePage::decode`].
+    pub fn decode_to_string(self, bytes: &[u8]) -> String {
+        self.decode(bytes).into_iter().map(|(c, _)| c).collect()
+    }
+
+    const fn is_double_byte(self) -> bool {
+        matches!(self, Self::CP932 | Self::CP936 | Self::CP949 | Self::CP950)
+    }
+
+    const fn table(self) -> &'static [u8] {
+        match self {
+            Self::CP437 => include_bytes!("tables/cp437.bin"),
+            Self::CP874 => include_bytes!("tables/cp874.bin"),
+            Self::CP932 => include_bytes!("tables/cp932.bin"),
+            Self::CP936 => include_bytes!("tables/cp936.bin"),
+            Self::CP949 => include_bytes!("tables/cp949.bin"),
+            Self::CP950 => include_bytes!("tables/cp950.bin"),
+            Self::CP1250 => include_bytes!("tables/cp1250.bin"),
+            Self::CP1251 => include_bytes!("tables/cp1251.bin"),
+            Self::CP1252 => include_bytes!("tables/cp1252.bin"),
+            Self::CP1253 => include_bytes!("tables/cp1253.bin"),
+            Self::CP1254 => include_bytes!("tables/cp1254.bin"),
+            Self::CP1255 => include_bytes!("tables/cp1255.bin"),
+            Self::CP1256 => include_bytes!("tables/cp1256.bin"),
+            Self::CP1257 => include_bytes!("tables/cp1257.bin"),
+            Self::CP1258 => include_bytes!("tables/cp1258.bin"),
+            Self::CP1361 | Self::Latin1 => &[],
+        }
+    }
+}
+
+/// Decodes the Johab character starting with `lead`, returning it with the
+/// number of bytes it takes. As in the table driven code pages, a lead byte
+/// without a valid trail byte decodes to U+FFFD on its own.
+fn decode_johab(lead: u8, trail: Option<u8>) -> (char, usize) {
+    // The compatibility jamo of the initial consonants and of the final
+    // consonants 1 to 27 of a syllable; the vowels are contiguous.
+    const INITIAL_JAMO: [u16; 19] = [
+        0x3131, 0x3132, 0x3134, 0x3137, 0x3138, 0x3139, 0x3141, 0x3142, 0x3143,
+        0x3145, 0x3146, 0x3147, 0x3148, 0x3149, 0x314A, 0x314B, 0x314C, 0x314D,
+        0x314E,
+    ];
+    const FINAL_JAMO: [u16; 27] = [
+        0x3131, 0x3132, 0x3133, 0x3134, 0x3135, 0x3136, 0x3137, 0x3139, 0x313A,
+        0x313B, 0x313C, 0x313D, 0x313E, 0x313F, 0x3140, 0x3141, 0x3142, 0x3144,
+        0x3145, 0x3146, 0x3147, 0x3148, 0x314A, 0x314B, 0x314C, 0x314D, 0x314E,
+    ];
+
+    let Some(trail) = trail else {
+        return (char::REPLACEMENT_CHARACTER, 1);
+    };
+    let invalid = (char::REPLACEMENT_CHARACTER, 2);
+
+    match lead {
+        // Hangul: a set bit followed by 5-bit codes of the initial
+        // consonant, the vowel and the final consonant.
+        0x84..=0xD3 if matches!(trail, 0x41..=0x7E | 0x81..=0xFE) => {
+            let code = u16::from_be_bytes([lead, trail]);
+            let initial = match (code >> 10) & 0x1F {
+                1 => None,
+                v @ 2..=20 => Some(v - 2),
+                _ => return invalid,
+            };
+            let vowel = match (code >> 5) & 0x1F {
+                2 => None,
+                v @ 3..=7 => Some(v - 3),
+                v @ 10..=15 => Some(v - 5),
+                v @ 18..=23 => Some(v - 7),
+                v @ 26..=29 => Some(v - 9),
+                _ => return invalid,
+            };
+            let last = match code & 0x1F {
+                v @ 1..=17 => v - 1,
+                v @ 19..=29 => v - 2,
+                _ => return invalid,
+            };
+            let c = match (initial, vowel, last) {
+                (Some(l), Some(v), t) => 0xAC00 + (l * 21 + v) * 28 + t,
+                (Some(l), None, 0) => INITIAL_JAMO[usize::from(l)],
+                (None, Some(v), 0) => 0x314F + v,
+                (None, None, 0) => 0x3000,
+                (None, None, t) => FINAL_JAMO[usize::from(t - 1)],
+                _ => return invalid,
+            };
+
+            char::from_u32(u32::from(c)).map_or(invalid, |c| (c, 2))
+        }
+        // Symbols and Hanja: two KS X 1001 rows per lead byte. The Hangul
+        // jamo row is encoded as Hangul above.
+        0xD9..=0xDE | 0xE0..=0xF9
+            if matches!(trail, 0x31..=0x7E | 0x91..=0xFE)
+                && !(lead == 0xDA && matches!(trail, 0xA1..=0xD3)) =>
+        {
+            let mut row = if lead < 0xE0 {
+                2 * (lead - 0xD9)
+            } else {
+                2 * (lead - 0xE0) + 0x29
+            };
+            let mut cell =
+                if trail < 0x91 { trail - 0x31 } else { trail - 0x43 };
+
+            if cell >= 0x5E {
+                row += 1;
+                cell -= 0x5E;
+            }
+
+            match CodePage::CP949.decode(&[row + 0xA1, cell + 0xA1])[..] {
+                [(c, 2)] => (c, 2),
+                _ => invalid,
+            }
+        }
+        _ => (char::REPLACEMENT_CHARACTER, 1),
+    }
+}
+
+/// Reads the little-endian u16 entry at `index`, mapping unmapped (zero)
+/// entries to U+FFFD.
+#[inline]
+fn lookup(table: &[u8], index: usize) -> char {
+    let v = u16::from_le_bytes([table[index * 2], table[index * 2 + 1]]);
+
+    if v == 0 {
+        return char::REPLACEMENT_CHARACTER;
+    }
+
+    char::from_u32(u32::from(v)).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_is_identical_in_every_code_page() {
+        for cp in [CodePage::CP932, CodePage::CP1251, CodePage::Latin1] {
+            assert_eq!(cp.decode_to_string(b"EMF 1.0"), "EMF 1.0");
+        }
+    }
+
+    #[test]
+    fn shift_jis_decodes_double_and_half_width_characters() {
+        // "日本" followed by half-width katakana "ｱ".
+        let decoded = CodePage::CP932.decode(&[0x93, 0xFA, 0x96, 0x7B, 0xB1]);
+
+        assert_eq!(decoded, vec![('日', 2), ('本', 2), ('ｱ', 1)]);
+    }
+
+    #[test]
+    fn double_byte_code_pages_decode_cjk() {
+        assert_eq!(CodePage::CP936.decode_to_string(&[0xD6, 0xD0]), "中");
+        assert_eq!(CodePage::CP950.decode_to_string(&[0xA4, 0xA4]), "中");
+        assert_eq!(CodePage::CP949.decode_to_string(&[0xC7, 0xD1]), "한");
+        assert_eq!(CodePage::CP936.decode_to_string(&[0x80]), "€");
+    }
+
+    #[test]
+    fn johab_composes_hangul_and_maps_hanja_onto_cp949() {
+        assert_eq!(
+            CodePage::from_charset(
+                wmf_core::parser::CharacterSet::JOHAB_CHARSET
+            ),
+            CodePage::CP1361
+        );
+        // "한글", the jamo ㄱ and ㅏ on their own, and the Hanja "中".
+        assert_eq!(
+            CodePage::CP1361.decode_to_string(&[
+                0xD0, 0x65, 0x8B, 0x69, 0x88, 0x41, 0x84, 0x61, 0xF3, 0xE9,
+            ]),
+            "한글ㄱㅏ中"
+        );
+        assert_eq!(CodePage::CP1361.decode(&[0xD0, 0x20]), vec![
+            (char::REPLACEMENT_CHARACTER, 1),
+            (' ', 1)
+        ]);
+    }
+
+    #[test]
+    fn single_byte_code_pages_decode_upper_half() {
+        assert_eq!(CodePage::CP1251.decode_to_string(&[0xC0, 0xE1]), "Аб");
+        assert_eq!(CodePage::CP1253.decode_to_string(&[0xC1, 0xE2]), "Αβ");
+        assert_eq!(CodePage::CP1252.decode_to_string(&[0x80, 0xE9]), "€é");
+        assert_eq!(CodePage::CP1250.decode_to_string(&[0x8A]), "Š");
+    }
+
+    #[test]
+    fn truncated_lead_byte_decodes_to_replacement_character() {
+        assert_eq!(CodePage::CP932.decode(&[0x41, 0x93]), vec![
+            ('A', 1),
+            (char::REPLACEMENT_CHARACTER, 1)
+        ],);
+        assert_eq!(CodePage::CP932.decode(&[0x93, 0x0A]), vec![
+            (char::REPLACEMENT_CHARACTER, 1),
+            ('\n', 1)
+        ],);
+    }
+}
@@ -0,0 +1,60 @@
+#!/usr/bin/env python3
+"""Regenerates the code page tables embedded by `parser::code_page`.
+
+The mappings come from the codecs shipped with CPython, which follow the
+Windows code page tables published by Microsoft and the Unicode
+consortium. Run from this directory:
+
+    python3 generate.py
+
+Single-byte tables (`cpXXXX.bin`) hold 128 little-endian u16 values for
+bytes 0x80..=0xFF. Double-byte tables hold a 256 byte row index (zero
+for bytes that are not lead bytes, otherwise the 1-based row number),
+followed by the 128 single-byte values and one row of 191 values per
+lead byte for trail bytes 0x40..=0xFE. Unmapped entries are zero.
+"""
+
+import struct
+
+SINGLE_BYTE = [437, 874, 1250, 1251, 1252, 1253, 1254, 1255, 1256, 1257, 1258]
+DOUBLE_BYTE = {932: "cp932", 936: "gbk", 949: "cp949", 950: "cp950"}
+
+# Windows code page 936 maps 0x80 to the euro sign, which the GBK codec
+# leaves undefined.
+OVERRIDES = {936: {0x80: 0x20AC}}
+
+
+def decode(codec, data):
+    try:
+        s = data.decode(codec)
+    except UnicodeDecodeError:
+        return 0
+    if len(s) != 1 or ord(s) > 0xFFFF:
+        return 0
+    return ord(s)
+
+
+def single_bytes(cp, codec):
+    overrides = OVERRIDES.get(cp, {})
+    return [
+        overrides.get(b, decode(codec, bytes([b]))) for b in range(0x80, 0x100)
+    ]
+
+
+for cp in SINGLE_BYTE:
+    with open(f"cp{cp}.bin", "wb") as f:
+        f.write(struct.pack("<128H", *single_bytes(cp, f"cp{cp}")))
+
+for cp, codec in DOUBLE_BYTE.items():
+    index = bytearray(256)
+    rows = []
+    for lead in range(0x81, 0xFF):
+        row = [decode(codec, bytes([lead, t])) for t in range(0x40, 0xFF)]
+        if any(row):
+            rows.append(row)
+            index[lead] = len(rows)
+    with open(f"cp{cp}.bin", "wb") as f:
+        f.write(index)
+        f.write(struct.pack("<128H", *single_bytes(cp, codec)))
+        for row in rows:
+            f.write(struct.pack("<191H", *row))
@@ -66,6 +66,9 @@ pub use self::{
 /// wrapped with `#[tracing::instrument]` at the TRACE level so per-
 /// record decode failures surface in the log stream.
 ///
+/// The matching `write_to` and `WriteLeField` impl write the discriminant
+/// back as a `$raw`.
+///
 /// Invoke as `impl_parser!(EnumType, u32);`. The `(_, ...)` arm is an
 /// internal dispatch detail and is not part of the public surface.
 #[rustfmt::skip]
@@ -112,6 +115,23 @@ macro_rules! impl_parser {
 
                 Ok((v, consumed_bytes))
             }
+
+            pub fn write_to<W: $crate::Write>(
+                &self,
+                buf: &mut W,
+            ) -> Result<usize, $crate::parser::WriteError> {
+                $crate::parser::WriteLeField::write_le(*self, buf)
+            }
+        }
+
+        impl $crate::parser::WriteLeField for $t {
+            #[inline]
+            fn write_le<W: $crate::Write>(
+                self,
+                buf: &mut W,
+            ) -> Result<usize, $crate::parser::WriteError> {
+                $crate::parser::WriteLeField::write_le(self as $raw, buf)
+            }
         }
     };
 }
@@ -179,6 +199,16 @@ macro_rules! impl_flags {
             }
         }
 
+        impl $crate::parser::WriteLeField for $flags {
+            #[inline]
+            fn write_le<W: $crate::Write>(
+                self,
+                buf: &mut W,
+            ) -> Result<usize, $crate::parser::WriteError> {
+                $crate::parser::WriteLeField::write_le(self.0, buf)
+            }
+        }
+
         impl ::core::fmt::Debug for $flags {
             fn fmt(
                 &self,
@@ -85,3 +85,76 @@ crate::parser::enums::impl_parser!(PenStyle, u32);
 pub struct PenStyleFlags(u32);
 
 crate::parser::enums::impl_flags!(PenStyleFlags, PenStyle, u32);
+
+impl PenStyleFlags {
+    /// Bits holding the line cap (`PS_ENDCAP_*`).
+    pub const ENDCAP_MASK: u32 = 0x0000_0F00;
+    /// Bits holding the line join (`PS_JOIN_*`).
+    pub const JOIN_MASK: u32 = 0x0000_F000;
+    /// Bits holding the line style (`PS_SOLID` .. `PS_ALTERNATE`). The
+    /// line styles are an enumerated field rather than independent
+    /// flags, so `PS_DASHDOT` (0x3) must not be read as `PS_DASH` plus
+    /// `PS_DOT`.
+    pub const STYLE_MASK: u32 = 0x0000_000F;
+    /// Bits holding the pen type (`PS_COSMETIC` / `PS_GEOMETRIC`).
+    pub const TYPE_MASK: u32 = 0x000F_0000;
+
+    /// Returns the line style encoded in the low nibble, or `None` when
+    /// the nibble holds an undefined value.
+    pub fn line_style(self) -> Option<PenStyle> {
+        PenStyle::from_repr(self.0 & Self::STYLE_MASK)
+    }
+
+    /// Returns the line cap. A zero cap field is `PS_ENDCAP_ROUND`.
+    pub const fn end_cap(self) -> PenEndCap {
+        match self.0 & Self::ENDCAP_MASK {
+            0x0000_0100 => PenEndCap::PS_ENDCAP_SQUARE,
+            0x0000_0200 => PenEndCap::PS_ENDCAP_FLAT,
+            _ => PenEndCap::PS_ENDCAP_ROUND,
+        }
+    }
+
+    /// Returns the line join. A zero join field is `PS_JOIN_ROUND`.
+    pub const fn line_join(self) -> PenLineJoin {
+        match self.0 & Self::JOIN_MASK {
+            0x0000_1000 => PenLineJoin::PS_JOIN_BEVEL,
+            0x0000_2000 => PenLineJoin::PS_JOIN_MITER,
+            _ => PenLineJoin::PS_JOIN_ROUND,
+        }
+    }
+
+    /// Returns true when the pen type is `PS_GEOMETRIC`; otherwise the
+    /// pen is `PS_COSMETIC`.
+    pub const fn is_geometric(self) -> bool {
+        (self.0 & Self::TYPE_MASK) == PenStyle::PS_GEOMETRIC as u32
+    }
+}
+
+/// The line cap portion of a pen style. `PS_ENDCAP_ROUND` shares the
+/// zero discriminant with `PS_SOLID` in the PenStyle enumeration, so
+/// the cap is modeled as its own enumeration extracted with
+/// `PenStyleFlags::end_cap`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PenEndCap {
+    /// A line cap that specifies round ends.
+    PS_ENDCAP_ROUND,
+    /// A line cap that specifies square ends.
+    PS_ENDCAP_SQUARE,
+    /// A line cap that specifies flat ends.
+    PS_ENDCAP_FLAT,
+}
+
+/// The line join portion of a pen style. `PS_JOIN_ROUND` shares the
+/// zero discriminant with `PS_SOLID` in the PenStyle enumeration, so
+/// the join is modeled as its own enumeration extracted with
+/// `PenStyleFlags::line_join`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PenLineJoin {
+    /// A line join that specifies round joins.
+    PS_JOIN_ROUND,
+    /// A line join that specifies beveled joins.
+    PS_JOIN_BEVEL,
+    /// A line join that specifies mitered joins when the lengths of the
+    /// joins are within the current miter length limit.
+    PS_JOIN_MITER,
+}
@@ -38,3 +38,19 @@ pub enum Point {
 }
 
 crate::parser::enums::impl_parser!(Point, u8);
+
+/// A byte of the abTypes array of EMR_POLYDRAW records: a `Point` type,
+/// optionally combined with PT_CLOSEFIGURE.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct PointFlags(u8);
+
+crate::parser::enums::impl_flags!(PointFlags, Point, u8);
+
+impl PointFlags {
+    /// Returns the point type without PT_CLOSEFIGURE, or `None` when the
+    /// byte holds no valid type.
+    pub fn point_type(self) -> Option<Point> {
+        Point::from_repr(self.0 & !(Point::PT_CLOSEFIGURE as u8))
+    }
+}
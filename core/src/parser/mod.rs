@@ -1,9 +1,16 @@
+mod builder;
+mod code_page;
 mod enums;
 mod objects;
 mod primitive;
+mod reader;
 pub(crate) mod records;
+mod writer;
 
-pub use self::{enums::*, objects::*, primitive::*, records::*};
+pub use self::{
+    builder::*, code_page::*, enums::*, objects::*, primitive::*, reader::*,
+    records::*, writer::*,
+};
 use crate::imports::*;
 
 #[derive(Clone, Debug, snafu::prelude::Snafu)]
@@ -328,6 +335,41 @@ impl From<wmf_core::parser::ReadError> for ReadError {
     }
 }
 
+#[derive(Clone, Debug, snafu::prelude::Snafu)]
+pub enum WriteError {
+    #[snafu(display("failed to write buffer: {cause}"))]
+    FailedWriteBuffer { cause: Cow<'static, str> },
+    /// A value does not fit the field the specification stores it in,
+    /// such as more points than a 32-bit count can hold, or a string of
+    /// the wrong encoding for its record type.
+    #[snafu(display("field `{field}` cannot be written: {cause}"))]
+    UnrepresentableField { field: &'static str, cause: Cow<'static, str> },
+    /// A record would be larger than `MAX_RECORD_BYTES`, which the parser
+    /// refuses to read back.
+    #[snafu(display(
+        "record of {size} bytes exceeds {MAX_RECORD_BYTES} bytes"
+    ))]
+    RecordTooLarge { size: usize },
+    /// An object handle that was never created, or was already deleted,
+    /// is selected or deleted through an `EmfBuilder`.
+    #[snafu(display("object {handle} does not exist"))]
+    UnknownObject { handle: u32 },
+}
+
+impl WriteError {
+    /// Returns the value of a count, size or offset field as the `u32` the
+    /// specification stores it in.
+    pub(crate) fn u32_field(
+        field: &'static str,
+        value: usize,
+    ) -> Result<u32, Self> {
+        u32::try_from(value).map_err(|_| Self::UnrepresentableField {
+            field,
+            cause: format!("{value} does not fit in 32 bits").into(),
+        })
+    }
+}
+
 #[inline]
 pub(in crate::parser) fn read<R: crate::Read, const N: usize>(
     buf: &mut R,
@@ -481,6 +523,137 @@ pub(in crate::parser) fn read_exact<R: crate::Read>(
     Ok(())
 }
 
+/// Writes all of `bytes` to `buf`, returning how many were written.
+pub(crate) fn write_variable<W: crate::Write>(
+    buf: &mut W,
+    bytes: &[u8],
+) -> Result<usize, WriteError> {
+    buf.write_all(bytes).map_err(|err| WriteError::FailedWriteBuffer {
+        cause: format!("{err:?}").into(),
+    })?;
+
+    Ok(bytes.len())
+}
+
+/// Type-driven dispatch for little-endian writes, the counterpart of
+/// `ReadLeField`.
+///
+/// Besides integers, it covers the small `wmf-core` objects the EMF
+/// records embed, which are written by reference.
+pub(crate) trait WriteLeField {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError>;
+}
+
+macro_rules! impl_write_le_field {
+    ($($t:ty),*) => {
+        $(
+            impl WriteLeField for $t {
+                #[inline]
+                fn write_le<W: crate::Write>(
+                    self,
+                    buf: &mut W,
+                ) -> Result<usize, WriteError> {
+                    write_variable(buf, &self.to_le_bytes())
+                }
+            }
+        )*
+    };
+}
+
+impl_write_le_field!(i8, i16, i32, u8, u16, u32, f32);
+
+impl<const N: usize> WriteLeField for [u8; N] {
+    #[inline]
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        write_variable(buf, &self)
+    }
+}
+
+impl WriteLeField for &wmf_core::parser::PointL {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        Ok(self.x.write_le(buf)? + self.y.write_le(buf)?)
+    }
+}
+
+impl WriteLeField for &wmf_core::parser::PointS {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        Ok(self.x.write_le(buf)? + self.y.write_le(buf)?)
+    }
+}
+
+impl WriteLeField for &wmf_core::parser::RectL {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        Ok(self.left.write_le(buf)?
+            + self.top.write_le(buf)?
+            + self.right.write_le(buf)?
+            + self.bottom.write_le(buf)?)
+    }
+}
+
+impl WriteLeField for &wmf_core::parser::SizeL {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        Ok(self.cx.write_le(buf)? + self.cy.write_le(buf)?)
+    }
+}
+
+impl WriteLeField for &wmf_core::parser::ColorRef {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        write_variable(buf, &[self.red, self.green, self.blue, self.reserved])
+    }
+}
+
+/// Convert a string to UTF16-LE bytes, the inverse of
+/// `utf16le_bytes_to_string`.
+fn string_to_utf16le_bytes(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// Convert a string to a fixed-size field of UTF16-LE bytes padded with
+/// nulls, the inverse of `null_terminated_utf16le_string`.
+fn string_to_utf16le_array<const N: usize>(
+    field: &'static str,
+    s: &str,
+) -> Result<[u8; N], WriteError> {
+    let bytes = string_to_utf16le_bytes(s);
+
+    if bytes.len() > N {
+        return Err(WriteError::UnrepresentableField {
+            field,
+            cause: format!(
+                "{} bytes of UTF-16 exceed the {N} bytes of the field",
+                bytes.len(),
+            )
+            .into(),
+        });
+    }
+
+    let mut array = [0; N];
+
+    array[..bytes.len()].copy_from_slice(&bytes);
+    Ok(array)
+}
+
 /// Convert UTF16-LE bytes to String.
 ///
 /// Streams `u16` code units straight into `char::decode_utf16` so the
@@ -31,4 +31,18 @@ impl BitFIX28_4 {
 
         Ok((Self { int_value, frac_value }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.int_value)?;
+        written += write_field(buf, self.frac_value)?;
+
+        Ok(written)
+    }
 }
@@ -165,6 +165,30 @@ impl ColorAdjustment {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, 0x0018_u16)?;
+        written += write_field(buf, self.values)?;
+        written += write_field(buf, self.illuminant_index)?;
+        written += write_field(buf, &self.red_gamma)?;
+        written += write_field(buf, &self.green_gamma)?;
+        written += write_field(buf, &self.blue_gamma)?;
+        written += write_field(buf, self.reference_black)?;
+        written += write_field(buf, self.reference_white)?;
+        written += write_field(buf, &self.contrast)?;
+        written += write_field(buf, &self.brightness)?;
+        written += write_field(buf, &self.colorfulness)?;
+        written += write_field(buf, &self.red_green_tint)?;
+
+        Ok(written)
+    }
 }
 
 impl Default for ColorAdjustment {
@@ -53,4 +53,22 @@ impl DesignVector {
 
         Ok((Self { signature, num_axes, values }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{write_field, write_len_field};
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.signature)?;
+        written += write_len_field(buf, "num_axes", self.values.len())?;
+
+        for value in &self.values {
+            written += write_field(buf, *value)?;
+        }
+
+        Ok(written)
+    }
 }
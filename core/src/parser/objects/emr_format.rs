@@ -51,4 +51,20 @@ impl EmrFormat {
 
         Ok((Self { signature, version, size_data, off_data }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.signature)?;
+        written += write_field(buf, self.version)?;
+        written += write_field(buf, self.size_data)?;
+        written += write_field(buf, self.off_data)?;
+
+        Ok(written)
+    }
 }
@@ -54,7 +54,7 @@ pub struct EmrText {
     /// to output. The location of this field is specified by the value of
     /// offString in bytes from the start of this record. The number of
     /// characters is specified by the value of Chars.
-    pub string_buffer: String,
+    pub string_buffer: OutputString,
     /// DxBuffer (variable, optional): The character spacing buffer.
     ///
     /// OutputDx (variable): An array of 32-bit unsigned integers that specify
@@ -66,6 +66,34 @@ pub struct EmrText {
     pub dx_buffer: Vec<u32>,
 }
 
+/// The characters of an EmrText object or an EMR_SMALLTEXTOUT record.
+///
+/// 8-bit strings are kept as recorded because their code page is implied by
+/// the charset of the font selected at playback time; use
+/// `CodePage::from_charset` to decode them.
+#[derive(Clone, Debug)]
+pub enum OutputString {
+    /// 8-bit characters of EMR_EXTTEXTOUTA and EMR_POLYTEXTOUTA records.
+    Ansi(Vec<u8>),
+    /// UTF16-LE characters of EMR_EXTTEXTOUTW, EMR_POLYTEXTOUTW and
+    /// EMR_SMALLTEXTOUT records.
+    Unicode(String),
+    /// Glyph indices of the selected font, stored instead of characters by
+    /// EMR_EXTTEXTOUTW, EMR_POLYTEXTOUTW and EMR_SMALLTEXTOUT records with
+    /// ETO_GLYPH_INDEX set. They can be mapped back with `converter::Font`.
+    GlyphIndices(Vec<u16>),
+}
+
+impl OutputString {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Ansi(v) => v.is_empty(),
+            Self::Unicode(v) => v.is_empty(),
+            Self::GlyphIndices(v) => v.is_empty(),
+        }
+    }
+}
+
 impl EmrText {
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = tracing::Level::TRACE,
@@ -121,16 +149,11 @@ impl EmrText {
         let string_buffer = match record_type {
             crate::parser::RecordType::EMR_EXTTEXTOUTA
             | crate::parser::RecordType::EMR_POLYTEXTOUTA => {
-                let buffer =
-                    read_bytes_field(buf, &mut consumed_bytes, chars as usize)?;
-
-                str::from_utf8(&buffer)
-                    .map_err(|err| {
-                        crate::parser::ParseError::UnexpectedPattern {
-                            cause: err.to_string().into(),
-                        }
-                    })?
-                    .to_string()
+                OutputString::Ansi(read_bytes_field(
+                    buf,
+                    &mut consumed_bytes,
+                    chars as usize,
+                )?)
             }
             crate::parser::RecordType::EMR_EXTTEXTOUTW
             | crate::parser::RecordType::EMR_POLYTEXTOUTW => {
@@ -144,7 +167,20 @@ impl EmrText {
                     (chars as usize) * 2,
                 )?;
 
-                crate::parser::utf16le_bytes_to_string(&buffer)?
+                if options
+                    .contains(crate::parser::ExtTextOutOptions::ETO_GLYPH_INDEX)
+                {
+                    OutputString::GlyphIndices(
+                        buffer
+                            .chunks_exact(2)
+                            .map(|v| u16::from_le_bytes([v[0], v[1]]))
+                            .collect(),
+                    )
+                } else {
+                    OutputString::Unicode(
+                        crate::parser::utf16le_bytes_to_string(&buffer)?,
+                    )
+                }
             }
             _ => {
                 return Err(crate::parser::ParseError::UnexpectedPattern {
@@ -199,4 +235,101 @@ impl EmrText {
             consumed_bytes,
         ))
     }
+
+    /// Writes the object at `offset` bytes from the start of its record,
+    /// followed by its string and intercharacter spacing, recomputing
+    /// `chars`, `off_string` and `off_dx`.
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+        record_type: &crate::parser::RecordType,
+        offset: usize,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            write_bytes_field, write_field, write_len_field,
+        };
+
+        let glyph_index = self
+            .options
+            .contains(crate::parser::ExtTextOutOptions::ETO_GLYPH_INDEX);
+        let (chars, string) = match (record_type, &self.string_buffer) {
+            (
+                crate::parser::RecordType::EMR_EXTTEXTOUTA
+                | crate::parser::RecordType::EMR_POLYTEXTOUTA,
+                OutputString::Ansi(v),
+            ) => (v.len(), v.clone()),
+            (
+                crate::parser::RecordType::EMR_EXTTEXTOUTW
+                | crate::parser::RecordType::EMR_POLYTEXTOUTW,
+                OutputString::Unicode(v),
+            ) if !glyph_index => {
+                let bytes = crate::parser::string_to_utf16le_bytes(v);
+
+                (bytes.len() / 2, bytes)
+            }
+            (
+                crate::parser::RecordType::EMR_EXTTEXTOUTW
+                | crate::parser::RecordType::EMR_POLYTEXTOUTW,
+                OutputString::GlyphIndices(v),
+            ) if glyph_index => {
+                (v.len(), v.iter().flat_map(|g| g.to_le_bytes()).collect())
+            }
+            (_, string_buffer) => {
+                return Err(crate::parser::WriteError::UnrepresentableField {
+                    field: "string_buffer",
+                    cause: format!(
+                        "{string_buffer:?} cannot be written in \
+                         {record_type:?} with options {:?}",
+                        self.options,
+                    )
+                    .into(),
+                });
+            }
+        };
+        let dx_len = chars
+            * if self
+                .options
+                .contains(crate::parser::ExtTextOutOptions::ETO_PDY)
+            {
+                2
+            } else {
+                1
+            };
+
+        if self.dx_buffer.len() != dx_len {
+            return Err(crate::parser::WriteError::UnrepresentableField {
+                field: "dx_buffer",
+                cause: format!(
+                    "{} spacing values given for {dx_len} expected",
+                    self.dx_buffer.len(),
+                )
+                .into(),
+            });
+        }
+
+        let off_string =
+            offset + if self.rectangle.is_some() { 40 } else { 24 };
+        let padding = string.len().next_multiple_of(4) - string.len();
+        let off_dx = off_string + string.len() + padding;
+        let mut written: usize = 0;
+
+        written += write_field(buf, &self.reference)?;
+        written += write_len_field(buf, "chars", chars)?;
+        written += write_len_field(buf, "off_string", off_string)?;
+        written += write_field(buf, self.options)?;
+
+        if let Some(rectangle) = &self.rectangle {
+            written += write_field(buf, rectangle)?;
+        }
+
+        written += write_len_field(buf, "off_dx", off_dx)?;
+        written += write_bytes_field(buf, &string)?;
+        written += write_bytes_field(buf, &[0; 3][..padding])?;
+
+        for dx in &self.dx_buffer {
+            written += write_field(buf, *dx)?;
+        }
+
+        Ok(written)
+    }
 }
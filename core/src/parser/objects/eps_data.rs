@@ -76,4 +76,27 @@ impl EpsData {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            write_bytes_field, write_field, write_len_field,
+        };
+
+        let mut written: usize = 0;
+
+        written +=
+            write_len_field(buf, "size_data", self.post_script_data.len())?;
+        written += write_field(buf, self.version)?;
+
+        for point in &self.points {
+            written += point.write_to(buf)?;
+        }
+
+        written += write_bytes_field(buf, &self.post_script_data)?;
+
+        Ok(written)
+    }
 }
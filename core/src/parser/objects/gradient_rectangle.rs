@@ -31,4 +31,18 @@ impl GradientRectangle {
 
         Ok((Self { upper_left, lower_right }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.upper_left)?;
+        written += write_field(buf, self.lower_right)?;
+
+        Ok(written)
+    }
 }
@@ -37,4 +37,19 @@ impl GradientTriangle {
 
         Ok((Self { vertex1, vertex2, vertex3 }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.vertex1)?;
+        written += write_field(buf, self.vertex2)?;
+        written += write_field(buf, self.vertex3)?;
+
+        Ok(written)
+    }
 }
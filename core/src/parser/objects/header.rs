@@ -122,4 +122,29 @@ impl Header {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, &self.bounds)?;
+        written += write_field(buf, &self.frame)?;
+        written += write_field(buf, self.record_signature)?;
+        written += write_field(buf, self.version)?;
+        written += write_field(buf, self.bytes)?;
+        written += write_field(buf, self.records)?;
+        written += write_field(buf, self.handles)?;
+        written += write_field(buf, self.reserved)?;
+        written += write_field(buf, self.n_description)?;
+        written += write_field(buf, self.off_description)?;
+        written += write_field(buf, self.n_pal_entries)?;
+        written += write_field(buf, &self.device)?;
+        written += write_field(buf, &self.millimeters)?;
+
+        Ok(written)
+    }
 }
@@ -42,4 +42,19 @@ impl HeaderExtension1 {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.cb_pixel_format)?;
+        written += write_field(buf, self.off_pixel_format)?;
+        written += write_field(buf, self.b_open_gl)?;
+
+        Ok(written)
+    }
 }
@@ -28,4 +28,18 @@ impl HeaderExtension2 {
 
         Ok((Self { micrometers_x, micrometers_y }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.micrometers_x)?;
+        written += write_field(buf, self.micrometers_y)?;
+
+        Ok(written)
+    }
 }
@@ -86,6 +86,45 @@ impl LogBrushEx {
         Ok((v, consumed_bytes))
     }
 
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        match self {
+            Self::Solid { color } => {
+                written += write_field(
+                    buf,
+                    wmf_core::parser::BrushStyle::BS_SOLID as u16,
+                )?;
+                written += write_field(buf, [0_u8; 2])?;
+                written += write_field(buf, color)?;
+                written += write_field(buf, [0_u8; 4])?;
+            }
+            Self::Null => {
+                written += write_field(
+                    buf,
+                    wmf_core::parser::BrushStyle::BS_NULL as u16,
+                )?;
+                written += write_field(buf, [0_u8; 10])?;
+            }
+            Self::Hatched { color, brush_hatch } => {
+                written += write_field(
+                    buf,
+                    wmf_core::parser::BrushStyle::BS_HATCHED as u16,
+                )?;
+                written += write_field(buf, [0_u8; 2])?;
+                written += write_field(buf, color)?;
+                written += write_field(buf, *brush_hatch)?;
+            }
+        }
+
+        Ok(written)
+    }
+
     pub fn black_brush() -> Self {
         Self::Solid { color: wmf_core::parser::ColorRef::black() }
     }
@@ -201,4 +201,43 @@ impl LogFont {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.height)?;
+        written += write_field(buf, self.width)?;
+        written += write_field(buf, self.escapement)?;
+        written += write_field(buf, self.orientation)?;
+        written += write_field(buf, self.weight)?;
+        written += write_field(buf, u8::from(self.italic))?;
+        written += write_field(buf, u8::from(self.underline))?;
+        written += write_field(buf, u8::from(self.strike_out))?;
+        written += write_field(buf, self.charset as u8)?;
+        written += write_field(buf, self.out_precision as u8)?;
+        written += write_field(
+            buf,
+            self.clip_precision.iter().fold(0_u8, |v, c| v | *c as u8),
+        )?;
+        written += write_field(buf, self.quality as u8)?;
+        written += write_field(
+            buf,
+            (self.pitch_and_family.family as u8) << 4
+                | self.pitch_and_family.pitch as u8,
+        )?;
+        written += write_field(
+            buf,
+            crate::parser::string_to_utf16le_array::<64>(
+                "facename",
+                &self.facename,
+            )?,
+        )?;
+
+        Ok(written)
+    }
 }
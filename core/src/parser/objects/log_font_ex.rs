@@ -49,4 +49,29 @@ impl LogFontEx {
 
         Ok((Self { log_font, full_name, style, script }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::{records::write_field, string_to_utf16le_array};
+
+        let mut written: usize = 0;
+
+        written += self.log_font.write_to(buf)?;
+        written += write_field(
+            buf,
+            string_to_utf16le_array::<128>("full_name", &self.full_name)?,
+        )?;
+        written += write_field(
+            buf,
+            string_to_utf16le_array::<64>("style", &self.style)?,
+        )?;
+        written += write_field(
+            buf,
+            string_to_utf16le_array::<64>("script", &self.script)?,
+        )?;
+
+        Ok(written)
+    }
 }
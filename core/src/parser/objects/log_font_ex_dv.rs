@@ -52,6 +52,14 @@ impl LogFontExDv {
 
         Ok((Self { log_font_ex, design_vector }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        Ok(self.log_font_ex.write_to(buf)?
+            + self.design_vector.write_to(buf)?)
+    }
 }
 
 impl From<crate::parser::LogFontPanose> for LogFontExDv {
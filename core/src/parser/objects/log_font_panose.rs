@@ -92,4 +92,33 @@ impl LogFontPanose {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::{records::write_field, string_to_utf16le_array};
+
+        let mut written: usize = 0;
+
+        written += self.log_font.write_to(buf)?;
+        written += write_field(
+            buf,
+            string_to_utf16le_array::<128>("full_name", &self.full_name)?,
+        )?;
+        written += write_field(
+            buf,
+            string_to_utf16le_array::<64>("style", &self.style)?,
+        )?;
+        written += write_field(buf, self.version)?;
+        written += write_field(buf, self.style_size)?;
+        written += write_field(buf, self._match)?;
+        written += write_field(buf, self.reserved)?;
+        written += write_field(buf, self.vendor_id)?;
+        written += write_field(buf, self.culture)?;
+        written += self.panose.write_to(buf)?;
+        written += write_field(buf, self.padding)?;
+
+        Ok(written)
+    }
 }
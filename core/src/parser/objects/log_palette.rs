@@ -61,4 +61,72 @@ impl LogPalette {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let number_of_entries = u16::try_from(self.palette_entries.len())
+            .map_err(|_| crate::parser::WriteError::UnrepresentableField {
+                field: "number_of_entries",
+                cause: format!(
+                    "{} entries do not fit in 16 bits",
+                    self.palette_entries.len(),
+                )
+                .into(),
+            })?;
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.version)?;
+        written += write_field(buf, number_of_entries)?;
+
+        for entry in &self.palette_entries {
+            written += entry.write_to(buf)?;
+        }
+
+        Ok(written)
+    }
+
+    /// The DEFAULT_PALETTE stock object: the 20 static colors of the system
+    /// palette.
+    pub fn default_palette() -> Self {
+        const COLORS: [(u8, u8, u8); 20] = [
+            (0x00, 0x00, 0x00),
+            (0x80, 0x00, 0x00),
+            (0x00, 0x80, 0x00),
+            (0x80, 0x80, 0x00),
+            (0x00, 0x00, 0x80),
+            (0x80, 0x00, 0x80),
+            (0x00, 0x80, 0x80),
+            (0xC0, 0xC0, 0xC0),
+            (0xC0, 0xDC, 0xC0),
+            (0xA6, 0xCA, 0xF0),
+            (0xFF, 0xFB, 0xF0),
+            (0xA0, 0xA0, 0xA4),
+            (0x80, 0x80, 0x80),
+            (0xFF, 0x00, 0x00),
+            (0x00, 0xFF, 0x00),
+            (0xFF, 0xFF, 0x00),
+            (0x00, 0x00, 0xFF),
+            (0xFF, 0x00, 0xFF),
+            (0x00, 0xFF, 0xFF),
+            (0xFF, 0xFF, 0xFF),
+        ];
+
+        Self {
+            version: 0x0300,
+            number_of_entries: COLORS.len() as u16,
+            palette_entries: COLORS
+                .iter()
+                .map(|&(red, green, blue)| crate::parser::LogPaletteEntry {
+                    reserved: 0,
+                    blue,
+                    green,
+                    red,
+                })
+                .collect(),
+        }
+    }
 }
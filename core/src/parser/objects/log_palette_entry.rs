@@ -38,4 +38,16 @@ impl LogPaletteEntry {
 
         Ok((Self { reserved, blue, green, red }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::write_variable(buf, &[
+            self.reserved,
+            self.blue,
+            self.green,
+            self.red,
+        ])
+    }
 }
@@ -3,7 +3,10 @@
 pub struct LogPen {
     /// PenStyle (4 bytes): An unsigned integer that specifies a value from the
     /// PenStyle enumeration.
-    pub pen_style: crate::parser::PenStyle,
+    ///
+    /// The value is kept as a bitmask because writers routinely OR the line
+    /// cap and line join bits into the line style.
+    pub pen_style: crate::parser::PenStyleFlags,
     /// Width (8 bytes): A PointL object ([MS-WMF] section 2.2.2.15) that
     /// specifies the width of the pen by the value of its x field. The value
     /// of its y field MUST be ignored.
@@ -27,14 +30,13 @@ impl LogPen {
     pub fn parse<R: crate::Read>(
         buf: &mut R,
     ) -> Result<(Self, usize), crate::parser::ParseError> {
-        use crate::parser::records::read_with;
+        use crate::parser::records::{read_field, read_with};
 
         let mut consumed_bytes: usize = 0;
-        let pen_style = read_with(
+        let pen_style = crate::parser::PenStyleFlags::from_raw(read_field(
             buf,
             &mut consumed_bytes,
-            crate::parser::PenStyle::parse,
-        )?;
+        )?);
         let width = read_with(
             buf,
             &mut consumed_bytes,
@@ -48,4 +50,19 @@ impl LogPen {
 
         Ok((Self { pen_style, width, color_ref }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.pen_style)?;
+        written += write_field(buf, &self.width)?;
+        written += write_field(buf, &self.color_ref)?;
+
+        Ok(written)
+    }
 }
@@ -104,6 +104,27 @@ impl LogPenEx {
         ))
     }
 
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{write_field, write_len_field};
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.pen_style)?;
+        written += write_field(buf, self.width)?;
+        written += self.brush.write_to(buf)?;
+        written +=
+            write_len_field(buf, "num_style_entries", self.style_entry.len())?;
+
+        for entry in &self.style_entry {
+            written += write_field(buf, *entry)?;
+        }
+
+        Ok(written)
+    }
+
     pub fn black_pen() -> Self {
         Self {
             pen_style: crate::parser::PenStyleFlags::single(
@@ -267,12 +288,70 @@ impl LogPenExBrush {
 
         Ok((v, consumed_bytes))
     }
+
+    fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let (brush_style, color, brush_hatch) = match self {
+            Self::Solid { color_ref } => (
+                wmf_core::parser::BrushStyle::BS_SOLID,
+                [
+                    color_ref.red,
+                    color_ref.green,
+                    color_ref.blue,
+                    color_ref.reserved,
+                ],
+                0,
+            ),
+            Self::Null => (wmf_core::parser::BrushStyle::BS_NULL, [0; 4], 0),
+            Self::Hatched { color_ref, brush_hatch } => (
+                wmf_core::parser::BrushStyle::BS_HATCHED,
+                [
+                    color_ref.red,
+                    color_ref.green,
+                    color_ref.blue,
+                    color_ref.reserved,
+                ],
+                *brush_hatch as u32,
+            ),
+            Self::Pattern { color_usage } => {
+                let [lo, hi] = (*color_usage as u16).to_le_bytes();
+
+                (wmf_core::parser::BrushStyle::BS_PATTERN, [0, 0, lo, hi], 0)
+            }
+            Self::DIBPattern { color_usage } => {
+                let [lo, hi] = (*color_usage as u16).to_le_bytes();
+
+                (wmf_core::parser::BrushStyle::BS_DIBPATTERN, [0, 0, lo, hi], 0)
+            }
+            Self::DIBPatternPT { color_usage } => {
+                let [lo, hi] = (*color_usage as u16).to_le_bytes();
+
+                (
+                    wmf_core::parser::BrushStyle::BS_DIBPATTERNPT,
+                    [0, 0, lo, hi],
+                    0,
+                )
+            }
+        };
+        let mut written: usize = 0;
+
+        written += write_field(buf, brush_style as u16)?;
+        written += write_field(buf, [0_u8; 2])?;
+        written += write_field(buf, color)?;
+        written += write_field(buf, brush_hatch)?;
+
+        Ok(written)
+    }
 }
 
 impl From<crate::parser::LogPen> for LogPenEx {
     fn from(v: crate::parser::LogPen) -> Self {
         Self {
-            pen_style: crate::parser::PenStyleFlags::single(v.pen_style),
+            pen_style: v.pen_style,
             width: v.width.x.unsigned_abs(),
             brush: LogPenExBrush::Solid { color_ref: v.color_ref },
             num_style_entries: 0,
@@ -106,4 +106,26 @@ impl Panose {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.family_type)?;
+        written += write_field(buf, self.serif_style)?;
+        written += write_field(buf, self.weight)?;
+        written += write_field(buf, self.proportion)?;
+        written += write_field(buf, self.contrast)?;
+        written += write_field(buf, self.stroke_variation)?;
+        written += write_field(buf, self.arm_style)?;
+        written += write_field(buf, self.letterform)?;
+        written += write_field(buf, self.midline)?;
+        written += write_field(buf, self.x_height)?;
+
+        Ok(written)
+    }
 }
@@ -176,6 +176,46 @@ impl PixelFormatDescriptor {
             consumed_bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.n_size)?;
+        written += write_field(buf, self.n_version)?;
+        written += self.dw_flags.write_to(buf)?;
+        written += crate::parser::write_variable(buf, &[
+            self.i_pixel_type,
+            self.c_color_bits,
+            self.c_red_bits,
+            self.c_red_shift,
+            self.c_green_bits,
+            self.c_green_shift,
+            self.c_blue_bits,
+            self.c_blue_shift,
+            self.c_alpha_bits,
+            self.c_alpha_shift,
+            self.c_accum_bits,
+            self.c_accum_red_bits,
+            self.c_accum_green_bits,
+            self.c_accum_blue_bits,
+            self.c_accum_alpha_bits,
+            self.c_depth_bits,
+            self.c_stencil_bits,
+            self.c_aux_buffers,
+            self.i_layer_type,
+            self.b_reserved,
+        ])?;
+        written += write_field(buf, self.dw_layer_mask)?;
+        written += write_field(buf, self.dw_visible_mask)?;
+        written += write_field(buf, self.dw_damage_mask)?;
+
+        Ok(written)
+    }
 }
 
 #[allow(clippy::struct_excessive_bools)]
@@ -318,4 +358,37 @@ impl DwFlags {
             bytes,
         ))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        let flags = [
+            (self.PFD_NEED_PALETTE, 31),
+            (self.PFD_GENERIC_FORMAT, 30),
+            (self.PFD_SUPPORT_OPENGL, 29),
+            (self.PFD_SUPPORT_GDI, 28),
+            (self.PFD_DRAW_TO_BITMAP, 27),
+            (self.PFD_DRAW_TO_WINDOW, 26),
+            (self.PFD_STEREO, 25),
+            (self.PFD_DOUBLEBUFFER, 24),
+            (self.PFD_SUPPORT_COMPOSITION, 23),
+            (self.PFD_DIRECT3D_ACCELERATED, 22),
+            (self.PFD_SUPPORT_DIRECTDRAW, 21),
+            (self.PFD_GENERIC_ACCELERATED, 20),
+            (self.PFD_SWAP_LAYER_BUFFERS, 19),
+            (self.PFD_SWAP_COPY, 18),
+            (self.PFD_SWAP_EXCHANGE, 17),
+            (self.PFD_NEED_SYSTEM_PALETTE, 16),
+            (self.PFD_STEREO_DONTCARE, 3),
+            (self.PFD_DOUBLEBUFFER_DONTCARE, 2),
+            (self.PFD_DEPTH_DONTCARE, 1),
+        ];
+        let v = flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .fold(0_u32, |v, (_, bit)| v | (1 << bit));
+
+        crate::parser::WriteLeField::write_le(v, buf)
+    }
 }
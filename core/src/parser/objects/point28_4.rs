@@ -35,4 +35,11 @@ impl Point28_4 {
 
         Ok((Self { x, y }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        Ok(self.x.write_to(buf)? + self.y.write_to(buf)?)
+    }
 }
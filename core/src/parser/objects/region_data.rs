@@ -54,4 +54,34 @@ impl RegionData {
 
         Ok((Self { region_data_header, data }, consumed_bytes))
     }
+
+    /// Writes the object, recomputing the header's `count_rects` and
+    /// `rgn_size` from `data`.
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let header = crate::parser::RegionDataHeader {
+            size: 0x00000020,
+            typ: 0x00000001,
+            count_rects: crate::parser::WriteError::u32_field(
+                "count_rects",
+                self.data.len(),
+            )?,
+            rgn_size: crate::parser::WriteError::u32_field(
+                "rgn_size",
+                self.data.len() * 16,
+            )?,
+            bounds: self.region_data_header.bounds.clone(),
+        };
+        let mut written = header.write_to(buf)?;
+
+        for rect in &self.data {
+            written += write_field(buf, rect)?;
+        }
+
+        Ok(written)
+    }
 }
@@ -53,4 +53,21 @@ impl RegionDataHeader {
 
         Ok((Self { size, typ, count_rects, rgn_size, bounds }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.size)?;
+        written += write_field(buf, self.typ)?;
+        written += write_field(buf, self.count_rects)?;
+        written += write_field(buf, self.rgn_size)?;
+        written += write_field(buf, &self.bounds)?;
+
+        Ok(written)
+    }
 }
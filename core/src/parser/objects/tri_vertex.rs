@@ -43,4 +43,22 @@ impl TriVertex {
 
         Ok((Self { x, y, red, green, blue, alpha }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.x)?;
+        written += write_field(buf, self.y)?;
+        written += write_field(buf, self.red)?;
+        written += write_field(buf, self.green)?;
+        written += write_field(buf, self.blue)?;
+        written += write_field(buf, self.alpha)?;
+
+        Ok(written)
+    }
 }
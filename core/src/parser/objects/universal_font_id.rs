@@ -35,6 +35,15 @@ impl UniversalFontId {
 
         Ok((Self { checksum, index }, consumed_bytes))
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        Ok(write_field(buf, self.checksum)? + write_field(buf, self.index)?)
+    }
 }
 
 #[cfg(test)]
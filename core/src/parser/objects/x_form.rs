@@ -47,6 +47,24 @@ impl XForm {
         Ok((Self { m11, m12, m21, m22, dx, dy }, consumed_bytes))
     }
 
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.m11)?;
+        written += write_field(buf, self.m12)?;
+        written += write_field(buf, self.m21)?;
+        written += write_field(buf, self.m22)?;
+        written += write_field(buf, self.dx)?;
+        written += write_field(buf, self.dy)?;
+
+        Ok(written)
+    }
+
     pub fn calc_scale(&self) -> f32 {
         (self.m11 * self.m22 - self.m12 * self.m21).sqrt()
     }
@@ -36,6 +36,20 @@ impl Gamma {
     pub fn must_not_be_performed(&self) -> bool {
         self.0 == 10_000
     }
+
+    /// Returns the gamma scaled by 10,000.
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+}
+
+impl crate::parser::WriteLeField for &Gamma {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::WriteLeField::write_le(self.0, buf)
+    }
 }
 
 /// A signed integer that specifies the amount to be applied to the source
@@ -69,6 +83,19 @@ impl Adjustment {
     pub fn must_not_be_performed(&self) -> bool {
         self.0 == 0
     }
+
+    pub fn value(&self) -> i16 {
+        self.0
+    }
+}
+
+impl crate::parser::WriteLeField for &Adjustment {
+    fn write_le<W: crate::Write>(
+        self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::WriteLeField::write_le(self.0, buf)
+    }
 }
 
 /// Upper bound on a single EMR record's `Size` field, in bytes.
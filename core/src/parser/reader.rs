@@ -0,0 +1,1329 @@
+//! Reading the records of an EMF metafile one at a time.
+//!
+//! [`EmrReader`] pulls records lazily from any [`crate::Read`] and parses
+//! them into [`Record`]s, so a metafile can be inspected, filtered or
+//! transformed without playing it through a
+//! [`Player`](crate::converter::Player). Reading from a `&[u8]` with
+//! [`EmrReader::read_borrowed_record`] borrows the bitmaps and private
+//! data of records instead of copying them.
+
+use crate::parser::{records::consume_remaining_bytes, *};
+
+/// A parsed EMF record, with one variant for each record type.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum Record<'a> {
+    // bitmap record
+    EMR_ALPHABLEND(EMR_ALPHABLEND<'a>),
+    EMR_BITBLT(EMR_BITBLT),
+    EMR_MASKBLT(EMR_MASKBLT),
+    EMR_PLGBLT(EMR_PLGBLT),
+    EMR_SETDIBITSTODEVICE(EMR_SETDIBITSTODEVICE),
+    EMR_STRETCHBLT(EMR_STRETCHBLT),
+    EMR_STRETCHDIBITS(EMR_STRETCHDIBITS<'a>),
+    EMR_TRANSPARENTBLT(EMR_TRANSPARENTBLT),
+    // clipping record
+    EMR_EXCLUDECLIPRECT(EMR_EXCLUDECLIPRECT),
+    EMR_EXTSELECTCLIPRGN(EMR_EXTSELECTCLIPRGN),
+    EMR_INTERSECTCLIPRECT(EMR_INTERSECTCLIPRECT),
+    EMR_OFFSETCLIPRGN(EMR_OFFSETCLIPRGN),
+    EMR_SELECTCLIPPATH(EMR_SELECTCLIPPATH),
+    EMR_SETMETARGN(EMR_SETMETARGN),
+    // comment record
+    EMR_COMMENT(EMR_COMMENT<'a>),
+    // control record
+    EMR_EOF(EMR_EOF),
+    EMR_HEADER(EMR_HEADER),
+    // drawing record
+    EMR_ANGLEARC(EMR_ANGLEARC),
+    EMR_ARC(EMR_ARC),
+    EMR_ARCTO(EMR_ARCTO),
+    EMR_CHORD(EMR_CHORD),
+    EMR_ELLIPSE(EMR_ELLIPSE),
+    EMR_EXTFLOODFILL(EMR_EXTFLOODFILL),
+    EMR_EXTTEXTOUTA(EMR_EXTTEXTOUTA),
+    EMR_EXTTEXTOUTW(EMR_EXTTEXTOUTW),
+    EMR_FILLPATH(EMR_FILLPATH),
+    EMR_FILLRGN(EMR_FILLRGN),
+    EMR_FRAMERGN(EMR_FRAMERGN),
+    EMR_GRADIENTFILL(EMR_GRADIENTFILL),
+    EMR_LINETO(EMR_LINETO),
+    EMR_PAINTRGN(EMR_PAINTRGN),
+    EMR_PIE(EMR_PIE),
+    EMR_POLYBEZIER(EMR_POLYBEZIER),
+    EMR_POLYBEZIER16(EMR_POLYBEZIER16),
+    EMR_POLYBEZIERTO(EMR_POLYBEZIERTO),
+    EMR_POLYBEZIERTO16(EMR_POLYBEZIERTO16),
+    EMR_POLYDRAW(EMR_POLYDRAW),
+    EMR_POLYDRAW16(EMR_POLYDRAW16),
+    EMR_POLYPOLYGON(EMR_POLYPOLYGON),
+    EMR_POLYPOLYGON16(EMR_POLYPOLYGON16),
+    EMR_POLYPOLYLINE(EMR_POLYPOLYLINE),
+    EMR_POLYPOLYLINE16(EMR_POLYPOLYLINE16),
+    EMR_POLYTEXTOUTA(EMR_POLYTEXTOUTA),
+    EMR_POLYTEXTOUTW(EMR_POLYTEXTOUTW),
+    EMR_POLYGON(EMR_POLYGON),
+    EMR_POLYGON16(EMR_POLYGON16),
+    EMR_POLYLINE(EMR_POLYLINE),
+    EMR_POLYLINE16(EMR_POLYLINE16),
+    EMR_POLYLINETO(EMR_POLYLINETO),
+    EMR_POLYLINETO16(EMR_POLYLINETO16),
+    EMR_RECTANGLE(EMR_RECTANGLE),
+    EMR_ROUNDRECT(EMR_ROUNDRECT),
+    EMR_SETPIXELV(EMR_SETPIXELV),
+    EMR_SMALLTEXTOUT(EMR_SMALLTEXTOUT),
+    EMR_STROKEANDFILLPATH(EMR_STROKEANDFILLPATH),
+    EMR_STROKEPATH(EMR_STROKEPATH),
+    // escape record
+    EMR_DRAWESCAPE(EMR_DRAWESCAPE),
+    EMR_EXTESCAPE(EMR_EXTESCAPE),
+    EMR_NAMEDESCAPE(EMR_NAMEDESCAPE),
+    // object creation record
+    EMR_CREATEBRUSHINDIRECT(EMR_CREATEBRUSHINDIRECT),
+    EMR_CREATECOLORSPACE(EMR_CREATECOLORSPACE),
+    EMR_CREATECOLORSPACEW(EMR_CREATECOLORSPACEW),
+    EMR_CREATEDIBPATTERNBRUSHPT(EMR_CREATEDIBPATTERNBRUSHPT),
+    EMR_CREATEMONOBRUSH(EMR_CREATEMONOBRUSH),
+    EMR_CREATEPALETTE(EMR_CREATEPALETTE),
+    EMR_CREATEPEN(EMR_CREATEPEN),
+    EMR_EXTCREATEFONTINDIRECTW(EMR_EXTCREATEFONTINDIRECTW),
+    EMR_EXTCREATEPEN(EMR_EXTCREATEPEN),
+    // object manipulation record
+    EMR_COLORCORRECTPALETTE(EMR_COLORCORRECTPALETTE),
+    EMR_DELETECOLORSPACE(EMR_DELETECOLORSPACE),
+    EMR_DELETEOBJECT(EMR_DELETEOBJECT),
+    EMR_RESIZEPALETTE(EMR_RESIZEPALETTE),
+    EMR_SELECTOBJECT(EMR_SELECTOBJECT),
+    EMR_SELECTPALETTE(EMR_SELECTPALETTE),
+    EMR_SETCOLORSPACE(EMR_SETCOLORSPACE),
+    EMR_SETPALETTEENTRIES(EMR_SETPALETTEENTRIES),
+    // opengl record
+    EMR_GLSBOUNDEDRECORD(EMR_GLSBOUNDEDRECORD),
+    EMR_GLSRECORD(EMR_GLSRECORD),
+    // path bracket record
+    EMR_ABORTPATH(EMR_ABORTPATH),
+    EMR_BEGINPATH(EMR_BEGINPATH),
+    EMR_CLOSEFIGURE(EMR_CLOSEFIGURE),
+    EMR_ENDPATH(EMR_ENDPATH),
+    EMR_FLATTENPATH(EMR_FLATTENPATH),
+    EMR_WIDENPATH(EMR_WIDENPATH),
+    // state record
+    EMR_COLORMATCHTOTARGETW(EMR_COLORMATCHTOTARGETW),
+    EMR_FORCEUFIMAPPING(EMR_FORCEUFIMAPPING),
+    EMR_INVERTRGN(EMR_INVERTRGN),
+    EMR_MOVETOEX(EMR_MOVETOEX),
+    EMR_PIXELFORMAT(EMR_PIXELFORMAT),
+    EMR_REALIZEPALETTE(EMR_REALIZEPALETTE),
+    EMR_RESTOREDC(EMR_RESTOREDC),
+    EMR_SAVEDC(EMR_SAVEDC),
+    EMR_SCALEVIEWPORTEXTEX(EMR_SCALEVIEWPORTEXTEX),
+    EMR_SCALEWINDOWEXTEX(EMR_SCALEWINDOWEXTEX),
+    EMR_SETARCDIRECTION(EMR_SETARCDIRECTION),
+    EMR_SETBKCOLOR(EMR_SETBKCOLOR),
+    EMR_SETBKMODE(EMR_SETBKMODE),
+    EMR_SETBRUSHORGEX(EMR_SETBRUSHORGEX),
+    EMR_SETCOLORADJUSTMENT(EMR_SETCOLORADJUSTMENT),
+    EMR_SETICMMODE(EMR_SETICMMODE),
+    EMR_SETICMPROFILEA(EMR_SETICMPROFILEA),
+    EMR_SETICMPROFILEW(EMR_SETICMPROFILEW),
+    EMR_SETLAYOUT(EMR_SETLAYOUT),
+    EMR_SETLINKEDUFIS(EMR_SETLINKEDUFIS),
+    EMR_SETMAPMODE(EMR_SETMAPMODE),
+    EMR_SETMAPPERFLAGS(EMR_SETMAPPERFLAGS),
+    EMR_SETMITERLIMIT(EMR_SETMITERLIMIT),
+    EMR_SETPOLYFILLMODE(EMR_SETPOLYFILLMODE),
+    EMR_SETROP2(EMR_SETROP2),
+    EMR_SETSTRETCHBLTMODE(EMR_SETSTRETCHBLTMODE),
+    EMR_SETTEXTALIGN(EMR_SETTEXTALIGN),
+    EMR_SETTEXTCOLOR(EMR_SETTEXTCOLOR),
+    EMR_SETTEXTJUSTIFICATION(EMR_SETTEXTJUSTIFICATION),
+    EMR_SETVIEWPORTEXTEX(EMR_SETVIEWPORTEXTEX),
+    EMR_SETVIEWPORTORGEX(EMR_SETVIEWPORTORGEX),
+    EMR_SETWINDOWEXTEX(EMR_SETWINDOWEXTEX),
+    EMR_SETWINDOWORGEX(EMR_SETWINDOWORGEX),
+    // transform record
+    EMR_MODIFYWORLDTRANSFORM(EMR_MODIFYWORLDTRANSFORM),
+    EMR_SETWORLDTRANSFORM(EMR_SETWORLDTRANSFORM),
+}
+
+impl Record<'_> {
+    pub fn record_type(&self) -> RecordType {
+        match self {
+            Self::EMR_ALPHABLEND(_) => RecordType::EMR_ALPHABLEND,
+            Self::EMR_BITBLT(_) => RecordType::EMR_BITBLT,
+            Self::EMR_MASKBLT(_) => RecordType::EMR_MASKBLT,
+            Self::EMR_PLGBLT(_) => RecordType::EMR_PLGBLT,
+            Self::EMR_SETDIBITSTODEVICE(_) => RecordType::EMR_SETDIBITSTODEVICE,
+            Self::EMR_STRETCHBLT(_) => RecordType::EMR_STRETCHBLT,
+            Self::EMR_STRETCHDIBITS(_) => RecordType::EMR_STRETCHDIBITS,
+            Self::EMR_TRANSPARENTBLT(_) => RecordType::EMR_TRANSPARENTBLT,
+            Self::EMR_EXCLUDECLIPRECT(_) => RecordType::EMR_EXCLUDECLIPRECT,
+            Self::EMR_EXTSELECTCLIPRGN(_) => RecordType::EMR_EXTSELECTCLIPRGN,
+            Self::EMR_INTERSECTCLIPRECT(_) => RecordType::EMR_INTERSECTCLIPRECT,
+            Self::EMR_OFFSETCLIPRGN(_) => RecordType::EMR_OFFSETCLIPRGN,
+            Self::EMR_SELECTCLIPPATH(_) => RecordType::EMR_SELECTCLIPPATH,
+            Self::EMR_SETMETARGN(_) => RecordType::EMR_SETMETARGN,
+            Self::EMR_COMMENT(_) => RecordType::EMR_COMMENT,
+            Self::EMR_EOF(_) => RecordType::EMR_EOF,
+            Self::EMR_HEADER(_) => RecordType::EMR_HEADER,
+            Self::EMR_ANGLEARC(_) => RecordType::EMR_ANGLEARC,
+            Self::EMR_ARC(_) => RecordType::EMR_ARC,
+            Self::EMR_ARCTO(_) => RecordType::EMR_ARCTO,
+            Self::EMR_CHORD(_) => RecordType::EMR_CHORD,
+            Self::EMR_ELLIPSE(_) => RecordType::EMR_ELLIPSE,
+            Self::EMR_EXTFLOODFILL(_) => RecordType::EMR_EXTFLOODFILL,
+            Self::EMR_EXTTEXTOUTA(_) => RecordType::EMR_EXTTEXTOUTA,
+            Self::EMR_EXTTEXTOUTW(_) => RecordType::EMR_EXTTEXTOUTW,
+            Self::EMR_FILLPATH(_) => RecordType::EMR_FILLPATH,
+            Self::EMR_FILLRGN(_) => RecordType::EMR_FILLRGN,
+            Self::EMR_FRAMERGN(_) => RecordType::EMR_FRAMERGN,
+            Self::EMR_GRADIENTFILL(_) => RecordType::EMR_GRADIENTFILL,
+            Self::EMR_LINETO(_) => RecordType::EMR_LINETO,
+            Self::EMR_PAINTRGN(_) => RecordType::EMR_PAINTRGN,
+            Self::EMR_PIE(_) => RecordType::EMR_PIE,
+            Self::EMR_POLYBEZIER(_) => RecordType::EMR_POLYBEZIER,
+            Self::EMR_POLYBEZIER16(_) => RecordType::EMR_POLYBEZIER16,
+            Self::EMR_POLYBEZIERTO(_) => RecordType::EMR_POLYBEZIERTO,
+            Self::EMR_POLYBEZIERTO16(_) => RecordType::EMR_POLYBEZIERTO16,
+            Self::EMR_POLYDRAW(_) => RecordType::EMR_POLYDRAW,
+            Self::EMR_POLYDRAW16(_) => RecordType::EMR_POLYDRAW16,
+            Self::EMR_POLYPOLYGON(_) => RecordType::EMR_POLYPOLYGON,
+            Self::EMR_POLYPOLYGON16(_) => RecordType::EMR_POLYPOLYGON16,
+            Self::EMR_POLYPOLYLINE(_) => RecordType::EMR_POLYPOLYLINE,
+            Self::EMR_POLYPOLYLINE16(_) => RecordType::EMR_POLYPOLYLINE16,
+            Self::EMR_POLYTEXTOUTA(_) => RecordType::EMR_POLYTEXTOUTA,
+            Self::EMR_POLYTEXTOUTW(_) => RecordType::EMR_POLYTEXTOUTW,
+            Self::EMR_POLYGON(_) => RecordType::EMR_POLYGON,
+            Self::EMR_POLYGON16(_) => RecordType::EMR_POLYGON16,
+            Self::EMR_POLYLINE(_) => RecordType::EMR_POLYLINE,
+            Self::EMR_POLYLINE16(_) => RecordType::EMR_POLYLINE16,
+            Self::EMR_POLYLINETO(_) => RecordType::EMR_POLYLINETO,
+            Self::EMR_POLYLINETO16(_) => RecordType::EMR_POLYLINETO16,
+            Self::EMR_RECTANGLE(_) => RecordType::EMR_RECTANGLE,
+            Self::EMR_ROUNDRECT(_) => RecordType::EMR_ROUNDRECT,
+            Self::EMR_SETPIXELV(_) => RecordType::EMR_SETPIXELV,
+            Self::EMR_SMALLTEXTOUT(_) => RecordType::EMR_SMALLTEXTOUT,
+            Self::EMR_STROKEANDFILLPATH(_) => RecordType::EMR_STROKEANDFILLPATH,
+            Self::EMR_STROKEPATH(_) => RecordType::EMR_STROKEPATH,
+            Self::EMR_DRAWESCAPE(_) => RecordType::EMR_DRAWESCAPE,
+            Self::EMR_EXTESCAPE(_) => RecordType::EMR_EXTESCAPE,
+            Self::EMR_NAMEDESCAPE(_) => RecordType::EMR_NAMEDESCAPE,
+            Self::EMR_CREATEBRUSHINDIRECT(_) => {
+                RecordType::EMR_CREATEBRUSHINDIRECT
+            }
+            Self::EMR_CREATECOLORSPACE(_) => RecordType::EMR_CREATECOLORSPACE,
+            Self::EMR_CREATECOLORSPACEW(_) => RecordType::EMR_CREATECOLORSPACEW,
+            Self::EMR_CREATEDIBPATTERNBRUSHPT(_) => {
+                RecordType::EMR_CREATEDIBPATTERNBRUSHPT
+            }
+            Self::EMR_CREATEMONOBRUSH(_) => RecordType::EMR_CREATEMONOBRUSH,
+            Self::EMR_CREATEPALETTE(_) => RecordType::EMR_CREATEPALETTE,
+            Self::EMR_CREATEPEN(_) => RecordType::EMR_CREATEPEN,
+            Self::EMR_EXTCREATEFONTINDIRECTW(_) => {
+                RecordType::EMR_EXTCREATEFONTINDIRECTW
+            }
+            Self::EMR_EXTCREATEPEN(_) => RecordType::EMR_EXTCREATEPEN,
+            Self::EMR_COLORCORRECTPALETTE(_) => {
+                RecordType::EMR_COLORCORRECTPALETTE
+            }
+            Self::EMR_DELETECOLORSPACE(_) => RecordType::EMR_DELETECOLORSPACE,
+            Self::EMR_DELETEOBJECT(_) => RecordType::EMR_DELETEOBJECT,
+            Self::EMR_RESIZEPALETTE(_) => RecordType::EMR_RESIZEPALETTE,
+            Self::EMR_SELECTOBJECT(_) => RecordType::EMR_SELECTOBJECT,
+            Self::EMR_SELECTPALETTE(_) => RecordType::EMR_SELECTPALETTE,
+            Self::EMR_SETCOLORSPACE(_) => RecordType::EMR_SETCOLORSPACE,
+            Self::EMR_SETPALETTEENTRIES(_) => RecordType::EMR_SETPALETTEENTRIES,
+            Self::EMR_GLSBOUNDEDRECORD(_) => RecordType::EMR_GLSBOUNDEDRECORD,
+            Self::EMR_GLSRECORD(_) => RecordType::EMR_GLSRECORD,
+            Self::EMR_ABORTPATH(_) => RecordType::EMR_ABORTPATH,
+            Self::EMR_BEGINPATH(_) => RecordType::EMR_BEGINPATH,
+            Self::EMR_CLOSEFIGURE(_) => RecordType::EMR_CLOSEFIGURE,
+            Self::EMR_ENDPATH(_) => RecordType::EMR_ENDPATH,
+            Self::EMR_FLATTENPATH(_) => RecordType::EMR_FLATTENPATH,
+            Self::EMR_WIDENPATH(_) => RecordType::EMR_WIDENPATH,
+            Self::EMR_COLORMATCHTOTARGETW(_) => {
+                RecordType::EMR_COLORMATCHTOTARGETW
+            }
+            Self::EMR_FORCEUFIMAPPING(_) => RecordType::EMR_FORCEUFIMAPPING,
+            Self::EMR_INVERTRGN(_) => RecordType::EMR_INVERTRGN,
+            Self::EMR_MOVETOEX(_) => RecordType::EMR_MOVETOEX,
+            Self::EMR_PIXELFORMAT(_) => RecordType::EMR_PIXELFORMAT,
+            Self::EMR_REALIZEPALETTE(_) => RecordType::EMR_REALIZEPALETTE,
+            Self::EMR_RESTOREDC(_) => RecordType::EMR_RESTOREDC,
+            Self::EMR_SAVEDC(_) => RecordType::EMR_SAVEDC,
+            Self::EMR_SCALEVIEWPORTEXTEX(_) => {
+                RecordType::EMR_SCALEVIEWPORTEXTEX
+            }
+            Self::EMR_SCALEWINDOWEXTEX(_) => RecordType::EMR_SCALEWINDOWEXTEX,
+            Self::EMR_SETARCDIRECTION(_) => RecordType::EMR_SETARCDIRECTION,
+            Self::EMR_SETBKCOLOR(_) => RecordType::EMR_SETBKCOLOR,
+            Self::EMR_SETBKMODE(_) => RecordType::EMR_SETBKMODE,
+            Self::EMR_SETBRUSHORGEX(_) => RecordType::EMR_SETBRUSHORGEX,
+            Self::EMR_SETCOLORADJUSTMENT(_) => {
+                RecordType::EMR_SETCOLORADJUSTMENT
+            }
+            Self::EMR_SETICMMODE(_) => RecordType::EMR_SETICMMODE,
+            Self::EMR_SETICMPROFILEA(_) => RecordType::EMR_SETICMPROFILEA,
+            Self::EMR_SETICMPROFILEW(_) => RecordType::EMR_SETICMPROFILEW,
+            Self::EMR_SETLAYOUT(_) => RecordType::EMR_SETLAYOUT,
+            Self::EMR_SETLINKEDUFIS(_) => RecordType::EMR_SETLINKEDUFIS,
+            Self::EMR_SETMAPMODE(_) => RecordType::EMR_SETMAPMODE,
+            Self::EMR_SETMAPPERFLAGS(_) => RecordType::EMR_SETMAPPERFLAGS,
+            Self::EMR_SETMITERLIMIT(_) => RecordType::EMR_SETMITERLIMIT,
+            Self::EMR_SETPOLYFILLMODE(_) => RecordType::EMR_SETPOLYFILLMODE,
+            Self::EMR_SETROP2(_) => RecordType::EMR_SETROP2,
+            Self::EMR_SETSTRETCHBLTMODE(_) => RecordType::EMR_SETSTRETCHBLTMODE,
+            Self::EMR_SETTEXTALIGN(_) => RecordType::EMR_SETTEXTALIGN,
+            Self::EMR_SETTEXTCOLOR(_) => RecordType::EMR_SETTEXTCOLOR,
+            Self::EMR_SETTEXTJUSTIFICATION(_) => {
+                RecordType::EMR_SETTEXTJUSTIFICATION
+            }
+            Self::EMR_SETVIEWPORTEXTEX(_) => RecordType::EMR_SETVIEWPORTEXTEX,
+            Self::EMR_SETVIEWPORTORGEX(_) => RecordType::EMR_SETVIEWPORTORGEX,
+            Self::EMR_SETWINDOWEXTEX(_) => RecordType::EMR_SETWINDOWEXTEX,
+            Self::EMR_SETWINDOWORGEX(_) => RecordType::EMR_SETWINDOWORGEX,
+            Self::EMR_MODIFYWORLDTRANSFORM(_) => {
+                RecordType::EMR_MODIFYWORLDTRANSFORM
+            }
+            Self::EMR_SETWORLDTRANSFORM(_) => RecordType::EMR_SETWORLDTRANSFORM,
+        }
+    }
+
+    /// Returns the `Bounds` field of the record in device units, or `None`
+    /// if the record has none.
+    pub fn bounds(&self) -> Option<&wmf_core::parser::RectL> {
+        match self {
+            Self::EMR_ALPHABLEND(record) => Some(&record.bounds),
+            Self::EMR_BITBLT(record) => Some(&record.bounds),
+            Self::EMR_MASKBLT(record) => Some(&record.bounds),
+            Self::EMR_PLGBLT(record) => Some(&record.bounds),
+            Self::EMR_SETDIBITSTODEVICE(record) => Some(&record.bounds),
+            Self::EMR_STRETCHBLT(record) => Some(&record.bounds),
+            Self::EMR_STRETCHDIBITS(record) => Some(&record.bounds),
+            Self::EMR_TRANSPARENTBLT(record) => Some(&record.bounds),
+            Self::EMR_EXTTEXTOUTA(record) => Some(&record.bounds),
+            Self::EMR_EXTTEXTOUTW(record) => Some(&record.bounds),
+            Self::EMR_FILLPATH(record) => Some(&record.bounds),
+            Self::EMR_FILLRGN(record) => Some(&record.bounds),
+            Self::EMR_FRAMERGN(record) => Some(&record.bounds),
+            Self::EMR_GRADIENTFILL(record) => Some(&record.bounds),
+            Self::EMR_PAINTRGN(record) => Some(&record.bounds),
+            Self::EMR_POLYBEZIER(record) => Some(&record.bounds),
+            Self::EMR_POLYBEZIER16(record) => Some(&record.bounds),
+            Self::EMR_POLYBEZIERTO(record) => Some(&record.bounds),
+            Self::EMR_POLYBEZIERTO16(record) => Some(&record.bounds),
+            Self::EMR_POLYDRAW(record) => Some(&record.bounds),
+            Self::EMR_POLYDRAW16(record) => Some(&record.bounds),
+            Self::EMR_POLYPOLYGON(record) => Some(&record.bounds),
+            Self::EMR_POLYPOLYGON16(record) => Some(&record.bounds),
+            Self::EMR_POLYPOLYLINE(record) => Some(&record.bounds),
+            Self::EMR_POLYPOLYLINE16(record) => Some(&record.bounds),
+            Self::EMR_POLYTEXTOUTA(record) => Some(&record.bounds),
+            Self::EMR_POLYTEXTOUTW(record) => Some(&record.bounds),
+            Self::EMR_POLYGON(record) => Some(&record.bounds),
+            Self::EMR_POLYGON16(record) => Some(&record.bounds),
+            Self::EMR_POLYLINE(record) => Some(&record.bounds),
+            Self::EMR_POLYLINE16(record) => Some(&record.bounds),
+            Self::EMR_POLYLINETO(record) => Some(&record.bounds),
+            Self::EMR_POLYLINETO16(record) => Some(&record.bounds),
+            Self::EMR_STROKEANDFILLPATH(record) => Some(&record.bounds),
+            Self::EMR_STROKEPATH(record) => Some(&record.bounds),
+            Self::EMR_GLSBOUNDEDRECORD(record) => Some(&record.bounds),
+            Self::EMR_INVERTRGN(record) => Some(&record.bounds),
+            Self::EMR_SMALLTEXTOUT(record) => record.bounds.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Writes the record to `buf` and returns the number of bytes written.
+    ///
+    /// Counts, sizes and offsets are recomputed from the data of the record
+    /// rather than copied from the fields they were parsed into, so a
+    /// modified record is written consistently.
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        match self {
+            Self::EMR_ALPHABLEND(record) => record.write_to(buf),
+            Self::EMR_BITBLT(record) => record.write_to(buf),
+            Self::EMR_MASKBLT(record) => record.write_to(buf),
+            Self::EMR_PLGBLT(record) => record.write_to(buf),
+            Self::EMR_SETDIBITSTODEVICE(record) => record.write_to(buf),
+            Self::EMR_STRETCHBLT(record) => record.write_to(buf),
+            Self::EMR_STRETCHDIBITS(record) => record.write_to(buf),
+            Self::EMR_TRANSPARENTBLT(record) => record.write_to(buf),
+            Self::EMR_EXCLUDECLIPRECT(record) => record.write_to(buf),
+            Self::EMR_EXTSELECTCLIPRGN(record) => record.write_to(buf),
+            Self::EMR_INTERSECTCLIPRECT(record) => record.write_to(buf),
+            Self::EMR_OFFSETCLIPRGN(record) => record.write_to(buf),
+            Self::EMR_SELECTCLIPPATH(record) => record.write_to(buf),
+            Self::EMR_SETMETARGN(record) => record.write_to(buf),
+            Self::EMR_COMMENT(record) => record.write_to(buf),
+            Self::EMR_EOF(record) => record.write_to(buf),
+            Self::EMR_HEADER(record) => record.write_to(buf),
+            Self::EMR_ANGLEARC(record) => record.write_to(buf),
+            Self::EMR_ARC(record) => record.write_to(buf),
+            Self::EMR_ARCTO(record) => record.write_to(buf),
+            Self::EMR_CHORD(record) => record.write_to(buf),
+            Self::EMR_ELLIPSE(record) => record.write_to(buf),
+            Self::EMR_EXTFLOODFILL(record) => record.write_to(buf),
+            Self::EMR_EXTTEXTOUTA(record) => record.write_to(buf),
+            Self::EMR_EXTTEXTOUTW(record) => record.write_to(buf),
+            Self::EMR_FILLPATH(record) => record.write_to(buf),
+            Self::EMR_FILLRGN(record) => record.write_to(buf),
+            Self::EMR_FRAMERGN(record) => record.write_to(buf),
+            Self::EMR_GRADIENTFILL(record) => record.write_to(buf),
+            Self::EMR_LINETO(record) => record.write_to(buf),
+            Self::EMR_PAINTRGN(record) => record.write_to(buf),
+            Self::EMR_PIE(record) => record.write_to(buf),
+            Self::EMR_POLYBEZIER(record) => record.write_to(buf),
+            Self::EMR_POLYBEZIER16(record) => record.write_to(buf),
+            Self::EMR_POLYBEZIERTO(record) => record.write_to(buf),
+            Self::EMR_POLYBEZIERTO16(record) => record.write_to(buf),
+            Self::EMR_POLYDRAW(record) => record.write_to(buf),
+            Self::EMR_POLYDRAW16(record) => record.write_to(buf),
+            Self::EMR_POLYPOLYGON(record) => record.write_to(buf),
+            Self::EMR_POLYPOLYGON16(record) => record.write_to(buf),
+            Self::EMR_POLYPOLYLINE(record) => record.write_to(buf),
+            Self::EMR_POLYPOLYLINE16(record) => record.write_to(buf),
+            Self::EMR_POLYTEXTOUTA(record) => record.write_to(buf),
+            Self::EMR_POLYTEXTOUTW(record) => record.write_to(buf),
+            Self::EMR_POLYGON(record) => record.write_to(buf),
+            Self::EMR_POLYGON16(record) => record.write_to(buf),
+            Self::EMR_POLYLINE(record) => record.write_to(buf),
+            Self::EMR_POLYLINE16(record) => record.write_to(buf),
+            Self::EMR_POLYLINETO(record) => record.write_to(buf),
+            Self::EMR_POLYLINETO16(record) => record.write_to(buf),
+            Self::EMR_RECTANGLE(record) => record.write_to(buf),
+            Self::EMR_ROUNDRECT(record) => record.write_to(buf),
+            Self::EMR_SETPIXELV(record) => record.write_to(buf),
+            Self::EMR_SMALLTEXTOUT(record) => record.write_to(buf),
+            Self::EMR_STROKEANDFILLPATH(record) => record.write_to(buf),
+            Self::EMR_STROKEPATH(record) => record.write_to(buf),
+            Self::EMR_DRAWESCAPE(record) => record.write_to(buf),
+            Self::EMR_EXTESCAPE(record) => record.write_to(buf),
+            Self::EMR_NAMEDESCAPE(record) => record.write_to(buf),
+            Self::EMR_CREATEBRUSHINDIRECT(record) => record.write_to(buf),
+            Self::EMR_CREATECOLORSPACE(record) => record.write_to(buf),
+            Self::EMR_CREATECOLORSPACEW(record) => record.write_to(buf),
+            Self::EMR_CREATEDIBPATTERNBRUSHPT(record) => record.write_to(buf),
+            Self::EMR_CREATEMONOBRUSH(record) => record.write_to(buf),
+            Self::EMR_CREATEPALETTE(record) => record.write_to(buf),
+            Self::EMR_CREATEPEN(record) => record.write_to(buf),
+            Self::EMR_EXTCREATEFONTINDIRECTW(record) => record.write_to(buf),
+            Self::EMR_EXTCREATEPEN(record) => record.write_to(buf),
+            Self::EMR_COLORCORRECTPALETTE(record) => record.write_to(buf),
+            Self::EMR_DELETECOLORSPACE(record) => record.write_to(buf),
+            Self::EMR_DELETEOBJECT(record) => record.write_to(buf),
+            Self::EMR_RESIZEPALETTE(record) => record.write_to(buf),
+            Self::EMR_SELECTOBJECT(record) => record.write_to(buf),
+            Self::EMR_SELECTPALETTE(record) => record.write_to(buf),
+            Self::EMR_SETCOLORSPACE(record) => record.write_to(buf),
+            Self::EMR_SETPALETTEENTRIES(record) => record.write_to(buf),
+            Self::EMR_GLSBOUNDEDRECORD(record) => record.write_to(buf),
+            Self::EMR_GLSRECORD(record) => record.write_to(buf),
+            Self::EMR_ABORTPATH(record) => record.write_to(buf),
+            Self::EMR_BEGINPATH(record) => record.write_to(buf),
+            Self::EMR_CLOSEFIGURE(record) => record.write_to(buf),
+            Self::EMR_ENDPATH(record) => record.write_to(buf),
+            Self::EMR_FLATTENPATH(record) => record.write_to(buf),
+            Self::EMR_WIDENPATH(record) => record.write_to(buf),
+            Self::EMR_COLORMATCHTOTARGETW(record) => record.write_to(buf),
+            Self::EMR_FORCEUFIMAPPING(record) => record.write_to(buf),
+            Self::EMR_INVERTRGN(record) => record.write_to(buf),
+            Self::EMR_MOVETOEX(record) => record.write_to(buf),
+            Self::EMR_PIXELFORMAT(record) => record.write_to(buf),
+            Self::EMR_REALIZEPALETTE(record) => record.write_to(buf),
+            Self::EMR_RESTOREDC(record) => record.write_to(buf),
+            Self::EMR_SAVEDC(record) => record.write_to(buf),
+            Self::EMR_SCALEVIEWPORTEXTEX(record) => record.write_to(buf),
+            Self::EMR_SCALEWINDOWEXTEX(record) => record.write_to(buf),
+            Self::EMR_SETARCDIRECTION(record) => record.write_to(buf),
+            Self::EMR_SETBKCOLOR(record) => record.write_to(buf),
+            Self::EMR_SETBKMODE(record) => record.write_to(buf),
+            Self::EMR_SETBRUSHORGEX(record) => record.write_to(buf),
+            Self::EMR_SETCOLORADJUSTMENT(record) => record.write_to(buf),
+            Self::EMR_SETICMMODE(record) => record.write_to(buf),
+            Self::EMR_SETICMPROFILEA(record) => record.write_to(buf),
+            Self::EMR_SETICMPROFILEW(record) => record.write_to(buf),
+            Self::EMR_SETLAYOUT(record) => record.write_to(buf),
+            Self::EMR_SETLINKEDUFIS(record) => record.write_to(buf),
+            Self::EMR_SETMAPMODE(record) => record.write_to(buf),
+            Self::EMR_SETMAPPERFLAGS(record) => record.write_to(buf),
+            Self::EMR_SETMITERLIMIT(record) => record.write_to(buf),
+            Self::EMR_SETPOLYFILLMODE(record) => record.write_to(buf),
+            Self::EMR_SETROP2(record) => record.write_to(buf),
+            Self::EMR_SETSTRETCHBLTMODE(record) => record.write_to(buf),
+            Self::EMR_SETTEXTALIGN(record) => record.write_to(buf),
+            Self::EMR_SETTEXTCOLOR(record) => record.write_to(buf),
+            Self::EMR_SETTEXTJUSTIFICATION(record) => record.write_to(buf),
+            Self::EMR_SETVIEWPORTEXTEX(record) => record.write_to(buf),
+            Self::EMR_SETVIEWPORTORGEX(record) => record.write_to(buf),
+            Self::EMR_SETWINDOWEXTEX(record) => record.write_to(buf),
+            Self::EMR_SETWINDOWORGEX(record) => record.write_to(buf),
+            Self::EMR_MODIFYWORLDTRANSFORM(record) => record.write_to(buf),
+            Self::EMR_SETWORLDTRANSFORM(record) => record.write_to(buf),
+        }
+    }
+}
+
+/// A record together with where it was read from.
+#[derive(Clone, Debug)]
+pub struct RecordEntry<'a> {
+    /// Zero-based position of the record in the metafile. The header is
+    /// record 0.
+    pub record_number: usize,
+    /// Byte offset of the start of the record from the start of the
+    /// metafile.
+    pub offset: usize,
+    pub record: Record<'a>,
+}
+
+/// An error reading a record, with where the record was read from.
+#[derive(Clone, Debug, snafu::prelude::Snafu)]
+#[snafu(display(
+    "failed to read record {record_number} at {offset:#X}: {source}"
+))]
+pub struct RecordError {
+    pub record_number: usize,
+    pub offset: usize,
+    /// The record type, or `None` if the record header could not be read or
+    /// holds an unknown record type.
+    pub record_type: Option<RecordType>,
+    pub source: ParseError,
+    recoverable: bool,
+}
+
+impl RecordError {
+    /// Returns `true` if the reader skipped the rest of the record and can
+    /// read the next one.
+    pub fn is_recoverable(&self) -> bool {
+        self.recoverable
+    }
+}
+
+/// Reads the records of an EMF metafile from `buffer`, starting with the
+/// EMR_HEADER record and ending with the EMR_EOF record.
+///
+/// Records of zero size are skipped but counted, so record numbers match
+/// the ones a [`Player`](crate::converter::Player) receives.
+#[derive(Debug)]
+pub struct EmrReader<R> {
+    buffer: R,
+    record_number: usize,
+    offset: usize,
+    finished: bool,
+}
+
+impl<R: crate::Read> EmrReader<R> {
+    pub fn new(buffer: R) -> Self {
+        Self { buffer, record_number: 0, offset: 0, finished: false }
+    }
+
+    /// Returns an iterator over the remaining records.
+    pub fn records(self) -> Records<R> {
+        Records(self)
+    }
+
+    /// Reads the next record, or returns `None` once the EMR_EOF record
+    /// has been read.
+    ///
+    /// When the body of a record is malformed or its record type is unknown,
+    /// the rest of the record is skipped using its `Size` field and the
+    /// returned error is [recoverable](RecordError::is_recoverable): reading
+    /// can continue with the next record. Any other error, such as a
+    /// truncated buffer or a malformed header, finishes the reader.
+    pub fn read_record(
+        &mut self,
+    ) -> Result<Option<RecordEntry<'static>>, RecordError> {
+        self.read_entry(|buf, record_type, size| {
+            parse_record(buf, record_type, size)
+        })
+    }
+
+    /// Returns `true` once the EMR_EOF record or an unrecoverable error has
+    /// been read.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip_all,
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn read_entry<'r>(
+        &mut self,
+        parse: impl Fn(
+            &mut CountingReader<'_, R>,
+            RecordType,
+            Size,
+        ) -> Result<Option<Record<'r>>, ParseError>,
+    ) -> Result<Option<RecordEntry<'r>>, RecordError> {
+        while !self.finished {
+            let (record_number, offset) = (self.record_number, self.offset);
+            let record = if record_number == 0 {
+                let header =
+                    EMR_HEADER::parse(&mut self.buffer).map_err(|source| {
+                        self.finished = true;
+
+                        RecordError {
+                            record_number,
+                            offset,
+                            record_type: Some(RecordType::EMR_HEADER),
+                            source,
+                            recoverable: false,
+                        }
+                    })?;
+
+                self.offset += header.size.byte_count();
+                Some(Record::EMR_HEADER(header))
+            } else {
+                let record =
+                    self.read_next_record(record_number, offset, &parse);
+
+                match &record {
+                    Err(err) if !err.recoverable => self.finished = true,
+                    _ => self.record_number += 1,
+                }
+
+                record?
+            };
+
+            if record_number == 0 {
+                self.record_number += 1;
+            }
+
+            if let Some(record) = record {
+                debug!(%record_number, ?record);
+
+                self.finished = matches!(record, Record::EMR_EOF(_));
+                return Ok(Some(RecordEntry { record_number, offset, record }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn read_next_record<'r>(
+        &mut self,
+        record_number: usize,
+        offset: usize,
+        parse: impl Fn(
+            &mut CountingReader<'_, R>,
+            RecordType,
+            Size,
+        ) -> Result<Option<Record<'r>>, ParseError>,
+    ) -> Result<Option<Record<'r>>, RecordError> {
+        use crate::parser::records::read_field;
+
+        let error = |record_type, source, recoverable| RecordError {
+            record_number,
+            offset,
+            record_type,
+            source,
+            recoverable,
+        };
+
+        // Track header bytes via a stand-alone counter; the record payload
+        // uses its own `Size` tracker created below.
+        let mut header_bytes: usize = 0;
+        let (record_type, size_raw) =
+            read_field(&mut self.buffer, &mut header_bytes)
+                .and_then(|record_type: u32| {
+                    Ok((
+                        record_type,
+                        read_field(&mut self.buffer, &mut header_bytes)?,
+                    ))
+                })
+                .map_err(|source| error(None, source, false))?;
+
+        // Reject malformed/oversized record sizes up front. Without this
+        // guard `byte_count()` and `remaining_bytes()` consumers could be
+        // steered toward huge allocations.
+        let mut size = Size::parse(size_raw)
+            .map_err(|source| error(None, source, false))?;
+        size.consume(header_bytes);
+
+        self.offset += size.byte_count().max(header_bytes);
+
+        if size.byte_count() == 0 {
+            debug!(%size, "skip parsing zero-sized record");
+
+            return Ok(None);
+        }
+
+        let remaining_bytes = size.remaining_bytes();
+        let mut buffer =
+            CountingReader { inner: &mut self.buffer, consumed_bytes: 0 };
+        let parsed = match RecordType::from_repr(record_type) {
+            Some(record_type) => parse(&mut buffer, record_type, size)
+                .map_err(|source| (Some(record_type), source)),
+            // Reuse the error the enum parser reports for unknown values.
+            None => {
+                RecordType::parse(&mut record_type.to_le_bytes().as_slice())
+                    .map(|_| None)
+                    .map_err(|source| (None, source))
+            }
+        };
+
+        let (record_type, source) = match parsed {
+            Ok(None) => {
+                debug!(%record_number, "skip EMR_HEADER after the first record");
+
+                return Ok(None);
+            }
+            Ok(record) => return Ok(record),
+            Err(err) => err,
+        };
+
+        // Skip what the parser left of the record so that the next read
+        // starts at the next record.
+        let consumed_bytes = buffer.consumed_bytes;
+        let recoverable = consumed_bytes <= remaining_bytes
+            && consume_remaining_bytes(
+                &mut self.buffer,
+                remaining_bytes - consumed_bytes,
+            )
+            .is_ok();
+
+        Err(error(record_type, source, recoverable))
+    }
+}
+
+impl<'a> EmrReader<&'a [u8]> {
+    /// Reads the next record like [`read_record`](Self::read_record), but
+    /// borrows the bitmaps of EMR_ALPHABLEND and EMR_STRETCHDIBITS records
+    /// and the private data of EMR_COMMENT records from the buffer instead
+    /// of copying them.
+    pub fn read_borrowed_record(
+        &mut self,
+    ) -> Result<Option<RecordEntry<'a>>, RecordError> {
+        self.read_entry(parse_borrowed_record)
+    }
+
+    /// Returns an iterator over the remaining records, borrowing their data
+    /// as [`read_borrowed_record`](Self::read_borrowed_record) does.
+    pub fn borrowed_records(self) -> BorrowedRecords<'a> {
+        BorrowedRecords(self)
+    }
+}
+
+/// Parses the body of a record whose type and size have been read.
+fn parse_record<R: crate::Read>(
+    buf: &mut R,
+    record_type: RecordType,
+    size: Size,
+) -> Result<Option<Record<'static>>, ParseError> {
+    let record = match record_type {
+        // bitmap record
+        RecordType::EMR_ALPHABLEND => Record::EMR_ALPHABLEND(
+            EMR_ALPHABLEND::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_BITBLT => {
+            Record::EMR_BITBLT(EMR_BITBLT::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_MASKBLT => {
+            Record::EMR_MASKBLT(EMR_MASKBLT::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_PLGBLT => {
+            Record::EMR_PLGBLT(EMR_PLGBLT::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SETDIBITSTODEVICE => Record::EMR_SETDIBITSTODEVICE(
+            EMR_SETDIBITSTODEVICE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_STRETCHBLT => Record::EMR_STRETCHBLT(
+            EMR_STRETCHBLT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_STRETCHDIBITS => Record::EMR_STRETCHDIBITS(
+            EMR_STRETCHDIBITS::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_TRANSPARENTBLT => Record::EMR_TRANSPARENTBLT(
+            EMR_TRANSPARENTBLT::parse(buf, record_type, size)?,
+        ),
+        // clipping record
+        RecordType::EMR_EXCLUDECLIPRECT => Record::EMR_EXCLUDECLIPRECT(
+            EMR_EXCLUDECLIPRECT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_EXTSELECTCLIPRGN => Record::EMR_EXTSELECTCLIPRGN(
+            EMR_EXTSELECTCLIPRGN::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_INTERSECTCLIPRECT => Record::EMR_INTERSECTCLIPRECT(
+            EMR_INTERSECTCLIPRECT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_OFFSETCLIPRGN => Record::EMR_OFFSETCLIPRGN(
+            EMR_OFFSETCLIPRGN::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SELECTCLIPPATH => Record::EMR_SELECTCLIPPATH(
+            EMR_SELECTCLIPPATH::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETMETARGN => Record::EMR_SETMETARGN(
+            EMR_SETMETARGN::parse(buf, record_type, size)?,
+        ),
+        // comment record
+        RecordType::EMR_COMMENT => {
+            Record::EMR_COMMENT(EMR_COMMENT::parse(buf, record_type, size)?)
+        }
+        // control record
+        RecordType::EMR_EOF => {
+            Record::EMR_EOF(EMR_EOF::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_HEADER => {
+            // Only the first record of a metafile is a header.
+            consume_remaining_bytes(buf, size.remaining_bytes())?;
+
+            return Ok(None);
+        }
+        // drawing record
+        RecordType::EMR_ANGLEARC => {
+            Record::EMR_ANGLEARC(EMR_ANGLEARC::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_ARC => {
+            Record::EMR_ARC(EMR_ARC::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_ARCTO => {
+            Record::EMR_ARCTO(EMR_ARCTO::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_CHORD => {
+            Record::EMR_CHORD(EMR_CHORD::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_ELLIPSE => {
+            Record::EMR_ELLIPSE(EMR_ELLIPSE::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_EXTFLOODFILL => Record::EMR_EXTFLOODFILL(
+            EMR_EXTFLOODFILL::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_EXTTEXTOUTA => Record::EMR_EXTTEXTOUTA(
+            EMR_EXTTEXTOUTA::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_EXTTEXTOUTW => Record::EMR_EXTTEXTOUTW(
+            EMR_EXTTEXTOUTW::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_FILLPATH => {
+            Record::EMR_FILLPATH(EMR_FILLPATH::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_FILLRGN => {
+            Record::EMR_FILLRGN(EMR_FILLRGN::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_FRAMERGN => {
+            Record::EMR_FRAMERGN(EMR_FRAMERGN::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_GRADIENTFILL => Record::EMR_GRADIENTFILL(
+            EMR_GRADIENTFILL::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_LINETO => {
+            Record::EMR_LINETO(EMR_LINETO::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_PAINTRGN => {
+            Record::EMR_PAINTRGN(EMR_PAINTRGN::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_PIE => {
+            Record::EMR_PIE(EMR_PIE::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_POLYBEZIER => Record::EMR_POLYBEZIER(
+            EMR_POLYBEZIER::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYBEZIER16 => Record::EMR_POLYBEZIER16(
+            EMR_POLYBEZIER16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYBEZIERTO => Record::EMR_POLYBEZIERTO(
+            EMR_POLYBEZIERTO::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYBEZIERTO16 => Record::EMR_POLYBEZIERTO16(
+            EMR_POLYBEZIERTO16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYDRAW => {
+            Record::EMR_POLYDRAW(EMR_POLYDRAW::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_POLYDRAW16 => Record::EMR_POLYDRAW16(
+            EMR_POLYDRAW16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYPOLYGON => Record::EMR_POLYPOLYGON(
+            EMR_POLYPOLYGON::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYPOLYGON16 => Record::EMR_POLYPOLYGON16(
+            EMR_POLYPOLYGON16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYPOLYLINE => Record::EMR_POLYPOLYLINE(
+            EMR_POLYPOLYLINE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYPOLYLINE16 => Record::EMR_POLYPOLYLINE16(
+            EMR_POLYPOLYLINE16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYTEXTOUTA => Record::EMR_POLYTEXTOUTA(
+            EMR_POLYTEXTOUTA::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYTEXTOUTW => Record::EMR_POLYTEXTOUTW(
+            EMR_POLYTEXTOUTW::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYGON => {
+            Record::EMR_POLYGON(EMR_POLYGON::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_POLYGON16 => {
+            Record::EMR_POLYGON16(EMR_POLYGON16::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_POLYLINE => {
+            Record::EMR_POLYLINE(EMR_POLYLINE::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_POLYLINE16 => Record::EMR_POLYLINE16(
+            EMR_POLYLINE16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYLINETO => Record::EMR_POLYLINETO(
+            EMR_POLYLINETO::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_POLYLINETO16 => Record::EMR_POLYLINETO16(
+            EMR_POLYLINETO16::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_RECTANGLE => {
+            Record::EMR_RECTANGLE(EMR_RECTANGLE::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_ROUNDRECT => {
+            Record::EMR_ROUNDRECT(EMR_ROUNDRECT::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SETPIXELV => {
+            Record::EMR_SETPIXELV(EMR_SETPIXELV::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SMALLTEXTOUT => Record::EMR_SMALLTEXTOUT(
+            EMR_SMALLTEXTOUT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_STROKEANDFILLPATH => Record::EMR_STROKEANDFILLPATH(
+            EMR_STROKEANDFILLPATH::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_STROKEPATH => Record::EMR_STROKEPATH(
+            EMR_STROKEPATH::parse(buf, record_type, size)?,
+        ),
+        // escape record
+        RecordType::EMR_DRAWESCAPE => Record::EMR_DRAWESCAPE(
+            EMR_DRAWESCAPE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_EXTESCAPE => {
+            Record::EMR_EXTESCAPE(EMR_EXTESCAPE::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_NAMEDESCAPE => Record::EMR_NAMEDESCAPE(
+            EMR_NAMEDESCAPE::parse(buf, record_type, size)?,
+        ),
+        // object creation record
+        RecordType::EMR_CREATEBRUSHINDIRECT => Record::EMR_CREATEBRUSHINDIRECT(
+            EMR_CREATEBRUSHINDIRECT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_CREATECOLORSPACE => Record::EMR_CREATECOLORSPACE(
+            EMR_CREATECOLORSPACE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_CREATECOLORSPACEW => Record::EMR_CREATECOLORSPACEW(
+            EMR_CREATECOLORSPACEW::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_CREATEDIBPATTERNBRUSHPT => {
+            Record::EMR_CREATEDIBPATTERNBRUSHPT(
+                EMR_CREATEDIBPATTERNBRUSHPT::parse(buf, record_type, size)?,
+            )
+        }
+        RecordType::EMR_CREATEMONOBRUSH => Record::EMR_CREATEMONOBRUSH(
+            EMR_CREATEMONOBRUSH::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_CREATEPALETTE => Record::EMR_CREATEPALETTE(
+            EMR_CREATEPALETTE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_CREATEPEN => {
+            Record::EMR_CREATEPEN(EMR_CREATEPEN::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_EXTCREATEFONTINDIRECTW => {
+            Record::EMR_EXTCREATEFONTINDIRECTW(
+                EMR_EXTCREATEFONTINDIRECTW::parse(buf, record_type, size)?,
+            )
+        }
+        RecordType::EMR_EXTCREATEPEN => Record::EMR_EXTCREATEPEN(
+            EMR_EXTCREATEPEN::parse(buf, record_type, size)?,
+        ),
+        // object manipulation record
+        RecordType::EMR_COLORCORRECTPALETTE => Record::EMR_COLORCORRECTPALETTE(
+            EMR_COLORCORRECTPALETTE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_DELETECOLORSPACE => Record::EMR_DELETECOLORSPACE(
+            EMR_DELETECOLORSPACE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_DELETEOBJECT => Record::EMR_DELETEOBJECT(
+            EMR_DELETEOBJECT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_RESIZEPALETTE => Record::EMR_RESIZEPALETTE(
+            EMR_RESIZEPALETTE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SELECTOBJECT => Record::EMR_SELECTOBJECT(
+            EMR_SELECTOBJECT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SELECTPALETTE => Record::EMR_SELECTPALETTE(
+            EMR_SELECTPALETTE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETCOLORSPACE => Record::EMR_SETCOLORSPACE(
+            EMR_SETCOLORSPACE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETPALETTEENTRIES => Record::EMR_SETPALETTEENTRIES(
+            EMR_SETPALETTEENTRIES::parse(buf, record_type, size)?,
+        ),
+        // opengl record
+        RecordType::EMR_GLSBOUNDEDRECORD => Record::EMR_GLSBOUNDEDRECORD(
+            EMR_GLSBOUNDEDRECORD::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_GLSRECORD => {
+            Record::EMR_GLSRECORD(EMR_GLSRECORD::parse(buf, record_type, size)?)
+        }
+        // path bracket record
+        RecordType::EMR_ABORTPATH => {
+            Record::EMR_ABORTPATH(EMR_ABORTPATH::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_BEGINPATH => {
+            Record::EMR_BEGINPATH(EMR_BEGINPATH::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_CLOSEFIGURE => Record::EMR_CLOSEFIGURE(
+            EMR_CLOSEFIGURE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_ENDPATH => {
+            Record::EMR_ENDPATH(EMR_ENDPATH::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_FLATTENPATH => Record::EMR_FLATTENPATH(
+            EMR_FLATTENPATH::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_WIDENPATH => {
+            Record::EMR_WIDENPATH(EMR_WIDENPATH::parse(buf, record_type, size)?)
+        }
+        // state record
+        RecordType::EMR_COLORMATCHTOTARGETW => Record::EMR_COLORMATCHTOTARGETW(
+            EMR_COLORMATCHTOTARGETW::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_FORCEUFIMAPPING => Record::EMR_FORCEUFIMAPPING(
+            EMR_FORCEUFIMAPPING::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_INVERTRGN => {
+            Record::EMR_INVERTRGN(EMR_INVERTRGN::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_MOVETOEX => {
+            Record::EMR_MOVETOEX(EMR_MOVETOEX::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_PIXELFORMAT => Record::EMR_PIXELFORMAT(
+            EMR_PIXELFORMAT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_REALIZEPALETTE => Record::EMR_REALIZEPALETTE(
+            EMR_REALIZEPALETTE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_RESTOREDC => {
+            Record::EMR_RESTOREDC(EMR_RESTOREDC::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SAVEDC => {
+            Record::EMR_SAVEDC(EMR_SAVEDC::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SCALEVIEWPORTEXTEX => Record::EMR_SCALEVIEWPORTEXTEX(
+            EMR_SCALEVIEWPORTEXTEX::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SCALEWINDOWEXTEX => Record::EMR_SCALEWINDOWEXTEX(
+            EMR_SCALEWINDOWEXTEX::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETARCDIRECTION => Record::EMR_SETARCDIRECTION(
+            EMR_SETARCDIRECTION::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETBKCOLOR => Record::EMR_SETBKCOLOR(
+            EMR_SETBKCOLOR::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETBKMODE => {
+            Record::EMR_SETBKMODE(EMR_SETBKMODE::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SETBRUSHORGEX => Record::EMR_SETBRUSHORGEX(
+            EMR_SETBRUSHORGEX::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETCOLORADJUSTMENT => Record::EMR_SETCOLORADJUSTMENT(
+            EMR_SETCOLORADJUSTMENT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETICMMODE => Record::EMR_SETICMMODE(
+            EMR_SETICMMODE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETICMPROFILEA => Record::EMR_SETICMPROFILEA(
+            EMR_SETICMPROFILEA::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETICMPROFILEW => Record::EMR_SETICMPROFILEW(
+            EMR_SETICMPROFILEW::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETLAYOUT => {
+            Record::EMR_SETLAYOUT(EMR_SETLAYOUT::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SETLINKEDUFIS => Record::EMR_SETLINKEDUFIS(
+            EMR_SETLINKEDUFIS::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETMAPMODE => Record::EMR_SETMAPMODE(
+            EMR_SETMAPMODE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETMAPPERFLAGS => Record::EMR_SETMAPPERFLAGS(
+            EMR_SETMAPPERFLAGS::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETMITERLIMIT => Record::EMR_SETMITERLIMIT(
+            EMR_SETMITERLIMIT::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETPOLYFILLMODE => Record::EMR_SETPOLYFILLMODE(
+            EMR_SETPOLYFILLMODE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETROP2 => {
+            Record::EMR_SETROP2(EMR_SETROP2::parse(buf, record_type, size)?)
+        }
+        RecordType::EMR_SETSTRETCHBLTMODE => Record::EMR_SETSTRETCHBLTMODE(
+            EMR_SETSTRETCHBLTMODE::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETTEXTALIGN => Record::EMR_SETTEXTALIGN(
+            EMR_SETTEXTALIGN::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETTEXTCOLOR => Record::EMR_SETTEXTCOLOR(
+            EMR_SETTEXTCOLOR::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETTEXTJUSTIFICATION => {
+            Record::EMR_SETTEXTJUSTIFICATION(EMR_SETTEXTJUSTIFICATION::parse(
+                buf,
+                record_type,
+                size,
+            )?)
+        }
+        RecordType::EMR_SETVIEWPORTEXTEX => Record::EMR_SETVIEWPORTEXTEX(
+            EMR_SETVIEWPORTEXTEX::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETVIEWPORTORGEX => Record::EMR_SETVIEWPORTORGEX(
+            EMR_SETVIEWPORTORGEX::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETWINDOWEXTEX => Record::EMR_SETWINDOWEXTEX(
+            EMR_SETWINDOWEXTEX::parse(buf, record_type, size)?,
+        ),
+        RecordType::EMR_SETWINDOWORGEX => Record::EMR_SETWINDOWORGEX(
+            EMR_SETWINDOWORGEX::parse(buf, record_type, size)?,
+        ),
+        // transform record
+        RecordType::EMR_MODIFYWORLDTRANSFORM => {
+            Record::EMR_MODIFYWORLDTRANSFORM(EMR_MODIFYWORLDTRANSFORM::parse(
+                buf,
+                record_type,
+                size,
+            )?)
+        }
+        RecordType::EMR_SETWORLDTRANSFORM => Record::EMR_SETWORLDTRANSFORM(
+            EMR_SETWORLDTRANSFORM::parse(buf, record_type, size)?,
+        ),
+    };
+
+    Ok(Some(record))
+}
+
+/// Parses the body of a record from a slice, borrowing the variable-length
+/// data of the records that support it.
+fn parse_borrowed_record<'a>(
+    buf: &mut CountingReader<'_, &'a [u8]>,
+    record_type: RecordType,
+    size: Size,
+) -> Result<Option<Record<'a>>, ParseError> {
+    let len = buf.inner.len();
+    let record = match record_type {
+        RecordType::EMR_ALPHABLEND => {
+            EMR_ALPHABLEND::parse_borrowed(buf.inner, record_type, size)
+                .map(Record::EMR_ALPHABLEND)
+        }
+        RecordType::EMR_STRETCHDIBITS => {
+            EMR_STRETCHDIBITS::parse_borrowed(buf.inner, record_type, size)
+                .map(Record::EMR_STRETCHDIBITS)
+        }
+        RecordType::EMR_COMMENT => {
+            EMR_COMMENT::parse_borrowed(buf.inner, record_type, size)
+                .map(Record::EMR_COMMENT)
+        }
+        _ => return parse_record(buf, record_type, size),
+    };
+
+    // Parsing advanced the slice directly, bypassing the count.
+    buf.consumed_bytes += len - buf.inner.len();
+    record.map(Some)
+}
+
+/// An iterator over the records of an [`EmrReader`]. It ends after the
+/// EMR_EOF record or the first error that is not
+/// [recoverable](RecordError::is_recoverable).
+#[derive(Debug)]
+pub struct Records<R>(EmrReader<R>);
+
+impl<R: crate::Read> Iterator for Records<R> {
+    type Item = Result<RecordEntry<'static>, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.read_record().transpose()
+    }
+}
+
+/// An iterator over the records of an [`EmrReader`] reading a slice, which
+/// borrows their data from it. It ends like [`Records`].
+#[derive(Debug)]
+pub struct BorrowedRecords<'a>(EmrReader<&'a [u8]>);
+
+impl<'a> Iterator for BorrowedRecords<'a> {
+    type Item = Result<RecordEntry<'a>, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.read_borrowed_record().transpose()
+    }
+}
+
+/// Counts the bytes read through it, so that the rest of a record can be
+/// skipped after a parser fails in the middle of it.
+struct CountingReader<'a, R> {
+    inner: &'a mut R,
+    consumed_bytes: usize,
+}
+
+impl<R: crate::Read> embedded_io::ErrorType for CountingReader<'_, R> {
+    type Error = R::Error;
+}
+
+impl<R: crate::Read> embedded_io::Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let read = self.inner.read(buf)?;
+
+        self.consumed_bytes += read;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(record_type: RecordType, fields: &[u32]) -> Vec<u8> {
+        let size = 8 + fields.len() as u32 * 4;
+
+        [record_type as u32, size]
+            .iter()
+            .chain(fields)
+            .flat_map(|v| v.to_le_bytes())
+            .collect()
+    }
+
+    fn metafile() -> Vec<u8> {
+        let header = record(RecordType::EMR_HEADER, &[
+            // Bounds and Frame
+            0,
+            0,
+            100,
+            100,
+            0,
+            0,
+            2646,
+            2646,
+            // RecordSignature, Version, Bytes, Records
+            0x464D_4520,
+            0x0001_0000,
+            132,
+            4,
+            // Handles and Reserved, nDescription, offDescription,
+            // nPalEntries
+            1,
+            0,
+            0,
+            0,
+            // Device and Millimeters
+            1024,
+            768,
+            320,
+            240,
+        ]);
+        let zero_sized = [RecordType::EMR_SETBKMODE as u32, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let set_bk_mode = record(RecordType::EMR_SETBKMODE, &[1]);
+        let eof = record(RecordType::EMR_EOF, &[0, 0x10, 20]);
+
+        [header, zero_sized, set_bk_mode, eof].concat()
+    }
+
+    #[test]
+    fn records_yield_record_numbers_and_offsets() {
+        let metafile = metafile();
+        let entries = EmrReader::new(metafile.as_slice())
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("records should parse");
+
+        let positions = entries
+            .iter()
+            .map(|v| (v.record_number, v.offset, v.record.record_type()))
+            .collect::<Vec<_>>();
+
+        // The zero-sized record at offset 88 is skipped but counted.
+        assert_eq!(positions, [
+            (0, 0, RecordType::EMR_HEADER),
+            (2, 96, RecordType::EMR_SETBKMODE),
+            (3, 108, RecordType::EMR_EOF),
+        ]);
+    }
+
+    #[test]
+    fn records_end_after_first_error() {
+        let metafile = metafile();
+        // Cut the metafile in the middle of EMR_SETBKMODE.
+        let mut records = EmrReader::new(&metafile[..100]).records();
+
+        assert!(matches!(records.next(), Some(Ok(_))));
+        assert!(matches!(records.next(), Some(Err(_))));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn read_record_skips_malformed_record_body() {
+        let mut metafile = metafile();
+        // Replace the background mode of EMR_SETBKMODE with an unknown one.
+        metafile[104..108].copy_from_slice(&7_u32.to_le_bytes());
+
+        let mut reader = EmrReader::new(metafile.as_slice());
+        assert!(matches!(reader.read_record(), Ok(Some(_))));
+
+        let err = reader.read_record().expect_err("record should be malformed");
+        assert!(err.is_recoverable());
+        assert_eq!(
+            (err.record_number, err.offset, err.record_type),
+            (2, 96, Some(RecordType::EMR_SETBKMODE))
+        );
+
+        let entry = reader.read_record().expect("record should parse");
+        assert!(matches!(
+            entry,
+            Some(RecordEntry {
+                record_number: 3,
+                offset: 108,
+                record: Record::EMR_EOF(_),
+            })
+        ));
+    }
+    #[test]
+    fn borrowed_records_borrow_private_data_from_the_buffer() {
+        let mut metafile = metafile();
+        let comment = record(RecordType::EMR_COMMENT, &[4, 0x1234_5678]);
+        // Insert the comment before EMR_EOF.
+        metafile.splice(108..108, comment);
+
+        let entries = EmrReader::new(metafile.as_slice())
+            .borrowed_records()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("records should parse");
+        let Record::EMR_COMMENT(borrowed) = &entries[2].record else {
+            panic!("record should be EMR_COMMENT");
+        };
+
+        assert!(matches!(
+            &borrowed.private_data,
+            Cow::Borrowed(data) if data.as_ptr() == metafile[120..].as_ptr()
+        ));
+        assert_eq!(entries[3].offset, 124);
+        assert!(matches!(entries[3].record, Record::EMR_EOF(_)));
+
+        let Some(Record::EMR_COMMENT(owned)) =
+            EmrReader::new(metafile.as_slice())
+                .records()
+                .map(|entry| entry.expect("records should parse").record)
+                .nth(2)
+        else {
+            panic!("record should be EMR_COMMENT");
+        };
+
+        assert!(matches!(owned.private_data, Cow::Owned(_)));
+        assert_eq!(owned.private_data, borrowed.private_data);
+    }
+}
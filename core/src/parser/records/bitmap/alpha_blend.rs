@@ -59,7 +59,7 @@ use crate::imports::*;
 /// If SrcConstantAlpha is 0xFF, there is in effect no premultiplication of the
 /// source values. See section 2.3.1 for more bitmap record types.
 #[derive(Clone, Debug)]
-pub struct EMR_ALPHABLEND {
+pub struct EMR_ALPHABLEND<'a> {
     /// Type (4 bytes): An unsigned integer that identifies this record type as
     /// EMR_ALPHABLEND. This value is 0x00000072.
     pub record_type: crate::parser::RecordType,
@@ -128,26 +128,99 @@ pub struct EMR_ALPHABLEND {
     /// "UndefinedSpace" are optional and MUST be ignored.
     ///
     /// BmiSrc (variable): The source bitmap header.
-    pub bmi_src: Vec<u8>,
+    pub bmi_src: Cow<'a, [u8]>,
     /// BitsSrc (variable): The source bitmap bits.
-    pub bits_src: Vec<u8>,
+    pub bits_src: Cow<'a, [u8]>,
 }
 
-impl EMR_ALPHABLEND {
+impl EMR_ALPHABLEND<'static> {
+    pub fn parse<R: crate::Read>(
+        buf: &mut R,
+        record_type: crate::parser::RecordType,
+        size: crate::parser::Size,
+    ) -> Result<Self, crate::parser::ParseError> {
+        Self::parse_with(buf, record_type, size, |buf, size, len| {
+            crate::parser::records::read_bytes_field(buf, size, len)
+                .map(Cow::Owned)
+        })
+    }
+}
+
+impl<'a> EMR_ALPHABLEND<'a> {
+    /// Parses the record from the front of `buf`, borrowing its
+    /// variable-length data instead of copying it.
+    pub fn parse_borrowed(
+        buf: &mut &'a [u8],
+        record_type: crate::parser::RecordType,
+        size: crate::parser::Size,
+    ) -> Result<Self, crate::parser::ParseError> {
+        Self::parse_with(buf, record_type, size, |buf, size, len| {
+            crate::parser::records::borrow_bytes_field(buf, size, len)
+                .map(Cow::Borrowed)
+        })
+    }
+
+    /// Copies the borrowed data so the record outlives its input.
+    pub fn into_owned(self) -> EMR_ALPHABLEND<'static> {
+        EMR_ALPHABLEND {
+            bmi_src: Cow::Owned(self.bmi_src.into_owned()),
+            bits_src: Cow::Owned(self.bits_src.into_owned()),
+            ..self
+        }
+    }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_ALPHABLEND);
+
+        write_field(&mut record, &self.bounds)?;
+        write_field(&mut record, self.x_dest)?;
+        write_field(&mut record, self.y_dest)?;
+        write_field(&mut record, self.cx_dest)?;
+        write_field(&mut record, self.cy_dest)?;
+        self.blend_function.write_to(&mut record)?;
+        write_field(&mut record, self.x_src)?;
+        write_field(&mut record, self.y_src)?;
+        self.x_form_src.write_to(&mut record)?;
+        write_field(&mut record, &self.bk_color_src)?;
+        write_field(&mut record, self.usage_src)?;
+
+        let bmi_src = record.reserve_buffer();
+        let bits_src = record.reserve_buffer();
+
+        write_field(&mut record, self.cx_src)?;
+        write_field(&mut record, self.cy_src)?;
+        record.write_buffer(bmi_src, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits_src, "bits_src", &self.bits_src)?;
+        record.finish(buf)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = tracing::Level::TRACE,
         skip_all,
         fields(record_type = ?record_type),
         err(level = tracing::Level::ERROR, Display),
     ))]
-    pub fn parse<R: crate::Read>(
+    fn parse_with<R: crate::Read>(
         buf: &mut R,
         record_type: crate::parser::RecordType,
         mut size: crate::parser::Size,
+        mut read_bytes: impl FnMut(
+            &mut R,
+            &mut crate::parser::Size,
+            usize,
+        ) -> Result<
+            Cow<'a, [u8]>,
+            crate::parser::ParseError,
+        >,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            consume_remaining_bytes, discard_bytes_field, read_bytes_field,
-            read_field, read_with,
+            consume_remaining_bytes, discard_bytes_field, read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -198,11 +271,11 @@ impl EMR_ALPHABLEND {
 
         let undef_offset_bmi = size.checked_offset(off_bmi_src)?;
         discard_bytes_field(buf, &mut size, undef_offset_bmi)?;
-        let bmi_src = read_bytes_field(buf, &mut size, cb_bmi_src as usize)?;
+        let bmi_src = read_bytes(buf, &mut size, cb_bmi_src as usize)?;
 
         let undef_offset_bits = size.checked_offset(off_bits_src)?;
         discard_bytes_field(buf, &mut size, undef_offset_bits)?;
-        let bits_src = read_bytes_field(buf, &mut size, cb_bits_src as usize)?;
+        let bits_src = read_bytes(buf, &mut size, cb_bits_src as usize)?;
 
         consume_remaining_bytes(buf, size.remaining_bytes())?;
 
@@ -285,4 +358,20 @@ impl BlendFunction {
             consumed_bytes,
         ))
     }
+
+    fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::write_field;
+
+        let mut written: usize = 0;
+
+        written += write_field(buf, self.blend_operation)?;
+        written += write_field(buf, self.blend_flags)?;
+        written += write_field(buf, self.src_constant_alpha)?;
+        written += write_field(buf, self.alpha_format)?;
+
+        Ok(written)
+    }
 }
@@ -181,4 +181,33 @@ impl EMR_BITBLT {
             bits_src,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_BITBLT);
+
+        write_field(&mut record, &self.bounds)?;
+        write_field(&mut record, self.x_dest)?;
+        write_field(&mut record, self.y_dest)?;
+        write_field(&mut record, self.cx_dest)?;
+        write_field(&mut record, self.cy_dest)?;
+        write_field(&mut record, self.bit_blt_raster_operation as u32)?;
+        write_field(&mut record, self.x_src)?;
+        write_field(&mut record, self.y_src)?;
+        self.x_form_src.write_to(&mut record)?;
+        write_field(&mut record, &self.bk_color_src)?;
+        write_field(&mut record, self.usage_src)?;
+
+        let bmi_src = record.reserve_buffer();
+        let bits_src = record.reserve_buffer();
+
+        record.write_buffer(bmi_src, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits_src, "bits_src", &self.bits_src)?;
+        record.finish(buf)
+    }
 }
@@ -235,4 +235,44 @@ impl EMR_PLGBLT {
             bits_mask,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_PLGBLT);
+
+        write_field(&mut record, &self.bounds)?;
+
+        for point in &self.aptl_dest {
+            write_field(&mut record, point)?;
+        }
+
+        write_field(&mut record, self.x_src)?;
+        write_field(&mut record, self.y_src)?;
+        write_field(&mut record, self.cx_src)?;
+        write_field(&mut record, self.cy_src)?;
+        self.x_form_src.write_to(&mut record)?;
+        write_field(&mut record, &self.bk_color_src)?;
+        write_field(&mut record, self.usage_src)?;
+
+        let bmi_src = record.reserve_buffer();
+        let bits_src = record.reserve_buffer();
+
+        write_field(&mut record, self.x_mask)?;
+        write_field(&mut record, self.y_mask)?;
+        write_field(&mut record, self.usage_mask)?;
+
+        let bmi_mask = record.reserve_buffer();
+        let bits_mask = record.reserve_buffer();
+
+        record.write_buffer(bmi_src, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits_src, "bits_src", &self.bits_src)?;
+        record.write_buffer(bmi_mask, "bmi_mask", &self.bmi_mask)?;
+        record.write_buffer(bits_mask, "bits_mask", &self.bits_mask)?;
+        record.finish(buf)
+    }
 }
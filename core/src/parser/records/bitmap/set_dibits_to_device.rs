@@ -155,4 +155,32 @@ impl EMR_SETDIBITSTODEVICE {
             bits_src,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETDIBITSTODEVICE);
+
+        write_field(&mut record, &self.bounds)?;
+        write_field(&mut record, self.x_dest)?;
+        write_field(&mut record, self.y_dest)?;
+        write_field(&mut record, self.x_src)?;
+        write_field(&mut record, self.y_src)?;
+        write_field(&mut record, self.cx_src)?;
+        write_field(&mut record, self.cy_src)?;
+
+        let bmi_src = record.reserve_buffer();
+        let bits_src = record.reserve_buffer();
+
+        write_field(&mut record, self.usage_src)?;
+        write_field(&mut record, self.i_start_scan)?;
+        write_field(&mut record, self.c_scans)?;
+        record.write_buffer(bmi_src, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits_src, "bits_src", &self.bits_src)?;
+        record.finish(buf)
+    }
 }
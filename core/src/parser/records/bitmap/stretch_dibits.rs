@@ -16,7 +16,7 @@ use crate::imports::*;
 /// cyDest have different signs, a mirror image of the source bitmap along the
 /// y-axis is specified.
 #[derive(Clone, Debug)]
-pub struct EMR_STRETCHDIBITS {
+pub struct EMR_STRETCHDIBITS<'a> {
     /// Type (4 bytes): An unsigned integer that identifies this record type as
     /// EMR_STRETCHDIBITS. This value is 0x00000051.
     pub record_type: crate::parser::RecordType,
@@ -86,26 +86,97 @@ pub struct EMR_STRETCHDIBITS {
     /// require a source bitmap, the source bitmap can be omitted.
     ///
     /// BmiSrc (variable): The source bitmap header.
-    pub bmi_src: Vec<u8>,
+    pub bmi_src: Cow<'a, [u8]>,
     /// BitsSrc (variable): The source bitmap bits.
-    pub bits_src: Vec<u8>,
+    pub bits_src: Cow<'a, [u8]>,
 }
 
-impl EMR_STRETCHDIBITS {
+impl EMR_STRETCHDIBITS<'static> {
+    pub fn parse<R: crate::Read>(
+        buf: &mut R,
+        record_type: crate::parser::RecordType,
+        size: crate::parser::Size,
+    ) -> Result<Self, crate::parser::ParseError> {
+        Self::parse_with(buf, record_type, size, |buf, size, len| {
+            crate::parser::records::read_bytes_field(buf, size, len)
+                .map(Cow::Owned)
+        })
+    }
+}
+
+impl<'a> EMR_STRETCHDIBITS<'a> {
+    /// Parses the record from the front of `buf`, borrowing its
+    /// variable-length data instead of copying it.
+    pub fn parse_borrowed(
+        buf: &mut &'a [u8],
+        record_type: crate::parser::RecordType,
+        size: crate::parser::Size,
+    ) -> Result<Self, crate::parser::ParseError> {
+        Self::parse_with(buf, record_type, size, |buf, size, len| {
+            crate::parser::records::borrow_bytes_field(buf, size, len)
+                .map(Cow::Borrowed)
+        })
+    }
+
+    /// Copies the borrowed data so the record outlives its input.
+    pub fn into_owned(self) -> EMR_STRETCHDIBITS<'static> {
+        EMR_STRETCHDIBITS {
+            bmi_src: Cow::Owned(self.bmi_src.into_owned()),
+            bits_src: Cow::Owned(self.bits_src.into_owned()),
+            ..self
+        }
+    }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_STRETCHDIBITS);
+
+        write_field(&mut record, &self.bounds)?;
+        write_field(&mut record, self.x_dest)?;
+        write_field(&mut record, self.y_dest)?;
+        write_field(&mut record, self.x_src)?;
+        write_field(&mut record, self.y_src)?;
+        write_field(&mut record, self.cx_src)?;
+        write_field(&mut record, self.cy_src)?;
+
+        let bmi_src = record.reserve_buffer();
+        let bits_src = record.reserve_buffer();
+
+        write_field(&mut record, self.usage_src)?;
+        write_field(&mut record, self.bit_bt_raster_operation as u32)?;
+        write_field(&mut record, self.cx_dest)?;
+        write_field(&mut record, self.cy_dest)?;
+        record.write_buffer(bmi_src, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits_src, "bits_src", &self.bits_src)?;
+        record.finish(buf)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = tracing::Level::TRACE,
         skip_all,
         fields(record_type = ?record_type),
         err(level = tracing::Level::ERROR, Display),
     ))]
-    pub fn parse<R: crate::Read>(
+    fn parse_with<R: crate::Read>(
         buf: &mut R,
         record_type: crate::parser::RecordType,
         mut size: crate::parser::Size,
+        mut read_bytes: impl FnMut(
+            &mut R,
+            &mut crate::parser::Size,
+            usize,
+        ) -> Result<
+            Cow<'a, [u8]>,
+            crate::parser::ParseError,
+        >,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            consume_remaining_bytes, discard_bytes_field, read_bytes_field,
-            read_field, read_with,
+            consume_remaining_bytes, discard_bytes_field, read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -151,11 +222,11 @@ impl EMR_STRETCHDIBITS {
 
         let undef_offset = size.checked_offset(off_bmi_src)?;
         discard_bytes_field(buf, &mut size, undef_offset)?;
-        let bmi_src = read_bytes_field(buf, &mut size, cb_bmi_src as usize)?;
+        let bmi_src = read_bytes(buf, &mut size, cb_bmi_src as usize)?;
 
         let undef_offset = size.checked_offset(off_bits_src)?;
         discard_bytes_field(buf, &mut size, undef_offset)?;
-        let bits_src = read_bytes_field(buf, &mut size, cb_bits_src as usize)?;
+        let bits_src = read_bytes(buf, &mut size, cb_bits_src as usize)?;
 
         consume_remaining_bytes(buf, size.remaining_bytes())?;
 
@@ -43,4 +43,18 @@ impl EMR_EXCLUDECLIPRECT {
 
         Ok(Self { record_type, size, clip })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_EXCLUDECLIPRECT);
+
+        write_field(&mut record, &self.clip)?;
+
+        record.finish(buf)
+    }
 }
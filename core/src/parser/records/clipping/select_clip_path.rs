@@ -42,4 +42,18 @@ impl EMR_SELECTCLIPPATH {
 
         Ok(Self { record_type, size, region_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SELECTCLIPPATH);
+
+        write_field(&mut record, self.region_mode)?;
+
+        record.finish(buf)
+    }
 }
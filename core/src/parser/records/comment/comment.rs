@@ -2,7 +2,7 @@ use crate::imports::*;
 
 /// The EMR_COMMENT record contains arbitrary private data.
 #[derive(Clone, Debug)]
-pub struct EMR_COMMENT {
+pub struct EMR_COMMENT<'a> {
     /// Type (4 bytes): An unsigned integer from the RecordType enumeration
     /// that identifies this record as a comment record. This value is
     /// 0x00000046.
@@ -23,24 +23,80 @@ pub struct EMR_COMMENT {
     /// that know the format of the data and how to use it. EMR_COMMENT private
     /// data records MAY(Windows NT 3.1, Windows NT 3.51, and Windows NT 4.0
     /// ignore EMR_COMMENT records.) be ignored.
-    pub private_data: Vec<u8>,
+    pub private_data: Cow<'a, [u8]>,
 }
 
-impl EMR_COMMENT {
+impl EMR_COMMENT<'static> {
+    pub fn parse<R: crate::Read>(
+        buf: &mut R,
+        record_type: crate::parser::RecordType,
+        size: crate::parser::Size,
+    ) -> Result<Self, crate::parser::ParseError> {
+        Self::parse_with(buf, record_type, size, |buf, size, len| {
+            crate::parser::records::read_bytes_field(buf, size, len)
+                .map(Cow::Owned)
+        })
+    }
+}
+
+impl<'a> EMR_COMMENT<'a> {
+    /// Parses the record from the front of `buf`, borrowing its
+    /// variable-length data instead of copying it.
+    pub fn parse_borrowed(
+        buf: &mut &'a [u8],
+        record_type: crate::parser::RecordType,
+        size: crate::parser::Size,
+    ) -> Result<Self, crate::parser::ParseError> {
+        Self::parse_with(buf, record_type, size, |buf, size, len| {
+            crate::parser::records::borrow_bytes_field(buf, size, len)
+                .map(Cow::Borrowed)
+        })
+    }
+
+    /// Copies the borrowed data so the record outlives its input.
+    pub fn into_owned(self) -> EMR_COMMENT<'static> {
+        EMR_COMMENT {
+            private_data: Cow::Owned(self.private_data.into_owned()),
+            ..self
+        }
+    }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_COMMENT);
+
+        write_len_field(&mut record, "data_size", self.private_data.len())?;
+        write_bytes_field(&mut record, &self.private_data)?;
+        record.finish(buf)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = tracing::Level::TRACE,
         skip_all,
         fields(record_type = ?record_type),
         err(level = tracing::Level::ERROR, Display),
     ))]
-    pub fn parse<R: crate::Read>(
+    fn parse_with<R: crate::Read>(
         buf: &mut R,
         record_type: crate::parser::RecordType,
         mut size: crate::parser::Size,
+        mut read_bytes: impl FnMut(
+            &mut R,
+            &mut crate::parser::Size,
+            usize,
+        ) -> Result<
+            Cow<'a, [u8]>,
+            crate::parser::ParseError,
+        >,
     ) -> Result<Self, crate::parser::ParseError> {
-        use crate::parser::records::{
-            consume_remaining_bytes, read_bytes_field, read_field,
-        };
+        use crate::parser::records::{consume_remaining_bytes, read_field};
 
         crate::parser::ParseError::expect_eq(
             "record_type",
@@ -49,8 +105,7 @@ impl EMR_COMMENT {
         )?;
 
         let data_size: u32 = read_field(buf, &mut size)?;
-        let private_data =
-            read_bytes_field(buf, &mut size, data_size as usize)?;
+        let private_data = read_bytes(buf, &mut size, data_size as usize)?;
 
         consume_remaining_bytes(buf, size.remaining_bytes())?;
 
@@ -106,4 +106,33 @@ impl EMR_EOF {
             size_last,
         })
     }
+
+    /// Writes the record with its palette entries following the fixed
+    /// fields, recomputing `n_pal_entries`, `off_pal_entries` and
+    /// `size_last`.
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_len_field};
+
+        let mut record = RecordBuffer::new(crate::parser::RecordType::EMR_EOF);
+
+        write_len_field(
+            &mut record,
+            "n_pal_entries",
+            self.palette_buffer.len(),
+        )?;
+        write_len_field(&mut record, "off_pal_entries", 16)?;
+
+        for entry in &self.palette_buffer {
+            entry.write_to(&mut record)?;
+        }
+
+        let size_last = record.offset() + 4;
+
+        write_len_field(&mut record, "size_last", size_last)?;
+
+        record.finish(buf)
+    }
 }
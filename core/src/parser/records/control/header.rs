@@ -65,6 +65,105 @@ impl EMR_HEADER {
 
         Ok(Self { record_type, size, emf_header, emf_header_record_buffer })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_bytes_field};
+
+        let (mut extension_1, extension_2, description, pixel_format) =
+            match &self.emf_header_record_buffer {
+                None => (None, None, None, None),
+                Some(EmfHeaderRecordBuffer::EmfMetafileHeader {
+                    emf_description,
+                }) => (None, None, emf_description.as_deref(), None),
+                Some(EmfHeaderRecordBuffer::EmfMetafileHeaderExtension1 {
+                    emf_header_extension_1,
+                    emf_description,
+                    emf_pixel_format,
+                }) => (
+                    Some(emf_header_extension_1.clone()),
+                    None,
+                    emf_description.as_deref(),
+                    emf_pixel_format.as_ref(),
+                ),
+                Some(EmfHeaderRecordBuffer::EmfMetafileHeaderExtension2 {
+                    emf_header_extension_1,
+                    emf_header_extension_2,
+                    emf_description,
+                    emf_pixel_format,
+                }) => (
+                    Some(emf_header_extension_1.clone()),
+                    Some(emf_header_extension_2),
+                    emf_description.as_deref(),
+                    emf_pixel_format.as_ref(),
+                ),
+            };
+        let description = description
+            .map(crate::parser::string_to_utf16le_bytes)
+            .unwrap_or_default();
+        let mut pixel_format_bytes = vec![];
+
+        if let Some(pixel_format) = pixel_format {
+            pixel_format.write_to(&mut pixel_format_bytes)?;
+        }
+
+        // The variable-length fields follow the 88-byte header and its
+        // 12-byte and 8-byte extensions.
+        let mut offset = 88
+            + extension_1.as_ref().map_or(0, |_| 12)
+            + extension_2.map_or(0, |_| 8);
+        let mut emf_header = self.emf_header.clone();
+
+        emf_header.n_description = crate::parser::WriteError::u32_field(
+            "n_description",
+            description.len() / 2,
+        )?;
+        emf_header.off_description = if description.is_empty() {
+            0
+        } else {
+            crate::parser::WriteError::u32_field("off_description", offset)?
+        };
+        offset += description.len().next_multiple_of(4);
+
+        if let Some(extension_1) = extension_1.as_mut() {
+            (extension_1.cb_pixel_format, extension_1.off_pixel_format) =
+                if pixel_format_bytes.is_empty() {
+                    (0, 0)
+                } else {
+                    (
+                        crate::parser::WriteError::u32_field(
+                            "cb_pixel_format",
+                            pixel_format_bytes.len(),
+                        )?,
+                        crate::parser::WriteError::u32_field(
+                            "off_pixel_format",
+                            offset,
+                        )?,
+                    )
+                };
+        }
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_HEADER);
+
+        emf_header.write_to(&mut record)?;
+
+        if let Some(extension_1) = extension_1 {
+            extension_1.write_to(&mut record)?;
+        }
+
+        if let Some(extension_2) = extension_2 {
+            extension_2.write_to(&mut record)?;
+        }
+
+        write_bytes_field(&mut record, &description)?;
+        record.align();
+
+        write_bytes_field(&mut record, &pixel_format_bytes)?;
+        record.finish(buf)
+    }
 }
 
 #[derive(Clone, Debug)]
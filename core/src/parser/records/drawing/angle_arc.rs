@@ -70,4 +70,21 @@ impl EMR_ANGLEARC {
 
         Ok(Self { record_type, size, center, radius, start_angle, sweep_angle })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_ANGLEARC);
+
+        write_field(&mut record, &self.center)?;
+        write_field(&mut record, self.radius)?;
+        write_field(&mut record, self.start_angle)?;
+        write_field(&mut record, self.sweep_angle)?;
+
+        record.finish(buf)
+    }
 }
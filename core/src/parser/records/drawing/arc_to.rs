@@ -48,4 +48,20 @@ impl EMR_ARCTO {
 
         Ok(Self { record_type, size, bx, start, end })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_ARCTO);
+
+        write_field(&mut record, &self.bx)?;
+        write_field(&mut record, &self.start)?;
+        write_field(&mut record, &self.end)?;
+
+        record.finish(buf)
+    }
 }
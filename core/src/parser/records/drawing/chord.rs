@@ -62,4 +62,20 @@ impl EMR_CHORD {
 
         Ok(Self { record_type, size, bx, start, end })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_CHORD);
+
+        write_field(&mut record, &self.bx)?;
+        write_field(&mut record, &self.start)?;
+        write_field(&mut record, &self.end)?;
+
+        record.finish(buf)
+    }
 }
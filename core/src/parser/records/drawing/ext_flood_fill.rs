@@ -50,4 +50,20 @@ impl EMR_EXTFLOODFILL {
 
         Ok(Self { record_type, size, start, color, flood_fill_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_EXTFLOODFILL);
+
+        write_field(&mut record, &self.start)?;
+        write_field(&mut record, &self.color)?;
+        write_field(&mut record, self.flood_fill_mode)?;
+
+        record.finish(buf)
+    }
 }
@@ -84,4 +84,24 @@ impl EMR_EXTTEXTOUTA {
             a_emr_text,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let record_type = crate::parser::RecordType::EMR_EXTTEXTOUTA;
+        let mut record = RecordBuffer::new(record_type);
+
+        write_field(&mut record, &self.bounds)?;
+        write_field(&mut record, self.i_graphics_mode)?;
+        write_field(&mut record, self.ex_scale)?;
+        write_field(&mut record, self.ey_scale)?;
+
+        let offset = record.offset();
+
+        self.a_emr_text.write_to(&mut record, &record_type, offset)?;
+        record.finish(buf)
+    }
 }
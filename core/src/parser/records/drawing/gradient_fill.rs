@@ -124,9 +124,9 @@ impl EMR_GRADIENTFILL {
             };
         let vertex_padding =
             if ul_mode == crate::parser::GradientFill::GRADIENT_FILL_TRIANGLE {
-                read_bytes_field(buf, &mut size, (n_tri * 4) as usize)?
-            } else {
                 vec![]
+            } else {
+                read_bytes_field(buf, &mut size, (n_tri * 4) as usize)?
             };
 
         let vertex_data =
@@ -144,6 +144,72 @@ impl EMR_GRADIENTFILL {
             vertex_data,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        let vertex_data = &self.vertex_data;
+        let (n_tri, triangles) = match &vertex_data.vertex_indexes {
+            VertexIndexes::GradientRectangle(v) => (v.len(), false),
+            VertexIndexes::GradientTriangle(v) => (v.len(), true),
+        };
+
+        // `parse` picks the index objects by `ul_mode`, so a mismatch would
+        // be read back as the other kind.
+        if triangles
+            != (self.ul_mode
+                == crate::parser::GradientFill::GRADIENT_FILL_TRIANGLE)
+        {
+            return Err(crate::parser::WriteError::UnrepresentableField {
+                field: "ul_mode",
+                cause: format!(
+                    "{:?} does not match the vertex indexes",
+                    self.ul_mode,
+                )
+                .into(),
+            });
+        }
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_GRADIENTFILL);
+
+        write_field(&mut record, &self.bounds)?;
+        write_len_field(
+            &mut record,
+            "n_ver",
+            vertex_data.vertex_objects.len(),
+        )?;
+        write_len_field(&mut record, "n_tri", n_tri)?;
+        write_field(&mut record, self.ul_mode)?;
+
+        for vertex in &vertex_data.vertex_objects {
+            vertex.write_to(&mut record)?;
+        }
+
+        match &vertex_data.vertex_indexes {
+            VertexIndexes::GradientRectangle(entries) => {
+                for entry in entries {
+                    entry.write_to(&mut record)?;
+                }
+
+                for _ in entries {
+                    write_field(&mut record, 0_u32)?;
+                }
+            }
+            VertexIndexes::GradientTriangle(entries) => {
+                for entry in entries {
+                    entry.write_to(&mut record)?;
+                }
+            }
+        }
+
+        record.finish(buf)
+    }
 }
 
 #[derive(Clone, Debug)]
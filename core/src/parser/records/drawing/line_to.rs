@@ -40,4 +40,18 @@ impl EMR_LINETO {
 
         Ok(Self { record_type, size, point })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_LINETO);
+
+        write_field(&mut record, &self.point)?;
+
+        record.finish(buf)
+    }
 }
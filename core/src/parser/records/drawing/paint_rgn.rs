@@ -41,7 +41,7 @@ impl EMR_PAINTRGN {
         mut size: crate::parser::Size,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            check_total_points, consume_remaining_bytes, read_field, read_with,
+            consume_remaining_bytes, read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -53,22 +53,27 @@ impl EMR_PAINTRGN {
         let bounds = read_with(buf, &mut size, wmf_core::parser::RectL::parse)?;
         let rgn_data_size: u32 = read_field(buf, &mut size)?;
 
-        // Cap `rgn_data_size` so a crafted u32::MAX cannot drive the
-        // loop to exhaustion. Pre-allocating is intentionally skipped
-        // because a single `RegionData` owns a nested `Vec<RectL>`.
-        check_total_points(rgn_data_size)?;
+        // `rgn_data_size` is a byte count; bounding it by the record keeps
+        // a crafted value from reading past it.
+        crate::parser::ParseError::expect_le(
+            "rgn_data_size",
+            rgn_data_size as usize,
+            size.remaining_bytes(),
+        )?;
 
         let rgn_data = {
             let mut entries = vec![];
+            let mut rgn_data_bytes: usize = 0;
 
-            for _ in 0..rgn_data_size {
+            while rgn_data_bytes < rgn_data_size as usize {
                 entries.push(read_with(
                     buf,
-                    &mut size,
+                    &mut rgn_data_bytes,
                     crate::parser::RegionData::parse,
                 )?);
             }
 
+            size.consume(rgn_data_bytes);
             entries
         };
 
@@ -76,4 +81,21 @@ impl EMR_PAINTRGN {
 
         Ok(Self { record_type, size, bounds, rgn_data_size, rgn_data })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_PAINTRGN);
+
+        write_field(&mut record, &self.bounds)?;
+
+        let rgn_data_size = record.reserve();
+
+        record.write_regions(rgn_data_size, &self.rgn_data)?;
+        record.finish(buf)
+    }
 }
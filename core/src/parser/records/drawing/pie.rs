@@ -56,4 +56,19 @@ impl EMR_PIE {
 
         Ok(Self { record_type, size, bx, start, end })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(crate::parser::RecordType::EMR_PIE);
+
+        write_field(&mut record, &self.bx)?;
+        write_field(&mut record, &self.start)?;
+        write_field(&mut record, &self.end)?;
+
+        record.finish(buf)
+    }
 }
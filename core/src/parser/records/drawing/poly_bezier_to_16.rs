@@ -71,4 +71,25 @@ impl EMR_POLYBEZIERTO16 {
 
         Ok(Self { record_type, size, bounds, count, a_points })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_POLYBEZIERTO16);
+
+        write_field(&mut record, &self.bounds)?;
+        write_len_field(&mut record, "count", self.a_points.len())?;
+
+        for point in &self.a_points {
+            write_field(&mut record, point)?;
+        }
+
+        record.finish(buf)
+    }
 }
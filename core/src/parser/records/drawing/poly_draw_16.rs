@@ -20,8 +20,9 @@ pub struct EMR_POLYDRAW16 {
     /// in [MS-WMF] section 2.2.2.16, which specifies the array of points.
     pub a_points: Vec<wmf_core::parser::PointS>,
     /// abTypes (variable): A Count length array of bytes that specifies the
-    /// point types. This value is in the Point enumeration.
-    pub ab_types: Vec<crate::parser::Point>,
+    /// point types. This value is in the Point enumeration, and PT_LINETO and
+    /// PT_BEZIERTO can be combined with PT_CLOSEFIGURE.
+    pub ab_types: Vec<crate::parser::PointFlags>,
 }
 
 impl EMR_POLYDRAW16 {
@@ -69,11 +70,9 @@ impl EMR_POLYDRAW16 {
             let mut entries = Vec::with_capacity(count as usize);
 
             for _ in 0..count {
-                entries.push(read_with(
-                    buf,
-                    &mut size,
-                    crate::parser::Point::parse,
-                )?);
+                entries.push(crate::parser::PointFlags::from_raw(read_field(
+                    buf, &mut size,
+                )?));
             }
 
             entries
@@ -83,4 +82,41 @@ impl EMR_POLYDRAW16 {
 
         Ok(Self { record_type, size, bounds, count, a_points, ab_types })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        if self.ab_types.len() != self.a_points.len() {
+            return Err(crate::parser::WriteError::UnrepresentableField {
+                field: "ab_types",
+                cause: format!(
+                    "{} point types given for {} points",
+                    self.ab_types.len(),
+                    self.a_points.len(),
+                )
+                .into(),
+            });
+        }
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_POLYDRAW16);
+
+        write_field(&mut record, &self.bounds)?;
+        write_len_field(&mut record, "count", self.a_points.len())?;
+
+        for point in &self.a_points {
+            write_field(&mut record, point)?;
+        }
+
+        for ab_type in &self.ab_types {
+            write_field(&mut record, *ab_type)?;
+        }
+
+        record.finish(buf)
+    }
 }
@@ -111,4 +111,48 @@ impl EMR_POLYPOLYGON {
             a_points,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        let total =
+            self.polygon_point_count.iter().map(|v| *v as usize).sum::<usize>();
+
+        if total != self.a_points.len() {
+            return Err(crate::parser::WriteError::UnrepresentableField {
+                field: "polygon_point_count",
+                cause: format!(
+                    "point counts sum to {total} for {} points",
+                    self.a_points.len(),
+                )
+                .into(),
+            });
+        }
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_POLYPOLYGON);
+
+        write_field(&mut record, &self.bounds)?;
+        write_len_field(
+            &mut record,
+            "number_of_polygons",
+            self.polygon_point_count.len(),
+        )?;
+        write_len_field(&mut record, "count", self.a_points.len())?;
+
+        for count in &self.polygon_point_count {
+            write_field(&mut record, *count)?;
+        }
+
+        for point in &self.a_points {
+            write_field(&mut record, point)?;
+        }
+
+        record.finish(buf)
+    }
 }
@@ -98,4 +98,32 @@ impl EMR_POLYTEXTOUTA {
             a_emr_text,
         })
     }
+
+    /// Writes the record with the string and spacing of each text object
+    /// following it, the layout `parse` reads.
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        let record_type = crate::parser::RecordType::EMR_POLYTEXTOUTA;
+        let mut record = RecordBuffer::new(record_type);
+
+        write_field(&mut record, &self.bounds)?;
+        write_field(&mut record, self.i_graphics_mode)?;
+        write_field(&mut record, self.ex_scale)?;
+        write_field(&mut record, self.ey_scale)?;
+        write_len_field(&mut record, "c_strings", self.a_emr_text.len())?;
+
+        for text in &self.a_emr_text {
+            let offset = record.offset();
+
+            text.write_to(&mut record, &record_type, offset)?;
+        }
+
+        record.finish(buf)
+    }
 }
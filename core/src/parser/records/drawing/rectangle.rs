@@ -44,4 +44,18 @@ impl EMR_RECTANGLE {
 
         Ok(Self { record_type, size, bx })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_RECTANGLE);
+
+        write_field(&mut record, &self.bx)?;
+
+        record.finish(buf)
+    }
 }
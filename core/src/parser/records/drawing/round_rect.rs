@@ -46,4 +46,19 @@ impl EMR_ROUNDRECT {
 
         Ok(Self { record_type, size, bx, corner })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_ROUNDRECT);
+
+        write_field(&mut record, &self.bx)?;
+        write_field(&mut record, &self.corner)?;
+
+        record.finish(buf)
+    }
 }
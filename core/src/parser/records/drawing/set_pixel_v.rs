@@ -44,4 +44,19 @@ impl EMR_SETPIXELV {
 
         Ok(Self { record_type, size, pixel, color })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETPIXELV);
+
+        write_field(&mut record, &self.pixel)?;
+        write_field(&mut record, &self.color)?;
+
+        record.finish(buf)
+    }
 }
@@ -43,8 +43,9 @@ pub struct EMR_SMALLTEXTOUT {
     pub bounds: Option<wmf_core::parser::RectL>,
     /// TextString (variable): A string that contains the text string to draw,
     /// in either 8-bit or 16-bit character codes, according to the value of
-    /// the fuOptions field.
-    pub text_string: String,
+    /// the fuOptions field. 8-bit codes are widened to Unicode, and with
+    /// ETO_GLYPH_INDEX the codes are kept as glyph indices.
+    pub text_string: crate::parser::OutputString,
 }
 
 impl EMR_SMALLTEXTOUT {
@@ -76,8 +77,7 @@ impl EMR_SMALLTEXTOUT {
 
         // `c_chars` is unbounded in the spec; cap it at 16 Mi before
         // it drives `read_bytes_field`'s `Vec::with_capacity(c_chars *
-        // 2)` and the downstream `Vec::with_capacity(bytes.len() /
-        // 2)` in the ETO_SMALL_CHARS branch.
+        // 2)`.
         check_total_points(c_chars)?;
         let fu_options = crate::parser::ExtTextOutOptionsFlags::from_raw(
             read_field(buf, &mut size)?,
@@ -96,28 +96,46 @@ impl EMR_SMALLTEXTOUT {
             Some(read_with(buf, &mut size, wmf_core::parser::RectL::parse)?)
         };
 
-        let text_string = {
+        let glyph_index = fu_options
+            .contains(crate::parser::ExtTextOutOptions::ETO_GLYPH_INDEX);
+        let text_string = if fu_options
+            .contains(crate::parser::ExtTextOutOptions::ETO_SMALL_CHARS)
+        {
+            // With ETO_SMALL_CHARS the string holds one byte per character
+            // ([MS-EMF] section 2.3.5.37): the low bytes of UTF16-LE
+            // character codes whose high bytes are 0, so each is a Latin-1
+            // character.
+            let bytes = read_bytes_field(buf, &mut size, c_chars as usize)?;
+
+            if glyph_index {
+                crate::parser::OutputString::GlyphIndices(
+                    bytes.into_iter().map(u16::from).collect(),
+                )
+            } else {
+                crate::parser::OutputString::Unicode(
+                    bytes.into_iter().map(char::from).collect(),
+                )
+            }
+        } else {
             // Multiply in usize so a crafted `c_chars` close to
             // u32::MAX cannot overflow before being passed to
             // `read_bytes_field`.
             let bytes =
                 read_bytes_field(buf, &mut size, (c_chars as usize) * 2)?;
 
-            if fu_options
-                .contains(crate::parser::ExtTextOutOptions::ETO_SMALL_CHARS)
-            {
-                let mut entries = Vec::with_capacity(bytes.len() / 2);
-
-                for mut v in bytes.chunks(2) {
-                    let (value, _) =
-                        <u16 as crate::parser::ReadLeField>::read_le(&mut v)?;
-
-                    entries.push(value & 0x00FF);
-                }
-
-                String::from_utf16_lossy(&entries)
+            // Glyph indices are not UTF-16 code units, so the ones in the
+            // surrogate range are kept as they are.
+            if glyph_index {
+                crate::parser::OutputString::GlyphIndices(
+                    bytes
+                        .chunks_exact(2)
+                        .map(|v| u16::from_le_bytes([v[0], v[1]]))
+                        .collect(),
+                )
             } else {
-                crate::parser::utf16le_bytes_to_string(&bytes)?
+                crate::parser::OutputString::Unicode(
+                    crate::parser::utf16le_bytes_to_string(&bytes)?,
+                )
             }
         };
 
@@ -137,4 +155,190 @@ impl EMR_SMALLTEXTOUT {
             text_string,
         })
     }
+
+    /// Writes the record, recomputing `c_chars` and setting ETO_NO_RECT in
+    /// `fu_options` when it has no `bounds`.
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field, write_len_field,
+        };
+
+        let no_rect = crate::parser::ExtTextOutOptions::ETO_NO_RECT as u32;
+        let fu_options = crate::parser::ExtTextOutOptionsFlags::from_raw(
+            if self.bounds.is_some() {
+                self.fu_options.raw() & !no_rect
+            } else {
+                self.fu_options.raw() | no_rect
+            },
+        );
+        let small_chars = fu_options
+            .contains(crate::parser::ExtTextOutOptions::ETO_SMALL_CHARS);
+        let glyph_index = fu_options
+            .contains(crate::parser::ExtTextOutOptions::ETO_GLYPH_INDEX);
+        let unrepresentable =
+            |cause: String| crate::parser::WriteError::UnrepresentableField {
+                field: "text_string",
+                cause: cause.into(),
+            };
+        let (c_chars, text_string) = match &self.text_string {
+            crate::parser::OutputString::Unicode(v)
+                if small_chars && !glyph_index =>
+            {
+                let bytes = v
+                    .chars()
+                    .map(u8::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| {
+                        unrepresentable(format!("ETO_SMALL_CHARS: {err}"))
+                    })?;
+
+                (bytes.len(), bytes)
+            }
+            crate::parser::OutputString::Unicode(v) if !glyph_index => {
+                let bytes = crate::parser::string_to_utf16le_bytes(v);
+
+                (bytes.len() / 2, bytes)
+            }
+            crate::parser::OutputString::GlyphIndices(v)
+                if small_chars && glyph_index =>
+            {
+                let bytes = v
+                    .iter()
+                    .map(|&g| u8::try_from(g))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| {
+                        unrepresentable(format!("ETO_SMALL_CHARS: {err}"))
+                    })?;
+
+                (bytes.len(), bytes)
+            }
+            crate::parser::OutputString::GlyphIndices(v) if glyph_index => {
+                (v.len(), v.iter().flat_map(|g| g.to_le_bytes()).collect())
+            }
+            string_buffer => {
+                return Err(unrepresentable(format!(
+                    "{string_buffer:?} cannot be written with options {:?}",
+                    self.fu_options,
+                )));
+            }
+        };
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SMALLTEXTOUT);
+
+        write_field(&mut record, self.x)?;
+        write_field(&mut record, self.y)?;
+        write_len_field(&mut record, "c_chars", c_chars)?;
+        write_field(&mut record, fu_options)?;
+        write_field(&mut record, self.i_graphics_mode)?;
+        write_field(&mut record, self.ex_scale)?;
+        write_field(&mut record, self.ey_scale)?;
+
+        if let Some(bounds) = &self.bounds {
+            write_field(&mut record, bounds)?;
+        }
+
+        write_bytes_field(&mut record, &text_string)?;
+
+        record.finish(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{RecordType, Size};
+
+    #[test]
+    fn small_chars_are_read_one_byte_per_character() {
+        let record = [
+            // Type and Size
+            0x6C_u32.to_le_bytes().as_slice(),
+            &44_u32.to_le_bytes(),
+            // x, y and cChars
+            &10_i32.to_le_bytes(),
+            &20_i32.to_le_bytes(),
+            &5_u32.to_le_bytes(),
+            // fuOptions: ETO_SMALL_CHARS | ETO_NO_RECT
+            &0x0300_u32.to_le_bytes(),
+            // iGraphicsMode: GM_COMPATIBLE, exScale and eyScale
+            &1_u32.to_le_bytes(),
+            &0_f32.to_le_bytes(),
+            &0_f32.to_le_bytes(),
+            // TextString, padded to a multiple of 4 bytes
+            &[b'H', 0xE9, b'l', b'l', b'o', 0, 0, 0],
+        ]
+        .concat();
+        let mut size = Size::from(44);
+        size.consume(8);
+
+        let mut buf = &record[8..];
+        let record = EMR_SMALLTEXTOUT::parse(
+            &mut buf,
+            RecordType::EMR_SMALLTEXTOUT,
+            size,
+        )
+        .expect("record should parse");
+
+        assert!(buf.is_empty(), "{} bytes left", buf.len());
+        assert_eq!((record.x, record.y, record.c_chars), (10, 20, 5));
+        assert!(record.bounds.is_none());
+        assert!(
+            matches!(
+                record.text_string,
+                crate::parser::OutputString::Unicode(ref v) if v == "H\u{e9}llo"
+            ),
+            "{:?}",
+            record.text_string
+        );
+    }
+
+    #[test]
+    fn glyph_indices_are_read_as_they_are() {
+        let bytes = [
+            // Type and Size
+            0x6C_u32.to_le_bytes().as_slice(),
+            &40_u32.to_le_bytes(),
+            // x, y and cChars
+            &10_i32.to_le_bytes(),
+            &20_i32.to_le_bytes(),
+            &2_u32.to_le_bytes(),
+            // fuOptions: ETO_GLYPH_INDEX | ETO_NO_RECT
+            &0x0110_u32.to_le_bytes(),
+            // iGraphicsMode: GM_COMPATIBLE, exScale and eyScale
+            &1_u32.to_le_bytes(),
+            &0_f32.to_le_bytes(),
+            &0_f32.to_le_bytes(),
+            // TextString: glyph indices in the UTF-16 surrogate range
+            &0xD800_u16.to_le_bytes(),
+            &0xDFFF_u16.to_le_bytes(),
+        ]
+        .concat();
+        let mut size = Size::from(40);
+        size.consume(8);
+
+        let record = EMR_SMALLTEXTOUT::parse(
+            &mut &bytes[8..],
+            RecordType::EMR_SMALLTEXTOUT,
+            size,
+        )
+        .expect("record should parse");
+
+        assert!(
+            matches!(
+                record.text_string,
+                crate::parser::OutputString::GlyphIndices(ref v)
+                    if v == &[0xD800, 0xDFFF]
+            ),
+            "{:?}",
+            record.text_string
+        );
+
+        let mut written = vec![];
+        record.write_to(&mut written).expect("record should be written");
+
+        assert_eq!(written, bytes);
+    }
 }
@@ -39,4 +39,18 @@ impl EMR_STROKEPATH {
 
         Ok(Self { record_type, size, bounds })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_STROKEPATH);
+
+        write_field(&mut record, &self.bounds)?;
+
+        record.finish(buf)
+    }
 }
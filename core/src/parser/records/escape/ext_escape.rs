@@ -35,7 +35,8 @@ impl EMR_EXTESCAPE {
         mut size: crate::parser::Size,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            consume_remaining_bytes, read_bytes_field, read_field, read_with,
+            consume_remaining_bytes, read_array_field, read_bytes_field,
+            read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -49,6 +50,8 @@ impl EMR_EXTESCAPE {
             &mut size,
             wmf_core::parser::MetafileEscapes::parse,
         )?;
+        // iEscape is a 32-bit field holding a 16-bit escape function.
+        let _: [u8; 2] = read_array_field(buf, &mut size)?;
         let cj_in: u32 = read_field(buf, &mut size)?;
 
         let data = read_bytes_field(buf, &mut size, cj_in as usize)?;
@@ -57,4 +60,22 @@ impl EMR_EXTESCAPE {
 
         Ok(Self { record_type, size, i_escape, cj_in, data })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_EXTESCAPE);
+
+        write_field(&mut record, u32::from(self.i_escape as u16))?;
+        write_len_field(&mut record, "cj_in", self.data.len())?;
+        write_bytes_field(&mut record, &self.data)?;
+
+        record.finish(buf)
+    }
 }
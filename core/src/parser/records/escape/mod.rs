@@ -24,13 +24,13 @@ mod tests {
 
     #[test]
     fn draw_escape_empty_payload_parses() {
-        // i_escape = NEWFRAME (0x0001 as u16); cj_in = 0; no Data.
-        // Total record bytes = 8 (header) + 2 + 4 = 14.
+        // i_escape = NEWFRAME (0x0001 in 32 bits); cj_in = 0; no Data.
+        // Total record bytes = 8 (header) + 4 + 4 = 16.
         let mut buf: &[u8] = &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
         let result = EMR_DRAWESCAPE::parse(
             &mut buf,
             RecordType::EMR_DRAWESCAPE,
-            record_size(14 + 2),
+            record_size(16),
         );
         assert!(result.is_ok(), "{:?}", result.err());
         let r = result.unwrap();
@@ -44,7 +44,7 @@ mod tests {
         let result = EMR_EXTESCAPE::parse(
             &mut buf,
             RecordType::EMR_EXTESCAPE,
-            record_size(14 + 2),
+            record_size(16),
         );
         assert!(result.is_ok(), "{:?}", result.err());
         let r = result.unwrap();
@@ -55,18 +55,18 @@ mod tests {
     #[test]
     fn named_escape_minimal_driver_name_parses() {
         // i_escape = NEWFRAME; cj_driver = 2 (single null terminator);
-        // cj_in = 0; driver_name = "" (just the null terminator);
+        // cj_in = 0; driver_name = "" (just the UTF-16 null terminator);
         // no Data.
         let mut buf: &[u8] = &[
-            0x01, 0x00, // i_escape
+            0x01, 0x00, 0x00, 0x00, // i_escape
             0x02, 0x00, 0x00, 0x00, // cj_driver = 2
             0x00, 0x00, 0x00, 0x00, // cj_in = 0
-            0x00, // driver_name terminator (null)
+            0x00, 0x00, // driver_name terminator (null)
         ];
         let result = EMR_NAMEDESCAPE::parse(
             &mut buf,
             RecordType::EMR_NAMEDESCAPE,
-            record_size(8 + 2 + 4 + 4 + 1),
+            record_size(8 + 4 + 4 + 4 + 2),
         );
         assert!(result.is_ok(), "{:?}", result.err());
         let r = result.unwrap();
@@ -41,7 +41,8 @@ impl EMR_NAMEDESCAPE {
         mut size: crate::parser::Size,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            consume_remaining_bytes, read_bytes_field, read_field, read_with,
+            consume_remaining_bytes, read_array_field, read_bytes_field,
+            read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -55,23 +56,15 @@ impl EMR_NAMEDESCAPE {
             &mut size,
             wmf_core::parser::MetafileEscapes::parse,
         )?;
-        let cj_driver = read_field(buf, &mut size)?;
+        // iEscape is a 32-bit field holding a 16-bit escape function.
+        let _: [u8; 2] = read_array_field(buf, &mut size)?;
+        let cj_driver: u32 = read_field(buf, &mut size)?;
         let cj_in: u32 = read_field(buf, &mut size)?;
 
         let driver_name = {
-            let mut bytes = vec![];
+            let bytes = read_bytes_field(buf, &mut size, cj_driver as usize)?;
 
-            loop {
-                let v: u8 = read_field(buf, &mut size)?;
-
-                if v == 0 {
-                    break;
-                }
-
-                bytes.push(v);
-            }
-
-            crate::parser::utf16le_bytes_to_string(&bytes)?
+            crate::parser::null_terminated_utf16le_string(&bytes)?
         };
 
         let data = read_bytes_field(buf, &mut size, cj_in as usize)?;
@@ -88,4 +81,28 @@ impl EMR_NAMEDESCAPE {
             data,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_NAMEDESCAPE);
+        let mut driver_name =
+            crate::parser::string_to_utf16le_bytes(&self.driver_name);
+
+        driver_name.extend_from_slice(&[0, 0]);
+
+        write_field(&mut record, u32::from(self.i_escape as u16))?;
+        write_len_field(&mut record, "cj_driver", driver_name.len())?;
+        write_len_field(&mut record, "cj_in", self.data.len())?;
+        write_bytes_field(&mut record, &driver_name)?;
+        write_bytes_field(&mut record, &self.data)?;
+
+        record.finish(buf)
+    }
 }
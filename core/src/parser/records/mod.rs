@@ -145,6 +145,31 @@ where
     Ok(v)
 }
 
+/// Borrows a variable-length byte buffer of `len` bytes from the front of
+/// `buf` and advances the `tracker` accordingly.
+///
+/// The zero-copy counterpart of `read_bytes_field` for records parsed from
+/// a slice, so large bitmaps and comments are not duplicated.
+#[inline]
+pub(in crate::parser) fn borrow_bytes_field<'a>(
+    buf: &mut &'a [u8],
+    tracker: &mut impl crate::parser::ConsumeTracker,
+    len: usize,
+) -> Result<&'a [u8], crate::parser::ParseError> {
+    let Some((v, rest)) = buf.split_at_checked(len) else {
+        return Err(crate::parser::ReadError::new(format!(
+            "expected {len} bytes read, but {} bytes read (unexpected end of \
+             stream)",
+            buf.len(),
+        ))
+        .into());
+    };
+
+    *buf = rest;
+    tracker.track(len);
+    Ok(v)
+}
+
 /// Drains exactly `len` bytes from `buf` without materializing them as
 /// a `Vec<u8>`, advancing the `tracker` once the read succeeds.
 ///
@@ -173,7 +198,7 @@ where
     Ok(())
 }
 
-fn consume_remaining_bytes<R: crate::Read>(
+pub(crate) fn consume_remaining_bytes<R: crate::Read>(
     buf: &mut R,
     remaining_bytes: usize,
 ) -> Result<(), crate::parser::ParseError> {
@@ -205,6 +230,199 @@ fn discard_bytes<R: crate::Read>(
     Ok(())
 }
 
+/// Write a little-endian field or small object and return the number of
+/// bytes written. The counterpart of `read_field` and `read_with`.
+#[inline]
+pub(crate) fn write_field<W, T>(
+    buf: &mut W,
+    value: T,
+) -> Result<usize, crate::parser::WriteError>
+where
+    W: crate::Write,
+    T: crate::parser::WriteLeField,
+{
+    value.write_le(buf)
+}
+
+/// Write the `u32` count or size field describing a variable-length field
+/// of `len` entries or bytes, so it cannot disagree with the data written.
+#[inline]
+pub(crate) fn write_len_field<W: crate::Write>(
+    buf: &mut W,
+    field: &'static str,
+    len: usize,
+) -> Result<usize, crate::parser::WriteError> {
+    write_field(buf, crate::parser::WriteError::u32_field(field, len)?)
+}
+
+/// Write a variable-length byte buffer. The counterpart of
+/// `read_bytes_field`.
+#[inline]
+pub(crate) fn write_bytes_field<W: crate::Write>(
+    buf: &mut W,
+    bytes: &[u8],
+) -> Result<usize, crate::parser::WriteError> {
+    crate::parser::write_variable(buf, bytes)
+}
+
+/// Keeps a copy of the bytes read through it, so that objects parsed by
+/// `wmf_core` can be written back as they were read.
+pub(crate) struct RecordingReader<'a, R> {
+    inner: &'a mut R,
+    bytes: crate::imports::Vec<u8>,
+}
+
+impl<'a, R: crate::Read> RecordingReader<'a, R> {
+    pub(crate) fn new(inner: &'a mut R) -> Self {
+        Self { inner, bytes: crate::imports::Vec::new() }
+    }
+
+    pub(crate) fn into_bytes(self) -> crate::imports::Vec<u8> {
+        self.bytes
+    }
+}
+
+impl<R: crate::Read> embedded_io::ErrorType for RecordingReader<'_, R> {
+    type Error = R::Error;
+}
+
+impl<R: crate::Read> embedded_io::Read for RecordingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let read = self.inner.read(buf)?;
+
+        self.bytes.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Collects the bytes of a record so its `Size`, and the offsets and sizes
+/// of its variable-length fields, can be filled in once they are known.
+///
+/// Offsets are relative to the start of the record, as the specification
+/// defines them. The stored `size`, offset and count fields of a parsed
+/// record are ignored when writing it; they are recomputed from its data.
+pub(crate) struct RecordBuffer {
+    bytes: crate::imports::Vec<u8>,
+}
+
+impl RecordBuffer {
+    /// Starts a record with its `Type` field and a placeholder `Size`.
+    pub(crate) fn new(record_type: crate::parser::RecordType) -> Self {
+        let mut bytes = crate::imports::Vec::with_capacity(64);
+
+        bytes.extend_from_slice(&(record_type as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+
+        Self { bytes }
+    }
+
+    /// Returns the offset from the start of the record of the next byte.
+    pub(crate) fn offset(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Writes a `u32` placeholder to `fill` later and returns its position.
+    pub(crate) fn reserve(&mut self) -> usize {
+        let at = self.offset();
+
+        self.bytes.extend_from_slice(&[0; 4]);
+        at
+    }
+
+    /// Writes the offset and size placeholders of a buffer that
+    /// `write_buffer` appends later and returns their position.
+    pub(crate) fn reserve_buffer(&mut self) -> usize {
+        let at = self.reserve();
+        self.reserve();
+        at
+    }
+
+    /// Fills the placeholder at `at` with `value`.
+    pub(crate) fn fill(
+        &mut self,
+        at: usize,
+        field: &'static str,
+        value: usize,
+    ) -> Result<(), crate::parser::WriteError> {
+        let value = crate::parser::WriteError::u32_field(field, value)?;
+
+        self.bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    /// Pads the record with zeros to a multiple of 4 bytes.
+    pub(crate) fn align(&mut self) {
+        self.bytes.resize(self.bytes.len().next_multiple_of(4), 0);
+    }
+
+    /// Writes `bytes` at the next 4-byte boundary and fills the offset and
+    /// size placeholders reserved for them, in that order, at `at`.
+    ///
+    /// An empty buffer is given a zero size and the offset it would have
+    /// started at, which every parser reads back as empty.
+    pub(crate) fn write_buffer(
+        &mut self,
+        at: usize,
+        field: &'static str,
+        bytes: &[u8],
+    ) -> Result<(), crate::parser::WriteError> {
+        self.align();
+        self.fill(at, field, self.offset())?;
+        self.fill(at + 4, field, bytes.len())?;
+        self.bytes.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Writes `regions` and fills the placeholder reserved for their size
+    /// in bytes at `at`.
+    pub(crate) fn write_regions(
+        &mut self,
+        at: usize,
+        regions: &[crate::parser::RegionData],
+    ) -> Result<(), crate::parser::WriteError> {
+        let start = self.offset();
+
+        for region in regions {
+            region.write_to(self)?;
+        }
+
+        self.fill(at, "rgn_data_size", self.offset() - start)
+    }
+
+    /// Pads the record to a multiple of 4 bytes, fills in its `Size` and
+    /// writes it to `buf`.
+    pub(crate) fn finish<W: crate::Write>(
+        mut self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        self.align();
+
+        let size = self.offset();
+
+        if size > crate::parser::MAX_RECORD_BYTES as usize {
+            return Err(crate::parser::WriteError::RecordTooLarge { size });
+        }
+
+        self.fill(4, "size", size)?;
+        crate::parser::write_variable(buf, &self.bytes)
+    }
+}
+
+impl embedded_io::ErrorType for RecordBuffer {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_io::Write for RecordBuffer {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::string::ToString;
@@ -53,4 +53,19 @@ impl EMR_CREATEBRUSHINDIRECT {
 
         Ok(Self { record_type, size, ih_brush, log_brush })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_CREATEBRUSHINDIRECT,
+        );
+
+        write_field(&mut record, self.ih_brush)?;
+        self.log_brush.write_to(&mut record)?;
+        record.finish(buf)
+    }
 }
@@ -1,3 +1,5 @@
+use crate::imports::*;
+
 /// The EMR_CREATECOLORSPACE record creates a logical color space object from a
 /// color profile with a name consisting of ASCII characters.
 ///
@@ -22,6 +24,9 @@ pub struct EMR_CREATECOLORSPACE {
     /// lcs (variable): A LogColorSpace object ([MS-WMF] section 2.2.2.11),
     /// which can specify the name of a color profile in ASCII characters.
     pub lcs: wmf_core::parser::LogColorSpace,
+    /// The bytes `lcs` was parsed from, which are written back as they are
+    /// since `wmf_core` cannot serialize it.
+    pub lcs_bytes: Vec<u8>,
 }
 
 impl EMR_CREATECOLORSPACE {
@@ -37,7 +42,7 @@ impl EMR_CREATECOLORSPACE {
         mut size: crate::parser::Size,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            consume_remaining_bytes, read_field, read_with,
+            RecordingReader, consume_remaining_bytes, read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -47,11 +52,35 @@ impl EMR_CREATECOLORSPACE {
         )?;
 
         let ih_cs: u32 = read_field(buf, &mut size)?;
-        let lcs =
-            read_with(buf, &mut size, wmf_core::parser::LogColorSpace::parse)?;
+        let (lcs, lcs_bytes) = {
+            let mut reader = RecordingReader::new(buf);
+            let lcs = read_with(
+                &mut reader,
+                &mut size,
+                wmf_core::parser::LogColorSpace::parse,
+            )?;
+
+            (lcs, reader.into_bytes())
+        };
 
         consume_remaining_bytes(buf, size.remaining_bytes())?;
 
-        Ok(Self { record_type, size, ih_cs, lcs })
+        Ok(Self { record_type, size, ih_cs, lcs, lcs_bytes })
+    }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_CREATECOLORSPACE);
+
+        write_field(&mut record, self.ih_cs)?;
+        write_bytes_field(&mut record, &self.lcs_bytes)?;
+        record.finish(buf)
     }
 }
@@ -25,6 +25,9 @@ pub struct EMR_CREATECOLORSPACEW {
     /// that can specify the name of a color profile in Unicode UTF16-LE
     /// characters.
     pub lcs: wmf_core::parser::LogColorSpaceW,
+    /// The bytes `lcs` was parsed from, which are written back as they are
+    /// since `wmf_core` cannot serialize it.
+    pub lcs_bytes: Vec<u8>,
     /// dwFlags (4 bytes): An unsigned integer that provides information about
     /// the data in this record.
     ///
@@ -52,7 +55,8 @@ impl EMR_CREATECOLORSPACEW {
         mut size: crate::parser::Size,
     ) -> Result<Self, crate::parser::ParseError> {
         use crate::parser::records::{
-            consume_remaining_bytes, read_bytes_field, read_field, read_with,
+            RecordingReader, consume_remaining_bytes, read_bytes_field,
+            read_field, read_with,
         };
 
         crate::parser::ParseError::expect_eq(
@@ -62,14 +66,50 @@ impl EMR_CREATECOLORSPACEW {
         )?;
 
         let ih_cs: u32 = read_field(buf, &mut size)?;
-        let lcs =
-            read_with(buf, &mut size, wmf_core::parser::LogColorSpaceW::parse)?;
+        let (lcs, lcs_bytes) = {
+            let mut reader = RecordingReader::new(buf);
+            let lcs = read_with(
+                &mut reader,
+                &mut size,
+                wmf_core::parser::LogColorSpaceW::parse,
+            )?;
+
+            (lcs, reader.into_bytes())
+        };
         let dw_flags: u32 = read_field(buf, &mut size)?;
         let cb_data: u32 = read_field(buf, &mut size)?;
         let data = read_bytes_field(buf, &mut size, cb_data as usize)?;
 
         consume_remaining_bytes(buf, size.remaining_bytes())?;
 
-        Ok(Self { record_type, size, ih_cs, lcs, dw_flags, cb_data, data })
+        Ok(Self {
+            record_type,
+            size,
+            ih_cs,
+            lcs,
+            lcs_bytes,
+            dw_flags,
+            cb_data,
+            data,
+        })
+    }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_CREATECOLORSPACEW);
+
+        write_field(&mut record, self.ih_cs)?;
+        write_bytes_field(&mut record, &self.lcs_bytes)?;
+        write_field(&mut record, self.dw_flags)?;
+        write_len_field(&mut record, "cb_data", self.data.len())?;
+        write_bytes_field(&mut record, &self.data)?;
+        record.finish(buf)
     }
 }
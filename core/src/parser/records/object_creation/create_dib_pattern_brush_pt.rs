@@ -40,7 +40,7 @@ pub struct EMR_CREATEDIBPATTERNBRUSHPT {
     ///
     /// BmiSrc (variable): The DIB header, which is the DibHeaderInfo field of
     /// a DeviceIndependentBitmap object.
-    pub bmi_src: wmf_core::parser::BitmapInfoHeader,
+    pub bmi_src: Vec<u8>,
     /// BitsSrc (variable): The DIB bits, which is the aData field of a
     /// DeviceIndependentBitmap object.
     pub bits_src: Vec<u8>,
@@ -71,17 +71,23 @@ impl EMR_CREATEDIBPATTERNBRUSHPT {
         let ih_brush = read_field(buf, &mut size)?;
         let usage = read_with(buf, &mut size, crate::parser::DIBColors::parse)?;
         let off_bmi = read_field(buf, &mut size)?;
-        let cb_bmi = read_field(buf, &mut size)?;
+        let cb_bmi: u32 = read_field(buf, &mut size)?;
         let off_bits = read_field(buf, &mut size)?;
         let cb_bits: u32 = read_field(buf, &mut size)?;
 
+        crate::parser::ParseError::expect_le(
+            "cb_bmi",
+            cb_bmi,
+            crate::parser::MAX_RECORD_BYTES,
+        )?;
+
         let undef_space_bmi = size.checked_offset(off_bmi)?;
         let _ = read_bytes_field(buf, &mut size, undef_space_bmi)?;
-        let bmi_src = read_with(
-            buf,
-            &mut size,
-            wmf_core::parser::BitmapInfoHeader::parse,
-        )?;
+        let bmi_src = read_bytes_field(buf, &mut size, cb_bmi as usize)?;
+
+        // The DIB header is kept as raw bytes so that its color table
+        // survives, but it must still parse.
+        let _ = wmf_core::parser::BitmapInfoHeader::parse(&mut &bmi_src[..])?;
 
         let undef_space_bits = size.checked_offset(off_bits)?;
         let _ = read_bytes_field(buf, &mut size, undef_space_bits)?;
@@ -102,18 +108,44 @@ impl EMR_CREATEDIBPATTERNBRUSHPT {
             bits_src,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_CREATEDIBPATTERNBRUSHPT,
+        );
+
+        write_field(&mut record, self.ih_brush)?;
+        write_field(&mut record, self.usage)?;
+
+        let bmi = record.reserve_buffer();
+        let bits = record.reserve_buffer();
+
+        record.write_buffer(bmi, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits, "bits_src", &self.bits_src)?;
+        record.finish(buf)
+    }
 }
 
-impl From<EMR_CREATEDIBPATTERNBRUSHPT>
+impl TryFrom<EMR_CREATEDIBPATTERNBRUSHPT>
     for wmf_core::parser::DeviceIndependentBitmap
 {
-    fn from(v: EMR_CREATEDIBPATTERNBRUSHPT) -> Self {
-        Self {
-            dib_header_info: v.bmi_src,
+    type Error = crate::parser::ParseError;
+
+    fn try_from(v: EMR_CREATEDIBPATTERNBRUSHPT) -> Result<Self, Self::Error> {
+        let (dib_header_info, _) =
+            wmf_core::parser::BitmapInfoHeader::parse(&mut &v.bmi_src[..])?;
+
+        Ok(Self {
+            dib_header_info,
             colors: wmf_core::parser::Colors::Null,
             bitmap_buffer: wmf_core::parser::BitmapBuffer {
                 a_data: v.bits_src,
             },
-        }
+        })
     }
 }
@@ -41,7 +41,7 @@ pub struct EMR_CREATEMONOBRUSH {
     ///
     /// BmiSrc (variable): The DIB header, which is the DibHeaderInfo field of
     /// a DeviceIndependentBitmap object.
-    pub bmi_src: wmf_core::parser::BitmapInfoHeader,
+    pub bmi_src: Vec<u8>,
     /// BitsSrc (variable): The DIB bits, which is the aData field of a
     /// DeviceIndependentBitmap object.
     pub bits_src: Vec<u8>,
@@ -72,17 +72,23 @@ impl EMR_CREATEMONOBRUSH {
         let ih_brush = read_field(buf, &mut size)?;
         let usage = read_with(buf, &mut size, crate::parser::DIBColors::parse)?;
         let off_bmi = read_field(buf, &mut size)?;
-        let cb_bmi = read_field(buf, &mut size)?;
+        let cb_bmi: u32 = read_field(buf, &mut size)?;
         let off_bits = read_field(buf, &mut size)?;
         let cb_bits: u32 = read_field(buf, &mut size)?;
 
+        crate::parser::ParseError::expect_le(
+            "cb_bmi",
+            cb_bmi,
+            crate::parser::MAX_RECORD_BYTES,
+        )?;
+
         let undef_space_bmi = size.checked_offset(off_bmi)?;
         let _ = read_bytes_field(buf, &mut size, undef_space_bmi)?;
-        let bmi_src = read_with(
-            buf,
-            &mut size,
-            wmf_core::parser::BitmapInfoHeader::parse,
-        )?;
+        let bmi_src = read_bytes_field(buf, &mut size, cb_bmi as usize)?;
+
+        // The DIB header is kept as raw bytes so that its color table
+        // survives, but it must still parse.
+        let _ = wmf_core::parser::BitmapInfoHeader::parse(&mut &bmi_src[..])?;
 
         let undef_space_bits = size.checked_offset(off_bits)?;
         let _ = read_bytes_field(buf, &mut size, undef_space_bits)?;
@@ -103,4 +109,24 @@ impl EMR_CREATEMONOBRUSH {
             bits_src,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_CREATEMONOBRUSH);
+
+        write_field(&mut record, self.ih_brush)?;
+        write_field(&mut record, self.usage)?;
+
+        let bmi = record.reserve_buffer();
+        let bits = record.reserve_buffer();
+
+        record.write_buffer(bmi, "bmi_src", &self.bmi_src)?;
+        record.write_buffer(bits, "bits_src", &self.bits_src)?;
+        record.finish(buf)
+    }
 }
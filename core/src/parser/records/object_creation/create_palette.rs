@@ -58,4 +58,18 @@ impl EMR_CREATEPALETTE {
 
         Ok(Self { record_type, size, ih_pal, log_palette })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_CREATEPALETTE);
+
+        write_field(&mut record, self.ih_pal)?;
+        self.log_palette.write_to(&mut record)?;
+        record.finish(buf)
+    }
 }
@@ -54,4 +54,18 @@ impl EMR_CREATEPEN {
 
         Ok(Self { record_type, size, ih_pen, log_pen })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_CREATEPEN);
+
+        write_field(&mut record, self.ih_pen)?;
+        self.log_pen.write_to(&mut record)?;
+        record.finish(buf)
+    }
 }
@@ -97,6 +97,32 @@ impl EMR_EXTCREATEFONTINDIRECTW {
 
         Ok(Self { record_type, size, ih_fonts, elw })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_EXTCREATEFONTINDIRECTW,
+        );
+
+        write_field(&mut record, self.ih_fonts)?;
+
+        match &self.elw {
+            ELW::LogFontPanose(font) => {
+                font.write_to(&mut record)?;
+            }
+            ELW::LogFontExDv(entries) => {
+                for entry in entries {
+                    entry.write_to(&mut record)?;
+                }
+            }
+        }
+
+        record.finish(buf)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -41,7 +41,7 @@ pub struct EMR_EXTCREATEPEN {
     ///
     /// BmiSrc (variable): The DIB header, which is the DibHeaderInfo field of
     /// a DeviceIndependentBitmap object.
-    pub bmi_src: Option<wmf_core::parser::BitmapInfoHeader>,
+    pub bmi_src: Option<Vec<u8>>,
     /// BitsSrc (variable): The DIB bits, which is the aData field of a
     /// DeviceIndependentBitmap object.
     pub bits_src: Option<Vec<u8>>,
@@ -76,14 +76,21 @@ impl EMR_EXTCREATEPEN {
         let cb_bits: u32 = read_field(buf, &mut size)?;
         let elp = read_with(buf, &mut size, crate::parser::LogPenEx::parse)?;
 
+        crate::parser::ParseError::expect_le(
+            "cb_bmi",
+            cb_bmi,
+            crate::parser::MAX_RECORD_BYTES,
+        )?;
+
         let (bmi_src, bits_src) = if off_bmi > 0 && cb_bmi > 0 {
             let undef_offset_bmi = size.checked_offset(off_bmi)?;
             let _ = read_bytes_field(buf, &mut size, undef_offset_bmi)?;
-            let bmi_src = read_with(
-                buf,
-                &mut size,
-                wmf_core::parser::BitmapInfoHeader::parse,
-            )?;
+            let bmi_src = read_bytes_field(buf, &mut size, cb_bmi as usize)?;
+
+            // The DIB header is kept as raw bytes so that its color table
+            // survives, but it must still parse.
+            let _ =
+                wmf_core::parser::BitmapInfoHeader::parse(&mut &bmi_src[..])?;
 
             let undef_offset_bits = size.checked_offset(off_bits)?;
             let _ = read_bytes_field(buf, &mut size, undef_offset_bits)?;
@@ -109,4 +116,30 @@ impl EMR_EXTCREATEPEN {
             bits_src,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_EXTCREATEPEN);
+
+        write_field(&mut record, self.ih_pen)?;
+
+        let bmi = record.reserve_buffer();
+        let bits = record.reserve_buffer();
+
+        self.elp.write_to(&mut record)?;
+
+        // A pen without a pattern leaves both offsets and sizes zero.
+        if let (Some(bmi_src), Some(bits_src)) = (&self.bmi_src, &self.bits_src)
+        {
+            record.write_buffer(bmi, "bmi_src", bmi_src)?;
+            record.write_buffer(bits, "bits_src", bits_src)?;
+        }
+
+        record.finish(buf)
+    }
 }
@@ -65,4 +65,22 @@ impl EMR_COLORCORRECTPALETTE {
             n_reserved,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_COLORCORRECTPALETTE,
+        );
+
+        write_field(&mut record, self.ih_palette)?;
+        write_field(&mut record, self.n_first_entry)?;
+        write_field(&mut record, self.n_pal_entries)?;
+        write_field(&mut record, self.n_reserved)?;
+
+        record.finish(buf)
+    }
 }
@@ -49,4 +49,18 @@ impl EMR_DELETECOLORSPACE {
 
         Ok(Self { record_type, size, ih_cs })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_DELETECOLORSPACE);
+
+        write_field(&mut record, self.ih_cs)?;
+
+        record.finish(buf)
+    }
 }
@@ -50,4 +50,18 @@ impl EMR_DELETEOBJECT {
 
         Ok(Self { record_type, size, in_object })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_DELETEOBJECT);
+
+        write_field(&mut record, self.in_object)?;
+
+        record.finish(buf)
+    }
 }
@@ -58,4 +58,19 @@ impl EMR_RESIZEPALETTE {
 
         Ok(Self { record_type, size, ih_pal, number_of_entries })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_RESIZEPALETTE);
+
+        write_field(&mut record, self.ih_pal)?;
+        write_field(&mut record, self.number_of_entries)?;
+
+        record.finish(buf)
+    }
 }
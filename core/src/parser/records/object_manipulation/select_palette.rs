@@ -55,4 +55,18 @@ impl EMR_SELECTPALETTE {
 
         Ok(Self { record_type, size, in_pal })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SELECTPALETTE);
+
+        write_field(&mut record, self.in_pal)?;
+
+        record.finish(buf)
+    }
 }
@@ -54,4 +54,18 @@ impl EMR_SETCOLORSPACE {
 
         Ok(Self { record_type, size, in_cs })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETCOLORSPACE);
+
+        write_field(&mut record, self.in_cs)?;
+
+        record.finish(buf)
+    }
 }
@@ -80,4 +80,30 @@ impl EMR_SETPALETTEENTRIES {
             a_pal_entries,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETPALETTEENTRIES);
+
+        write_field(&mut record, self.ih_pal)?;
+        write_field(&mut record, self.start)?;
+        write_len_field(
+            &mut record,
+            "number_of_entries",
+            self.a_pal_entries.len(),
+        )?;
+
+        for entry in &self.a_pal_entries {
+            entry.write_to(&mut record)?;
+        }
+
+        record.finish(buf)
+    }
 }
@@ -47,4 +47,21 @@ impl EMR_GLSRECORD {
 
         Ok(Self { record_type, size, cb_data, data })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_GLSRECORD);
+
+        write_len_field(&mut record, "cb_data", self.data.len())?;
+        write_bytes_field(&mut record, &self.data)?;
+
+        record.finish(buf)
+    }
 }
@@ -47,4 +47,14 @@ impl EMR_BEGINPATH {
 
         Ok(Self { record_type, size })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::records::RecordBuffer::new(
+            crate::parser::RecordType::EMR_BEGINPATH,
+        )
+        .finish(buf)
+    }
 }
@@ -56,4 +56,14 @@ impl EMR_CLOSEFIGURE {
 
         Ok(Self { record_type, size })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::records::RecordBuffer::new(
+            crate::parser::RecordType::EMR_CLOSEFIGURE,
+        )
+        .finish(buf)
+    }
 }
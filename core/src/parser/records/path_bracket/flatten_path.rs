@@ -41,4 +41,14 @@ impl EMR_FLATTENPATH {
 
         Ok(Self { record_type, size })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::records::RecordBuffer::new(
+            crate::parser::RecordType::EMR_FLATTENPATH,
+        )
+        .finish(buf)
+    }
 }
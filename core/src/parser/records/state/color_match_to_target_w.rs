@@ -97,4 +97,26 @@ impl EMR_COLORMATCHTOTARGETW {
             data,
         })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field, write_len_field,
+        };
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_COLORMATCHTOTARGETW,
+        );
+        let cb_name = (self.cb_name as usize).min(self.data.len());
+
+        write_field(&mut record, self.dw_action)?;
+        write_field(&mut record, self.dw_flags)?;
+        write_len_field(&mut record, "cb_name", cb_name)?;
+        write_len_field(&mut record, "cb_data", self.data.len() - cb_name)?;
+        write_bytes_field(&mut record, &self.data)?;
+
+        record.finish(buf)
+    }
 }
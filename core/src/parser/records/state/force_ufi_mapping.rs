@@ -39,4 +39,18 @@ impl EMR_FORCEUFIMAPPING {
 
         Ok(Self { record_type, size, ufi })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::RecordBuffer;
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_FORCEUFIMAPPING);
+
+        self.ufi.write_to(&mut record)?;
+
+        record.finish(buf)
+    }
 }
@@ -40,4 +40,18 @@ impl EMR_MOVETOEX {
 
         Ok(Self { record_type, size, offset })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_MOVETOEX);
+
+        write_field(&mut record, &self.offset)?;
+
+        record.finish(buf)
+    }
 }
@@ -46,4 +46,18 @@ impl EMR_PIXELFORMAT {
 
         Ok(Self { record_type, size, pfd })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::RecordBuffer;
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_PIXELFORMAT);
+
+        self.pfd.write_to(&mut record)?;
+
+        record.finish(buf)
+    }
 }
@@ -34,4 +34,14 @@ impl EMR_REALIZEPALETTE {
 
         Ok(Self { record_type, size })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::records::RecordBuffer::new(
+            crate::parser::RecordType::EMR_REALIZEPALETTE,
+        )
+        .finish(buf)
+    }
 }
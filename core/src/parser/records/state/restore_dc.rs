@@ -13,6 +13,9 @@ pub struct EMR_RESTOREDC {
     /// restore relative to the current state. This value MUST be negative; -1
     /// represents the state that was most recently saved on the stack, -2 the
     /// one before that, etc.
+    ///
+    /// GDI also writes the positive instance numbers accepted by RestoreDC,
+    /// where 1 is the state saved first, so those are kept as well.
     pub saved_dc: i32,
 }
 
@@ -44,16 +47,12 @@ impl EMR_RESTOREDC {
 
         let saved_dc: i32 = read_field(buf, &mut size)?;
 
-        // SavedDC MUST be negative per MS-EMF 2.3.11.21; reject values
-        // >= 0 directly. `expect_le` does not fit since the upper bound
-        // is `-1` for signed values.
-        if saved_dc >= 0 {
+        // SavedDC MUST be negative per MS-EMF 2.3.11.21, but positive
+        // instance numbers are left to the player; only zero refers to no
+        // saved state at all.
+        if saved_dc == 0 {
             return Err(crate::parser::ParseError::UnexpectedPattern {
-                cause: format!(
-                    "saved_dc must be negative, but parsed value is \
-                     {saved_dc:#010X}",
-                )
-                .into(),
+                cause: "saved_dc must not be zero".into(),
             });
         }
 
@@ -61,4 +60,18 @@ impl EMR_RESTOREDC {
 
         Ok(Self { record_type, size, saved_dc })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_RESTOREDC);
+
+        write_field(&mut record, self.saved_dc)?;
+
+        record.finish(buf)
+    }
 }
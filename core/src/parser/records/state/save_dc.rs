@@ -38,4 +38,14 @@ impl EMR_SAVEDC {
 
         Ok(Self { record_type, size })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        crate::parser::records::RecordBuffer::new(
+            crate::parser::RecordType::EMR_SAVEDC,
+        )
+        .finish(buf)
+    }
 }
@@ -67,4 +67,21 @@ impl EMR_SCALEWINDOWEXTEX {
 
         Ok(Self { record_type, size, x_num, x_denom, y_num, y_denom })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SCALEWINDOWEXTEX);
+
+        write_field(&mut record, self.x_num)?;
+        write_field(&mut record, self.x_denom)?;
+        write_field(&mut record, self.y_num)?;
+        write_field(&mut record, self.y_denom)?;
+
+        record.finish(buf)
+    }
 }
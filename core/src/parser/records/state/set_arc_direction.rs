@@ -56,4 +56,18 @@ impl EMR_SETARCDIRECTION {
 
         Ok(Self { record_type, size, arc_direction })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETARCDIRECTION);
+
+        write_field(&mut record, self.arc_direction)?;
+
+        record.finish(buf)
+    }
 }
@@ -39,4 +39,18 @@ impl EMR_SETBKCOLOR {
 
         Ok(Self { record_type, size, color })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETBKCOLOR);
+
+        write_field(&mut record, &self.color)?;
+
+        record.finish(buf)
+    }
 }
@@ -40,4 +40,18 @@ impl EMR_SETBKMODE {
 
         Ok(Self { record_type, size, background_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETBKMODE);
+
+        write_field(&mut record, self.background_mode)?;
+
+        record.finish(buf)
+    }
 }
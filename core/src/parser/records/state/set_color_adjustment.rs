@@ -55,4 +55,19 @@ impl EMR_SETCOLORADJUSTMENT {
 
         Ok(Self { record_type, size, color_adjustment })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::RecordBuffer;
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_SETCOLORADJUSTMENT,
+        );
+
+        self.color_adjustment.write_to(&mut record)?;
+
+        record.finish(buf)
+    }
 }
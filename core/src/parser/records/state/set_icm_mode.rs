@@ -54,4 +54,18 @@ impl EMR_SETICMMODE {
 
         Ok(Self { record_type, size, icm_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETICMMODE);
+
+        write_field(&mut record, self.icm_mode)?;
+
+        record.finish(buf)
+    }
 }
@@ -59,4 +59,24 @@ impl EMR_SETICMPROFILEA {
 
         Ok(Self { record_type, size, dw_flags, cb_name, cb_data, data })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_bytes_field, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETICMPROFILEA);
+        let cb_name = (self.cb_name as usize).min(self.data.len());
+
+        write_field(&mut record, self.dw_flags)?;
+        write_len_field(&mut record, "cb_name", cb_name)?;
+        write_len_field(&mut record, "cb_data", self.data.len() - cb_name)?;
+        write_bytes_field(&mut record, &self.data)?;
+
+        record.finish(buf)
+    }
 }
@@ -48,4 +48,18 @@ impl EMR_SETLAYOUT {
 
         Ok(Self { record_type, size, layout_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETLAYOUT);
+
+        write_field(&mut record, self.layout_mode)?;
+
+        record.finish(buf)
+    }
 }
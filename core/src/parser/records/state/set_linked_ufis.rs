@@ -70,4 +70,26 @@ impl EMR_SETLINKEDUFIS {
 
         Ok(Self { record_type, size, u_num_linked_ufi, ufis, reserved })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{
+            RecordBuffer, write_field, write_len_field,
+        };
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETLINKEDUFIS);
+
+        write_len_field(&mut record, "u_num_linked_ufi", self.ufis.len())?;
+
+        for ufi in &self.ufis {
+            ufi.write_to(&mut record)?;
+        }
+
+        write_field(&mut record, self.reserved)?;
+
+        record.finish(buf)
+    }
 }
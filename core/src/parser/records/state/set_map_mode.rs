@@ -53,4 +53,18 @@ impl EMR_SETMAPMODE {
 
         Ok(Self { record_type, size, map_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETMAPMODE);
+
+        write_field(&mut record, self.map_mode)?;
+
+        record.finish(buf)
+    }
 }
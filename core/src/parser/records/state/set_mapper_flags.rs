@@ -53,4 +53,18 @@ impl EMR_SETMAPPERFLAGS {
 
         Ok(Self { record_type, size, flags })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETMAPPERFLAGS);
+
+        write_field(&mut record, self.flags)?;
+
+        record.finish(buf)
+    }
 }
@@ -41,4 +41,18 @@ impl EMR_SETMITERLIMIT {
 
         Ok(Self { record_type, size, miter_limit })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETMITERLIMIT);
+
+        write_field(&mut record, self.miter_limit)?;
+
+        record.finish(buf)
+    }
 }
@@ -56,4 +56,18 @@ impl EMR_SETPOLYFILLMODE {
 
         Ok(Self { record_type, size, polygon_fill_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETPOLYFILLMODE);
+
+        write_field(&mut record, self.polygon_fill_mode)?;
+
+        record.finish(buf)
+    }
 }
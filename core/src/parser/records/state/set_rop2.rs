@@ -50,4 +50,18 @@ impl EMR_SETROP2 {
 
         Ok(Self { record_type, size, rop2_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETROP2);
+
+        write_field(&mut record, self.rop2_mode as u32)?;
+
+        record.finish(buf)
+    }
 }
@@ -52,4 +52,18 @@ impl EMR_SETSTRETCHBLTMODE {
 
         Ok(Self { record_type, size, stretch_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETSTRETCHBLTMODE);
+
+        write_field(&mut record, self.stretch_mode)?;
+
+        record.finish(buf)
+    }
 }
@@ -54,6 +54,20 @@ impl EMR_SETTEXTALIGN {
         Ok(Self { record_type, size, text_alignment_mode })
     }
 
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETTEXTALIGN);
+
+        write_field(&mut record, self.text_alignment_mode)?;
+
+        record.finish(buf)
+    }
+
     pub fn horizontal_baseline(
         &self,
     ) -> BTreeSet<wmf_core::parser::TextAlignmentMode> {
@@ -50,4 +50,20 @@ impl EMR_SETTEXTJUSTIFICATION {
 
         Ok(Self { record_type, size, n_break_extra, n_break_count })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_SETTEXTJUSTIFICATION,
+        );
+
+        write_field(&mut record, self.n_break_extra)?;
+        write_field(&mut record, self.n_break_count)?;
+
+        record.finish(buf)
+    }
 }
@@ -39,4 +39,18 @@ impl EMR_SETVIEWPORTORGEX {
 
         Ok(Self { record_type, size, origin })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETVIEWPORTORGEX);
+
+        write_field(&mut record, &self.origin)?;
+
+        record.finish(buf)
+    }
 }
@@ -38,4 +38,18 @@ impl EMR_SETWINDOWEXTEX {
 
         Ok(Self { record_type, size, extent })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETWINDOWEXTEX);
+
+        write_field(&mut record, &self.extent)?;
+
+        record.finish(buf)
+    }
 }
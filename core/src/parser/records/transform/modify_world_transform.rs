@@ -55,4 +55,20 @@ impl EMR_MODIFYWORLDTRANSFORM {
 
         Ok(Self { record_type, size, x_form, modify_world_transform_mode })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::{RecordBuffer, write_field};
+
+        let mut record = RecordBuffer::new(
+            crate::parser::RecordType::EMR_MODIFYWORLDTRANSFORM,
+        );
+
+        self.x_form.write_to(&mut record)?;
+        write_field(&mut record, self.modify_world_transform_mode)?;
+
+        record.finish(buf)
+    }
 }
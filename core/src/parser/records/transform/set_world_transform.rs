@@ -45,4 +45,18 @@ impl EMR_SETWORLDTRANSFORM {
 
         Ok(Self { record_type, size, x_form })
     }
+
+    pub fn write_to<W: crate::Write>(
+        &self,
+        buf: &mut W,
+    ) -> Result<usize, crate::parser::WriteError> {
+        use crate::parser::records::RecordBuffer;
+
+        let mut record =
+            RecordBuffer::new(crate::parser::RecordType::EMR_SETWORLDTRANSFORM);
+
+        self.x_form.write_to(&mut record)?;
+
+        record.finish(buf)
+    }
 }
@@ -0,0 +1,476 @@
+//! Writing records back into an EMF metafile.
+//!
+//! [`EmfWriter`] serializes [`Record`]s, parsed by an
+//! [`EmrReader`](crate::parser::EmrReader) or built by hand, into a
+//! metafile that Windows can play. The fields of the header that describe
+//! the whole metafile are recomputed from the records actually written, so
+//! records can be dropped or modified on the way through.
+
+use crate::{imports::*, parser::*};
+
+/// Writes records into a metafile, filling in the header last.
+///
+/// The `Bytes`, `Records`, `Handles` and `nPalEntries` fields of the header
+/// are recomputed from the written records. `Bounds` is the union of the
+/// `Bounds` fields of the records that have one and of the rectangles
+/// passed to `include_bounds`, and keeps the value of the header if there
+/// are none. Drawing records without a `Bounds` field, such as
+/// EMR_RECTANGLE, have an extent the writer does not know, so once one is
+/// written the bounds of the header are kept in the union as well.
+#[derive(Clone, Debug)]
+pub struct EmfWriter {
+    header: EMR_HEADER,
+    eof: Option<EMR_EOF>,
+    records: Vec<u8>,
+    record_count: usize,
+    handles: usize,
+    bounds: Option<wmf_core::parser::RectL>,
+    // Whether a drawing record without a `Bounds` field was written.
+    unbounded: bool,
+}
+
+impl EmfWriter {
+    pub fn new(header: EMR_HEADER) -> Self {
+        Self {
+            header,
+            eof: None,
+            records: vec![],
+            record_count: 0,
+            // Index 0 of the object table is reserved.
+            handles: 1,
+            bounds: None,
+            unbounded: false,
+        }
+    }
+
+    /// Appends `record` to the metafile.
+    ///
+    /// An EMR_HEADER record replaces the header the writer was created
+    /// with and an EMR_EOF record is held back to be written last, so every
+    /// record read from a metafile can be passed through unchanged.
+    pub fn write_record(
+        &mut self,
+        record: &Record<'_>,
+    ) -> Result<(), WriteError> {
+        match record {
+            Record::EMR_HEADER(header) => {
+                self.header = header.clone();
+                return Ok(());
+            }
+            Record::EMR_EOF(eof) => {
+                self.eof = Some(eof.clone());
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        record.write_to(&mut self.records)?;
+        self.record_count += 1;
+
+        if let Some(index) = object_index(record) {
+            self.handles = self.handles.max((index as usize).saturating_add(1));
+        }
+
+        if let Some(bounds) = record.bounds() {
+            self.include_bounds(bounds);
+        } else if draws_without_bounds(record) {
+            self.unbounded = true;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the header the metafile will be written with, before its
+    /// fields are recomputed.
+    pub(crate) fn header_mut(&mut self) -> &mut EMR_HEADER {
+        &mut self.header
+    }
+
+    /// Widens the recomputed bounds of the header to `bounds`, in device
+    /// units, such as the extent of a record without a `Bounds` field.
+    /// Empty rectangles are ignored.
+    pub fn include_bounds(&mut self, bounds: &wmf_core::parser::RectL) {
+        if bounds.left > bounds.right || bounds.top > bounds.bottom {
+            return;
+        }
+
+        self.bounds = Some(match self.bounds.take() {
+            Some(union) => wmf_core::parser::RectL {
+                left: union.left.min(bounds.left),
+                top: union.top.min(bounds.top),
+                right: union.right.max(bounds.right),
+                bottom: union.bottom.max(bounds.bottom),
+            },
+            None => bounds.clone(),
+        });
+    }
+
+    /// Writes the metafile to `buf` and returns the number of bytes
+    /// written. An EMR_EOF record without a palette ends the metafile if
+    /// none was written.
+    pub fn finish_to<W: crate::Write>(
+        mut self,
+        buf: &mut W,
+    ) -> Result<usize, WriteError> {
+        if self.unbounded {
+            let bounds = self.header.emf_header.bounds.clone();
+
+            self.include_bounds(&bounds);
+        }
+
+        let Self {
+            mut header,
+            eof,
+            mut records,
+            record_count,
+            handles,
+            bounds,
+            ..
+        } = self;
+        let eof = eof.unwrap_or_else(|| EMR_EOF {
+            record_type: RecordType::EMR_EOF,
+            size: Size::from(20),
+            n_pal_entries: 0,
+            off_pal_entries: 16,
+            palette_buffer: vec![],
+            size_last: 20,
+        });
+
+        eof.write_to(&mut records)?;
+
+        let header_bytes = header.write_to(&mut Vec::new())?;
+        let emf_header = &mut header.emf_header;
+
+        emf_header.bytes =
+            WriteError::u32_field("bytes", header_bytes + records.len())?;
+        // The header and EOF records are counted too.
+        emf_header.records =
+            WriteError::u32_field("records", record_count + 2)?;
+        emf_header.handles = u16::try_from(handles).map_err(|_| {
+            WriteError::UnrepresentableField {
+                field: "handles",
+                cause: format!("{handles} does not fit in 16 bits").into(),
+            }
+        })?;
+        emf_header.n_pal_entries =
+            WriteError::u32_field("n_pal_entries", eof.palette_buffer.len())?;
+
+        if let Some(bounds) = bounds {
+            emf_header.bounds = bounds;
+        }
+
+        let written = header.write_to(buf)?;
+
+        Ok(written + write_variable(buf, &records)?)
+    }
+
+    /// Returns the metafile as bytes.
+    pub fn finish(self) -> Result<Vec<u8>, WriteError> {
+        let mut buf = vec![];
+
+        self.finish_to(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Returns whether `record` draws without a `Bounds` field telling where.
+fn draws_without_bounds(record: &Record<'_>) -> bool {
+    matches!(
+        record,
+        Record::EMR_ANGLEARC(_)
+            | Record::EMR_ARC(_)
+            | Record::EMR_ARCTO(_)
+            | Record::EMR_CHORD(_)
+            | Record::EMR_ELLIPSE(_)
+            | Record::EMR_EXTFLOODFILL(_)
+            | Record::EMR_LINETO(_)
+            | Record::EMR_PIE(_)
+            | Record::EMR_RECTANGLE(_)
+            | Record::EMR_ROUNDRECT(_)
+            | Record::EMR_SETPIXELV(_)
+            | Record::EMR_SMALLTEXTOUT(_)
+            | Record::EMR_GLSRECORD(_)
+    )
+}
+
+/// Returns the object table index that `record` creates an object at.
+pub(crate) fn object_index(record: &Record<'_>) -> Option<u32> {
+    match record {
+        Record::EMR_CREATEBRUSHINDIRECT(record) => Some(record.ih_brush),
+        Record::EMR_CREATECOLORSPACE(record) => Some(record.ih_cs),
+        Record::EMR_CREATECOLORSPACEW(record) => Some(record.ih_cs),
+        Record::EMR_CREATEDIBPATTERNBRUSHPT(record) => Some(record.ih_brush),
+        Record::EMR_CREATEMONOBRUSH(record) => Some(record.ih_brush),
+        Record::EMR_CREATEPALETTE(record) => Some(record.ih_pal),
+        Record::EMR_CREATEPEN(record) => Some(record.ih_pen),
+        Record::EMR_EXTCREATEFONTINDIRECTW(record) => Some(record.ih_fonts),
+        Record::EMR_EXTCREATEPEN(record) => Some(record.ih_pen),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(record_type: RecordType, fields: &[u32]) -> Vec<u8> {
+        let size = 8 + fields.len() as u32 * 4;
+
+        [record_type as u32, size]
+            .iter()
+            .chain(fields)
+            .flat_map(|v| v.to_le_bytes())
+            .collect()
+    }
+
+    fn metafile() -> Vec<u8> {
+        let header = record(RecordType::EMR_HEADER, &[
+            // Bounds and Frame
+            10,
+            10,
+            20,
+            20,
+            0,
+            0,
+            2646,
+            2646,
+            // RecordSignature, Version, Bytes, Records
+            0x464D_4520,
+            0x0001_0000,
+            200,
+            6,
+            // Handles and Reserved, nDescription, offDescription,
+            // nPalEntries
+            2,
+            0,
+            0,
+            0,
+            // Device and Millimeters
+            1024,
+            768,
+            320,
+            240,
+        ]);
+        let create_pen =
+            record(RecordType::EMR_CREATEPEN, &[1, 0, 1, 0, 0x00FF_0000]);
+        let select_object = record(RecordType::EMR_SELECTOBJECT, &[1]);
+        // Bounds, cpts and two points of 16-bit coordinates.
+        let polyline = record(RecordType::EMR_POLYLINE16, &[
+            10,
+            10,
+            20,
+            20,
+            2,
+            0x000A_000A,
+            0x0014_0014,
+        ]);
+        let comment = record(RecordType::EMR_COMMENT, &[4, 0x1234_5678]);
+        let eof = record(RecordType::EMR_EOF, &[0, 0x10, 20]);
+
+        [header, create_pen, select_object, polyline, comment, eof].concat()
+    }
+
+    fn read_records(metafile: &[u8]) -> Vec<Record<'_>> {
+        EmrReader::new(metafile)
+            .borrowed_records()
+            .map(|entry| entry.map(|v| v.record))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("records should parse")
+    }
+
+    fn read_header(metafile: &[u8]) -> Header {
+        let Some(Record::EMR_HEADER(header)) =
+            read_records(metafile).into_iter().next()
+        else {
+            panic!("first record should be EMR_HEADER");
+        };
+
+        header.emf_header
+    }
+
+    #[test]
+    fn round_trips_a_metafile() {
+        let metafile = metafile();
+        let records = read_records(&metafile);
+        let Record::EMR_HEADER(header) = &records[0] else {
+            panic!("first record should be EMR_HEADER");
+        };
+        let mut writer = EmfWriter::new(header.clone());
+
+        for record in &records {
+            writer.write_record(record).expect("record should be written");
+        }
+
+        assert_eq!(
+            writer.finish().expect("metafile should be written"),
+            metafile
+        );
+    }
+
+    #[test]
+    fn recomputes_header_after_dropping_records() {
+        let metafile = metafile();
+        let records = read_records(&metafile);
+        let Record::EMR_HEADER(header) = &records[0] else {
+            panic!("first record should be EMR_HEADER");
+        };
+        let mut writer = EmfWriter::new(header.clone());
+
+        for record in &records[1..] {
+            if !matches!(
+                record,
+                Record::EMR_COMMENT(_)
+                    | Record::EMR_POLYLINE16(_)
+                    | Record::EMR_EOF(_)
+            ) {
+                writer.write_record(record).expect("record should be written");
+            }
+        }
+
+        let written = writer.finish().expect("metafile should be written");
+        let header = read_header(&written);
+
+        // The EMR_EOF record is added back.
+        assert_eq!(written.len(), 148);
+        assert_eq!((header.bytes, header.records, header.handles), (148, 4, 2));
+        assert!(matches!(
+            read_records(&written).last(),
+            Some(Record::EMR_EOF(_))
+        ));
+    }
+
+    #[test]
+    fn bounds_and_handles_follow_written_records() {
+        let metafile = metafile();
+        let records = read_records(&metafile);
+        let Record::EMR_HEADER(header) = &records[0] else {
+            panic!("first record should be EMR_HEADER");
+        };
+        let mut writer = EmfWriter::new(header.clone());
+
+        for record in &records[1..] {
+            let mut record = record.clone();
+
+            match &mut record {
+                Record::EMR_CREATEPEN(pen) => pen.ih_pen = 5,
+                Record::EMR_POLYLINE16(polyline) => {
+                    polyline.bounds.right = 40;
+                }
+                _ => {}
+            }
+
+            writer.write_record(&record).expect("record should be written");
+        }
+
+        let header =
+            read_header(&writer.finish().expect("metafile should be written"));
+
+        assert_eq!(header.handles, 6);
+        assert_eq!(
+            (
+                header.bounds.left,
+                header.bounds.top,
+                header.bounds.right,
+                header.bounds.bottom
+            ),
+            (10, 10, 40, 20)
+        );
+    }
+
+    #[test]
+    fn keeps_header_bounds_with_unbounded_drawing_records() {
+        let metafile = metafile();
+        let records = read_records(&metafile);
+        let Record::EMR_HEADER(mut header) = records[0].clone() else {
+            panic!("first record should be EMR_HEADER");
+        };
+
+        header.emf_header.bounds = wmf_core::parser::RectL {
+            left: 0,
+            top: 0,
+            right: 100,
+            bottom: 100,
+        };
+
+        // The header and EOF of the metafile around an EMR_LINETO.
+        let with_line_to = [
+            &metafile[..88],
+            &record(RecordType::EMR_LINETO, &[100, 100]),
+            &metafile[metafile.len() - 20..],
+        ]
+        .concat();
+        let line_to = read_records(&with_line_to).remove(1);
+        let bounds_of = |records: &[Record<'_>]| {
+            let mut writer = EmfWriter::new(header.clone());
+
+            for record in records {
+                writer.write_record(record).expect("record should be written");
+            }
+
+            let bounds = read_header(
+                &writer.finish().expect("metafile should be written"),
+            )
+            .bounds;
+
+            (bounds.left, bounds.top, bounds.right, bounds.bottom)
+        };
+
+        // The polyline alone bounds the picture.
+        assert_eq!(bounds_of(&records[1..]), (10, 10, 20, 20));
+        // The extent of the line is unknown, so the header bounds are kept.
+        assert_eq!(
+            bounds_of(&[&records[1..], &[line_to]].concat()),
+            (0, 0, 100, 100)
+        );
+    }
+
+    #[test]
+    fn header_lays_out_description_after_extensions() {
+        let metafile = metafile();
+        let Record::EMR_HEADER(mut header) = read_records(&metafile).remove(0)
+        else {
+            panic!("first record should be EMR_HEADER");
+        };
+
+        header.emf_header_record_buffer =
+            Some(EmfHeaderRecordBuffer::EmfMetafileHeaderExtension2 {
+                emf_header_extension_1: HeaderExtension1 {
+                    cb_pixel_format: 0,
+                    off_pixel_format: 0,
+                    b_open_gl: 0,
+                },
+                emf_header_extension_2: HeaderExtension2 {
+                    micrometers_x: 320_000,
+                    micrometers_y: 240_000,
+                },
+                emf_description: Some("app\0title\0\0".into()),
+                emf_pixel_format: None,
+            });
+
+        let written = EmfWriter::new(header)
+            .finish()
+            .expect("metafile should be written");
+        let Some(Record::EMR_HEADER(header)) =
+            read_records(&written).into_iter().next()
+        else {
+            panic!("first record should be EMR_HEADER");
+        };
+
+        assert_eq!(
+            (
+                header.emf_header.n_description,
+                header.emf_header.off_description
+            ),
+            (11, 108)
+        );
+        assert!(matches!(
+            header.emf_header_record_buffer,
+            Some(EmfHeaderRecordBuffer::EmfMetafileHeaderExtension2 {
+                emf_description: Some(description),
+                ..
+            }) if description == "app\0title\0\0"
+        ));
+        // The 22-byte description is padded to 132 bytes.
+        assert_eq!(header.emf_header.bytes, 132 + 20);
+    }
+}
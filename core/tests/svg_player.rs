@@ -6,16 +6,38 @@
 #![allow(clippy::cast_sign_loss)]
 
 use emf_core::{
-    converter::{Player, SVGPlayer},
+    converter::{
+        ConvertOptions, Diagnostic, ImageMode, OutputSize, PlayError, Player,
+        ResourceLimits, SVGPlayer,
+    },
     parser::{
-        EMR_BEGINPATH, EMR_ENDPATH, EMR_FILLPATH, EMR_HEADER, EMR_MOVETOEX,
-        EMR_POLYBEZIER, EMR_POLYBEZIERTO, EMR_POLYPOLYGON16,
-        EMR_POLYPOLYLINE16, EMR_RECTANGLE, EMR_SETMAPMODE,
-        EMR_SETVIEWPORTEXTEX, EMR_SETWINDOWEXTEX, FormatSignature, Header,
-        MapMode, RecordType, Size,
+        ColorAdjustment, DIBColors, DesignVector, ELW, EMR_BEGINPATH,
+        EMR_CLOSEFIGURE, EMR_CREATEBRUSHINDIRECT, EMR_CREATECOLORSPACE,
+        EMR_CREATEPALETTE, EMR_DELETEOBJECT, EMR_ELLIPSE, EMR_ENDPATH,
+        EMR_EXTCREATEFONTINDIRECTW, EMR_EXTCREATEPEN, EMR_EXTFLOODFILL,
+        EMR_EXTSELECTCLIPRGN, EMR_EXTTEXTOUTA, EMR_EXTTEXTOUTW, EMR_FILLPATH,
+        EMR_FLATTENPATH, EMR_HEADER, EMR_LINETO, EMR_MOVETOEX, EMR_POLYBEZIER,
+        EMR_POLYBEZIERTO, EMR_POLYDRAW, EMR_POLYDRAW16, EMR_POLYLINE16,
+        EMR_POLYPOLYGON, EMR_POLYPOLYGON16, EMR_POLYPOLYLINE16,
+        EMR_POLYTEXTOUTW, EMR_REALIZEPALETTE, EMR_RECTANGLE, EMR_RESTOREDC,
+        EMR_SAVEDC, EMR_SELECTCLIPPATH, EMR_SELECTOBJECT, EMR_SELECTPALETTE,
+        EMR_SETCOLORADJUSTMENT, EMR_SETCOLORSPACE, EMR_SETICMMODE,
+        EMR_SETMAPMODE, EMR_SETMETARGN, EMR_SETPALETTEENTRIES, EMR_SETPIXELV,
+        EMR_SETROP2, EMR_SETSTRETCHBLTMODE, EMR_SETTEXTALIGN,
+        EMR_SETTEXTJUSTIFICATION, EMR_SETVIEWPORTEXTEX, EMR_SETWINDOWEXTEX,
+        EMR_SMALLTEXTOUT, EMR_STRETCHDIBITS, EMR_STROKEPATH, EMR_WIDENPATH,
+        EmrText, ExtTextOutOptionsFlags, FloodFill, FormatSignature,
+        GraphicsMode, HatchStyle, Header, ICMMode, LogBrushEx, LogFont,
+        LogFontEx, LogFontExDv, LogPalette, LogPaletteEntry, LogPenEx,
+        LogPenExBrush, MapMode, OutputString, PenStyleFlags, Point, PointFlags,
+        RecordType, RegionMode, Size, StockObject, StretchMode,
     },
 };
-use wmf_core::parser::{PointL, PointS, RectL, SizeL};
+use wmf_core::parser::{
+    BinaryRasterOperation, CharacterSet, ColorRef, FamilyFont, FontQuality,
+    LogColorSpace, LogicalColorSpace, OutPrecision, PitchAndFamily, PitchFont,
+    PointL, PointS, RectL, SizeL, TernaryRasterOperation,
+};
 
 fn build_header(width: i32, height: i32) -> EMR_HEADER {
     EMR_HEADER {
@@ -45,13 +67,200 @@ fn render(player: SVGPlayer) -> String {
     String::from_utf8(bytes).expect("SVG output is not UTF-8")
 }
 
+fn select_object(player: &mut SVGPlayer, record_number: usize, in_object: u32) {
+    player
+        .select_object(record_number, EMR_SELECTOBJECT {
+            record_type: RecordType::EMR_SELECTOBJECT,
+            size: Size::from(0),
+            in_object,
+        })
+        .expect("select_object should succeed");
+}
+
+fn with_pen(player: &mut SVGPlayer, pen_style: u32, width: u32) {
+    player
+        .ext_create_pen(1, EMR_EXTCREATEPEN {
+            record_type: RecordType::EMR_EXTCREATEPEN,
+            size: Size::from(0),
+            ih_pen: 1,
+            off_bmi: 0,
+            cb_bmi: 0,
+            off_bits: 0,
+            cb_bits: 0,
+            elp: LogPenEx {
+                pen_style: PenStyleFlags::from_raw(pen_style),
+                width,
+                brush: LogPenExBrush::Solid { color_ref: ColorRef::black() },
+                num_style_entries: 0,
+                style_entry: vec![],
+            },
+            bmi_src: None,
+            bits_src: None,
+        })
+        .expect("ext_create_pen should succeed");
+    select_object(player, 2, 1);
+}
+
+fn stroke_line(mut player: SVGPlayer, record_number: usize) -> String {
+    player
+        .line_to(record_number, EMR_LINETO {
+            record_type: RecordType::EMR_LINETO,
+            size: Size::from(0),
+            point: PointL { x: 100, y: 100 },
+        })
+        .expect("line_to should succeed");
+
+    render(player)
+}
+
+// Records a path bracket with one figure through `points`, closed when
+// `close` is set.
+fn with_line_path(player: &mut SVGPlayer, points: &[(i32, i32)], close: bool) {
+    player
+        .begin_path(10, EMR_BEGINPATH {
+            record_type: RecordType::EMR_BEGINPATH,
+            size: Size::from(8),
+        })
+        .expect("begin_path should succeed");
+    player
+        .move_to_ex(11, EMR_MOVETOEX {
+            record_type: RecordType::EMR_MOVETOEX,
+            size: Size::from(0),
+            offset: PointL { x: points[0].0, y: points[0].1 },
+        })
+        .expect("move_to_ex should succeed");
+
+    for &(x, y) in &points[1..] {
+        player
+            .line_to(12, EMR_LINETO {
+                record_type: RecordType::EMR_LINETO,
+                size: Size::from(0),
+                point: PointL { x, y },
+            })
+            .expect("line_to should succeed");
+    }
+
+    if close {
+        player
+            .close_figure(13, EMR_CLOSEFIGURE {
+                record_type: RecordType::EMR_CLOSEFIGURE,
+                size: Size::from(8),
+            })
+            .expect("close_figure should succeed");
+    }
+
+    player
+        .end_path(14, EMR_ENDPATH {
+            record_type: RecordType::EMR_ENDPATH,
+            size: Size::from(8),
+        })
+        .expect("end_path should succeed");
+}
+
+// Plays a 100x100 header and the square from 10 to 50 drawn with
+// `rop2_mode` as record 2.
+fn square_with_rop2(
+    mut player: SVGPlayer,
+    rop2_mode: BinaryRasterOperation,
+) -> SVGPlayer {
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    player
+        .set_rop2(1, EMR_SETROP2 {
+            record_type: RecordType::EMR_SETROP2,
+            size: Size::from(12),
+            rop2_mode,
+        })
+        .expect("set_rop2 should succeed");
+    player
+        .rectangle(2, EMR_RECTANGLE {
+            record_type: RecordType::EMR_RECTANGLE,
+            size: Size::from(24),
+            bx: RectL { left: 10, top: 10, right: 50, bottom: 50 },
+        })
+        .expect("rectangle should succeed");
+
+    player
+}
+
+fn set_pixel(
+    player: &mut SVGPlayer,
+    record_number: usize,
+    pixel: PointL,
+    color: ColorRef,
+) {
+    player
+        .set_pixel_v(record_number, EMR_SETPIXELV {
+            record_type: RecordType::EMR_SETPIXELV,
+            size: Size::from(20),
+            pixel,
+            color,
+        })
+        .expect("set_pixel_v should succeed");
+}
+
+// Plays a 1024x768 header and selects the stock DEFAULT_GUI_FONT, or a
+// created font of `charset`.
+fn player_with_font(charset: Option<CharacterSet>) -> SVGPlayer {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    if let Some(charset) = charset {
+        select_font(&mut player, charset);
+    } else {
+        select_object(&mut player, 1, StockObject::DEFAULT_GUI_FONT as u32);
+    }
+
+    player
+}
+
+fn select_font(player: &mut SVGPlayer, charset: CharacterSet) {
+    let log_font = LogFont {
+        height: 16,
+        width: 0,
+        escapement: 0,
+        orientation: 0,
+        weight: 400,
+        italic: false,
+        underline: false,
+        strike_out: false,
+        charset,
+        out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+        clip_precision: std::collections::BTreeSet::new(),
+        quality: FontQuality::DEFAULT_QUALITY,
+        pitch_and_family: PitchAndFamily {
+            family: FamilyFont::FF_DONTCARE,
+            pitch: PitchFont::VARIABLE_PITCH,
+        },
+        facename: "MS Gothic".to_owned(),
+    };
+    player
+        .ext_create_font_indirect_w(1, EMR_EXTCREATEFONTINDIRECTW {
+            record_type: RecordType::EMR_EXTCREATEFONTINDIRECTW,
+            size: Size::from(0),
+            ih_fonts: 1,
+            elw: ELW::LogFontExDv(vec![LogFontExDv {
+                log_font_ex: LogFontEx {
+                    log_font,
+                    full_name: String::new(),
+                    style: String::new(),
+                    script: String::new(),
+                },
+                design_vector: DesignVector {
+                    signature: 0x0800_7664,
+                    num_axes: 0,
+                    values: vec![],
+                },
+            }]),
+        })
+        .expect("ext_create_font_indirect_w should succeed");
+    select_object(player, 2, 1);
+}
+
 #[test]
 fn rectangle_record_emits_rect_in_viewbox() {
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed");
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
 
-    let player = player
+    player
         .rectangle(1, EMR_RECTANGLE {
             record_type: RecordType::EMR_RECTANGLE,
             size: Size::from(0),
@@ -79,11 +288,10 @@ fn rectangle_record_emits_rect_in_viewbox() {
 
 #[test]
 fn multiple_records_assign_unique_element_ids() {
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed");
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
 
-    let player = player
+    player
         .rectangle(1, EMR_RECTANGLE {
             record_type: RecordType::EMR_RECTANGLE,
             size: Size::from(0),
@@ -91,7 +299,7 @@ fn multiple_records_assign_unique_element_ids() {
         })
         .expect("first rectangle should succeed");
 
-    let player = player
+    player
         .rectangle(2, EMR_RECTANGLE {
             record_type: RecordType::EMR_RECTANGLE,
             size: Size::from(0),
@@ -114,14 +322,15 @@ fn poly_bezier_path_starts_with_moveto() {
     // point was suppressed. The first aPoints entry is the curve's
     // starting point, so the SVG path must begin with `M`.
     let bounds = RectL { left: 0, top: 0, right: 200, bottom: 200 };
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed")
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
         .begin_path(1, EMR_BEGINPATH {
             record_type: RecordType::EMR_BEGINPATH,
             size: Size::from(8),
         })
-        .expect("begin_path should succeed")
+        .expect("begin_path should succeed");
+    player
         .poly_bezier(2, EMR_POLYBEZIER {
             record_type: RecordType::EMR_POLYBEZIER,
             size: Size::from(0),
@@ -134,12 +343,14 @@ fn poly_bezier_path_starts_with_moveto() {
                 PointL { x: 70, y: 80 },
             ],
         })
-        .expect("poly_bezier should succeed")
+        .expect("poly_bezier should succeed");
+    player
         .end_path(3, EMR_ENDPATH {
             record_type: RecordType::EMR_ENDPATH,
             size: Size::from(8),
         })
-        .expect("end_path should succeed")
+        .expect("end_path should succeed");
+    player
         .fill_path(4, EMR_FILLPATH {
             record_type: RecordType::EMR_FILLPATH,
             size: Size::from(0),
@@ -162,20 +373,22 @@ fn poly_bezier_to_seeds_moveto_when_path_empty() {
     // the shared path buffer is still empty (e.g. PolyBezierTo is the
     // first command after BeginPath without an intervening MoveToEx).
     let bounds = RectL { left: 0, top: 0, right: 200, bottom: 200 };
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed")
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
         .move_to_ex(1, EMR_MOVETOEX {
             record_type: RecordType::EMR_MOVETOEX,
             size: Size::from(0),
             offset: PointL { x: 5, y: 6 },
         })
-        .expect("move_to_ex should succeed")
+        .expect("move_to_ex should succeed");
+    player
         .begin_path(2, EMR_BEGINPATH {
             record_type: RecordType::EMR_BEGINPATH,
             size: Size::from(8),
         })
-        .expect("begin_path should succeed")
+        .expect("begin_path should succeed");
+    player
         .poly_bezier_to(3, EMR_POLYBEZIERTO {
             record_type: RecordType::EMR_POLYBEZIERTO,
             size: Size::from(0),
@@ -187,12 +400,14 @@ fn poly_bezier_to_seeds_moveto_when_path_empty() {
                 PointL { x: 50, y: 60 },
             ],
         })
-        .expect("poly_bezier_to should succeed")
+        .expect("poly_bezier_to should succeed");
+    player
         .end_path(4, EMR_ENDPATH {
             record_type: RecordType::EMR_ENDPATH,
             size: Size::from(8),
         })
-        .expect("end_path should succeed")
+        .expect("end_path should succeed");
+    player
         .fill_path(5, EMR_FILLPATH {
             record_type: RecordType::EMR_FILLPATH,
             size: Size::from(0),
@@ -238,31 +453,34 @@ fn header_does_not_offset_viewport_origin() {
         emf_header_record_buffer: None,
     };
 
-    let player = SVGPlayer::new()
-        .header(0, header)
-        .expect("header should succeed")
+    let mut player = SVGPlayer::new();
+    player.header(0, header).expect("header should succeed");
+    player
         .set_map_mode(1, EMR_SETMAPMODE {
             record_type: RecordType::EMR_SETMAPMODE,
             size: Size::from(0),
             map_mode: MapMode::MM_ANISOTROPIC,
         })
-        .expect("set_map_mode should succeed")
+        .expect("set_map_mode should succeed");
+    player
         .set_viewport_ext_ex(2, EMR_SETVIEWPORTEXTEX {
             record_type: RecordType::EMR_SETVIEWPORTEXTEX,
             size: Size::from(0),
             extent: SizeL { cx: 200, cy: 100 },
         })
-        .expect("set_viewport_ext_ex should succeed")
+        .expect("set_viewport_ext_ex should succeed");
+    player
         .set_window_ext_ex(3, EMR_SETWINDOWEXTEX {
             record_type: RecordType::EMR_SETWINDOWEXTEX,
             size: Size::from(0),
             extent: SizeL { cx: 100, cy: 50 },
         })
-        .expect("set_window_ext_ex should succeed")
-        // Logical (10, 10) should map to device (10 * 200/100, 10 *
-        // 100/50) = (20, 20). If header pre-loaded the viewport origin
-        // with bounds.top-left, the result would shift by (33, 211)
-        // and land at (53, 231), which is past the viewBox top.
+        .expect("set_window_ext_ex should succeed");
+    // Logical (10, 10) should map to device (10 * 200/100, 10 *
+    // 100/50) = (20, 20). If header pre-loaded the viewport origin
+    // with bounds.top-left, the result would shift by (33, 211)
+    // and land at (53, 231), which is past the viewBox top.
+    player
         .rectangle(4, EMR_RECTANGLE {
             record_type: RecordType::EMR_RECTANGLE,
             size: Size::from(0),
@@ -289,9 +507,9 @@ fn poly_polyline_16_assigns_suffixed_ids_per_subpath() {
     // sub-shape keeps the bare `elem{N}` id; later sub-shapes get
     // `elem{N}-1`, `elem{N}-2`, ... so the SVG id-uniqueness rule
     // holds while the per-record prefix stays addressable.
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed")
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
         .poly_polyline_16(7, EMR_POLYPOLYLINE16 {
             record_type: RecordType::EMR_POLYPOLYLINE16,
             size: Size::from(0),
@@ -339,9 +557,9 @@ fn poly_polygon_16_assigns_suffixed_ids_per_subpolygon() {
     // Regression: poly_polygon_16 emitted a `<polygon>` per sub-shape
     // each with the same id. Multi-shape records must give the second
     // and later sub-shapes a numeric suffix so SVG ids stay unique.
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed")
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
         .poly_polygon_16(11, EMR_POLYPOLYGON16 {
             record_type: RecordType::EMR_POLYPOLYGON16,
             size: Size::from(0),
@@ -379,14 +597,124 @@ fn poly_polygon_16_assigns_suffixed_ids_per_subpolygon() {
     );
 }
 
+#[test]
+fn poly_polygon_assigns_suffixed_ids_per_subpolygon() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
+        .poly_polygon(11, EMR_POLYPOLYGON {
+            record_type: RecordType::EMR_POLYPOLYGON,
+            size: Size::from(0),
+            bounds: RectL { left: 0, top: 0, right: 100, bottom: 100 },
+            number_of_polygons: 2,
+            count: 6,
+            polygon_point_count: vec![3, 3],
+            a_points: vec![
+                PointL { x: 0, y: 0 },
+                PointL { x: 10, y: 0 },
+                PointL { x: 5, y: 10 },
+                PointL { x: 20, y: 20 },
+                PointL { x: 30, y: 20 },
+                PointL { x: 25, y: 30 },
+            ],
+        })
+        .expect("poly_polygon should succeed");
+
+    let svg = render(player);
+
+    assert!(svg.contains(r#"id="elem11" points="0,0 10,0 5,10""#), "{svg}",);
+    assert!(
+        svg.contains(r#"id="elem11-1" points="20,20 30,20 25,30""#),
+        "{svg}",
+    );
+}
+
+#[test]
+fn shapes_inside_path_bracket_become_figures_of_the_path() {
+    let bounds = RectL { left: 0, top: 0, right: 100, bottom: 100 };
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
+        .begin_path(1, EMR_BEGINPATH {
+            record_type: RecordType::EMR_BEGINPATH,
+            size: Size::from(8),
+        })
+        .expect("begin_path should succeed");
+    player
+        .rectangle(2, EMR_RECTANGLE {
+            record_type: RecordType::EMR_RECTANGLE,
+            size: Size::from(0),
+            bx: RectL { left: 0, top: 0, right: 100, bottom: 100 },
+        })
+        .expect("rectangle should succeed");
+    player
+        .poly_polygon_16(3, EMR_POLYPOLYGON16 {
+            record_type: RecordType::EMR_POLYPOLYGON16,
+            size: Size::from(0),
+            bounds: bounds.clone(),
+            number_of_polygons: 1,
+            count: 3,
+            polygon_point_count: vec![3],
+            a_points: vec![
+                PointS { x: 20, y: 20 },
+                PointS { x: 30, y: 20 },
+                PointS { x: 25, y: 30 },
+            ],
+        })
+        .expect("poly_polygon_16 should succeed");
+    player
+        .ellipse(4, EMR_ELLIPSE {
+            record_type: RecordType::EMR_ELLIPSE,
+            size: Size::from(0),
+            bx: RectL { left: 40, top: 40, right: 60, bottom: 80 },
+        })
+        .expect("ellipse should succeed");
+    player
+        .polyline_16(5, EMR_POLYLINE16 {
+            record_type: RecordType::EMR_POLYLINE16,
+            size: Size::from(0),
+            bounds: bounds.clone(),
+            count: 2,
+            a_points: vec![PointS { x: 70, y: 70 }, PointS { x: 90, y: 70 }],
+        })
+        .expect("polyline_16 should succeed");
+    player
+        .end_path(6, EMR_ENDPATH {
+            record_type: RecordType::EMR_ENDPATH,
+            size: Size::from(8),
+        })
+        .expect("end_path should succeed");
+    player
+        .fill_path(7, EMR_FILLPATH {
+            record_type: RecordType::EMR_FILLPATH,
+            size: Size::from(0),
+            bounds,
+        })
+        .expect("fill_path should succeed");
+
+    let svg = render(player);
+
+    assert!(!svg.contains("<rect") && !svg.contains("<polygon"), "{svg}");
+    assert!(!svg.contains("<ellipse"), "{svg}");
+    assert!(
+        svg.contains(
+            "M 0 0 L 100 0 L 100 100 L 0 100 Z M 20 20 L 30 20 L 25 30 Z M 60 \
+             60 C 60 71.05 55.52 80 50 80"
+        ),
+        "{svg}",
+    );
+    assert!(svg.contains(r#"Z M 70 70 L 90 70""#), "{svg}");
+    assert!(svg.contains(r#"fill-rule="evenodd""#), "{svg}");
+}
+
 #[test]
 fn poly_polyline_16_keeps_bare_id_when_single_subpath() {
     // When the record collapses to a single sub-shape no suffix is
     // appended; the bare `elem{N}` id is kept so element-level
     // addressing matches the simple-record case.
-    let player = SVGPlayer::new()
-        .header(0, build_header(1024, 768))
-        .expect("header should succeed")
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
         .poly_polyline_16(13, EMR_POLYPOLYLINE16 {
             record_type: RecordType::EMR_POLYPOLYLINE16,
             size: Size::from(0),
@@ -412,9 +740,8 @@ fn poly_polyline_16_keeps_bare_id_when_single_subpath() {
 
 #[test]
 fn empty_metafile_emits_only_svg_skeleton() {
-    let player = SVGPlayer::new()
-        .header(0, build_header(640, 480))
-        .expect("header should succeed");
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(640, 480)).expect("header should succeed");
 
     let svg = render(player);
     assert!(svg.starts_with("<svg"));
@@ -425,3 +752,1493 @@ fn empty_metafile_emits_only_svg_skeleton() {
     assert!(!svg.contains("<polyline"));
     assert!(!svg.contains("<path"));
 }
+
+#[test]
+fn geometric_pen_end_caps_and_joins() {
+    // PS_GEOMETRIC with an empty cap/join field stands for
+    // PS_ENDCAP_ROUND | PS_JOIN_ROUND.
+    let stroke_line_with_pen = |pen_style, width| {
+        let mut player = SVGPlayer::new();
+        player
+            .header(0, build_header(1024, 768))
+            .expect("header should succeed");
+        with_pen(&mut player, pen_style, width);
+
+        stroke_line(player, 3)
+    };
+
+    let svg = stroke_line_with_pen(0x0001_0000, 8);
+    assert!(svg.contains(r#"stroke-linecap="round""#), "{svg}");
+    assert!(svg.contains(r#"stroke-linejoin="round""#), "{svg}");
+
+    // PS_GEOMETRIC | PS_ENDCAP_FLAT | PS_JOIN_BEVEL
+    let svg = stroke_line_with_pen(0x0001_1200, 8);
+    assert!(svg.contains(r#"stroke-linecap="butt""#), "{svg}");
+    assert!(svg.contains(r#"stroke-linejoin="bevel""#), "{svg}");
+
+    // PS_GEOMETRIC | PS_ENDCAP_SQUARE | PS_JOIN_MITER | PS_DASHDOT
+    let svg = stroke_line_with_pen(0x0001_2103, 2);
+    assert!(svg.contains(r#"stroke-linecap="square""#), "{svg}");
+    assert!(svg.contains(r#"stroke-linejoin="miter""#), "{svg}");
+    assert!(svg.contains(r#"stroke-dasharray="20 4 2 4""#), "{svg}");
+}
+
+#[test]
+fn cosmetic_pen_keeps_flat_caps() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    with_pen(&mut player, 0x0000_0000, 1);
+
+    let svg = stroke_line(player, 3);
+    assert!(svg.contains(r#"stroke-linecap="butt""#), "{svg}");
+    assert!(svg.contains(r#"stroke-linejoin="miter""#), "{svg}");
+}
+
+#[test]
+fn flatten_path_replaces_curves_with_lines() {
+    let bounds = RectL { left: 0, top: 0, right: 200, bottom: 200 };
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
+        .begin_path(1, EMR_BEGINPATH {
+            record_type: RecordType::EMR_BEGINPATH,
+            size: Size::from(8),
+        })
+        .expect("begin_path should succeed");
+    player
+        .poly_bezier(2, EMR_POLYBEZIER {
+            record_type: RecordType::EMR_POLYBEZIER,
+            size: Size::from(0),
+            bounds: bounds.clone(),
+            count: 4,
+            a_points: vec![
+                PointL { x: 0, y: 0 },
+                PointL { x: 0, y: 100 },
+                PointL { x: 100, y: 100 },
+                PointL { x: 100, y: 0 },
+            ],
+        })
+        .expect("poly_bezier should succeed");
+    player
+        .end_path(3, EMR_ENDPATH {
+            record_type: RecordType::EMR_ENDPATH,
+            size: Size::from(8),
+        })
+        .expect("end_path should succeed");
+    player
+        .flatten_path(4, EMR_FLATTENPATH {
+            record_type: RecordType::EMR_FLATTENPATH,
+            size: Size::from(8),
+        })
+        .expect("flatten_path should succeed");
+
+    player
+        .stroke_path(5, EMR_STROKEPATH {
+            record_type: RecordType::EMR_STROKEPATH,
+            size: Size::from(0),
+            bounds,
+        })
+        .expect("stroke_path should succeed");
+
+    let svg = render(player);
+
+    // Flattening alone draws nothing.
+    assert!(!svg.contains(r#"id="elem4""#), "{svg}");
+    assert!(svg.contains(r#"d="M 0 0 L "#), "{svg}");
+    assert!(svg.contains(r#" L 100 0""#), "{svg}");
+    assert!(!svg.contains(" C "), "{svg}");
+}
+
+#[test]
+fn widen_path_outlines_the_stroke_of_the_pen() {
+    let bounds = RectL { left: 0, top: 0, right: 200, bottom: 200 };
+    // PS_GEOMETRIC | PS_ENDCAP_FLAT | PS_JOIN_MITER
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    with_pen(&mut player, 0x0001_2200, 4);
+    with_line_path(&mut player, &[(10, 10), (50, 10), (50, 30)], false);
+    player
+        .widen_path(15, EMR_WIDENPATH {
+            record_type: RecordType::EMR_WIDENPATH,
+            size: Size::from(8),
+        })
+        .expect("widen_path should succeed");
+    player
+        .fill_path(16, EMR_FILLPATH {
+            record_type: RecordType::EMR_FILLPATH,
+            size: Size::from(0),
+            bounds,
+        })
+        .expect("fill_path should succeed");
+
+    let svg = render(player);
+
+    assert!(
+        svg.contains(r#"d="M 10 12 L 48 12 L 48 30 L 52 30 L 52 8 L 10 8 Z""#),
+        "{svg}",
+    );
+}
+
+#[test]
+fn select_clip_path_clips_following_elements() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    with_line_path(&mut player, &[(0, 0), (100, 0), (100, 50), (0, 50)], true);
+    player
+        .select_clip_path(15, EMR_SELECTCLIPPATH {
+            record_type: RecordType::EMR_SELECTCLIPPATH,
+            size: Size::from(0),
+            region_mode: RegionMode::RGN_COPY,
+        })
+        .expect("select_clip_path should succeed");
+    player
+        .rectangle(16, EMR_RECTANGLE {
+            record_type: RecordType::EMR_RECTANGLE,
+            size: Size::from(0),
+            bx: RectL { left: 20, top: 20, right: 200, bottom: 200 },
+        })
+        .expect("rectangle should succeed");
+
+    let svg = render(player);
+
+    assert!(
+        svg.contains(
+            r#"<clipPath id="defs0"><path clip-rule="evenodd" d="M 0 0 L 100 0 L 100 50 L 0 50 Z"></path></clipPath>"#
+        ),
+        "{svg}",
+    );
+    assert!(svg.contains(r#"<rect clip-path="url(#defs0)""#), "{svg}");
+}
+
+#[test]
+fn ext_select_clip_rgn_copy_without_region_resets_clip() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    with_line_path(&mut player, &[(0, 0), (100, 0), (100, 50), (0, 50)], true);
+    player
+        .select_clip_path(15, EMR_SELECTCLIPPATH {
+            record_type: RecordType::EMR_SELECTCLIPPATH,
+            size: Size::from(0),
+            region_mode: RegionMode::RGN_COPY,
+        })
+        .expect("select_clip_path should succeed");
+    player
+        .ext_select_clip_rgn(16, EMR_EXTSELECTCLIPRGN {
+            record_type: RecordType::EMR_EXTSELECTCLIPRGN,
+            size: Size::from(0),
+            rgn_data_size: 0,
+            region_mode: RegionMode::RGN_COPY,
+            rgn_data: vec![],
+        })
+        .expect("ext_select_clip_rgn should succeed");
+    player
+        .rectangle(17, EMR_RECTANGLE {
+            record_type: RecordType::EMR_RECTANGLE,
+            size: Size::from(0),
+            bx: RectL { left: 20, top: 20, right: 200, bottom: 200 },
+        })
+        .expect("rectangle should succeed");
+
+    let svg = render(player);
+
+    assert!(svg.contains(r#"id="elem17""#), "{svg}");
+    assert!(!svg.contains("<rect clip-path"), "{svg}");
+}
+
+#[test]
+fn poly_draw_strokes_point_types_outside_path_bracket() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
+        .poly_draw(1, EMR_POLYDRAW {
+            record_type: RecordType::EMR_POLYDRAW,
+            size: Size::from(0),
+            bounds: RectL { left: 0, top: 0, right: 20, bottom: 10 },
+            count: 4,
+            a_points: vec![
+                PointL { x: 10, y: 0 },
+                PointL { x: 20, y: 0 },
+                PointL { x: 20, y: 10 },
+                PointL { x: 10, y: 10 },
+            ],
+            ab_types: vec![
+                PointFlags::single(Point::PT_LINETO),
+                PointFlags::single(Point::PT_BEZIERTO),
+                PointFlags::single(Point::PT_BEZIERTO),
+                // PT_BEZIERTO | PT_CLOSEFIGURE
+                PointFlags::from_raw(0x05),
+            ],
+        })
+        .expect("poly_draw should succeed");
+    // The closed figure leaves the current position at its start.
+    player
+        .line_to(2, EMR_LINETO {
+            record_type: RecordType::EMR_LINETO,
+            size: Size::from(0),
+            point: PointL { x: 5, y: 5 },
+        })
+        .expect("line_to should succeed");
+
+    let svg = render(player);
+
+    assert!(svg.contains(r#"d="M 0 0 L 10 0 C 20 0 20 10 10 10 Z""#), "{svg}");
+    assert!(svg.contains(r#"d="M 0 0 L 5 5""#), "{svg}");
+}
+
+#[test]
+fn poly_draw_16_appends_to_path_bracket() {
+    let bounds = RectL { left: 0, top: 0, right: 40, bottom: 40 };
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    player
+        .begin_path(1, EMR_BEGINPATH {
+            record_type: RecordType::EMR_BEGINPATH,
+            size: Size::from(8),
+        })
+        .expect("begin_path should succeed");
+    player
+        .poly_draw_16(2, EMR_POLYDRAW16 {
+            record_type: RecordType::EMR_POLYDRAW16,
+            size: Size::from(0),
+            bounds: bounds.clone(),
+            count: 5,
+            a_points: vec![
+                PointS { x: 10, y: 10 },
+                PointS { x: 30, y: 10 },
+                PointS { x: 30, y: 30 },
+                PointS { x: 35, y: 35 },
+                PointS { x: 40, y: 35 },
+            ],
+            ab_types: vec![
+                PointFlags::single(Point::PT_MOVETO),
+                PointFlags::single(Point::PT_LINETO),
+                // PT_LINETO | PT_CLOSEFIGURE
+                PointFlags::from_raw(0x03),
+                PointFlags::single(Point::PT_MOVETO),
+                PointFlags::single(Point::PT_LINETO),
+            ],
+        })
+        .expect("poly_draw_16 should succeed");
+    player
+        .end_path(3, EMR_ENDPATH {
+            record_type: RecordType::EMR_ENDPATH,
+            size: Size::from(8),
+        })
+        .expect("end_path should succeed");
+
+    // Nothing is drawn before the path is stroked.
+    player
+        .stroke_path(4, EMR_STROKEPATH {
+            record_type: RecordType::EMR_STROKEPATH,
+            size: Size::from(0),
+            bounds,
+        })
+        .expect("stroke_path should succeed");
+    let svg = render(player);
+
+    assert!(!svg.contains(r#"id="elem2""#), "{svg}");
+    assert!(
+        svg.contains(r#"d="M 10 10 L 30 10 L 30 30 Z M 35 35 L 40 35""#),
+        "{svg}",
+    );
+}
+
+#[test]
+fn poly_draw_rejects_incomplete_bezier() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+
+    let result = player.poly_draw(1, EMR_POLYDRAW {
+        record_type: RecordType::EMR_POLYDRAW,
+        size: Size::from(0),
+        bounds: RectL { left: 0, top: 0, right: 20, bottom: 10 },
+        count: 2,
+        a_points: vec![PointL { x: 10, y: 0 }, PointL { x: 20, y: 0 }],
+        ab_types: vec![
+            PointFlags::single(Point::PT_BEZIERTO),
+            PointFlags::single(Point::PT_BEZIERTO),
+        ],
+    });
+
+    assert!(result.is_err());
+}
+
+fn save_dc(player: &mut SVGPlayer, record_number: usize) {
+    player
+        .save_dc(record_number, EMR_SAVEDC {
+            record_type: RecordType::EMR_SAVEDC,
+            size: Size::from(8),
+        })
+        .expect("save_dc should succeed");
+}
+
+fn restore_dc(player: &mut SVGPlayer, record_number: usize, saved_dc: i32) {
+    player
+        .restore_dc(record_number, EMR_RESTOREDC {
+            record_type: RecordType::EMR_RESTOREDC,
+            size: Size::from(12),
+            saved_dc,
+        })
+        .expect("restore_dc should succeed");
+}
+
+#[test]
+fn restore_dc_restores_selected_pen() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    save_dc(&mut player, 1);
+    // PS_GEOMETRIC | PS_ENDCAP_ROUND | PS_JOIN_ROUND
+    with_pen(&mut player, 0x0001_0000, 8);
+    restore_dc(&mut player, 3, -1);
+    let svg = stroke_line(player, 4);
+
+    assert!(svg.contains(r#"stroke-linecap="butt""#), "{svg}");
+    assert!(!svg.contains(r#"stroke-width="8""#), "{svg}");
+}
+
+#[test]
+fn restore_dc_with_instance_number_discards_later_states() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    save_dc(&mut player, 1);
+    with_pen(&mut player, 0x0001_0000, 8);
+    save_dc(&mut player, 3);
+    with_pen(&mut player, 0x0001_1200, 4);
+    // The first saved state drops the second one, so relative -1 has
+    // nothing left to restore and keeps the current state.
+    restore_dc(&mut player, 5, 1);
+    restore_dc(&mut player, 6, -1);
+    let diagnostics = player.take_diagnostics();
+    let svg = stroke_line(player, 7);
+
+    assert!(svg.contains(r#"stroke-linecap="butt""#), "{svg}");
+    assert!(svg.contains(r#"stroke-linejoin="miter""#), "{svg}");
+    assert_eq!(
+        diagnostics
+            .approximated
+            .iter()
+            .map(|v| (v.record_number, v.record_type))
+            .collect::<Vec<_>>(),
+        [(6, RecordType::EMR_RESTOREDC)]
+    );
+}
+
+#[test]
+fn restore_dc_restores_path_bracket() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    with_line_path(&mut player, &[(0, 0), (100, 0)], false);
+    save_dc(&mut player, 15);
+    with_line_path(&mut player, &[(0, 50), (50, 50)], false);
+    restore_dc(&mut player, 16, -1);
+    player
+        .stroke_path(17, EMR_STROKEPATH {
+            record_type: RecordType::EMR_STROKEPATH,
+            size: Size::from(0),
+            bounds: RectL { left: 0, top: 0, right: 100, bottom: 100 },
+        })
+        .expect("stroke_path should succeed");
+
+    let svg = render(player);
+
+    assert!(svg.contains(r#"d="M 0 0 L 100 0""#), "{svg}");
+}
+
+#[test]
+fn set_pixel_v_emits_one_device_pixel() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    set_pixel(&mut player, 1, PointL { x: 5, y: 7 }, ColorRef {
+        red: 255,
+        green: 0,
+        blue: 0,
+        reserved: 0,
+    });
+
+    let svg = render(player);
+
+    assert!(
+        svg.contains(
+            r##"<rect fill="#FF0000" height="1" id="elem1" stroke="none" width="1" x="5" y="7">"##
+        ),
+        "{svg}"
+    );
+}
+
+// Returns the SVG of a flood fill from (x, y) around the square of
+// `square_with_rop2`, and the records the fill is approximated in.
+fn flood_fill_square(
+    player: SVGPlayer,
+    rop2_mode: BinaryRasterOperation,
+    start: PointL,
+    flood_fill_mode: FloodFill,
+) -> (String, Vec<Diagnostic>) {
+    let mut player = square_with_rop2(player, rop2_mode);
+    player
+        .ext_flood_fill(3, EMR_EXTFLOODFILL {
+            record_type: RecordType::EMR_EXTFLOODFILL,
+            size: Size::from(24),
+            start,
+            color: ColorRef::black(),
+            flood_fill_mode,
+        })
+        .expect("ext_flood_fill should succeed");
+
+    let diagnostics = player.take_diagnostics();
+
+    (render(player), diagnostics.approximated)
+}
+
+#[test]
+fn ext_flood_fill_fills_area_from_raster_copy() {
+    // The black square, outlined by the pen, covers pixels 10 to 50.
+    let (svg, _) = flood_fill_square(
+        SVGPlayer::new().with_flood_fill(),
+        BinaryRasterOperation::R2_COPYPEN,
+        PointL { x: 20, y: 20 },
+        FloodFill::FLOODFILLSURFACE,
+    );
+    assert!(svg.contains(r#"d="M 10 10 L 51 10 L 51 51 L 10 51 Z""#), "{svg}");
+
+    let (svg, _) = flood_fill_square(
+        SVGPlayer::new().with_flood_fill(),
+        BinaryRasterOperation::R2_COPYPEN,
+        PointL { x: 5, y: 5 },
+        FloodFill::FLOODFILLBORDER,
+    );
+    assert!(
+        svg.contains(
+            "M 0 0 L 100 0 L 100 10 L 0 10 Z M 0 10 L 10 10 L 10 51 L 0 51 Z \
+             M 51 10 L 100 10 L 100 51 L 51 51 Z M 0 51 L 100 51 L 100 100 L \
+             0 100 Z"
+        ),
+        "{svg}"
+    );
+
+    // Starting on the border color fills nothing.
+    let (svg, approximated) = flood_fill_square(
+        SVGPlayer::new().with_flood_fill(),
+        BinaryRasterOperation::R2_COPYPEN,
+        PointL { x: 20, y: 20 },
+        FloodFill::FLOODFILLBORDER,
+    );
+    assert!(!svg.contains(r#"id="elem3""#), "{svg}");
+    assert_eq!(approximated.len(), 1, "{approximated:?}");
+    assert_eq!(approximated[0].record_type, RecordType::EMR_EXTFLOODFILL);
+}
+
+#[test]
+fn ext_flood_fill_sees_shapes_as_the_raster_operation_draws_them() {
+    for rop2_mode in
+        [BinaryRasterOperation::R2_NOP, BinaryRasterOperation::R2_WHITE]
+    {
+        let (svg, _) = flood_fill_square(
+            SVGPlayer::new().with_flood_fill(),
+            rop2_mode,
+            PointL { x: 20, y: 20 },
+            FloodFill::FLOODFILLSURFACE,
+        );
+
+        // The square is not drawn, or drawn white, so no black area is
+        // there to fill.
+        assert!(!svg.contains(r#"id="elem3""#), "{rop2_mode:?}: {svg}");
+    }
+}
+
+#[test]
+fn ext_flood_fill_is_skipped_without_raster_copy() {
+    let (svg, approximated) = flood_fill_square(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_COPYPEN,
+        PointL { x: 20, y: 20 },
+        FloodFill::FLOODFILLSURFACE,
+    );
+    assert!(!svg.contains(r#"id="elem3""#), "{svg}");
+    assert_eq!(
+        approximated
+            .iter()
+            .map(|v| (v.record_number, v.record_type))
+            .collect::<Vec<_>>(),
+        [(3, RecordType::EMR_EXTFLOODFILL)]
+    );
+    assert!(
+        approximated[0].description.contains("with_flood_fill"),
+        "{approximated:?}"
+    );
+}
+
+fn palette_entry(red: u8, green: u8, blue: u8) -> LogPaletteEntry {
+    LogPaletteEntry { reserved: 0, blue, green, red }
+}
+
+#[test]
+fn palette_colors_resolve_against_selected_palette() {
+    // PALETTEINDEX(1) and PALETTERGB(0x10, 0x10, 0xF0)
+    let index = ColorRef { red: 1, green: 0, blue: 0, reserved: 0x01 };
+    let rgb = ColorRef { red: 0x10, green: 0x10, blue: 0xF0, reserved: 0x02 };
+
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    // PALETTEINDEX(13) is red in the default palette.
+    set_pixel(&mut player, 1, PointL { x: 0, y: 0 }, ColorRef {
+        red: 13,
+        green: 0,
+        blue: 0,
+        reserved: 0x01,
+    });
+    player
+        .create_palette(2, EMR_CREATEPALETTE {
+            record_type: RecordType::EMR_CREATEPALETTE,
+            size: Size::from(0),
+            ih_pal: 1,
+            log_palette: LogPalette {
+                version: 0x0300,
+                number_of_entries: 2,
+                palette_entries: vec![
+                    palette_entry(0x00, 0x00, 0xFF),
+                    palette_entry(0x00, 0x80, 0x00),
+                ],
+            },
+        })
+        .expect("create_palette should succeed");
+    player
+        .select_palette(3, EMR_SELECTPALETTE {
+            record_type: RecordType::EMR_SELECTPALETTE,
+            size: Size::from(12),
+            in_pal: 1,
+        })
+        .expect("select_palette should succeed");
+    set_pixel(&mut player, 4, PointL { x: 0, y: 0 }, index.clone());
+    set_pixel(&mut player, 5, PointL { x: 0, y: 0 }, rgb);
+    player
+        .set_palette_entries(6, EMR_SETPALETTEENTRIES {
+            record_type: RecordType::EMR_SETPALETTEENTRIES,
+            size: Size::from(0),
+            ih_pal: 1,
+            start: 1,
+            number_of_entries: 1,
+            a_pal_entries: vec![palette_entry(0x12, 0x34, 0x56)],
+        })
+        .expect("set_palette_entries should succeed");
+    // The new entry is used once the palette is realized.
+    set_pixel(&mut player, 7, PointL { x: 0, y: 0 }, index.clone());
+    player
+        .realize_palette(8, EMR_REALIZEPALETTE {
+            record_type: RecordType::EMR_REALIZEPALETTE,
+            size: Size::from(8),
+        })
+        .expect("realize_palette should succeed");
+    set_pixel(&mut player, 9, PointL { x: 0, y: 0 }, index);
+    let svg = render(player);
+
+    for (id, fill) in [
+        ("elem1", "#FF0000"),
+        ("elem4", "#008000"),
+        ("elem5", "#0000FF"),
+        ("elem7", "#008000"),
+        ("elem9", "#123456"),
+    ] {
+        assert!(
+            svg.contains(&format!(r#"fill="{fill}" height="1" id="{id}""#)),
+            "{id}: {svg}"
+        );
+    }
+}
+
+fn set_icm_mode(
+    player: &mut SVGPlayer,
+    record_number: usize,
+    icm_mode: ICMMode,
+) {
+    player
+        .set_icm_mode(record_number, EMR_SETICMMODE {
+            record_type: RecordType::EMR_SETICMMODE,
+            size: Size::from(12),
+            icm_mode,
+        })
+        .expect("set_icm_mode should succeed");
+}
+
+#[test]
+fn icm_converts_colors_from_selected_color_space() {
+    let gray = ColorRef { red: 0x80, green: 0x80, blue: 0x80, reserved: 0 };
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    player
+        .create_color_space(1, EMR_CREATECOLORSPACE {
+            record_type: RecordType::EMR_CREATECOLORSPACE,
+            size: Size::from(0),
+            ih_cs: 1,
+            // Linear RGB: gamma 1.0 with the endpoints left to sRGB.
+            lcs: LogColorSpace {
+                color_space_type: LogicalColorSpace::LCS_CALIBRATED_RGB,
+                gamma_red: 0x0001_0000,
+                gamma_green: 0x0001_0000,
+                gamma_blue: 0x0001_0000,
+                ..LogColorSpace::default()
+            },
+            lcs_bytes: vec![],
+        })
+        .expect("create_color_space should succeed");
+    player
+        .set_color_space(2, EMR_SETCOLORSPACE {
+            record_type: RecordType::EMR_SETCOLORSPACE,
+            size: Size::from(12),
+            in_cs: 1,
+        })
+        .expect("set_color_space should succeed");
+    // Colors are converted only while ICM is on.
+    set_pixel(&mut player, 3, PointL { x: 0, y: 0 }, gray.clone());
+    set_icm_mode(&mut player, 4, ICMMode::ICM_ON);
+    set_pixel(&mut player, 5, PointL { x: 0, y: 0 }, gray.clone());
+    set_icm_mode(&mut player, 6, ICMMode::ICM_OFF);
+    set_pixel(&mut player, 7, PointL { x: 0, y: 0 }, gray);
+    let svg = render(player);
+
+    for (id, fill) in
+        [("elem3", "#808080"), ("elem5", "#BCBCBC"), ("elem7", "#808080")]
+    {
+        assert!(
+            svg.contains(&format!(r#"fill="{fill}" height="1" id="{id}""#)),
+            "{id}: {svg}"
+        );
+    }
+}
+
+fn stretch_dibits(player: &mut SVGPlayer, record_number: usize) {
+    player
+        .stretch_dibits(record_number, stretch_dibits_record())
+        .expect("stretch_dibits should succeed");
+}
+
+fn stretch_dibits_record() -> EMR_STRETCHDIBITS<'static> {
+    // A BITMAPINFOHEADER of a 1x1 24-bit bitmap.
+    let bmi_src = [
+        40_u32.to_le_bytes().as_slice(),
+        &1_i32.to_le_bytes(),
+        &1_i32.to_le_bytes(),
+        &1_u16.to_le_bytes(),
+        &24_u16.to_le_bytes(),
+        &[0; 24],
+    ]
+    .concat();
+
+    EMR_STRETCHDIBITS {
+        record_type: RecordType::EMR_STRETCHDIBITS,
+        size: Size::from(0),
+        bounds: RectL { left: 0, top: 0, right: 0, bottom: 0 },
+        x_dest: 0,
+        y_dest: 0,
+        x_src: 0,
+        y_src: 0,
+        cx_src: 1,
+        cy_src: 1,
+        off_bmi_src: 80,
+        cb_bmi_src: 40,
+        off_bits_src: 120,
+        cb_bits_src: 4,
+        usage_src: DIBColors::DIB_RGB_COLORS,
+        bit_bt_raster_operation: TernaryRasterOperation::SRCCOPY,
+        cx_dest: 1,
+        cy_dest: 1,
+        bmi_src: bmi_src.into(),
+        bits_src: vec![0x00, 0x00, 0xFF, 0x00].into(),
+    }
+}
+
+#[test]
+fn color_adjustment_filters_halftoned_bitmaps() {
+    // A negative grayscale: CA_NEGATIVE with a colorfulness of -100.
+    let adjustment = [
+        0x0018_u16,
+        0x0001,
+        0,
+        10_000,
+        10_000,
+        10_000,
+        0,
+        10_000,
+        0,
+        0,
+        (-100_i16).cast_unsigned(),
+        0,
+    ]
+    .iter()
+    .flat_map(|v| v.to_le_bytes())
+    .collect::<Vec<_>>();
+    let (color_adjustment, _) = ColorAdjustment::parse(&mut &adjustment[..])
+        .expect("color adjustment should parse");
+
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    player
+        .set_color_adjustment(1, EMR_SETCOLORADJUSTMENT {
+            record_type: RecordType::EMR_SETCOLORADJUSTMENT,
+            size: Size::from(32),
+            color_adjustment,
+        })
+        .expect("set_color_adjustment should succeed");
+    stretch_dibits(&mut player, 2);
+    player
+        .set_stretch_blt_mode(3, EMR_SETSTRETCHBLTMODE {
+            record_type: RecordType::EMR_SETSTRETCHBLTMODE,
+            size: Size::from(12),
+            stretch_mode: StretchMode::STRETCH_HALFTONE,
+        })
+        .expect("set_stretch_blt_mode should succeed");
+    stretch_dibits(&mut player, 4);
+    let svg = render(player);
+
+    assert_eq!(svg.matches("<filter ").count(), 1, "{svg}");
+    assert!(svg.contains(r#"<feColorMatrix type="saturate" values="0""#));
+    assert!(svg.contains(r#"<feFuncR tableValues="1 0" type="table""#));
+
+    let unfiltered = svg.split("<image").nth(1).expect("first image");
+    let filtered = svg.split("<image").nth(2).expect("second image");
+    assert!(!unfiltered.contains("filter="), "{svg}");
+    assert!(filtered.contains(r#"filter="url(#"#), "{svg}");
+}
+
+#[test]
+fn stretch_dibits_hints_rendering_from_stretch_mode() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    stretch_dibits(&mut player, 1);
+    player
+        .set_stretch_blt_mode(2, EMR_SETSTRETCHBLTMODE {
+            record_type: RecordType::EMR_SETSTRETCHBLTMODE,
+            size: Size::from(12),
+            stretch_mode: StretchMode::STRETCH_HALFTONE,
+        })
+        .expect("set_stretch_blt_mode should succeed");
+    stretch_dibits(&mut player, 3);
+    let svg = render(player);
+
+    let scaled = svg.split("<image").nth(1).expect("first image");
+    let smoothed = svg.split("<image").nth(2).expect("second image");
+    assert!(scaled.contains(r#"image-rendering="pixelated""#), "{svg}");
+    assert!(scaled.contains(r#"preserveAspectRatio="none""#), "{svg}");
+    assert!(smoothed.contains(r#"image-rendering="optimizeQuality""#), "{svg}");
+}
+
+#[test]
+fn rop2_approximates_raster_operations_with_blend_modes() {
+    let svg = render(square_with_rop2(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_COPYPEN,
+    ));
+    assert!(svg.contains(r##"fill="#000000""##), "{svg}");
+    assert!(!svg.contains("mix-blend-mode"), "{svg}");
+
+    let svg = render(square_with_rop2(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_XORPEN,
+    ));
+    assert!(svg.contains(r#"style="mix-blend-mode:difference""#), "{svg}");
+
+    // NOT XOR with the black brush is the difference from white.
+    let svg = render(square_with_rop2(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_NOTXORPEN,
+    ));
+    assert!(svg.contains(r##"fill="#FFFFFF""##), "{svg}");
+    assert!(svg.contains(r#"style="mix-blend-mode:difference""#), "{svg}");
+
+    let svg = render(square_with_rop2(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_WHITE,
+    ));
+    assert!(svg.contains(r##"fill="#FFFFFF""##), "{svg}");
+    assert!(svg.contains(r##"stroke="#FFFFFF""##), "{svg}");
+
+    let svg = render(square_with_rop2(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_MERGEPEN,
+    ));
+    assert!(svg.contains(r#"style="mix-blend-mode:lighten""#), "{svg}");
+
+    let svg = render(square_with_rop2(
+        SVGPlayer::new(),
+        BinaryRasterOperation::R2_NOP,
+    ));
+    assert!(!svg.contains("elem2"), "{svg}");
+}
+
+fn ext_text_out_w(
+    options: u32,
+    rectangle: Option<RectL>,
+    string_buffer: &str,
+    dx_buffer: Vec<u32>,
+) -> EMR_EXTTEXTOUTW {
+    EMR_EXTTEXTOUTW {
+        record_type: RecordType::EMR_EXTTEXTOUTW,
+        size: Size::from(0),
+        bounds: RectL { left: 0, top: 0, right: 0, bottom: 0 },
+        i_graphics_mode: GraphicsMode::GM_COMPATIBLE,
+        ex_scale: 0.0,
+        ey_scale: 0.0,
+        w_emr_text: EmrText {
+            reference: PointL { x: 100, y: 200 },
+            chars: u32::try_from(string_buffer.encode_utf16().count())
+                .expect("string should be short"),
+            off_string: 0,
+            options: ExtTextOutOptionsFlags::from_raw(options),
+            rectangle,
+            off_dx: 0,
+            string_buffer: OutputString::Unicode(string_buffer.to_owned()),
+            dx_buffer,
+        },
+    }
+}
+
+#[test]
+fn ext_text_out_w_positions_glyphs_from_dx_buffer() {
+    let mut player = player_with_font(None);
+    player
+        .ext_text_out_w(2, ext_text_out_w(0, None, "abc", vec![10, 20, 30]))
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"x="100 110 130""#), "glyph x list missing: {svg}");
+    assert!(svg.contains(r#"y="200""#), "reference y missing: {svg}");
+    assert!(!svg.contains(" dy="), "dy must only follow ETO_PDY: {svg}");
+}
+
+#[test]
+fn ext_text_out_w_shifts_right_aligned_run_by_total_advance() {
+    let mut player = player_with_font(None);
+    player
+        .set_text_align(2, EMR_SETTEXTALIGN {
+            record_type: RecordType::EMR_SETTEXTALIGN,
+            size: Size::from(0),
+            // TA_RIGHT
+            text_alignment_mode: 0x0000_0002,
+        })
+        .expect("set_text_align should succeed");
+    player
+        .ext_text_out_w(3, ext_text_out_w(0, None, "abc", vec![10, 20, 30]))
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"x="40 50 70""#), "glyph x list missing: {svg}");
+    assert!(
+        svg.contains(r#"text-anchor="start""#),
+        "absolute glyph positions must not be anchored again: {svg}",
+    );
+}
+
+#[test]
+fn ext_text_out_w_reads_vertical_offsets_with_eto_pdy() {
+    // ETO_PDY
+    let mut player = player_with_font(None);
+    player
+        .ext_text_out_w(
+            2,
+            ext_text_out_w(0x0000_2000, None, "ab", vec![10, 5, 10, 5]),
+        )
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"x="100 110""#), "glyph x list missing: {svg}");
+    assert!(svg.contains(r#"dy="0 -5""#), "glyph dy list missing: {svg}");
+}
+
+#[test]
+fn ext_text_out_w_honours_opaque_and_clipped_rectangle() {
+    // ETO_OPAQUE | ETO_CLIPPED
+    let mut player = player_with_font(None);
+    player
+        .ext_text_out_w(
+            2,
+            ext_text_out_w(
+                0x0000_0006,
+                Some(RectL { left: 90, top: 180, right: 200, bottom: 220 }),
+                "abc",
+                vec![],
+            ),
+        )
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(
+        svg.contains(r#"<clipPath id="defs0"><rect height="40" width="110""#),
+        "clip path missing: {svg}",
+    );
+    assert!(
+        svg.contains(r##"<rect fill="#FFFFFF" height="40" id="elem2""##),
+        "opaque background rect missing: {svg}",
+    );
+    assert!(
+        svg.contains(r#"clip-path="url(#defs0)""#),
+        "text is not clipped: {svg}",
+    );
+    assert!(
+        svg.find("<rect fill=").unwrap() < svg.find("<text").unwrap(),
+        "background must be painted before the text: {svg}",
+    );
+}
+
+#[test]
+fn rectangles_spanning_the_coordinate_range_do_not_overflow() {
+    // ETO_OPAQUE
+    let mut player = player_with_font(None);
+    player
+        .ext_text_out_w(
+            2,
+            ext_text_out_w(
+                0x0000_0002,
+                Some(RectL {
+                    left: i32::MIN,
+                    top: i32::MIN,
+                    right: i32::MAX,
+                    bottom: i32::MAX,
+                }),
+                "abc",
+                vec![],
+            ),
+        )
+        .expect("ext_text_out_w should succeed");
+    player
+        .stretch_dibits(3, EMR_STRETCHDIBITS {
+            x_dest: i32::MAX,
+            y_dest: i32::MIN,
+            cx_dest: i32::MAX,
+            cy_dest: -1,
+            ..stretch_dibits_record()
+        })
+        .expect("stretch_dibits should succeed");
+
+    let svg = render(player);
+    assert!(
+        svg.contains(r#"height="4294967295" id="elem2""#),
+        "opaque background rect missing: {svg}",
+    );
+}
+
+#[test]
+fn ext_text_out_a_decodes_with_font_charset() {
+    let mut record = ext_text_out_w(0, None, "", vec![]);
+    // "日本a" in Shift_JIS with one spacing value per byte.
+    record.w_emr_text.string_buffer =
+        OutputString::Ansi(vec![0x93, 0xFA, 0x96, 0x7B, 0x61]);
+    record.w_emr_text.dx_buffer = vec![16, 0, 16, 0, 8];
+
+    let mut player = player_with_font(Some(CharacterSet::SHIFTJIS_CHARSET));
+    player
+        .ext_text_out_a(3, EMR_EXTTEXTOUTA {
+            record_type: RecordType::EMR_EXTTEXTOUTA,
+            size: record.size,
+            bounds: record.bounds,
+            i_graphics_mode: record.i_graphics_mode,
+            ex_scale: record.ex_scale,
+            ey_scale: record.ey_scale,
+            a_emr_text: record.w_emr_text,
+        })
+        .expect("ext_text_out_a should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(">日本a</text>"), "text is not decoded: {svg}");
+    assert!(svg.contains(r#"x="100 116 132""#), "glyph x list missing: {svg}");
+}
+
+#[test]
+fn ext_text_out_a_decodes_cyrillic_with_russian_charset() {
+    let mut record = ext_text_out_w(0, None, "", vec![]);
+    record.w_emr_text.string_buffer =
+        OutputString::Ansi(vec![0xCC, 0xE8, 0xF0]);
+
+    let mut player = player_with_font(Some(CharacterSet::RUSSIAN_CHARSET));
+    player
+        .ext_text_out_a(3, EMR_EXTTEXTOUTA {
+            record_type: RecordType::EMR_EXTTEXTOUTA,
+            size: record.size,
+            bounds: record.bounds,
+            i_graphics_mode: record.i_graphics_mode,
+            ex_scale: record.ex_scale,
+            ey_scale: record.ey_scale,
+            a_emr_text: record.w_emr_text,
+        })
+        .expect("ext_text_out_a should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(">Мир</text>"), "text is not decoded: {svg}");
+}
+
+#[test]
+fn poly_text_out_w_emits_one_text_per_string() {
+    let first = ext_text_out_w(0, None, "first", vec![]).w_emr_text;
+    let second = ext_text_out_w(0, None, "second", vec![]).w_emr_text;
+
+    let mut player = player_with_font(None);
+    player
+        .poly_text_out_w(2, EMR_POLYTEXTOUTW {
+            record_type: RecordType::EMR_POLYTEXTOUTW,
+            size: Size::from(0),
+            bounds: RectL { left: 0, top: 0, right: 0, bottom: 0 },
+            i_graphics_mode: GraphicsMode::GM_COMPATIBLE,
+            ex_scale: 0.0,
+            ey_scale: 0.0,
+            c_strings: 2,
+            w_emr_text: vec![first, second],
+        })
+        .expect("poly_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(">first</text>"), "first string missing: {svg}");
+    assert!(svg.contains(">second</text>"), "second string missing: {svg}");
+    assert!(
+        svg.contains(r#"id="elem2-1""#),
+        "second string needs a suffixed id: {svg}",
+    );
+}
+
+#[test]
+fn small_text_out_renders_at_reference_point() {
+    // ETO_NO_RECT | ETO_SMALL_CHARS
+    let mut player = player_with_font(None);
+    player
+        .small_text_out(2, EMR_SMALLTEXTOUT {
+            record_type: RecordType::EMR_SMALLTEXTOUT,
+            size: Size::from(0),
+            x: 40,
+            y: 60,
+            c_chars: 5,
+            fu_options: ExtTextOutOptionsFlags::from_raw(0x0000_0300),
+            i_graphics_mode: GraphicsMode::GM_COMPATIBLE,
+            ex_scale: 0.0,
+            ey_scale: 0.0,
+            bounds: None,
+            text_string: OutputString::Unicode("small".to_owned()),
+        })
+        .expect("small_text_out should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"x="40""#), "x attr missing: {svg}");
+    assert!(svg.contains(r#"y="60""#), "y attr missing: {svg}");
+    assert!(svg.contains(">small</text>"), "text missing: {svg}");
+}
+
+/// Builds a TrueType font with 1600 units per em whose cmap maps 'A' and
+/// 'B' to glyphs 1 and 2, and whose glyph 3 is an unmapped triangle.
+fn glyph_index_test_font() -> Vec<u8> {
+    fn push_u16(v: &mut Vec<u8>, values: &[u16]) {
+        for value in values {
+            v.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    let mut head = vec![0; 54];
+    head[18..20].copy_from_slice(&1600_u16.to_be_bytes());
+
+    let mut hhea = vec![0; 36];
+    hhea[4..6].copy_from_slice(&1200_i16.to_be_bytes());
+    hhea[6..8].copy_from_slice(&(-400_i16).to_be_bytes());
+    hhea[34..36].copy_from_slice(&4_u16.to_be_bytes());
+
+    let mut hmtx = vec![];
+    push_u16(&mut hmtx, &[800, 0, 800, 0, 800, 0, 800, 0]);
+
+    // Format 4 with the segments 'A'..='B' and the final 0xFFFF.
+    let mut cmap = vec![];
+    push_u16(&mut cmap, &[0, 1, 3, 1, 0, 12]);
+    push_u16(&mut cmap, &[4, 32, 0, 4, 4, 1, 0]);
+    push_u16(&mut cmap, &[0x42, 0xFFFF, 0, 0x41, 0xFFFF, 0xFFC0, 1, 0, 0]);
+
+    // One contour of three on-curve points with word deltas.
+    let mut glyf = vec![];
+    push_u16(&mut glyf, &[1, 0, 0, 800, 800, 2, 0]);
+    glyf.extend_from_slice(&[0x01, 0x01, 0x01]);
+    push_u16(&mut glyf, &[0, 800, 0xFE70, 0, 0, 800]);
+    glyf.push(0);
+
+    let mut loca = vec![];
+    push_u16(&mut loca, &[0, 0, 0, 0, 15]);
+
+    let tables: [(&[u8; 4], Vec<u8>); 6] = [
+        (b"cmap", cmap),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+    ];
+    let mut font = vec![];
+    push_u16(&mut font, &[1, 0, 6, 0, 0, 0]);
+
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        font.extend_from_slice(*tag);
+        font.extend_from_slice(&[0; 4]);
+        for v in [offset, data.len()] {
+            font.extend_from_slice(
+                &u32::try_from(v).expect("font should be small").to_be_bytes(),
+            );
+        }
+        offset += data.len();
+    }
+    for (_, data) in tables {
+        font.extend(data);
+    }
+
+    font
+}
+
+fn ext_text_out_glyph_indices(
+    mut player: SVGPlayer,
+    indices: Vec<u16>,
+) -> String {
+    // ETO_GLYPH_INDEX
+    let mut record = ext_text_out_w(0x0000_0010, None, "", vec![]);
+    record.w_emr_text.chars =
+        u32::try_from(indices.len()).expect("indices should be short");
+    record.w_emr_text.string_buffer = OutputString::GlyphIndices(indices);
+
+    player.header(0, build_header(1024, 768)).expect("header should succeed");
+    select_font(&mut player, CharacterSet::ANSI_CHARSET);
+    player.ext_text_out_w(3, record).expect("ext_text_out_w should succeed");
+
+    render(player)
+}
+
+#[test]
+fn ext_text_out_w_maps_glyph_indices_through_cmap() {
+    let player = SVGPlayer::new()
+        .with_font_provider(|_: &str| Some(glyph_index_test_font()));
+
+    let svg = ext_text_out_glyph_indices(player, vec![1, 2]);
+    assert!(svg.contains(">AB</text>"), "glyphs were not mapped: {svg}");
+}
+
+#[test]
+fn ext_text_out_w_draws_unmapped_glyph_indices_as_outlines() {
+    let player = SVGPlayer::new()
+        .with_font_provider(|_: &str| Some(glyph_index_test_font()));
+
+    // The em box is scaled to the 16 unit font height and the TA_TOP
+    // reference sits one ascender (12 units) above the baseline.
+    let svg = ext_text_out_glyph_indices(player, vec![1, 3]);
+    assert!(
+        svg.contains(r#"d="M 108 212 L 116 212 L 112 204 Z""#),
+        "glyph outline missing: {svg}",
+    );
+    assert!(!svg.contains("<text"), "unmapped glyphs must not be text: {svg}");
+}
+
+#[test]
+fn ext_text_out_w_skips_glyph_indices_without_font() {
+    let svg = ext_text_out_glyph_indices(SVGPlayer::new(), vec![1, 2]);
+    assert!(!svg.contains("<text"), "glyph indices are not characters: {svg}");
+    assert!(!svg.contains("<path"), "no outline without a font: {svg}");
+}
+
+fn with_text_align(player: &mut SVGPlayer, text_alignment_mode: u32) {
+    player
+        .set_text_align(2, EMR_SETTEXTALIGN {
+            record_type: RecordType::EMR_SETTEXTALIGN,
+            size: Size::from(0),
+            text_alignment_mode,
+        })
+        .expect("set_text_align should succeed");
+}
+
+#[test]
+fn ext_text_out_w_with_update_cp_continues_at_current_position() {
+    // TA_UPDATECP
+    let mut player = player_with_font(None);
+    with_text_align(&mut player, 0x0000_0001);
+    player
+        .move_to_ex(3, EMR_MOVETOEX {
+            record_type: RecordType::EMR_MOVETOEX,
+            size: Size::from(0),
+            offset: PointL { x: 10, y: 20 },
+        })
+        .expect("move_to_ex should succeed");
+    player
+        .ext_text_out_w(4, ext_text_out_w(0, None, "ab", vec![10, 20]))
+        .expect("ext_text_out_w should succeed");
+    player
+        .ext_text_out_w(5, ext_text_out_w(0, None, "cd", vec![5, 5]))
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"x="10 20""#), "first run misplaced: {svg}");
+    assert!(
+        svg.contains(r#"x="40 45""#),
+        "second run must start where the first one ended: {svg}",
+    );
+    assert!(!svg.contains(r#"x="100"#), "reference must be ignored: {svg}");
+}
+
+#[test]
+fn ext_text_out_w_lays_out_rtl_reading_from_the_right() {
+    // TA_RTLREADING
+    let mut player = player_with_font(None);
+    with_text_align(&mut player, 0x0000_0100);
+    player
+        .ext_text_out_w(3, ext_text_out_w(0, None, "abc", vec![10, 20, 30]))
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"x="150 130 100""#), "glyph x list missing: {svg}");
+}
+
+#[test]
+fn ext_text_out_w_rtl_without_spacing_keeps_left_edge_at_reference() {
+    // ETO_RTLREADING
+    let mut player = player_with_font(None);
+    player
+        .ext_text_out_w(2, ext_text_out_w(0x0000_0080, None, "abc", vec![]))
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    assert!(svg.contains(r#"direction="rtl""#), "direction missing: {svg}");
+    assert!(svg.contains(r#"text-anchor="end""#), "anchor missing: {svg}");
+}
+
+#[test]
+fn set_text_justification_spreads_extra_over_spaces() {
+    let mut player = player_with_font(None);
+    player
+        .set_text_justification(2, EMR_SETTEXTJUSTIFICATION {
+            record_type: RecordType::EMR_SETTEXTJUSTIFICATION,
+            size: Size::from(0),
+            n_break_extra: 7,
+            n_break_count: 2,
+        })
+        .expect("set_text_justification should succeed");
+    player
+        .ext_text_out_w(3, ext_text_out_w(0, None, "a b c", vec![10; 5]))
+        .expect("ext_text_out_w should succeed");
+    player
+        .ext_text_out_w(4, ext_text_out_w(0, None, "a b", vec![]))
+        .expect("ext_text_out_w should succeed");
+
+    let svg = render(player);
+    // The first space takes the remainder of 7 / 2.
+    assert!(
+        svg.contains(r#"x="100 110 124 134 147""#),
+        "extra space missing from spacing: {svg}",
+    );
+    assert!(
+        svg.contains(r#"word-spacing="4""#),
+        "extra space missing without spacing: {svg}",
+    );
+}
+
+#[test]
+fn diagnostics_report_unimplemented_and_approximated_records() {
+    let mut player = SVGPlayer::new();
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    player
+        .set_rop2(1, EMR_SETROP2 {
+            record_type: RecordType::EMR_SETROP2,
+            size: Size::from(12),
+            rop2_mode: BinaryRasterOperation::R2_XORPEN,
+        })
+        .expect("set_rop2 should succeed");
+    player
+        .create_brush_indirect(2, EMR_CREATEBRUSHINDIRECT {
+            record_type: RecordType::EMR_CREATEBRUSHINDIRECT,
+            size: Size::from(24),
+            ih_brush: 1,
+            log_brush: LogBrushEx::Hatched {
+                color: ColorRef::black(),
+                brush_hatch: HatchStyle::HS_DITHEREDCLR,
+            },
+        })
+        .expect("create_brush_indirect should succeed");
+    player
+        .set_meta_rgn(3, EMR_SETMETARGN {
+            record_type: RecordType::EMR_SETMETARGN,
+            size: Size::from(8),
+        })
+        .expect("set_meta_rgn should succeed");
+
+    let diagnostics = player.take_diagnostics();
+    let positions = |v: &[Diagnostic]| {
+        v.iter().map(|v| (v.record_number, v.record_type)).collect::<Vec<_>>()
+    };
+
+    assert_eq!(positions(&diagnostics.unimplemented), [(
+        3,
+        RecordType::EMR_SETMETARGN
+    )]);
+    assert_eq!(positions(&diagnostics.approximated), [
+        (1, RecordType::EMR_SETROP2),
+        (2, RecordType::EMR_CREATEBRUSHINDIRECT),
+    ]);
+    assert!(
+        diagnostics.approximated[1].description.contains("HS_DITHEREDCLR"),
+        "{:?}",
+        diagnostics.approximated
+    );
+    assert!(diagnostics.recovered.is_empty());
+    assert!(player.take_diagnostics().is_empty());
+}
+
+#[test]
+fn options_size_background_and_round_the_svg() {
+    let options = ConvertOptions {
+        size: OutputSize::Dpi(96.0),
+        precision: Some(2),
+        background: Some("white".to_owned()),
+        ..Default::default()
+    };
+    let mut player = SVGPlayer::new().with_options(options);
+    player.header(0, build_header(1000, 500)).expect("header should succeed");
+    let svg = render(player);
+
+    // A frame of 10 x 5 millimeters at 96 DPI.
+    assert!(svg.contains(r#"height="18.9" viewBox="#), "{svg}");
+    assert!(svg.contains(r#"width="37.8""#), "{svg}");
+    assert!(
+        svg.contains(
+            r#"<rect fill="white" height="500" width="1000" x="0" y="0">"#
+        ),
+        "{svg}"
+    );
+
+    let mut player = SVGPlayer::new().with_options(ConvertOptions {
+        size: OutputSize::Pixels { width: 640, height: 480 },
+        ..Default::default()
+    });
+    player.header(0, build_header(1000, 500)).expect("header should succeed");
+    let svg = render(player);
+
+    assert!(
+        svg.contains(r#"height="480" preserveAspectRatio="none" viewBox="#),
+        "{svg}"
+    );
+    assert!(svg.contains(r#"width="640""#), "{svg}");
+}
+
+#[test]
+fn options_store_images_externally_within_the_pixel_budget() {
+    let store = |media_type: &str, _: &[u8]| {
+        Some(format!("picture.{}", media_type.trim_start_matches("image/")))
+    };
+    let mut player = SVGPlayer::new().with_options(ConvertOptions {
+        images: ImageMode::External(std::rc::Rc::new(store)),
+        limits: ResourceLimits {
+            max_bitmap_pixels: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+    stretch_dibits(&mut player, 1);
+
+    assert!(render(player.clone()).contains(r#"href="picture.bmp""#));
+    assert!(matches!(
+        player.stretch_dibits(2, stretch_dibits_record()),
+        Err(PlayError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn bitmap_limit_is_checked_before_the_bitmap_is_decoded() {
+    let mut player = SVGPlayer::new().with_options(ConvertOptions {
+        limits: ResourceLimits {
+            max_bitmap_pixels: Some(u32::MAX.into()),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+
+    // A header of a 65536 x 65536 bitmap, whose pixels would not fit in
+    // memory.
+    let mut record = stretch_dibits_record();
+    record.bmi_src = [
+        40_u32.to_le_bytes().as_slice(),
+        &0x0001_0000_i32.to_le_bytes(),
+        &0x0001_0000_i32.to_le_bytes(),
+        &1_u16.to_le_bytes(),
+        &24_u16.to_le_bytes(),
+        &[0; 24],
+    ]
+    .concat()
+    .into();
+
+    assert!(matches!(
+        player.stretch_dibits(1, record),
+        Err(PlayError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn output_limit_fails_the_record_that_exceeds_it() {
+    let mut player = SVGPlayer::new().with_options(ConvertOptions {
+        limits: ResourceLimits {
+            max_output_bytes: Some(64),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    player.header(0, build_header(100, 100)).expect("header should succeed");
+
+    assert!(matches!(
+        player.rectangle(1, EMR_RECTANGLE {
+            record_type: RecordType::EMR_RECTANGLE,
+            size: Size::from(24),
+            bx: RectL { left: 10, top: 10, right: 50, bottom: 50 },
+        }),
+        Err(PlayError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn object_table_grows_past_header_handles_within_its_limit() {
+    let pen = |player: &mut SVGPlayer, record_number, ih_pen| {
+        player.ext_create_pen(record_number, EMR_EXTCREATEPEN {
+            record_type: RecordType::EMR_EXTCREATEPEN,
+            size: Size::from(0),
+            ih_pen,
+            off_bmi: 0,
+            cb_bmi: 0,
+            off_bits: 0,
+            cb_bits: 0,
+            elp: LogPenEx::black_pen(),
+            bmi_src: None,
+            bits_src: None,
+        })
+    };
+    let header = || {
+        let mut player = SVGPlayer::new().with_options(ConvertOptions {
+            limits: ResourceLimits {
+                max_objects: Some(16),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        player
+            .header(0, build_header(100, 100))
+            .expect("header should succeed");
+
+        player
+    };
+
+    // The header declares a single handle.
+    let mut player = header();
+    pen(&mut player, 1, 15).expect("pen within the limit");
+    player
+        .delete_object(2, EMR_DELETEOBJECT {
+            record_type: RecordType::EMR_DELETEOBJECT,
+            size: Size::from(12),
+            in_object: 1000,
+        })
+        .expect("deleting a missing object should succeed");
+
+    assert!(matches!(
+        pen(&mut player, 3, 16),
+        Err(PlayError::LimitExceeded { .. })
+    ));
+    assert!(matches!(
+        pen(&mut header(), 1, 0),
+        Err(PlayError::InvalidRecord { .. })
+    ));
+    assert!(matches!(
+        pen(&mut SVGPlayer::new(), 1, u32::MAX),
+        Err(PlayError::InvalidRecord { .. })
+    ));
+}
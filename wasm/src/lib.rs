@@ -2,7 +2,10 @@
 
 extern crate alloc;
 
-use alloc::string::{String, ToString};
+use alloc::{
+    borrow::ToOwned,
+    string::{String, ToString},
+};
 
 use wasm_bindgen::prelude::*;
 
@@ -49,6 +52,136 @@ pub fn convert_emf_to_svg(buf: &[u8]) -> Result<String, JsValue> {
     Ok(String::from_utf8_lossy(&output).to_string())
 }
 
+/// Options of `convertEmf2SvgWithOptions`. Bitmaps are always embedded,
+/// since WebAssembly cannot write them out as files.
+///
+/// # Example
+///
+/// ```js
+/// import { ConvertOptions, convertEmf2SvgWithOptions } from "emf-wasm";
+///
+/// const options = new ConvertOptions();
+/// options.setDpi(96);
+/// options.setBackground("white");
+/// options.setRecoveryPolicy("skip");
+///
+/// const svg = convertEmf2SvgWithOptions(emfBytes, options);
+/// ```
+#[wasm_bindgen(js_name = ConvertOptions)]
+#[derive(Default)]
+pub struct Options {
+    inner: emf_core::converter::ConvertOptions,
+}
+
+#[wasm_bindgen(js_class = ConvertOptions)]
+impl Options {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size of the SVG in pixels.
+    #[wasm_bindgen(js_name = setSize)]
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.inner.size =
+            emf_core::converter::OutputSize::Pixels { width, height };
+    }
+
+    /// Sizes the SVG from the picture frame at `dpi` dots per inch.
+    #[wasm_bindgen(js_name = setDpi)]
+    pub fn set_dpi(&mut self, dpi: f32) {
+        self.inner.size = emf_core::converter::OutputSize::Dpi(dpi);
+    }
+
+    /// Rounds coordinates to `precision` decimal places.
+    #[wasm_bindgen(js_name = setPrecision)]
+    pub fn set_precision(&mut self, precision: usize) {
+        self.inner.precision = Some(precision);
+    }
+
+    /// Fills the picture with a CSS color before drawing the records.
+    #[wasm_bindgen(js_name = setBackground)]
+    pub fn set_background(&mut self, color: &str) {
+        self.inner.background = Some(color.to_owned());
+    }
+
+    /// Draws text as glyph outlines when its font is available.
+    #[wasm_bindgen(js_name = setTextAsPaths)]
+    pub fn set_text_as_paths(&mut self, text_as_paths: bool) {
+        self.inner.text_as_paths = text_as_paths;
+    }
+
+    /// Sets how broken records are handled: "strict", "skip" or "stop".
+    #[wasm_bindgen(js_name = setRecoveryPolicy)]
+    pub fn set_recovery_policy(&mut self, policy: &str) -> Result<(), JsValue> {
+        use emf_core::converter::RecoveryPolicy;
+
+        self.inner.recovery_policy = match policy {
+            "strict" => RecoveryPolicy::Strict,
+            "skip" => RecoveryPolicy::SkipRecord,
+            "stop" => RecoveryPolicy::StopAtFirstError,
+            _ => {
+                return Err(JsValue::from(alloc::format!(
+                    "unknown recovery policy: {policy}"
+                )));
+            }
+        };
+
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setMaxRecords)]
+    pub fn set_max_records(&mut self, max: usize) {
+        self.inner.limits.max_records = Some(max);
+    }
+
+    #[wasm_bindgen(js_name = setMaxBitmapPixels)]
+    pub fn set_max_bitmap_pixels(&mut self, max: f64) {
+        self.inner.limits.max_bitmap_pixels = Some(max as u64);
+    }
+
+    #[wasm_bindgen(js_name = setMaxOutputBytes)]
+    pub fn set_max_output_bytes(&mut self, max: usize) {
+        self.inner.limits.max_output_bytes = Some(max);
+    }
+
+    #[wasm_bindgen(js_name = setMaxObjects)]
+    pub fn set_max_objects(&mut self, max: usize) {
+        self.inner.limits.max_objects = Some(max);
+    }
+}
+
+/// Converts EMF binary data to an SVG string with `options`.
+///
+/// # Arguments
+///
+/// - `buf` - Byte array of a EMF file
+/// - `options` - Options of the conversion
+///
+/// # Returns
+///
+/// - SVG string (UTF-8)
+/// - On failure, returns a JsValue containing error details
+#[wasm_bindgen(js_name = convertEmf2SvgWithOptions)]
+pub fn convert_emf_to_svg_with_options(
+    buf: &[u8],
+    options: &Options,
+) -> Result<String, JsValue> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    set_log_level("info");
+
+    let wmf_player = wmf_core::converter::SVGPlayer::new();
+    let converter = emf_core::converter::EMFConverter::builder()
+        .options(options.inner.clone())
+        .build(buf, wmf_player);
+    let output =
+        converter.run().map_err(|err| JsValue::from(err.to_string()))?;
+
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
 /// Sets the log level (only when the `tracing` feature is enabled).
 ///
 /// # Arguments